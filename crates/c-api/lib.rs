@@ -5,6 +5,7 @@
 //! C bindings.
 
 #![allow(non_camel_case_types)]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
 #![warn(missing_docs)]
 #![warn(missing_copy_implementations)]
 
@@ -76,7 +77,7 @@ pub struct resvg_transform {
 
 impl resvg_transform {
     #[inline]
-    fn to_tiny_skia(&self) -> tiny_skia::Transform {
+    fn to_tiny_skia(self) -> tiny_skia::Transform {
         tiny_skia::Transform::from_row(self.a, self.b, self.c, self.d, self.e, self.f)
     }
 }
@@ -171,7 +172,7 @@ pub extern "C" fn resvg_options_set_resources_dir(opt: *mut resvg_options, path:
 /// Default: 96
 #[no_mangle]
 pub extern "C" fn resvg_options_set_dpi(opt: *mut resvg_options, dpi: f32) {
-    cast_opt(opt).dpi = dpi as f32;
+    cast_opt(opt).dpi = dpi;
 }
 
 /// @brief Sets the default font family.
@@ -534,7 +535,7 @@ pub extern "C" fn resvg_parse_tree_from_file(
 
     #[cfg(feature = "text")]
     {
-        utree.convert_text(&raw_opt.fontdb);
+        utree.convert_text_with_svg_glyphs(&raw_opt.fontdb, &usvg::parse_svg_glyph, None);
     }
 
     let tree_box = Box::new(resvg_render_tree(utree));
@@ -576,7 +577,7 @@ pub extern "C" fn resvg_parse_tree_from_data(
 
     #[cfg(feature = "text")]
     {
-        utree.convert_text(&raw_opt.fontdb);
+        utree.convert_text_with_svg_glyphs(&raw_opt.fontdb, &usvg::parse_svg_glyph, None);
     }
 
     let tree_box = Box::new(resvg_render_tree(utree));
@@ -935,7 +936,7 @@ impl log::Log for SimpleLogger {
 
     fn log(&self, record: &log::Record) {
         if self.enabled(record.metadata()) {
-            let target = if record.target().len() > 0 {
+            let target = if !record.target().is_empty() {
                 record.target()
             } else {
                 record.module_path().unwrap_or_default()
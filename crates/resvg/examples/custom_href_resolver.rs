@@ -6,11 +6,12 @@ fn main() {
     let ferris_image = std::sync::Arc::new(std::fs::read("./examples/ferris.png").unwrap());
 
     // We know that our SVG won't have DataUrl hrefs, just return None for such case.
-    let resolve_data = Box::new(|_: &str, _: std::sync::Arc<Vec<u8>>, _: &usvg::Options| None);
+    let resolve_data =
+        std::sync::Arc::new(|_: &str, _: std::sync::Arc<Vec<u8>>, _: &usvg::Options| None);
 
     // Here we handle xlink:href attribute as string,
     // let's use already loaded Ferris image to match that string.
-    let resolve_string = Box::new(move |href: &str, _: &usvg::Options| match href {
+    let resolve_string = std::sync::Arc::new(move |href: &str, _: &usvg::Options| match href {
         "ferris_image" => Some(usvg::ImageKind::PNG(ferris_image.clone())),
         _ => None,
     });
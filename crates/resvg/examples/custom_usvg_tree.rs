@@ -1,4 +1,5 @@
 use std::rc::Rc;
+use std::sync::Arc;
 
 fn main() {
     let size = usvg::Size::from_wh(200.0, 200.0).unwrap();
@@ -9,6 +10,8 @@ fn main() {
             aspect: usvg::AspectRatio::default(),
         },
         root: usvg::Node::new(usvg::NodeKind::Group(usvg::Group::default())),
+        animation: None,
+        font_faces: Vec::new(),
     };
 
     let gradient = usvg::LinearGradient {
@@ -21,6 +24,7 @@ fn main() {
             units: usvg::Units::ObjectBoundingBox,
             transform: usvg::Transform::default(),
             spread_method: usvg::SpreadMethod::Pad,
+            color_interpolation: usvg::filter::ColorInterpolation::SRGB,
             stops: vec![
                 usvg::Stop {
                     offset: usvg::StopOffset::ZERO,
@@ -41,7 +45,7 @@ fn main() {
         ..usvg::Fill::default()
     });
 
-    let mut path = usvg::Path::new(Rc::new(tiny_skia::PathBuilder::from_rect(
+    let mut path = usvg::Path::new(Arc::new(tiny_skia::PathBuilder::from_rect(
         tiny_skia::Rect::from_xywh(20.0, 20.0, 160.0, 160.0).unwrap(),
     )));
     path.fill = fill;
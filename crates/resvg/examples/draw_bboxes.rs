@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use usvg::{fontdb, NodeExt, TreeParsing, TreeTextToPath};
 
@@ -19,11 +19,13 @@ fn main() {
         1.0
     };
 
-    let mut opt = usvg::Options::default();
-    // Get file's absolute directory.
-    opt.resources_dir = std::fs::canonicalize(&args[1])
-        .ok()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()));
+    let opt = usvg::Options {
+        // Get file's absolute directory.
+        resources_dir: std::fs::canonicalize(&args[1])
+            .ok()
+            .and_then(|p| p.parent().map(|p| p.to_path_buf())),
+        ..usvg::Options::default()
+    };
     // let fit_to = resvg::FitTo::Zoom(zoom);
 
     let mut fontdb = fontdb::Database::new();
@@ -61,13 +63,13 @@ fn main() {
     });
 
     for bbox in bboxes {
-        let mut path = usvg::Path::new(Rc::new(tiny_skia::PathBuilder::from_rect(bbox)));
+        let mut path = usvg::Path::new(Arc::new(tiny_skia::PathBuilder::from_rect(bbox)));
         path.stroke = stroke.clone();
         tree.root.append_kind(usvg::NodeKind::Path(path));
     }
 
     for bbox in text_bboxes {
-        let mut path = usvg::Path::new(Rc::new(tiny_skia::PathBuilder::from_rect(bbox)));
+        let mut path = usvg::Path::new(Arc::new(tiny_skia::PathBuilder::from_rect(bbox)));
         path.stroke = stroke2.clone();
         tree.root.append_kind(usvg::NodeKind::Path(path));
     }
@@ -10,11 +10,13 @@ fn main() {
     // resvg::Tree own all the required data and does not require
     // the input file, usvg::Tree or anything else.
     let rtree = {
-        let mut opt = usvg::Options::default();
-        // Get file's absolute directory.
-        opt.resources_dir = std::fs::canonicalize(&args[1])
-            .ok()
-            .and_then(|p| p.parent().map(|p| p.to_path_buf()));
+        let opt = usvg::Options {
+            // Get file's absolute directory.
+            resources_dir: std::fs::canonicalize(&args[1])
+                .ok()
+                .and_then(|p| p.parent().map(|p| p.to_path_buf())),
+            ..usvg::Options::default()
+        };
 
         let mut fontdb = fontdb::Database::new();
         fontdb.load_system_fonts();
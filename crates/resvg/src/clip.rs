@@ -70,6 +70,7 @@ fn draw_children(
                 // We could use any values here. They will not be used anyway.
                 let ctx = Context {
                     max_bbox: tiny_skia::IntRect::from_xywh(0, 0, 1, 1).unwrap(),
+                    tile: None,
                 };
 
                 crate::path::render_fill_path(path, mode, &ctx, transform, pixmap);
@@ -8,8 +8,6 @@ use rgb::{FromSlice, RGBA8};
 use tiny_skia::IntRect;
 use usvg::{ApproxEqUlps, ApproxZeroUlps};
 
-use crate::tree::Node;
-
 mod box_blur;
 mod color_matrix;
 mod component_transfer;
@@ -90,7 +88,7 @@ pub struct Primitive {
     pub region: tiny_skia::NonZeroRect,
     pub color_interpolation: usvg::filter::ColorInterpolation,
     pub result: String,
-    pub kind: usvg::filter::Kind,
+    pub kind: Kind,
 }
 
 pub struct Filter {
@@ -98,6 +96,43 @@ pub struct Filter {
     pub primitives: Vec<Primitive>,
 }
 
+/// A filter primitive kind.
+///
+/// Mirrors `usvg::filter::Kind`, but doesn't reference `usvg` nodes,
+/// so the render tree could be shared between threads.
+pub enum Kind {
+    Blend(usvg::filter::Blend),
+    ColorMatrix(usvg::filter::ColorMatrix),
+    ComponentTransfer(usvg::filter::ComponentTransfer),
+    Composite(usvg::filter::Composite),
+    ConvolveMatrix(usvg::filter::ConvolveMatrix),
+    DiffuseLighting(usvg::filter::DiffuseLighting),
+    DisplacementMap(usvg::filter::DisplacementMap),
+    DropShadow(usvg::filter::DropShadow),
+    Flood(usvg::filter::Flood),
+    GaussianBlur(usvg::filter::GaussianBlur),
+    Image(ImagePrimitive),
+    Merge(usvg::filter::Merge),
+    Morphology(usvg::filter::Morphology),
+    Offset(usvg::filter::Offset),
+    SpecularLighting(usvg::filter::SpecularLighting),
+    Tile(usvg::filter::Tile),
+    Turbulence(usvg::filter::Turbulence),
+}
+
+/// A `feImage` filter primitive with an already converted content.
+pub struct ImagePrimitive {
+    pub aspect: usvg::AspectRatio,
+    pub quality: tiny_skia::FilterQuality,
+    /// `None` when the image cannot be rendered.
+    pub data: Option<ImagePrimitiveKind>,
+}
+
+pub enum ImagePrimitiveKind {
+    Image(crate::image::ImageKind),
+    Use(Box<crate::Tree>),
+}
+
 pub fn convert(
     ufilters: &[Rc<usvg::filter::Filter>],
     object_bbox: Option<tiny_skia::Rect>,
@@ -153,25 +188,23 @@ fn convert_primitive(
     uprimitive: &usvg::filter::Primitive,
     units: usvg::Units,
     object_bbox: Option<tiny_skia::NonZeroRect>,
-) -> Option<usvg::filter::Kind> {
+) -> Option<Kind> {
     match uprimitive.kind {
         usvg::filter::Kind::DisplacementMap(ref fe) => {
             let (sx, _) = scale_coordinates(fe.scale, fe.scale, units, object_bbox)?;
-            Some(usvg::filter::Kind::DisplacementMap(
-                usvg::filter::DisplacementMap {
-                    input1: fe.input1.clone(),
-                    input2: fe.input2.clone(),
-                    scale: sx,
-                    x_channel_selector: fe.x_channel_selector,
-                    y_channel_selector: fe.y_channel_selector,
-                },
-            ))
+            Some(Kind::DisplacementMap(usvg::filter::DisplacementMap {
+                input1: fe.input1.clone(),
+                input2: fe.input2.clone(),
+                scale: sx,
+                x_channel_selector: fe.x_channel_selector,
+                y_channel_selector: fe.y_channel_selector,
+            }))
         }
         usvg::filter::Kind::DropShadow(ref fe) => {
             let (dx, dy) = scale_coordinates(fe.dx, fe.dy, units, object_bbox)?;
             let (std_dev_x, std_dev_y) =
                 scale_coordinates(fe.std_dev_x.get(), fe.std_dev_y.get(), units, object_bbox)?;
-            Some(usvg::filter::Kind::DropShadow(usvg::filter::DropShadow {
+            Some(Kind::DropShadow(usvg::filter::DropShadow {
                 input: fe.input.clone(),
                 dx,
                 dy,
@@ -184,18 +217,16 @@ fn convert_primitive(
         usvg::filter::Kind::GaussianBlur(ref fe) => {
             let (std_dev_x, std_dev_y) =
                 scale_coordinates(fe.std_dev_x.get(), fe.std_dev_y.get(), units, object_bbox)?;
-            Some(usvg::filter::Kind::GaussianBlur(
-                usvg::filter::GaussianBlur {
-                    input: fe.input.clone(),
-                    std_dev_x: usvg::PositiveF32::new(std_dev_x).unwrap_or_default(),
-                    std_dev_y: usvg::PositiveF32::new(std_dev_y).unwrap_or_default(),
-                },
-            ))
+            Some(Kind::GaussianBlur(usvg::filter::GaussianBlur {
+                input: fe.input.clone(),
+                std_dev_x: usvg::PositiveF32::new(std_dev_x).unwrap_or_default(),
+                std_dev_y: usvg::PositiveF32::new(std_dev_y).unwrap_or_default(),
+            }))
         }
         usvg::filter::Kind::Morphology(ref fe) => {
             let (radius_x, radius_y) =
                 scale_coordinates(fe.radius_x.get(), fe.radius_y.get(), units, object_bbox)?;
-            Some(usvg::filter::Kind::Morphology(usvg::filter::Morphology {
+            Some(Kind::Morphology(usvg::filter::Morphology {
                 input: fe.input.clone(),
                 operator: fe.operator,
                 radius_x: usvg::PositiveF32::new(radius_x).unwrap_or_default(),
@@ -204,13 +235,44 @@ fn convert_primitive(
         }
         usvg::filter::Kind::Offset(ref fe) => {
             let (dx, dy) = scale_coordinates(fe.dx, fe.dy, units, object_bbox)?;
-            Some(usvg::filter::Kind::Offset(usvg::filter::Offset {
+            Some(Kind::Offset(usvg::filter::Offset {
                 input: fe.input.clone(),
                 dx,
                 dy,
             }))
         }
-        _ => Some(uprimitive.kind.clone()),
+        usvg::filter::Kind::Image(ref fe) => Some(Kind::Image(convert_image(fe))),
+        usvg::filter::Kind::Blend(ref fe) => Some(Kind::Blend(fe.clone())),
+        usvg::filter::Kind::ColorMatrix(ref fe) => Some(Kind::ColorMatrix(fe.clone())),
+        usvg::filter::Kind::ComponentTransfer(ref fe) => Some(Kind::ComponentTransfer(fe.clone())),
+        usvg::filter::Kind::Composite(ref fe) => Some(Kind::Composite(fe.clone())),
+        usvg::filter::Kind::ConvolveMatrix(ref fe) => Some(Kind::ConvolveMatrix(fe.clone())),
+        usvg::filter::Kind::DiffuseLighting(ref fe) => Some(Kind::DiffuseLighting(fe.clone())),
+        usvg::filter::Kind::Flood(ref fe) => Some(Kind::Flood(*fe)),
+        usvg::filter::Kind::Merge(ref fe) => Some(Kind::Merge(fe.clone())),
+        usvg::filter::Kind::SpecularLighting(ref fe) => Some(Kind::SpecularLighting(fe.clone())),
+        usvg::filter::Kind::Tile(ref fe) => Some(Kind::Tile(fe.clone())),
+        usvg::filter::Kind::Turbulence(ref fe) => Some(Kind::Turbulence(*fe)),
+    }
+}
+
+fn convert_image(fe: &usvg::filter::Image) -> ImagePrimitive {
+    let data = match fe.data {
+        usvg::filter::ImageKind::Image(ref kind) => {
+            crate::image::convert_kind(kind).map(ImagePrimitiveKind::Image)
+        }
+        usvg::filter::ImageKind::Use(ref node) => {
+            crate::Tree::from_usvg_node(node).map(|mut rtree| {
+                rtree.view_box.rect = rtree.view_box.rect.translate_to(0.0, 0.0).unwrap();
+                ImagePrimitiveKind::Use(Box::new(rtree))
+            })
+        }
+    };
+
+    ImagePrimitive {
+        aspect: fe.aspect,
+        quality: crate::image::convert_quality(fe.rendering_mode),
+        data,
     }
 }
 
@@ -220,7 +282,7 @@ pub(crate) enum Error {
     NoResults,
 }
 
-trait PixmapExt: Sized {
+pub(crate) trait PixmapExt: Sized {
     fn try_create(width: u32, height: u32) -> Result<tiny_skia::Pixmap, Error>;
     fn copy_region(&self, region: IntRect) -> Result<tiny_skia::Pixmap, Error>;
     fn clear(&mut self);
@@ -347,7 +409,7 @@ const LINEAR_RGB_TO_SRGB_TABLE: &[u8; 256] = &[
 /// Provided pixels should have an **unpremultiplied alpha**.
 ///
 /// RGB channels order of the input image doesn't matter, but alpha channel must be the last one.
-fn into_linear_rgb(data: &mut [RGBA8]) {
+pub(crate) fn into_linear_rgb(data: &mut [RGBA8]) {
     for p in data {
         p.r = SRGB_TO_LINEAR_RGB_TABLE[p.r as usize];
         p.g = SRGB_TO_LINEAR_RGB_TABLE[p.g as usize];
@@ -360,7 +422,7 @@ fn into_linear_rgb(data: &mut [RGBA8]) {
 /// Provided pixels should have an **unpremultiplied alpha**.
 ///
 /// RGB channels order of the input image doesn't matter, but alpha channel must be the last one.
-fn from_linear_rgb(data: &mut [RGBA8]) {
+pub(crate) fn from_linear_rgb(data: &mut [RGBA8]) {
     for p in data {
         p.r = LINEAR_RGB_TO_SRGB_TABLE[p.r as usize];
         p.g = LINEAR_RGB_TO_SRGB_TABLE[p.g as usize];
@@ -503,7 +565,7 @@ fn apply_inner(
             .ok_or(Error::InvalidRegion)?;
 
         // `feOffset` inherits its region from the input.
-        if let usvg::filter::Kind::Offset(ref fe) = primitive.kind {
+        if let Kind::Offset(ref fe) = primitive.kind {
             if let usvg::filter::Input::Reference(ref name) = fe.input {
                 if let Some(res) = results.iter().rev().find(|v| v.name == *name) {
                     subregion = res.image.region;
@@ -512,62 +574,62 @@ fn apply_inner(
         }
 
         let mut result = match primitive.kind {
-            usvg::filter::Kind::Blend(ref fe) => {
+            Kind::Blend(ref fe) => {
                 let input1 = get_input(&fe.input1, region, source, &results)?;
                 let input2 = get_input(&fe.input2, region, source, &results)?;
                 apply_blend(fe, cs, region, input1, input2)
             }
-            usvg::filter::Kind::DropShadow(ref fe) => {
+            Kind::DropShadow(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_drop_shadow(fe, cs, ts, input)
             }
-            usvg::filter::Kind::Flood(ref fe) => apply_flood(fe, region),
-            usvg::filter::Kind::GaussianBlur(ref fe) => {
+            Kind::Flood(ref fe) => apply_flood(fe, region),
+            Kind::GaussianBlur(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_blur(fe, cs, ts, input)
             }
-            usvg::filter::Kind::Offset(ref fe) => {
+            Kind::Offset(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_offset(fe, ts, input)
             }
-            usvg::filter::Kind::Composite(ref fe) => {
+            Kind::Composite(ref fe) => {
                 let input1 = get_input(&fe.input1, region, source, &results)?;
                 let input2 = get_input(&fe.input2, region, source, &results)?;
                 apply_composite(fe, cs, region, input1, input2)
             }
-            usvg::filter::Kind::Merge(ref fe) => apply_merge(fe, cs, region, source, &results),
-            usvg::filter::Kind::Tile(ref fe) => {
+            Kind::Merge(ref fe) => apply_merge(fe, cs, region, source, &results),
+            Kind::Tile(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_tile(input, region)
             }
-            usvg::filter::Kind::Image(ref fe) => apply_image(fe, region, subregion, ts),
-            usvg::filter::Kind::ComponentTransfer(ref fe) => {
+            Kind::Image(ref fe) => apply_image(fe, region, subregion, ts),
+            Kind::ComponentTransfer(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_component_transfer(fe, cs, input)
             }
-            usvg::filter::Kind::ColorMatrix(ref fe) => {
+            Kind::ColorMatrix(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_color_matrix(fe, cs, input)
             }
-            usvg::filter::Kind::ConvolveMatrix(ref fe) => {
+            Kind::ConvolveMatrix(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_convolve_matrix(fe, cs, input)
             }
-            usvg::filter::Kind::Morphology(ref fe) => {
+            Kind::Morphology(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_morphology(fe, cs, ts, input)
             }
-            usvg::filter::Kind::DisplacementMap(ref fe) => {
+            Kind::DisplacementMap(ref fe) => {
                 let input1 = get_input(&fe.input1, region, source, &results)?;
                 let input2 = get_input(&fe.input2, region, source, &results)?;
                 apply_displacement_map(fe, region, cs, ts, input1, input2)
             }
-            usvg::filter::Kind::Turbulence(ref fe) => apply_turbulence(fe, region, cs, ts),
-            usvg::filter::Kind::DiffuseLighting(ref fe) => {
+            Kind::Turbulence(ref fe) => apply_turbulence(fe, region, cs, ts),
+            Kind::DiffuseLighting(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_diffuse_lighting(fe, region, cs, ts, input)
             }
-            usvg::filter::Kind::SpecularLighting(ref fe) => {
+            Kind::SpecularLighting(ref fe) => {
                 let input = get_input(&fe.input, region, source, &results)?;
                 apply_specular_lighting(fe, region, cs, ts, input)
             }
@@ -577,7 +639,7 @@ fn apply_inner(
             // Clip result.
 
             // TODO: explain
-            let subregion2 = if let usvg::filter::Kind::Offset(..) = primitive.kind {
+            let subregion2 = if let Kind::Offset(..) = primitive.kind {
                 // We do not support clipping on feOffset.
                 region.translate_to(0, 0)
             } else {
@@ -1062,7 +1124,7 @@ fn apply_tile(input: Image, region: IntRect) -> Result<Image, Error> {
 }
 
 fn apply_image(
-    fe: &usvg::filter::Image,
+    fe: &ImagePrimitive,
     region: IntRect,
     subregion: IntRect,
     ts: usvg::Transform,
@@ -1070,7 +1132,7 @@ fn apply_image(
     let mut pixmap = tiny_skia::Pixmap::try_create(region.width(), region.height())?;
 
     match fe.data {
-        usvg::filter::ImageKind::Image(ref kind) => {
+        Some(ImagePrimitiveKind::Image(ref kind)) => {
             let dx = (subregion.x() - region.x()) as f32;
             let dy = (subregion.y() - region.y()) as f32;
             let transform = tiny_skia::Transform::from_translate(dx, dy);
@@ -1085,29 +1147,20 @@ fn apply_image(
                 aspect: fe.aspect,
             };
 
-            let uimage = usvg::Image {
-                id: String::new(),
-                visibility: usvg::Visibility::Visible,
-                view_box,
-                rendering_mode: fe.rendering_mode,
-                kind: kind.clone(),
-            };
-
-            let mut children = Vec::new();
-            crate::image::convert(&uimage, &mut children);
-            if let Some(Node::Image(image)) = children.first() {
-                crate::image::render_image(image, transform, &mut pixmap.as_mut());
-            }
+            crate::image::render_image_kind(
+                kind,
+                &view_box,
+                fe.quality,
+                transform,
+                &mut pixmap.as_mut(),
+            );
         }
-        usvg::filter::ImageKind::Use(ref node) => {
+        Some(ImagePrimitiveKind::Use(ref rtree)) => {
             let (sx, sy) = ts.get_scale();
             let transform = tiny_skia::Transform::from_scale(sx, sy);
-
-            if let Some(mut rtree) = crate::Tree::from_usvg_node(node) {
-                rtree.view_box.rect = rtree.view_box.rect.translate_to(0.0, 0.0).unwrap();
-                rtree.render(transform, &mut pixmap.as_mut());
-            }
+            rtree.render(transform, &mut pixmap.as_mut());
         }
+        None => {}
     }
 
     Ok(Image::from_image(
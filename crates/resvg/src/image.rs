@@ -22,61 +22,86 @@ pub fn convert(image: &usvg::Image, children: &mut Vec<Node>) -> Option<BBoxes>
     let bboxes = BBoxes {
         object: usvg::BBox::from(object_bbox),
         layer: usvg::BBox::from(object_bbox),
+        canvas_padding: 0.0,
     };
 
     if image.visibility != usvg::Visibility::Visible {
         return Some(bboxes);
     }
 
-    let mut quality = tiny_skia::FilterQuality::Bicubic;
-    if image.rendering_mode == usvg::ImageRendering::OptimizeSpeed {
-        quality = tiny_skia::FilterQuality::Nearest;
-    }
-
-    let kind = match image.kind {
-        usvg::ImageKind::SVG(ref utree) => ImageKind::Vector(Tree::from_usvg(utree)),
-        #[cfg(feature = "raster-images")]
-        _ => ImageKind::Raster(raster_images::decode_raster(image)?),
-        #[cfg(not(feature = "raster-images"))]
-        _ => {
-            log::warn!("Images decoding was disabled by a build feature.");
-            return None;
-        }
-    };
+    let kind = convert_kind(&image.kind)?;
 
     children.push(Node::Image(Image {
         view_box: image.view_box,
-        quality,
+        quality: convert_quality(image.rendering_mode),
         kind,
     }));
 
     Some(bboxes)
 }
 
+pub fn convert_kind(kind: &usvg::ImageKind) -> Option<ImageKind> {
+    match kind {
+        usvg::ImageKind::SVG(ref utree) => Some(ImageKind::Vector(Tree::from_usvg(utree))),
+        #[cfg(feature = "raster-images")]
+        _ => Some(ImageKind::Raster(raster_images::decode_raster(kind)?)),
+        #[cfg(not(feature = "raster-images"))]
+        _ => {
+            log::warn!("Images decoding was disabled by a build feature.");
+            None
+        }
+    }
+}
+
+pub fn convert_quality(mode: usvg::ImageRendering) -> tiny_skia::FilterQuality {
+    if mode == usvg::ImageRendering::OptimizeSpeed {
+        tiny_skia::FilterQuality::Nearest
+    } else {
+        tiny_skia::FilterQuality::Bicubic
+    }
+}
+
 pub fn render_image(
     image: &Image,
     transform: tiny_skia::Transform,
     pixmap: &mut tiny_skia::PixmapMut,
 ) {
-    match image.kind {
+    render_image_kind(
+        &image.kind,
+        &image.view_box,
+        image.quality,
+        transform,
+        pixmap,
+    );
+}
+
+#[cfg_attr(not(feature = "raster-images"), allow(unused_variables))]
+pub fn render_image_kind(
+    kind: &ImageKind,
+    view_box: &usvg::ViewBox,
+    quality: tiny_skia::FilterQuality,
+    transform: tiny_skia::Transform,
+    pixmap: &mut tiny_skia::PixmapMut,
+) {
+    match kind {
         #[cfg(feature = "raster-images")]
         ImageKind::Raster(ref raster) => {
-            raster_images::render_raster(image, raster, transform, pixmap);
+            raster_images::render_raster(view_box, quality, raster, transform, pixmap);
         }
         ImageKind::Vector(ref rtree) => {
-            render_vector(image, rtree, transform, pixmap);
+            render_vector(view_box, rtree, transform, pixmap);
         }
     }
 }
 
 fn render_vector(
-    image: &Image,
+    view_box: &usvg::ViewBox,
     tree: &Tree,
     transform: tiny_skia::Transform,
     pixmap: &mut tiny_skia::PixmapMut,
 ) -> Option<()> {
     let img_size = tree.size.to_int_size();
-    let (ts, clip) = crate::geom::view_box_to_transform_with_clip(&image.view_box, img_size);
+    let (ts, clip) = crate::geom::view_box_to_transform_with_clip(view_box, img_size);
 
     let mut sub_pixmap = tiny_skia::Pixmap::new(pixmap.width(), pixmap.height()).unwrap();
 
@@ -104,13 +129,12 @@ fn render_vector(
 }
 
 #[cfg(feature = "raster-images")]
-mod raster_images {
-    use super::Image;
+pub mod raster_images {
     use crate::render::TinySkiaPixmapMutExt;
     use crate::tree::OptionLog;
 
-    pub fn decode_raster(image: &usvg::Image) -> Option<tiny_skia::Pixmap> {
-        match image.kind {
+    pub fn decode_raster(kind: &usvg::ImageKind) -> Option<tiny_skia::Pixmap> {
+        match kind {
             usvg::ImageKind::SVG(_) => None,
             usvg::ImageKind::JPEG(ref data) => {
                 decode_jpeg(data).log_none(|| log::warn!("Failed to decode a JPEG image."))
@@ -205,13 +229,14 @@ mod raster_images {
     }
 
     pub(crate) fn render_raster(
-        image: &Image,
+        view_box: &usvg::ViewBox,
+        quality: tiny_skia::FilterQuality,
         raster: &tiny_skia::Pixmap,
         transform: tiny_skia::Transform,
         pixmap: &mut tiny_skia::PixmapMut,
     ) -> Option<()> {
         let img_size = tiny_skia::IntSize::from_wh(raster.width(), raster.height())?;
-        let rect = image_rect(&image.view_box, img_size);
+        let rect = image_rect(view_box, img_size);
 
         let ts = tiny_skia::Transform::from_row(
             rect.width() / raster.width() as f32,
@@ -225,15 +250,15 @@ mod raster_images {
         let pattern = tiny_skia::Pattern::new(
             raster.as_ref(),
             tiny_skia::SpreadMode::Pad,
-            image.quality,
+            quality,
             1.0,
             ts,
         );
         let mut paint = tiny_skia::Paint::default();
         paint.shader = pattern;
 
-        let mask = if image.view_box.aspect.slice {
-            pixmap.create_rect_mask(transform, image.view_box.rect.to_rect())
+        let mask = if view_box.aspect.slice {
+            pixmap.create_rect_mask(transform, view_box.rect.to_rect())
         } else {
             None
         };
@@ -244,7 +269,7 @@ mod raster_images {
     }
 
     /// Calculates an image rect depending on the provided view box.
-    fn image_rect(
+    pub fn image_rect(
         view_box: &usvg::ViewBox,
         img_size: tiny_skia::IntSize,
     ) -> tiny_skia::NonZeroRect {
@@ -25,7 +25,10 @@ mod image;
 mod mask;
 mod paint_server;
 mod path;
+#[cfg(feature = "pdf")]
+mod pdf;
 mod render;
+mod stroke;
 mod tree;
 
 pub use crate::tree::Tree;
@@ -85,10 +85,11 @@ fn process() -> Result<(), String> {
     })?;
 
     // fontdb initialization is pretty expensive, so perform it only when needed.
+    let mut fontdb = None;
     if tree.has_text_nodes() {
-        let fontdb = timed(args.perf, "FontDB", || load_fonts(&mut args));
+        let db = timed(args.perf, "FontDB", || load_fonts(&mut args));
         if args.list_fonts {
-            for face in fontdb.faces() {
+            for face in db.faces() {
                 if let fontdb::Source::File(ref path) = &face.source {
                     let families: Vec<_> = face
                         .families
@@ -109,13 +110,30 @@ fn process() -> Result<(), String> {
             }
         }
 
-        timed(args.perf, "Text Conversion", || tree.convert_text(&fontdb));
+        timed(args.perf, "Text Conversion", || {
+            tree.convert_text_with_svg_glyphs(&db, &usvg::parse_svg_glyph, None)
+        });
+        fontdb = Some(db);
     }
 
     if args.query_all {
         return query_all(&tree);
     }
 
+    if let Some(frames) = args.frames {
+        return render_animation(&args, &tree, fontdb.as_ref(), frames);
+    }
+
+    if let Some(OutputTo::File(ref file)) = args.out_png {
+        if is_pdf_path(file) {
+            let data = render_svg_pdf(&args, &tree)?;
+            timed(args.perf, "Saving", || {
+                std::fs::write(file, &data).map_err(|e| e.to_string())
+            })?;
+            return Ok(());
+        }
+    }
+
     // Render.
     let img = render_svg(&args, &tree)?;
 
@@ -146,7 +164,9 @@ USAGE:
 
   resvg in.svg out.png
   resvg -z 4 in.svg out.png
+  resvg in.svg out.pdf
   resvg --query-all in.svg
+  resvg --frames 30 --fps 10 in.svg out.png
 
 OPTIONS:
       --help                    Prints this help
@@ -182,6 +202,17 @@ OPTIONS:
                                 Expected to be the same as the directory that
                                 contains the SVG file, but can be set to any.
                                 [default: input file directory]
+  --style-sheet PATH            Applies a user style sheet.
+                                Normal declarations are overridden by the SVG styles,
+                                while '!important' ones override everything
+  --color-scheme SCHEME         Sets the preferred color scheme that will be used
+                                during the 'prefers-color-scheme' media queries
+                                resolving
+                                [default: light] [possible values: light, dark]
+  --current-color COLOR         Sets the initial 'color' property value,
+                                which is used by 'currentColor'
+                                Examples: red, #fff, #fff000
+                                [default: black]
 
   --font-family FAMILY          Sets the default font family that will be
                                 used when no 'font-family' is present
@@ -221,12 +252,20 @@ OPTIONS:
   --export-area-drawing         Use drawing's tight bounding box instead of image size.
                                 Used during normal rendering and not during --export-id
 
+  --frames N                    Renders N frames of an SVG animation into
+                                an animated PNG or, when the output file
+                                has a '.gif' extension, into an animated GIF
+  --fps FPS                     Sets the animation frame rate
+                                [default: 10]
+
   --perf                        Prints performance stats
   --quiet                       Disables warnings
 
 ARGS:
   <in-svg>                      Input file
   <out-png>                     Output file
+                                A PDF document will be created when the file
+                                has a '.pdf' extension
 ";
 
 #[derive(Debug)]
@@ -242,6 +281,9 @@ struct CliArgs {
     text_rendering: usvg::TextRendering,
     image_rendering: usvg::ImageRendering,
     resources_dir: Option<path::PathBuf>,
+    style_sheet: Option<path::PathBuf>,
+    color_scheme: usvg::ColorScheme,
+    current_color: Option<svgtypes::Color>,
 
     font_family: Option<String>,
     font_size: u32,
@@ -261,6 +303,9 @@ struct CliArgs {
 
     export_area_drawing: bool,
 
+    frames: Option<u32>,
+    fps: f32,
+
     perf: bool,
     quiet: bool,
 
@@ -303,6 +348,11 @@ fn collect_args() -> Result<CliArgs, pico_args::Error> {
         resources_dir: input
             .opt_value_from_str("--resources-dir")
             .unwrap_or_default(),
+        style_sheet: input.opt_value_from_str("--style-sheet")?,
+        color_scheme: input
+            .opt_value_from_fn("--color-scheme", parse_color_scheme)?
+            .unwrap_or_default(),
+        current_color: input.opt_value_from_str("--current-color")?,
 
         font_family: input.opt_value_from_str("--font-family")?,
         font_size: input
@@ -324,6 +374,9 @@ fn collect_args() -> Result<CliArgs, pico_args::Error> {
 
         export_area_drawing: input.contains("--export-area-drawing"),
 
+        frames: input.opt_value_from_fn("--frames", parse_frames)?,
+        fps: input.opt_value_from_fn("--fps", parse_fps)?.unwrap_or(10.0),
+
         perf: input.contains("--perf"),
         quiet: input.contains("--quiet"),
 
@@ -362,6 +415,26 @@ fn parse_zoom(s: &str) -> Result<f32, String> {
     }
 }
 
+fn parse_frames(s: &str) -> Result<u32, String> {
+    let n: u32 = s.parse().map_err(|_| "invalid number")?;
+
+    if n > 0 {
+        Ok(n)
+    } else {
+        Err("frames count cannot be zero".to_string())
+    }
+}
+
+fn parse_fps(s: &str) -> Result<f32, String> {
+    let n: f32 = s.parse().map_err(|_| "invalid frame rate")?;
+
+    if n > 0.0 && n <= 1000.0 {
+        Ok(n)
+    } else {
+        Err("FPS out of bounds".to_string())
+    }
+}
+
 fn parse_font_size(s: &str) -> Result<u32, String> {
     let n: u32 = s.parse().map_err(|_| "invalid number")?;
 
@@ -385,6 +458,14 @@ fn parse_languages(s: &str) -> Result<Vec<String>, String> {
     Ok(langs)
 }
 
+fn parse_color_scheme(s: &str) -> Result<usvg::ColorScheme, String> {
+    match s {
+        "light" => Ok(usvg::ColorScheme::Light),
+        "dark" => Ok(usvg::ColorScheme::Dark),
+        _ => Err("invalid color scheme".to_string()),
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 enum InputFrom {
     Stdin,
@@ -442,6 +523,8 @@ struct Args {
     export_id: Option<String>,
     export_area_page: bool,
     export_area_drawing: bool,
+    frames: Option<u32>,
+    fps: f32,
     perf: bool,
     quiet: bool,
     usvg: usvg::Options,
@@ -494,6 +577,12 @@ fn parse_args() -> Result<Args, String> {
         println!("Warning: Make sure to set --resources-dir when reading SVG from stdin.");
     }
 
+    if let (Some(OutputTo::File(ref file)), Some(_)) = (&out_png, args.background) {
+        if is_pdf_path(file) {
+            println!("Warning: --background is not supported for PDF output.");
+        }
+    }
+
     if args.export_area_page && args.export_id.is_none() {
         println!("Warning: --export-area-page has no effect without --export-id.");
     }
@@ -502,6 +591,18 @@ fn parse_args() -> Result<Args, String> {
         println!("Warning: --export-area-drawing has no effect when --export-id is set.");
     }
 
+    if args.frames.is_some() {
+        if let Some(OutputTo::File(ref file)) = out_png {
+            if is_pdf_path(file) {
+                return Err("animations cannot be saved as PDF".to_string());
+            }
+        }
+
+        if args.export_area_drawing {
+            return Err("--export-area-drawing cannot be used with --frames".to_string());
+        }
+    }
+
     let export_id = args.export_id.as_ref().map(|v| v.to_string());
 
     let mut fit_to = FitTo::Original;
@@ -530,6 +631,14 @@ fn parse_args() -> Result<Args, String> {
         None => None,
     };
 
+    let style_sheet = match args.style_sheet {
+        Some(ref path) => Some(
+            std::fs::read_to_string(path)
+                .map_err(|_| "failed to read the style sheet".to_string())?,
+        ),
+        None => None,
+    };
+
     let usvg = usvg::Options {
         resources_dir,
         dpi: args.dpi as f32,
@@ -544,6 +653,12 @@ fn parse_args() -> Result<Args, String> {
         image_rendering: args.image_rendering,
         default_size,
         image_href_resolver: usvg::ImageHrefResolver::default(),
+        use_href_resolver: usvg::UseHrefResolver::default(),
+        style_sheet,
+        color_scheme: args.color_scheme,
+        current_color: args
+            .current_color
+            .map(|c| usvg::Color::new_rgb(c.red, c.green, c.blue)),
     };
 
     Ok(Args {
@@ -553,6 +668,8 @@ fn parse_args() -> Result<Args, String> {
         export_id,
         export_area_page: args.export_area_page,
         export_area_drawing: args.export_area_drawing,
+        frames: args.frames,
+        fps: args.fps,
         perf: args.perf,
         quiet: args.quiet,
         usvg,
@@ -725,6 +842,230 @@ fn render_svg(args: &Args, tree: &usvg::Tree) -> Result<tiny_skia::Pixmap, Strin
     Ok(img)
 }
 
+fn render_animation(
+    args: &Args,
+    tree: &usvg::Tree,
+    fontdb: Option<&fontdb::Database>,
+    frames_count: u32,
+) -> Result<(), String> {
+    if tree.animation.is_none() {
+        println!("Warning: SVG doesn't have any animations.");
+    }
+
+    let mut frames = Vec::new();
+    for i in 0..frames_count {
+        let mut frame = tree.at_time(i as f32 / args.fps);
+        if let Some(fontdb) = fontdb {
+            frame.convert_text_with_svg_glyphs(fontdb, &usvg::parse_svg_glyph, None);
+        }
+
+        frames.push(render_svg(args, &frame)?);
+    }
+
+    let is_gif = match args.out_png {
+        Some(OutputTo::File(ref file)) => is_gif_path(file),
+        _ => false,
+    };
+
+    let data = timed(args.perf, "Encoding", || {
+        if is_gif {
+            encode_gif(&frames, args.fps)
+        } else {
+            encode_apng(&frames, args.fps)
+        }
+    })?;
+
+    match args.out_png {
+        Some(OutputTo::File(ref file)) => timed(args.perf, "Saving", || {
+            std::fs::write(file, &data).map_err(|e| e.to_string())
+        })?,
+        _ => {
+            use std::io::Write;
+            std::io::stdout().write_all(&data).unwrap();
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns frames size, which must be the same for all frames.
+#[cfg(feature = "raster-images")]
+fn frames_size(frames: &[tiny_skia::Pixmap]) -> Result<(u32, u32), String> {
+    let first = &frames[0];
+    if frames
+        .iter()
+        .any(|f| f.width() != first.width() || f.height() != first.height())
+    {
+        return Err("animation frames have different sizes".to_string());
+    }
+
+    Ok((first.width(), first.height()))
+}
+
+/// Converts a pixmap into a non-premultiplied RGBA buffer.
+#[cfg(feature = "raster-images")]
+fn demultiply_pixmap(pixmap: &tiny_skia::Pixmap) -> Vec<u8> {
+    let mut data = Vec::with_capacity(pixmap.data().len());
+    for pixel in pixmap.pixels() {
+        let c = pixel.demultiply();
+        data.extend_from_slice(&[c.red(), c.green(), c.blue(), c.alpha()]);
+    }
+
+    data
+}
+
+#[cfg(feature = "raster-images")]
+fn encode_apng(frames: &[tiny_skia::Pixmap], fps: f32) -> Result<Vec<u8>, String> {
+    let (width, height) = frames_size(frames)?;
+
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(frames.len() as u32, 0)
+        .map_err(|e| e.to_string())?;
+    let delay = (1000.0 / fps).round().clamp(1.0, u16::MAX as f32) as u16;
+    encoder
+        .set_frame_delay(delay, 1000)
+        .map_err(|e| e.to_string())?;
+
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    for frame in frames {
+        writer
+            .write_image_data(&demultiply_pixmap(frame))
+            .map_err(|e| e.to_string())?;
+    }
+    writer.finish().map_err(|e| e.to_string())?;
+
+    Ok(data)
+}
+
+#[cfg(not(feature = "raster-images"))]
+fn encode_apng(_: &[tiny_skia::Pixmap], _: f32) -> Result<Vec<u8>, String> {
+    Err("APNG output requires the `raster-images` feature".to_string())
+}
+
+#[cfg(feature = "raster-images")]
+fn encode_gif(frames: &[tiny_skia::Pixmap], fps: f32) -> Result<Vec<u8>, String> {
+    let (width, height) = frames_size(frames)?;
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err("image is too big for GIF".to_string());
+    }
+
+    let mut data = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut data, width as u16, height as u16, &[])
+            .map_err(|e| e.to_string())?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| e.to_string())?;
+
+        // GIF delays are in hundredths of a second.
+        let delay = (100.0 / fps).round().clamp(1.0, u16::MAX as f32) as u16;
+        for frame in frames {
+            let mut rgba = demultiply_pixmap(frame);
+            let mut frame = gif::Frame::from_rgba_speed(width as u16, height as u16, &mut rgba, 10);
+            frame.delay = delay;
+            frame.dispose = gif::DisposalMethod::Background;
+            encoder.write_frame(&frame).map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(data)
+}
+
+#[cfg(not(feature = "raster-images"))]
+fn encode_gif(_: &[tiny_skia::Pixmap], _: f32) -> Result<Vec<u8>, String> {
+    Err("GIF output requires the `raster-images` feature".to_string())
+}
+
+fn is_gif_path(path: &path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("gif"))
+        .unwrap_or(false)
+}
+
+fn is_pdf_path(path: &path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("pdf"))
+        .unwrap_or(false)
+}
+
+#[cfg(feature = "pdf")]
+fn render_svg_pdf(args: &Args, tree: &usvg::Tree) -> Result<Vec<u8>, String> {
+    let now = std::time::Instant::now();
+
+    let ts = args.fit_to.fit_to_transform(tree.size.to_int_size());
+
+    let data = if let Some(ref id) = args.export_id {
+        let node = match tree.root.descendants().find(|n| &*n.id() == id) {
+            Some(node) => node,
+            None => return Err(format!("SVG doesn't have '{}' ID", id)),
+        };
+
+        let bbox = node
+            .calculate_bbox()
+            .and_then(|r| r.to_non_zero_rect())
+            .ok_or_else(|| "node has zero size".to_string())?;
+
+        let rtree = resvg::Tree::from_usvg_node(&node)
+            .ok_or_else(|| "zero-size node detected".to_string())?;
+
+        if args.export_area_page {
+            let size = args
+                .fit_to
+                .fit_to_size(tree.size.to_int_size())
+                .ok_or_else(|| "target size is zero".to_string())?;
+
+            let ts = tiny_skia::Transform::from_translate(bbox.x(), bbox.y()).pre_concat(ts);
+            rtree.render_pdf(ts, size.to_size())
+        } else {
+            let size = args
+                .fit_to
+                .fit_to_size(bbox.size().to_int_size())
+                .ok_or_else(|| "target size is zero".to_string())?;
+
+            rtree.render_pdf(ts, size.to_size())
+        }
+    } else {
+        let size = args
+            .fit_to
+            .fit_to_size(tree.size.to_int_size())
+            .ok_or_else(|| "target size is zero".to_string())?;
+
+        let rtree = resvg::Tree::from_usvg(tree);
+
+        let content_area = if args.export_area_drawing {
+            rtree.content_area.and_then(|r| r.transform(ts))
+        } else {
+            None
+        };
+
+        match content_area.and_then(|r| r.to_non_zero_rect()) {
+            Some(r) => {
+                let ts = tiny_skia::Transform::from_translate(-r.x(), -r.y()).pre_concat(ts);
+                rtree.render_pdf(ts, r.size())
+            }
+            None => rtree.render_pdf(ts, size.to_size()),
+        }
+    };
+
+    if args.perf {
+        let elapsed = now.elapsed().as_micros() as f64 / 1000.0;
+        println!("Rendering: {:.2}ms", elapsed);
+    }
+
+    Ok(data)
+}
+
+#[cfg(not(feature = "pdf"))]
+fn render_svg_pdf(_: &Args, _: &usvg::Tree) -> Result<Vec<u8>, String> {
+    Err("PDF output requires the `pdf` feature".to_string())
+}
+
 fn trim_pixmap(
     rtree: &resvg::Tree,
     transform: tiny_skia::Transform,
@@ -2,23 +2,80 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::rc::Rc;
-
-use crate::render::Context;
+use crate::render::{Context, TinySkiaPixmapMutExt};
 use crate::tree::{Node, OptionLog};
 
+pub struct MaskLayer {
+    pub source: MaskSource,
+    pub kind: tiny_skia::MaskType,
+    pub composite: usvg::MaskComposite,
+}
+
+pub enum MaskSource {
+    Mask(Mask),
+    Image(MaskImage),
+}
+
 pub struct Mask {
     pub mask_all: bool,
     pub region: tiny_skia::Rect,
     pub content_transform: tiny_skia::Transform,
-    pub kind: usvg::MaskType,
-    pub mask: Option<Box<Self>>,
+    pub masks: Vec<MaskLayer>,
     pub children: Vec<Node>,
 }
 
-pub fn convert(umask: Option<Rc<usvg::Mask>>, object_bbox: tiny_skia::Rect) -> Option<Mask> {
-    let umask = umask?;
+pub struct MaskImage {
+    pub kind: crate::image::ImageKind,
+    /// The first image tile.
+    pub rect: tiny_skia::NonZeroRect,
+    /// Mask painting area. Image tiles are clipped by it.
+    pub region: tiny_skia::Rect,
+    pub repeat: usvg::MaskRepeat,
+}
+
+pub fn convert(ulayers: &[usvg::MaskLayer], object_bbox: tiny_skia::Rect) -> Vec<MaskLayer> {
+    let mut layers = Vec::new();
+    for ulayer in ulayers {
+        let (source, source_kind) = match ulayer.source {
+            usvg::MaskSource::Mask(ref umask) => {
+                let mask = match convert_mask(umask, object_bbox) {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                (MaskSource::Mask(mask), umask.kind)
+            }
+            usvg::MaskSource::Image(ref ukind) => {
+                let image = match convert_image(ukind, ulayer, object_bbox) {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                // Images are always alpha masks by default.
+                (MaskSource::Image(image), usvg::MaskType::Alpha)
+            }
+        };
+
+        let kind = match (ulayer.mode, source_kind) {
+            (usvg::MaskMode::Alpha, _) => tiny_skia::MaskType::Alpha,
+            (usvg::MaskMode::Luminance, _) => tiny_skia::MaskType::Luminance,
+            (usvg::MaskMode::MatchSource, usvg::MaskType::Alpha) => tiny_skia::MaskType::Alpha,
+            (usvg::MaskMode::MatchSource, usvg::MaskType::Luminance) => {
+                tiny_skia::MaskType::Luminance
+            }
+        };
 
+        layers.push(MaskLayer {
+            source,
+            kind,
+            composite: ulayer.composite,
+        });
+    }
+
+    layers
+}
+
+fn convert_mask(umask: &usvg::Mask, object_bbox: tiny_skia::Rect) -> Option<Mask> {
     let mut content_transform = tiny_skia::Transform::default();
     if umask.content_units == usvg::Units::ObjectBoundingBox {
         let object_bbox = object_bbox
@@ -52,29 +109,177 @@ pub fn convert(umask: Option<Rc<usvg::Mask>>, object_bbox: tiny_skia::Rect) -> O
         mask_all,
         region: region.to_rect(),
         content_transform,
-        kind: umask.kind,
-        mask: convert(umask.mask.clone(), object_bbox).map(Box::new),
+        masks: convert(&umask.masks, object_bbox),
         children,
     })
 }
 
+fn convert_image(
+    ukind: &usvg::ImageKind,
+    ulayer: &usvg::MaskLayer,
+    object_bbox: tiny_skia::Rect,
+) -> Option<MaskImage> {
+    let bbox = object_bbox
+        .to_non_zero_rect()
+        .log_none(|| log::warn!("Masking of zero-sized shapes is not allowed."))?;
+
+    let kind = crate::image::convert_kind(ukind)?;
+    let image_size = match kind {
+        #[cfg(feature = "raster-images")]
+        crate::image::ImageKind::Raster(ref pixmap) => {
+            usvg::Size::from_wh(pixmap.width() as f32, pixmap.height() as f32)?
+        }
+        crate::image::ImageKind::Vector(ref tree) => tree.size,
+    };
+
+    let resolve = |length: usvg::MaskLength, side: f32| match length {
+        usvg::MaskLength::Absolute(n) => n,
+        usvg::MaskLength::Relative(n) => n * side,
+    };
+
+    let (iw, ih) = (image_size.width(), image_size.height());
+    let (width, height) = match ulayer.size {
+        usvg::MaskSize::Cover => {
+            let scale = f32::max(bbox.width() / iw, bbox.height() / ih);
+            (iw * scale, ih * scale)
+        }
+        usvg::MaskSize::Contain => {
+            let scale = f32::min(bbox.width() / iw, bbox.height() / ih);
+            (iw * scale, ih * scale)
+        }
+        usvg::MaskSize::Explicit { width, height } => {
+            let width = width.map(|w| resolve(w, bbox.width()));
+            let height = height.map(|h| resolve(h, bbox.height()));
+            match (width, height) {
+                (Some(w), Some(h)) => (w, h),
+                (Some(w), None) => (w, w * ih / iw),
+                (None, Some(h)) => (h * iw / ih, h),
+                (None, None) => (iw, ih),
+            }
+        }
+    };
+
+    // Percentages align the same point of the image and of the painting area.
+    let position = |length: usvg::MaskLength, side: f32, image_side: f32| match length {
+        usvg::MaskLength::Absolute(n) => n,
+        usvg::MaskLength::Relative(n) => (side - image_side) * n,
+    };
+
+    let rect = tiny_skia::NonZeroRect::from_xywh(
+        bbox.x() + position(ulayer.position.x, bbox.width(), width),
+        bbox.y() + position(ulayer.position.y, bbox.height(), height),
+        width,
+        height,
+    )
+    .log_none(|| log::warn!("Mask image has an invalid size. Skipped."))?;
+
+    Some(MaskImage {
+        kind,
+        rect,
+        region: object_bbox,
+        repeat: ulayer.repeat,
+    })
+}
+
 pub fn apply(
-    mask: &Mask,
+    layers: &[MaskLayer],
     ctx: &Context,
     transform: tiny_skia::Transform,
     pixmap: &mut tiny_skia::Pixmap,
 ) {
-    if mask.mask_all {
-        pixmap.fill(tiny_skia::Color::TRANSPARENT);
+    // A single mask doesn't require compositing,
+    // therefore its nested masks can be applied to the content directly.
+    if let [MaskLayer {
+        source: MaskSource::Mask(ref mask),
+        kind,
+        ..
+    }] = *layers
+    {
+        if mask.mask_all {
+            pixmap.fill(tiny_skia::Color::TRANSPARENT);
+            return;
+        }
+
+        self::apply(&mask.masks, ctx, transform, pixmap);
+
+        let mask = render_mask_content(mask, kind, ctx, transform, pixmap.width(), pixmap.height());
+        pixmap.apply_mask(&mask);
         return;
     }
 
-    let mut mask_pixmap = tiny_skia::Pixmap::new(pixmap.width(), pixmap.height()).unwrap();
+    if let Some(mask) = render_layers(layers, ctx, transform, pixmap.width(), pixmap.height()) {
+        pixmap.apply_mask(&mask);
+    }
+}
+
+fn render_layers(
+    layers: &[MaskLayer],
+    ctx: &Context,
+    transform: tiny_skia::Transform,
+    width: u32,
+    height: u32,
+) -> Option<tiny_skia::Mask> {
+    // Layers are composited starting from the bottom one, which is the last one.
+    let mut result: Option<tiny_skia::Mask> = None;
+    for layer in layers.iter().rev() {
+        let mask = match layer.source {
+            MaskSource::Mask(ref mask) => {
+                render_mask(mask, layer.kind, ctx, transform, width, height)
+            }
+            MaskSource::Image(ref image) => {
+                render_image(image, layer.kind, transform, width, height)
+            }
+        };
+
+        result = Some(match result {
+            Some(mut dest) => {
+                composite(&mask, &mut dest, layer.composite);
+                dest
+            }
+            None => mask,
+        });
+    }
+
+    result
+}
+
+fn render_mask(
+    mask: &Mask,
+    kind: tiny_skia::MaskType,
+    ctx: &Context,
+    transform: tiny_skia::Transform,
+    width: u32,
+    height: u32,
+) -> tiny_skia::Mask {
+    if mask.mask_all {
+        return tiny_skia::Mask::new(width, height).unwrap();
+    }
+
+    let mut result = render_mask_content(mask, kind, ctx, transform, width, height);
+
+    // Nested masks are applied to the masked content and not to the mask itself,
+    // which is the same as intersecting them.
+    if let Some(sub_mask) = render_layers(&mask.masks, ctx, transform, width, height) {
+        composite(&sub_mask, &mut result, usvg::MaskComposite::Intersect);
+    }
+
+    result
+}
+
+fn render_mask_content(
+    mask: &Mask,
+    kind: tiny_skia::MaskType,
+    ctx: &Context,
+    transform: tiny_skia::Transform,
+    width: u32,
+    height: u32,
+) -> tiny_skia::Mask {
+    let mut mask_pixmap = tiny_skia::Pixmap::new(width, height).unwrap();
 
     {
         // TODO: only when needed
         // Mask has to be clipped by mask.region
-        let mut alpha_mask = tiny_skia::Mask::new(pixmap.width(), pixmap.height()).unwrap();
+        let mut alpha_mask = tiny_skia::Mask::new(width, height).unwrap();
         alpha_mask.fill_path(
             &tiny_skia::PathBuilder::from_rect(mask.region),
             tiny_skia::FillRule::Winding,
@@ -93,15 +298,99 @@ pub fn apply(
         mask_pixmap.apply_mask(&alpha_mask);
     }
 
-    if let Some(ref mask) = mask.mask {
-        self::apply(mask, ctx, transform, pixmap);
-    }
+    tiny_skia::Mask::from_pixmap(mask_pixmap.as_ref(), kind)
+}
 
-    let mask_type = match mask.kind {
-        usvg::MaskType::Luminance => tiny_skia::MaskType::Luminance,
-        usvg::MaskType::Alpha => tiny_skia::MaskType::Alpha,
+fn render_image(
+    image: &MaskImage,
+    kind: tiny_skia::MaskType,
+    transform: tiny_skia::Transform,
+    width: u32,
+    height: u32,
+) -> tiny_skia::Mask {
+    let mut pixmap = tiny_skia::Pixmap::new(width, height).unwrap();
+
+    let tiles = |repeat: bool, start: f32, end: f32, offset: f32, size: f32| {
+        if repeat {
+            ((start - offset) / size).floor() as i32..((end - offset) / size).ceil() as i32
+        } else {
+            0..1
+        }
     };
 
-    let mask = tiny_skia::Mask::from_pixmap(mask_pixmap.as_ref(), mask_type);
-    pixmap.apply_mask(&mask);
+    let rect = image.rect;
+    let region = image.region;
+    let columns = tiles(
+        image.repeat.x,
+        region.left(),
+        region.right(),
+        rect.x(),
+        rect.width(),
+    );
+    let rows = tiles(
+        image.repeat.y,
+        region.top(),
+        region.bottom(),
+        rect.y(),
+        rect.height(),
+    );
+
+    // Prevent rendering of tiny tiles.
+    if columns.len() * rows.len() > 10_000 {
+        log::warn!("Too many mask image tiles. Skipped.");
+        return tiny_skia::Mask::new(width, height).unwrap();
+    }
+
+    for row in rows {
+        for column in columns.clone() {
+            let tile_rect = match tiny_skia::NonZeroRect::from_xywh(
+                rect.x() + column as f32 * rect.width(),
+                rect.y() + row as f32 * rect.height(),
+                rect.width(),
+                rect.height(),
+            ) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let view_box = usvg::ViewBox {
+                rect: tile_rect,
+                aspect: usvg::AspectRatio {
+                    defer: false,
+                    align: usvg::Align::None,
+                    slice: false,
+                },
+            };
+
+            crate::image::render_image_kind(
+                &image.kind,
+                &view_box,
+                tiny_skia::FilterQuality::Bicubic,
+                transform,
+                &mut pixmap.as_mut(),
+            );
+        }
+    }
+
+    if let Some(clip) = pixmap.as_mut().create_rect_mask(transform, region) {
+        pixmap.apply_mask(&clip);
+    }
+
+    tiny_skia::Mask::from_pixmap(pixmap.as_ref(), kind)
+}
+
+/// Composites a source mask layer with the layers below it.
+fn composite(source: &tiny_skia::Mask, dest: &mut tiny_skia::Mask, op: usvg::MaskComposite) {
+    for (s, d) in source.data().iter().zip(dest.data_mut().iter_mut()) {
+        let sa = *s as f32 / 255.0;
+        let da = *d as f32 / 255.0;
+        let a = match op {
+            usvg::MaskComposite::Add => sa + da * (1.0 - sa),
+            usvg::MaskComposite::Subtract => sa * (1.0 - da),
+            usvg::MaskComposite::Intersect => sa * da,
+            usvg::MaskComposite::Exclude => sa * (1.0 - da) + da * (1.0 - sa),
+        };
+
+        *d = (a * 255.0 + 0.5) as u8;
+    }
 }
@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::render::Context;
 use crate::tree::{Node, OptionLog};
@@ -16,10 +16,58 @@ pub struct Pattern {
     pub children: Vec<Node>,
 }
 
+#[derive(Clone, Copy)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: tiny_skia::Color,
+}
+
+// The geometry is used only by vector backends.
+#[cfg_attr(not(feature = "pdf"), allow(dead_code))]
+pub enum GradientKind {
+    Linear {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+    },
+    Radial {
+        cx: f32,
+        cy: f32,
+        r: f32,
+        fx: f32,
+        fy: f32,
+        fr: f32,
+    },
+}
+
+/// A gradient with its source data preserved.
+///
+/// tiny-skia shaders are opaque, while vector backends need the actual gradient geometry.
+pub struct Gradient {
+    pub shader: tiny_skia::Shader<'static>,
+    pub kind: GradientKind,
+    pub spread_mode: tiny_skia::SpreadMode,
+    pub transform: tiny_skia::Transform,
+    pub stops: Vec<GradientStop>,
+}
+
+impl Gradient {
+    /// Checks that the gradient is a radial gradient with a focal radius.
+    ///
+    /// tiny-skia supports only a zero start radius,
+    /// therefore such gradients have to be rendered via [`prepare_two_point_conical_pixmap`].
+    /// `shader` is just an approximation in this case.
+    pub fn is_two_point_conical(&self) -> bool {
+        matches!(self.kind, GradientKind::Radial { fr, .. } if fr > 0.0)
+    }
+}
+
 #[derive(Clone)]
 pub enum Paint {
     Shader(tiny_skia::Shader<'static>),
-    Pattern(Rc<Pattern>),
+    Gradient(Arc<Gradient>),
+    Pattern(Arc<Pattern>),
 }
 
 pub fn convert(
@@ -43,7 +91,11 @@ fn convert_linear_gradient(
     opacity: usvg::Opacity,
     object_bbox: Option<tiny_skia::NonZeroRect>,
 ) -> Option<Paint> {
-    let (mode, transform, points) = convert_base_gradient(gradient, opacity, object_bbox)?;
+    let (mode, transform, stops) = convert_base_gradient(gradient, opacity, object_bbox)?;
+    let points = stops
+        .iter()
+        .map(|stop| tiny_skia::GradientStop::new(stop.offset, stop.color))
+        .collect();
 
     let shader = tiny_skia::LinearGradient::new(
         (gradient.x1, gradient.y1).into(),
@@ -53,7 +105,14 @@ fn convert_linear_gradient(
         transform,
     )?;
 
-    Some(Paint::Shader(shader))
+    let kind = GradientKind::Linear {
+        x1: gradient.x1,
+        y1: gradient.y1,
+        x2: gradient.x2,
+        y2: gradient.y2,
+    };
+
+    Some(make_gradient_paint(shader, kind, mode, transform, stops))
 }
 
 fn convert_radial_gradient(
@@ -61,7 +120,11 @@ fn convert_radial_gradient(
     opacity: usvg::Opacity,
     object_bbox: Option<tiny_skia::NonZeroRect>,
 ) -> Option<Paint> {
-    let (mode, transform, points) = convert_base_gradient(gradient, opacity, object_bbox)?;
+    let (mode, transform, stops) = convert_base_gradient(gradient, opacity, object_bbox)?;
+    let points = stops
+        .iter()
+        .map(|stop| tiny_skia::GradientStop::new(stop.offset, stop.color))
+        .collect();
 
     let shader = tiny_skia::RadialGradient::new(
         (gradient.fx, gradient.fy).into(),
@@ -72,7 +135,47 @@ fn convert_radial_gradient(
         transform,
     )?;
 
-    Some(Paint::Shader(shader))
+    let kind = GradientKind::Radial {
+        cx: gradient.cx,
+        cy: gradient.cy,
+        r: gradient.r.get(),
+        fx: gradient.fx,
+        fy: gradient.fy,
+        fr: gradient.fr.get(),
+    };
+
+    Some(make_gradient_paint(shader, kind, mode, transform, stops))
+}
+
+fn make_gradient_paint(
+    shader: tiny_skia::Shader<'static>,
+    kind: GradientKind,
+    spread_mode: tiny_skia::SpreadMode,
+    transform: tiny_skia::Transform,
+    stops: Vec<GradientStop>,
+) -> Paint {
+    // tiny-skia will fallback to a solid color when a gradient has only one stop.
+    if let tiny_skia::Shader::SolidColor(_) = shader {
+        return Paint::Shader(shader);
+    }
+
+    Paint::Gradient(Arc::new(Gradient {
+        shader,
+        kind,
+        spread_mode,
+        transform,
+        stops,
+    }))
+}
+
+fn convert_stop_color(stop: &usvg::Stop, opacity: usvg::Opacity) -> tiny_skia::Color {
+    let alpha = stop.opacity * opacity;
+    tiny_skia::Color::from_rgba8(
+        stop.color.red,
+        stop.color.green,
+        stop.color.blue,
+        alpha.to_u8(),
+    )
 }
 
 fn convert_base_gradient(
@@ -82,7 +185,7 @@ fn convert_base_gradient(
 ) -> Option<(
     tiny_skia::SpreadMode,
     tiny_skia::Transform,
-    Vec<tiny_skia::GradientStop>,
+    Vec<GradientStop>,
 )> {
     let mode = match gradient.spread_method {
         usvg::SpreadMethod::Pad => tiny_skia::SpreadMode::Pad,
@@ -99,19 +202,66 @@ fn convert_base_gradient(
         gradient.transform
     };
 
-    let mut points = Vec::with_capacity(gradient.stops.len());
+    let mut stops: Vec<GradientStop> = Vec::with_capacity(gradient.stops.len());
     for stop in &gradient.stops {
-        let alpha = stop.opacity * opacity;
-        let color = tiny_skia::Color::from_rgba8(
-            stop.color.red,
-            stop.color.green,
-            stop.color.blue,
-            alpha.to_u8(),
-        );
-        points.push(tiny_skia::GradientStop::new(stop.offset.get(), color))
+        let stop = GradientStop {
+            offset: stop.offset.get(),
+            color: convert_stop_color(stop, opacity),
+        };
+
+        if gradient.color_interpolation == usvg::filter::ColorInterpolation::LinearRGB {
+            if let Some(prev) = stops.last().copied() {
+                push_linear_rgb_stops(&prev, &stop, &mut stops);
+            }
+        }
+
+        stops.push(stop);
+    }
+
+    Some((mode, transform, stops))
+}
+
+/// Approximates a `linearRGB` interpolation between two stops.
+///
+/// tiny-skia can interpolate gradients only in sRGB, so we are adding intermediate stops
+/// with colors interpolated in the linearRGB color space.
+fn push_linear_rgb_stops(start: &GradientStop, end: &GradientStop, stops: &mut Vec<GradientStop>) {
+    const STEPS: u32 = 16;
+
+    // Nothing to interpolate between hard stops.
+    if end.offset - start.offset <= 0.0 {
+        return;
     }
 
-    Some((mode, transform, points))
+    let (c1, c2) = (start.color, end.color);
+    let to_rgba8 = |c: tiny_skia::Color| {
+        let c = c.to_color_u8();
+        rgb::RGBA8::new(c.red(), c.green(), c.blue(), c.alpha())
+    };
+    let mut linear = [to_rgba8(c1), to_rgba8(c2)];
+    crate::filter::into_linear_rgb(&mut linear);
+    let [l1, l2] = linear;
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let lerp_u8 = |a: u8, b: u8, t: f32| lerp(a as f32, b as f32, t).round() as u8;
+    for i in 1..STEPS {
+        let t = i as f32 / STEPS as f32;
+        let mut color = [rgb::RGBA8::new(
+            lerp_u8(l1.r, l2.r, t),
+            lerp_u8(l1.g, l2.g, t),
+            lerp_u8(l1.b, l2.b, t),
+            0,
+        )];
+        crate::filter::from_linear_rgb(&mut color);
+        let [c] = color;
+
+        let mut color = tiny_skia::Color::from_rgba8(c.r, c.g, c.b, 255);
+        color.set_alpha(lerp(c1.alpha(), c2.alpha(), t));
+        stops.push(GradientStop {
+            offset: lerp(start.offset, end.offset, t),
+            color,
+        });
+    }
 }
 
 fn convert_pattern(
@@ -144,7 +294,7 @@ fn convert_pattern(
         pattern.rect
     };
 
-    Some(Paint::Pattern(Rc::new(Pattern {
+    Some(Paint::Pattern(Arc::new(Pattern {
         rect,
         view_box: pattern.view_box,
         opacity,
@@ -187,3 +337,145 @@ pub fn prepare_pattern_pixmap(
 
     Some((pixmap, ts))
 }
+
+/// Renders a two-point conical gradient into a pixmap.
+///
+/// The pixmap covers only the `region` of the canvas.
+/// Returns the pixmap and a pattern transform relative to `transform`.
+pub fn prepare_two_point_conical_pixmap(
+    gradient: &Gradient,
+    region: tiny_skia::IntRect,
+    transform: tiny_skia::Transform,
+) -> Option<(tiny_skia::Pixmap, tiny_skia::Transform)> {
+    let (cx, cy, r, fx, fy, fr) = match gradient.kind {
+        GradientKind::Radial {
+            cx,
+            cy,
+            r,
+            fx,
+            fy,
+            fr,
+        } => (cx, cy, r, fx, fy, fr),
+        GradientKind::Linear { .. } => return None,
+    };
+
+    let mut pixmap = tiny_skia::Pixmap::new(region.width(), region.height())?;
+    let ts = transform.pre_concat(gradient.transform).invert()?;
+
+    // A circle at `t` has a center at `f + t * cd` and a radius of `fr + t * dr`.
+    let cd = (f64::from(cx - fx), f64::from(cy - fy));
+    let dr = f64::from(r - fr);
+    let fr = f64::from(fr);
+    let a = cd.0 * cd.0 + cd.1 * cd.1 - dr * dr;
+    let is_degenerate = cd.0.hypot(cd.1) <= 1e-9 && dr.abs() <= 1e-9;
+
+    let width = region.width() as usize;
+    for (idx, pixel) in pixmap.pixels_mut().iter_mut().enumerate() {
+        let mut p = tiny_skia::Point::from_xy(
+            region.x() as f32 + (idx % width) as f32 + 0.5,
+            region.y() as f32 + (idx / width) as f32 + 0.5,
+        );
+        ts.map_points(std::slice::from_mut(&mut p));
+
+        let px = f64::from(p.x - fx);
+        let py = f64::from(p.y - fy);
+        let t = if is_degenerate {
+            // Like Skia, render identical circles as a hard stop.
+            if px.hypot(py) <= fr {
+                0.0
+            } else {
+                1.0
+            }
+        } else {
+            match conical_t(px, py, cd, fr, dr, a) {
+                Some(t) => apply_spread_mode(t, gradient.spread_mode),
+                // Pixels outside the cone are not painted.
+                None => continue,
+            }
+        };
+
+        *pixel = stops_color_at(&gradient.stops, t as f32)
+            .premultiply()
+            .to_color_u8();
+    }
+
+    let patt_ts = transform
+        .invert()?
+        .pre_translate(region.x() as f32, region.y() as f32);
+    Some((pixmap, patt_ts))
+}
+
+fn apply_spread_mode(t: f64, mode: tiny_skia::SpreadMode) -> f64 {
+    match mode {
+        tiny_skia::SpreadMode::Pad => t.clamp(0.0, 1.0),
+        tiny_skia::SpreadMode::Repeat => t - t.floor(),
+        tiny_skia::SpreadMode::Reflect => {
+            let t = t.rem_euclid(2.0);
+            if t > 1.0 {
+                2.0 - t
+            } else {
+                t
+            }
+        }
+    }
+}
+
+/// Finds the largest `t` for which a point lies on a gradient circle with a non-negative radius.
+///
+/// `px` and `py` are relative to the focal point.
+fn conical_t(px: f64, py: f64, cd: (f64, f64), fr: f64, dr: f64, a: f64) -> Option<f64> {
+    // |p - t * cd| = fr + t * dr
+    let b = px * cd.0 + py * cd.1 + fr * dr;
+    let c = px * px + py * py - fr * fr;
+    let is_valid = |t: f64| fr + t * dr >= 0.0;
+
+    if a.abs() <= 1e-9 {
+        if b.abs() <= 1e-9 {
+            return None;
+        }
+
+        let t = c / (2.0 * b);
+        return if is_valid(t) { Some(t) } else { None };
+    }
+
+    let disc = b * b - a * c;
+    if disc < 0.0 {
+        return None;
+    }
+
+    let sq = disc.sqrt();
+    let (t1, t2) = ((b + sq) / a, (b - sq) / a);
+    let (t1, t2) = if t1 > t2 { (t1, t2) } else { (t2, t1) };
+    if is_valid(t1) {
+        Some(t1)
+    } else if is_valid(t2) {
+        Some(t2)
+    } else {
+        None
+    }
+}
+
+fn stops_color_at(stops: &[GradientStop], t: f32) -> tiny_skia::Color {
+    let first = stops[0];
+    if t <= first.offset {
+        return first.color;
+    }
+
+    for pair in stops.windows(2) {
+        let (s1, s2) = (pair[0], pair[1]);
+        if t < s2.offset {
+            let k = (t - s1.offset) / (s2.offset - s1.offset);
+            let lerp = |a: f32, b: f32| a + (b - a) * k;
+            let (c1, c2) = (s1.color, s2.color);
+            return tiny_skia::Color::from_rgba(
+                lerp(c1.red(), c2.red()),
+                lerp(c1.green(), c2.green()),
+                lerp(c1.blue(), c2.blue()),
+                lerp(c1.alpha(), c2.alpha()),
+            )
+            .unwrap_or(s2.color);
+        }
+    }
+
+    stops[stops.len() - 1].color
+}
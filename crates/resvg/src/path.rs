@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::paint_server::Paint;
 use crate::render::Context;
@@ -12,31 +12,42 @@ pub struct FillPath {
     pub paint: Paint,
     pub rule: tiny_skia::FillRule,
     pub anti_alias: bool,
-    pub path: Rc<tiny_skia::Path>,
+    pub path: Arc<tiny_skia::Path>,
 }
 
 pub struct StrokePath {
     pub paint: Paint,
     pub stroke: tiny_skia::Stroke,
     pub anti_alias: bool,
-    pub path: Rc<tiny_skia::Path>,
+    /// Stroke in the canvas coordinates.
+    pub non_scaling: bool,
+    /// Use the `arcs` line join instead of `stroke.line_join`.
+    ///
+    /// Not supported by tiny-skia, therefore such paths are stroked manually.
+    pub arcs_join: bool,
+    pub path: Arc<tiny_skia::Path>,
 }
 
+/// `abs_ts` is used only to estimate the layer bbox of a non-scaling stroke.
 pub fn convert(
     upath: &usvg::Path,
+    abs_ts: tiny_skia::Transform,
     text_bbox: Option<tiny_skia::NonZeroRect>,
     children: &mut Vec<Node>,
 ) -> Option<BBoxes> {
     let anti_alias = upath.rendering_mode.use_shape_antialiasing();
 
+    let path = upath.data.clone();
+
     let fill_path = upath
         .fill
         .as_ref()
-        .and_then(|ufill| convert_fill_path(ufill, upath.data.clone(), text_bbox, anti_alias));
+        .and_then(|ufill| convert_fill_path(ufill, path.clone(), text_bbox, anti_alias));
 
-    let stroke_path = upath.stroke.as_ref().and_then(|ustroke| {
-        convert_stroke_path(ustroke, upath.data.clone(), text_bbox, anti_alias)
-    });
+    let stroke_path = upath
+        .stroke
+        .as_ref()
+        .and_then(|ustroke| convert_stroke_path(ustroke, path, abs_ts, text_bbox, anti_alias));
 
     if fill_path.is_none() && stroke_path.is_none() {
         return None;
@@ -48,9 +59,16 @@ pub fn convert(
         bboxes.layer = bboxes.layer.expand(o_bbox);
         bboxes.object = bboxes.object.expand(o_bbox);
     }
-    if let Some((_, l_bbox, o_bbox)) = stroke_path {
+    if let Some((ref path, l_bbox, o_bbox)) = stroke_path {
         bboxes.layer = bboxes.layer.expand(l_bbox);
         bboxes.object = bboxes.object.expand(o_bbox);
+
+        if path.non_scaling {
+            // The stroke cannot extend beyond the path by more than the miter length.
+            let stroke = &path.stroke;
+            bboxes.canvas_padding =
+                stroke.width / 2.0 * stroke.miter_limit.max(std::f32::consts::SQRT_2);
+        }
     }
 
     // Do not add hidden paths, but preserve the bbox.
@@ -82,7 +100,7 @@ pub fn convert(
 
 fn convert_fill_path(
     ufill: &usvg::Fill,
-    path: Rc<tiny_skia::Path>,
+    path: Arc<tiny_skia::Path>,
     text_bbox: Option<tiny_skia::NonZeroRect>,
     anti_alias: bool,
 ) -> Option<(FillPath, usvg::BBox)> {
@@ -116,7 +134,8 @@ fn convert_fill_path(
 
 fn convert_stroke_path(
     ustroke: &usvg::Stroke,
-    path: Rc<tiny_skia::Path>,
+    path: Arc<tiny_skia::Path>,
+    abs_ts: tiny_skia::Transform,
     text_bbox: Option<tiny_skia::NonZeroRect>,
     anti_alias: bool,
 ) -> Option<(StrokePath, usvg::BBox, usvg::BBox)> {
@@ -133,6 +152,8 @@ fn convert_stroke_path(
             usvg::LineJoin::MiterClip => tiny_skia::LineJoin::MiterClip,
             usvg::LineJoin::Round => tiny_skia::LineJoin::Round,
             usvg::LineJoin::Bevel => tiny_skia::LineJoin::Bevel,
+            // Not supported by tiny-skia. See `StrokePath::arcs_join`.
+            usvg::LineJoin::Arcs => tiny_skia::LineJoin::MiterClip,
         },
         dash: None,
     };
@@ -158,9 +179,27 @@ fn convert_stroke_path(
 
     // TODO: explain
     // TODO: expand by stroke width for round/bevel joins
-    let stroked_path = path.stroke(&stroke, 1.0)?;
+    let arcs_join = ustroke.linejoin == usvg::LineJoin::Arcs;
+    let stroke_outline = |path: &tiny_skia::Path| {
+        if arcs_join {
+            crate::stroke::stroke_arcs(path, &stroke, 1.0)
+        } else {
+            path.stroke(&stroke, 1.0)
+        }
+    };
+
+    let stroke_bbox = if ustroke.non_scaling {
+        // The actual stroke width depends on the rendering transform,
+        // which is unknown at this point. Use the absolute transform as an estimation
+        // and pad the layer during rendering.
+        let ts_path = path.as_ref().clone().transform(abs_ts)?;
+        let bbox = stroke_outline(&ts_path)?.compute_tight_bounds()?;
+        bbox.transform(abs_ts.invert()?)?
+    } else {
+        stroke_outline(&path)?.compute_tight_bounds()?
+    };
 
-    let mut layer_bbox = usvg::BBox::from(stroked_path.compute_tight_bounds()?);
+    let mut layer_bbox = usvg::BBox::from(stroke_bbox);
     if let Some(text_bbox) = text_bbox {
         layer_bbox = layer_bbox.expand(usvg::BBox::from(text_bbox));
     }
@@ -172,6 +211,8 @@ fn convert_stroke_path(
         paint,
         stroke,
         anti_alias,
+        non_scaling: ustroke.non_scaling,
+        arcs_join,
         path,
     };
 
@@ -191,6 +232,23 @@ pub fn render_fill_path(
         Paint::Shader(ref shader) => {
             paint.shader = shader.clone(); // TODO: avoid clone
         }
+        Paint::Gradient(ref gradient) if gradient.is_two_point_conical() => {
+            let region = canvas_region(path.path.bounds(), transform, 1.0, pixmap)?;
+            let (patt_pix, patt_ts) =
+                crate::paint_server::prepare_two_point_conical_pixmap(gradient, region, transform)?;
+
+            pattern_pixmap = patt_pix;
+            paint.shader = tiny_skia::Pattern::new(
+                pattern_pixmap.as_ref(),
+                tiny_skia::SpreadMode::Pad,
+                tiny_skia::FilterQuality::Nearest,
+                1.0,
+                patt_ts,
+            )
+        }
+        Paint::Gradient(ref gradient) => {
+            paint.shader = gradient.shader.clone(); // TODO: avoid clone
+        }
         Paint::Pattern(ref pattern) => {
             let (patt_pix, patt_ts) =
                 crate::paint_server::prepare_pattern_pixmap(pattern, ctx, transform)?;
@@ -227,6 +285,30 @@ pub fn render_stroke_path(
         Paint::Shader(ref shader) => {
             paint.shader = shader.clone(); // TODO: avoid clone
         }
+        Paint::Gradient(ref gradient) if gradient.is_two_point_conical() => {
+            // Joins and caps cannot extend further than the miter limit.
+            let mut outset = path.stroke.width / 2.0 * path.stroke.miter_limit.max(2.0);
+            if !path.non_scaling {
+                let (sx, sy) = transform.get_scale();
+                outset *= sx.max(sy);
+            }
+
+            let region = canvas_region(path.path.bounds(), transform, outset + 1.0, pixmap)?;
+            let (patt_pix, patt_ts) =
+                crate::paint_server::prepare_two_point_conical_pixmap(gradient, region, transform)?;
+
+            pattern_pixmap = patt_pix;
+            paint.shader = tiny_skia::Pattern::new(
+                pattern_pixmap.as_ref(),
+                tiny_skia::SpreadMode::Pad,
+                tiny_skia::FilterQuality::Nearest,
+                1.0,
+                patt_ts,
+            )
+        }
+        Paint::Gradient(ref gradient) => {
+            paint.shader = gradient.shader.clone(); // TODO: avoid clone
+        }
         Paint::Pattern(ref pattern) => {
             let (patt_pix, patt_ts) =
                 crate::paint_server::prepare_pattern_pixmap(pattern, ctx, transform)?;
@@ -247,7 +329,50 @@ pub fn render_stroke_path(
 
     // TODO: fallback to a stroked path when possible
 
-    pixmap.stroke_path(&path.path, &paint, &path.stroke, transform, None);
+    // A non-scaling stroke is applied in the canvas coordinates,
+    // so we have to transform the path and the paint, but not the stroke.
+    let ts_path;
+    let (data, transform) = if path.non_scaling {
+        ts_path = path.path.as_ref().clone().transform(transform)?;
+        paint.shader.transform(transform);
+        (&ts_path, tiny_skia::Transform::default())
+    } else {
+        (&*path.path, transform)
+    };
+
+    if path.arcs_join {
+        let res_scale = tiny_skia::PathStroker::compute_resolution_scale(&transform);
+        let outline = crate::stroke::stroke_arcs(data, &path.stroke, res_scale)?;
+        pixmap.fill_path(
+            &outline,
+            &paint,
+            tiny_skia::FillRule::Winding,
+            transform,
+            None,
+        );
+    } else {
+        pixmap.stroke_path(data, &paint, &path.stroke, transform, None);
+    }
 
     Some(())
 }
+
+/// Returns a canvas region that can be affected by a path.
+///
+/// `outset` is in canvas pixels.
+fn canvas_region(
+    bounds: tiny_skia::Rect,
+    transform: tiny_skia::Transform,
+    outset: f32,
+    pixmap: &tiny_skia::PixmapMut,
+) -> Option<tiny_skia::IntRect> {
+    let r = bounds.transform(transform)?;
+    let r = tiny_skia::Rect::from_ltrb(
+        r.left() - outset,
+        r.top() - outset,
+        r.right() + outset,
+        r.bottom() + outset,
+    )?;
+    let canvas = tiny_skia::IntRect::from_xywh(0, 0, pixmap.width(), pixmap.height())?;
+    crate::geom::fit_to_rect(r.round_out()?, canvas)
+}
@@ -0,0 +1,1233 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use pdf_writer::types::ColorSpaceOperand;
+use pdf_writer::types::{
+    BlendMode, FunctionShadingType, LineCapStyle, LineJoinStyle, MaskType, PaintType, TilingType,
+};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref};
+
+use crate::clip::ClipPath;
+use crate::image::{Image, ImageKind};
+use crate::mask::{Mask, MaskLayer, MaskSource};
+use crate::paint_server::{Gradient, GradientKind, GradientStop, Paint, Pattern};
+use crate::path::{FillPath, StrokePath};
+use crate::tree::{Group, Node, OptionLog, Tree};
+
+/// Groups with filters are rasterized using this amount of pixels per PDF point.
+const RASTER_SCALE: f32 = 2.0;
+
+/// The maximum number of spread method repetitions emitted for a single gradient.
+const MAX_GRADIENT_REPEATS: i32 = 256;
+
+impl Tree {
+    /// Renders the tree into a single-page PDF document.
+    ///
+    /// `size` is the page size in PDF points.
+    ///
+    /// `transform` will be used as a root transform, just like in [`Tree::render`].
+    ///
+    /// Everything except filters is converted into PDF operators.
+    /// Groups with filters are rasterized and embedded as images.
+    pub fn render_pdf(&self, transform: tiny_skia::Transform, size: tiny_skia::Size) -> Vec<u8> {
+        // Just like in `render`, raster layers are limited to 4x the page size.
+        let max_bbox = tiny_skia::Rect::from_xywh(
+            -size.width() * 2.0,
+            -size.height() * 2.0,
+            size.width() * 4.0,
+            size.height() * 4.0,
+        )
+        .unwrap();
+
+        let mut ctx = Context {
+            pdf: Pdf::new(),
+            next_id: 1,
+            max_bbox,
+        };
+
+        let catalog_id = ctx.alloc_ref();
+        let page_tree_id = ctx.alloc_ref();
+        let page_id = ctx.alloc_ref();
+        let content_id = ctx.alloc_ref();
+
+        // PDF uses a bottom-left origin.
+        let flip_ts = tiny_skia::Transform::from_row(1.0, 0.0, 0.0, -1.0, 0.0, size.height());
+        let view_box_ts =
+            usvg::utils::view_box_to_transform(self.view_box.rect, self.view_box.aspect, self.size);
+        let root_transform = flip_ts.pre_concat(transform).pre_concat(view_box_ts);
+
+        let mut stream = Stream::default();
+        render_nodes(&self.children, &mut ctx, root_transform, &mut stream);
+
+        let (data, resources) = stream.finish();
+        ctx.pdf
+            .stream(content_id, &data)
+            .filter(Filter::FlateDecode);
+
+        ctx.pdf.catalog(catalog_id).pages(page_tree_id);
+        ctx.pdf.pages(page_tree_id).kids([page_id]).count(1);
+
+        let mut page = ctx.pdf.page(page_id);
+        page.parent(page_tree_id)
+            .media_box(Rect::new(0.0, 0.0, size.width(), size.height()))
+            .contents(content_id);
+        resources.write(page.resources());
+        page.finish();
+
+        ctx.pdf.finish()
+    }
+}
+
+struct Context {
+    pdf: Pdf,
+    next_id: i32,
+    max_bbox: tiny_skia::Rect,
+}
+
+impl Context {
+    fn alloc_ref(&mut self) -> Ref {
+        let id = Ref::new(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Writes a stream as a transparency group form XObject.
+    fn write_form(&mut self, stream: Stream, bbox: tiny_skia::Rect, mask_group: bool) -> Ref {
+        let id = self.alloc_ref();
+        let (data, resources) = stream.finish();
+
+        let mut form = self.pdf.form_xobject(id, &data);
+        form.filter(Filter::FlateDecode);
+        form.bbox(convert_rect(bbox));
+
+        let mut group = form.group();
+        // Just like during rasterization, each group is rendered onto a new, transparent layer.
+        group.transparency().isolated(true).knockout(false);
+        if mask_group {
+            // Luminosity masks must have an explicit color space.
+            group.color_space().device_rgb();
+        }
+        group.finish();
+
+        resources.write(form.resources());
+        form.finish();
+
+        id
+    }
+
+    fn write_ext_g_state(&mut self, opacity: f32, blend_mode: tiny_skia::BlendMode) -> Ref {
+        let id = self.alloc_ref();
+        let mut gs = self.pdf.ext_graphics(id);
+        gs.non_stroking_alpha(opacity).stroking_alpha(opacity);
+        if blend_mode != tiny_skia::BlendMode::SourceOver {
+            gs.blend_mode(convert_blend_mode(blend_mode));
+        }
+        gs.finish();
+
+        id
+    }
+
+    fn write_soft_mask(&mut self, group: Ref, kind: MaskType) -> Ref {
+        let id = self.alloc_ref();
+        self.pdf
+            .ext_graphics(id)
+            .soft_mask()
+            .subtype(kind)
+            .group(group);
+
+        id
+    }
+}
+
+/// A content stream with its resources.
+struct Stream {
+    content: Content,
+    resources: Resources,
+}
+
+impl Default for Stream {
+    fn default() -> Self {
+        Stream {
+            content: Content::new(),
+            resources: Resources::default(),
+        }
+    }
+}
+
+impl Stream {
+    fn draw_x_object(&mut self, id: Ref) {
+        let name = self.resources.push_x_object(id);
+        self.content.x_object(Name(name.as_bytes()));
+    }
+
+    fn set_ext_g_state(&mut self, id: Ref) {
+        let name = self.resources.push_ext_g_state(id);
+        self.content.set_parameters(Name(name.as_bytes()));
+    }
+
+    fn finish(self) -> (Vec<u8>, Resources) {
+        let data = self.content.finish();
+        let data = miniz_oxide::deflate::compress_to_vec_zlib(&data, 6);
+        (data, self.resources)
+    }
+}
+
+#[derive(Default)]
+struct Resources {
+    x_objects: Vec<Ref>,
+    ext_g_states: Vec<Ref>,
+    patterns: Vec<Ref>,
+}
+
+impl Resources {
+    fn push_x_object(&mut self, id: Ref) -> String {
+        self.x_objects.push(id);
+        format!("x{}", id.get())
+    }
+
+    fn push_ext_g_state(&mut self, id: Ref) -> String {
+        self.ext_g_states.push(id);
+        format!("gs{}", id.get())
+    }
+
+    fn push_pattern(&mut self, id: Ref) -> String {
+        self.patterns.push(id);
+        format!("p{}", id.get())
+    }
+
+    fn write(&self, mut resources: pdf_writer::writers::Resources) {
+        if !self.x_objects.is_empty() {
+            let mut dict = resources.x_objects();
+            for id in &self.x_objects {
+                dict.pair(Name(format!("x{}", id.get()).as_bytes()), *id);
+            }
+        }
+
+        if !self.ext_g_states.is_empty() {
+            let mut dict = resources.ext_g_states();
+            for id in &self.ext_g_states {
+                dict.pair(Name(format!("gs{}", id.get()).as_bytes()), *id);
+            }
+        }
+
+        if !self.patterns.is_empty() {
+            let mut dict = resources.patterns();
+            for id in &self.patterns {
+                dict.pair(Name(format!("p{}", id.get()).as_bytes()), *id);
+            }
+        }
+    }
+}
+
+// All `transform` arguments below are relative to the default coordinate space
+// of the current content stream. We never leave a `cm` operator active while drawing
+// form XObjects or setting soft masks, because patterns and soft masks are
+// always resolved relative to the default coordinate space and not the current one.
+
+fn render_nodes(children: &[Node], ctx: &mut Context, ts: tiny_skia::Transform, s: &mut Stream) {
+    for node in children {
+        render_node(node, ctx, ts, s);
+    }
+}
+
+fn render_node(node: &Node, ctx: &mut Context, ts: tiny_skia::Transform, s: &mut Stream) {
+    match node {
+        Node::Group(ref group) => {
+            render_group(group, ctx, ts, s);
+        }
+        Node::FillPath(ref path) => {
+            render_fill_path(path, ctx, ts, s);
+        }
+        Node::StrokePath(ref path) => {
+            render_stroke_path(path, ctx, ts, s);
+        }
+        Node::Image(ref image) => {
+            render_image(image, ctx, ts, s);
+        }
+    }
+}
+
+fn render_group(
+    group: &Group,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    s: &mut Stream,
+) -> Option<()> {
+    if !group.filters.is_empty()
+        || has_raster_masks(&group.masks)
+        || group.color_interpolation == usvg::filter::ColorInterpolation::LinearRGB
+    {
+        return render_raster_group(group, ctx, ts, s);
+    }
+
+    let ts = ts.pre_concat(group.transform);
+
+    if group.is_transform_only() {
+        render_nodes(&group.children, ctx, ts, s);
+        return Some(());
+    }
+
+    if has_mask_all(&group.masks) {
+        return Some(());
+    }
+
+    let bbox = group.layer_bbox(ts)?;
+
+    let mut content = Stream::default();
+    render_nodes(&group.children, ctx, ts, &mut content);
+    let mut x_object = ctx.write_form(content, bbox, false);
+
+    if let Some(ref clip_path) = group.clip_path {
+        x_object = apply_clip_path(clip_path, ctx, ts, bbox, x_object);
+    }
+
+    x_object = apply_masks(&group.masks, ctx, ts, bbox, x_object);
+
+    s.content.save_state();
+    if group.opacity != usvg::Opacity::ONE || group.blend_mode != tiny_skia::BlendMode::SourceOver {
+        let gs = ctx.write_ext_g_state(group.opacity.get(), group.blend_mode);
+        s.set_ext_g_state(gs);
+    }
+    s.draw_x_object(x_object);
+    s.content.restore_state();
+
+    Some(())
+}
+
+/// Filters and mask layers compositing cannot be represented by PDF operators,
+/// therefore we have to render such groups into a raster layer and embed it as an image.
+fn render_raster_group(
+    group: &Group,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    s: &mut Stream,
+) -> Option<()> {
+    let ts = ts.pre_concat(group.transform);
+    let bbox = group.layer_bbox(ts)?.intersect(&ctx.max_bbox)?;
+
+    let layer_size = tiny_skia::IntSize::from_wh(
+        (bbox.width() * RASTER_SCALE).ceil() as u32,
+        (bbox.height() * RASTER_SCALE).ceil() as u32,
+    )?;
+    let mut layer = tiny_skia::Pixmap::new(layer_size.width(), layer_size.height())
+        .log_none(|| log::warn!("Failed to allocate a group layer for: {:?}.", layer_size))?;
+
+    // Maps the layer bbox onto the pixmap, while flipping the Y axis back,
+    // since the first image row is the top one.
+    let layer_ts = tiny_skia::Transform::from_row(
+        RASTER_SCALE,
+        0.0,
+        0.0,
+        -RASTER_SCALE,
+        -bbox.x() * RASTER_SCALE,
+        bbox.bottom() * RASTER_SCALE,
+    )
+    .pre_concat(ts);
+
+    let raster_ctx = crate::render::Context {
+        max_bbox: tiny_skia::IntRect::from_xywh(
+            -(layer.width() as i32) * 2,
+            -(layer.height() as i32) * 2,
+            layer.width() * 4,
+            layer.height() * 4,
+        )?,
+        tile: None,
+    };
+
+    crate::render::render_group_children(group, &raster_ctx, layer_ts, &mut layer);
+
+    for filter in &group.filters {
+        crate::filter::apply(filter, layer_ts, &mut layer);
+    }
+
+    if let Some(ref clip_path) = group.clip_path {
+        crate::clip::apply(clip_path, layer_ts, &mut layer);
+    }
+
+    if !group.masks.is_empty() {
+        crate::mask::apply(&group.masks, &raster_ctx, layer_ts, &mut layer);
+    }
+
+    let image = write_raster_image(&layer, true, ctx);
+
+    s.content.save_state();
+    if group.opacity != usvg::Opacity::ONE || group.blend_mode != tiny_skia::BlendMode::SourceOver {
+        let gs = ctx.write_ext_g_state(group.opacity.get(), group.blend_mode);
+        s.set_ext_g_state(gs);
+    }
+    // The layer size is rounded up, so we cannot use the bbox size here.
+    let width = layer.width() as f32 / RASTER_SCALE;
+    let height = layer.height() as f32 / RASTER_SCALE;
+    s.content
+        .transform([width, 0.0, 0.0, height, bbox.x(), bbox.bottom() - height]);
+    s.draw_x_object(image);
+    s.content.restore_state();
+
+    Some(())
+}
+
+/// Checks that mask layers cannot be written as a soft mask.
+///
+/// Only a single `mask` element layer is supported.
+fn has_raster_masks(layers: &[MaskLayer]) -> bool {
+    match layers {
+        [] => false,
+        [layer] => match layer.source {
+            MaskSource::Mask(ref mask) => has_raster_masks(&mask.masks),
+            MaskSource::Image(_) => true,
+        },
+        _ => true,
+    }
+}
+
+fn has_mask_all(layers: &[MaskLayer]) -> bool {
+    layers.iter().any(|layer| match layer.source {
+        MaskSource::Mask(ref mask) => mask.mask_all || has_mask_all(&mask.masks),
+        MaskSource::Image(_) => false,
+    })
+}
+
+/// Wraps `x_object` into a new form XObject masked by a clip path.
+///
+/// A clip path is written as a luminosity mask: white shapes on a black backdrop.
+fn apply_clip_path(
+    clip: &ClipPath,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    bbox: tiny_skia::Rect,
+    x_object: Ref,
+) -> Ref {
+    let mut stream = Stream::default();
+    write_clip_children(
+        &clip.children,
+        ctx,
+        ts.pre_concat(clip.transform),
+        bbox,
+        &mut stream,
+    );
+    let clip_form = ctx.write_form(stream, bbox, true);
+
+    let mut x_object = wrap_with_soft_mask(ctx, bbox, x_object, clip_form, MaskType::Luminosity);
+
+    // Just like during rasterization, a nested clip path is applied to the content
+    // and not to the clip path itself.
+    if let Some(ref clip) = clip.clip_path {
+        x_object = apply_clip_path(clip, ctx, ts, bbox, x_object);
+    }
+
+    x_object
+}
+
+fn write_clip_children(
+    children: &[Node],
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    bbox: tiny_skia::Rect,
+    s: &mut Stream,
+) {
+    for child in children {
+        match child {
+            Node::FillPath(ref path) => {
+                s.content.save_state();
+                s.content.set_fill_gray(1.0);
+                s.content.transform(convert_transform(ts));
+                write_path(&path.path, &mut s.content);
+                fill(path.rule, &mut s.content);
+                s.content.restore_state();
+            }
+            Node::Group(ref group) => {
+                let ts = ts.pre_concat(group.transform);
+
+                if let Some(ref clip) = group.clip_path {
+                    // A `clipPath` child with its own `clip-path`
+                    // should be clipped separately.
+                    let mut sub_stream = Stream::default();
+                    write_clip_children(&group.children, ctx, ts, bbox, &mut sub_stream);
+                    let form = ctx.write_form(sub_stream, bbox, true);
+                    let form = apply_clip_path(clip, ctx, ts, bbox, form);
+                    s.draw_x_object(form);
+                } else {
+                    write_clip_children(&group.children, ctx, ts, bbox, s);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Wraps `x_object` into new form XObjects masked by `mask` element layers.
+///
+/// Must be used only when [`has_raster_masks`] is `false`.
+fn apply_masks(
+    layers: &[MaskLayer],
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    bbox: tiny_skia::Rect,
+    mut x_object: Ref,
+) -> Ref {
+    for layer in layers {
+        if let MaskSource::Mask(ref mask) = layer.source {
+            x_object = apply_mask(mask, layer.kind, ctx, ts, bbox, x_object);
+        }
+    }
+
+    x_object
+}
+
+/// Wraps `x_object` into a new form XObject masked by a mask.
+fn apply_mask(
+    mask: &Mask,
+    kind: tiny_skia::MaskType,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    bbox: tiny_skia::Rect,
+    mut x_object: Ref,
+) -> Ref {
+    // Just like during rasterization, nested masks are applied to the content
+    // and not to the mask itself.
+    x_object = apply_masks(&mask.masks, ctx, ts, bbox, x_object);
+
+    let mut stream = Stream::default();
+    stream.content.save_state();
+    // Mask has to be clipped by mask.region
+    if let Some(region) = tiny_skia::PathBuilder::from_rect(mask.region).transform(ts) {
+        write_path(&region, &mut stream.content);
+        stream.content.clip_nonzero();
+        stream.content.end_path();
+    }
+    render_nodes(
+        &mask.children,
+        ctx,
+        ts.pre_concat(mask.content_transform),
+        &mut stream,
+    );
+    stream.content.restore_state();
+
+    let mask_form = ctx.write_form(stream, bbox, true);
+    let kind = match kind {
+        tiny_skia::MaskType::Luminance => MaskType::Luminosity,
+        tiny_skia::MaskType::Alpha => MaskType::Alpha,
+    };
+
+    wrap_with_soft_mask(ctx, bbox, x_object, mask_form, kind)
+}
+
+fn wrap_with_soft_mask(
+    ctx: &mut Context,
+    bbox: tiny_skia::Rect,
+    x_object: Ref,
+    mask_form: Ref,
+    kind: MaskType,
+) -> Ref {
+    let gs = ctx.write_soft_mask(mask_form, kind);
+
+    let mut stream = Stream::default();
+    stream.set_ext_g_state(gs);
+    stream.draw_x_object(x_object);
+    ctx.write_form(stream, bbox, false)
+}
+
+fn render_fill_path(
+    path: &FillPath,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    s: &mut Stream,
+) -> Option<()> {
+    let bbox = path.path.compute_tight_bounds()?.transform(ts)?;
+
+    with_pattern_opacity(&path.paint, bbox, ctx, s, |ctx, s| {
+        s.content.save_state();
+        set_paint(&path.paint, true, ctx, ts, bbox, s);
+        s.content.transform(convert_transform(ts));
+        write_path(&path.path, &mut s.content);
+        fill(path.rule, &mut s.content);
+        s.content.restore_state();
+    });
+
+    Some(())
+}
+
+fn render_stroke_path(
+    path: &StrokePath,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    s: &mut Stream,
+) -> Option<()> {
+    // A non-scaling stroke is applied in the stream coordinates,
+    // so we have to transform the path beforehand.
+    let ts_path;
+    let (data, path_ts) = if path.non_scaling {
+        ts_path = path.path.as_ref().clone().transform(ts)?;
+        (&ts_path, tiny_skia::Transform::default())
+    } else {
+        (&*path.path, ts)
+    };
+
+    // Outlines are generated in the path coordinates, so their precision
+    // must depend on the final scale, just like in tiny-skia.
+    let res_scale = tiny_skia::PathStroker::compute_resolution_scale(&path_ts);
+
+    // PDF dash patterns are defined differently, so it's easier to dash beforehand,
+    // just like tiny-skia does.
+    let dashed_path;
+    let data = match path.stroke.dash {
+        Some(ref dash) => {
+            dashed_path = data.dash(dash, res_scale)?;
+            &dashed_path
+        }
+        None => data,
+    };
+
+    // PDF supports neither `miter-clip` nor `arcs` joins,
+    // therefore such strokes are written as filled outlines.
+    let outline = if path.arcs_join {
+        Some(crate::stroke::stroke_arcs(data, &path.stroke, res_scale)?)
+    } else if path.stroke.line_join == tiny_skia::LineJoin::MiterClip {
+        Some(data.stroke(&path.stroke, res_scale)?)
+    } else {
+        None
+    };
+
+    let bbox = match outline {
+        Some(ref outline) => outline.compute_tight_bounds()?,
+        None => data
+            .stroke(&path.stroke, res_scale)?
+            .compute_tight_bounds()?,
+    };
+    let bbox = bbox.transform(path_ts)?;
+
+    if let Some(outline) = outline {
+        with_pattern_opacity(&path.paint, bbox, ctx, s, |ctx, s| {
+            s.content.save_state();
+            set_paint(&path.paint, true, ctx, ts, bbox, s);
+            s.content.transform(convert_transform(path_ts));
+            write_path(&outline, &mut s.content);
+            s.content.fill_nonzero();
+            s.content.restore_state();
+        });
+
+        return Some(());
+    }
+
+    with_pattern_opacity(&path.paint, bbox, ctx, s, |ctx, s| {
+        s.content.save_state();
+        set_paint(&path.paint, false, ctx, ts, bbox, s);
+        s.content.transform(convert_transform(path_ts));
+        s.content.set_line_width(path.stroke.width);
+        s.content.set_miter_limit(path.stroke.miter_limit);
+        s.content.set_line_cap(match path.stroke.line_cap {
+            tiny_skia::LineCap::Butt => LineCapStyle::ButtCap,
+            tiny_skia::LineCap::Round => LineCapStyle::RoundCap,
+            tiny_skia::LineCap::Square => LineCapStyle::ProjectingSquareCap,
+        });
+        s.content.set_line_join(match path.stroke.line_join {
+            tiny_skia::LineJoin::Miter | tiny_skia::LineJoin::MiterClip => LineJoinStyle::MiterJoin,
+            tiny_skia::LineJoin::Round => LineJoinStyle::RoundJoin,
+            tiny_skia::LineJoin::Bevel => LineJoinStyle::BevelJoin,
+        });
+        write_path(data, &mut s.content);
+        s.content.stroke();
+        s.content.restore_state();
+    });
+
+    Some(())
+}
+
+/// Pattern opacity has to be applied to the whole pattern fill and not to the pattern cell.
+/// Therefore such paths are drawn via a separate transparency group.
+fn with_pattern_opacity<F>(
+    paint: &Paint,
+    bbox: tiny_skia::Rect,
+    ctx: &mut Context,
+    s: &mut Stream,
+    draw: F,
+) where
+    F: FnOnce(&mut Context, &mut Stream),
+{
+    match paint {
+        Paint::Pattern(ref pattern) if pattern.opacity != usvg::Opacity::ONE => {
+            let mut layer = Stream::default();
+            draw(ctx, &mut layer);
+            let form = ctx.write_form(layer, bbox, false);
+
+            s.content.save_state();
+            let gs = ctx.write_ext_g_state(pattern.opacity.get(), tiny_skia::BlendMode::SourceOver);
+            s.set_ext_g_state(gs);
+            s.draw_x_object(form);
+            s.content.restore_state();
+        }
+        _ => draw(ctx, s),
+    }
+}
+
+fn fill(rule: tiny_skia::FillRule, content: &mut Content) {
+    match rule {
+        tiny_skia::FillRule::Winding => content.fill_nonzero(),
+        tiny_skia::FillRule::EvenOdd => content.fill_even_odd(),
+    };
+}
+
+/// Sets a fill or stroke paint.
+///
+/// `bbox` is the painted area in the current stream coordinates.
+/// Used for gradients with transparent stops.
+fn set_paint(
+    paint: &Paint,
+    is_fill: bool,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    bbox: tiny_skia::Rect,
+    s: &mut Stream,
+) {
+    match paint {
+        Paint::Shader(tiny_skia::Shader::SolidColor(ref c)) => {
+            if c.alpha() != 1.0 {
+                let gs = ctx.write_ext_g_state(c.alpha(), tiny_skia::BlendMode::SourceOver);
+                s.set_ext_g_state(gs);
+            }
+
+            if is_fill {
+                s.content.set_fill_rgb(c.red(), c.green(), c.blue());
+            } else {
+                s.content.set_stroke_rgb(c.red(), c.green(), c.blue());
+            }
+        }
+        Paint::Shader(_) => {
+            // Only solid colors are stored as raw shaders.
+            log::warn!("Unsupported shader type.");
+        }
+        Paint::Gradient(ref gradient) => {
+            if gradient.stops.iter().any(|stop| stop.color.alpha() != 1.0) {
+                // PDF shadings do not support transparency,
+                // therefore we have to use a separate soft mask with stop alphas.
+                let shading = write_shading(gradient, true, ctx, ts, bbox);
+
+                let mut mask_stream = Stream::default();
+                let name = mask_stream.resources.push_pattern(shading);
+                mask_stream
+                    .content
+                    .set_fill_color_space(ColorSpaceOperand::Pattern);
+                mask_stream
+                    .content
+                    .set_fill_pattern(None, Name(name.as_bytes()));
+                mask_stream
+                    .content
+                    .rect(bbox.x(), bbox.y(), bbox.width(), bbox.height());
+                mask_stream.content.fill_nonzero();
+
+                let mask_form = ctx.write_form(mask_stream, bbox, true);
+                let gs = ctx.write_soft_mask(mask_form, MaskType::Luminosity);
+                s.set_ext_g_state(gs);
+            }
+
+            let shading = write_shading(gradient, false, ctx, ts, bbox);
+            set_pattern(shading, is_fill, s);
+        }
+        Paint::Pattern(ref pattern) => {
+            let id = write_tiling_pattern(pattern, ctx, ts);
+            set_pattern(id, is_fill, s);
+        }
+    }
+}
+
+fn set_pattern(id: Ref, is_fill: bool, s: &mut Stream) {
+    let name = s.resources.push_pattern(id);
+    if is_fill {
+        s.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+        s.content.set_fill_pattern(None, Name(name.as_bytes()));
+    } else {
+        s.content.set_stroke_color_space(ColorSpaceOperand::Pattern);
+        s.content.set_stroke_pattern(None, Name(name.as_bytes()));
+    }
+}
+
+/// Writes a gradient as a shading pattern.
+///
+/// When `alpha` is set, the shading will contain stop opacities in the gray color space.
+fn write_shading(
+    gradient: &Gradient,
+    alpha: bool,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    bbox: tiny_skia::Rect,
+) -> Ref {
+    let pattern_ts = ts.pre_concat(gradient.transform);
+
+    // PDF shadings can only pad. Other spread methods are emulated
+    // by repeating the gradient enough times to cover the painted area.
+    let repeats = if gradient.spread_mode == tiny_skia::SpreadMode::Pad {
+        0
+    } else {
+        gradient_repeats(gradient, pattern_ts, bbox)
+    };
+
+    let (coords, t0, t1) = match gradient.kind {
+        GradientKind::Linear { x1, y1, x2, y2 } => {
+            let n = repeats as f32;
+            let dx = x2 - x1;
+            let dy = y2 - y1;
+            let coords = vec![x1 - dx * n, y1 - dy * n, x2 + dx * n, y2 + dy * n];
+            (coords, -repeats, repeats + 1)
+        }
+        GradientKind::Radial {
+            cx,
+            cy,
+            r,
+            fx,
+            fy,
+            fr,
+        } => {
+            // Circles are linearly interpolated from the focal circle,
+            // so we can simply extrapolate the end circle.
+            let n = (repeats + 1) as f32;
+            let coords = vec![
+                fx,
+                fy,
+                fr,
+                fx + (cx - fx) * n,
+                fy + (cy - fy) * n,
+                fr + (r - fr) * n,
+            ];
+            (coords, 0, repeats + 1)
+        }
+    };
+
+    let function =
+        write_gradient_function(&gradient.stops, gradient.spread_mode, t0, t1, alpha, ctx);
+
+    let id = ctx.alloc_ref();
+    let mut pattern = ctx.pdf.shading_pattern(id);
+    let mut shading = pattern.function_shading();
+    shading.shading_type(match gradient.kind {
+        GradientKind::Linear { .. } => FunctionShadingType::Axial,
+        GradientKind::Radial { .. } => FunctionShadingType::Radial,
+    });
+    if alpha {
+        shading.color_space().device_gray();
+    } else {
+        shading.color_space().device_rgb();
+    }
+    shading
+        .coords(coords)
+        .function(function)
+        .extend([true, true]);
+    shading.finish();
+    pattern.matrix(convert_transform(pattern_ts));
+    pattern.finish();
+
+    id
+}
+
+/// Calculates how many times a gradient should be repeated to cover `bbox`.
+fn gradient_repeats(
+    gradient: &Gradient,
+    pattern_ts: tiny_skia::Transform,
+    bbox: tiny_skia::Rect,
+) -> i32 {
+    let ts = match pattern_ts.invert() {
+        Some(v) => v,
+        None => return 0,
+    };
+
+    let mut corners = [
+        tiny_skia::Point::from_xy(bbox.left(), bbox.top()),
+        tiny_skia::Point::from_xy(bbox.right(), bbox.top()),
+        tiny_skia::Point::from_xy(bbox.right(), bbox.bottom()),
+        tiny_skia::Point::from_xy(bbox.left(), bbox.bottom()),
+    ];
+    ts.map_points(&mut corners);
+
+    let mut max_t: f32 = 0.0;
+    for p in &corners {
+        let t = match gradient.kind {
+            GradientKind::Linear { x1, y1, x2, y2 } => {
+                let dx = x2 - x1;
+                let dy = y2 - y1;
+                let len = dx * dx + dy * dy;
+                if len == 0.0 {
+                    return 0;
+                }
+
+                let t = ((p.x - x1) * dx + (p.y - y1) * dy) / len;
+                if t < 0.0 {
+                    -t
+                } else {
+                    t - 1.0
+                }
+            }
+            GradientKind::Radial {
+                cx,
+                cy,
+                r,
+                fx,
+                fy,
+                fr,
+            } => {
+                // A rough estimate that always covers the area,
+                // since circles cannot move faster than the focal point distance plus radius.
+                let dist = ((p.x - fx).hypot(p.y - fy) - fr).max(0.0);
+                let step = (r - fr) - (cx - fx).hypot(cy - fy);
+                if step <= 0.0 {
+                    return 0;
+                }
+
+                dist / step
+            }
+        };
+
+        max_t = max_t.max(t);
+    }
+
+    (max_t.ceil() as i32).min(MAX_GRADIENT_REPEATS)
+}
+
+/// Writes a stitching function for the gradient stops repeated over the `t0..t1` range.
+///
+/// The function domain is always `0..1`, where the original gradient
+/// is mapped to the `-t0..(1 - t0)` part of the range.
+fn write_gradient_function(
+    stops: &[GradientStop],
+    spread_mode: tiny_skia::SpreadMode,
+    t0: i32,
+    t1: i32,
+    alpha: bool,
+    ctx: &mut Context,
+) -> Ref {
+    let segments = gradient_segments(stops);
+
+    // A list of segment start offsets in the `t0..t1` range with their colors.
+    let mut parts = Vec::new();
+    for period in t0..t1 {
+        let offset = period as f32;
+        if spread_mode == tiny_skia::SpreadMode::Reflect && period % 2 != 0 {
+            for (_, end, c0, c1) in segments.iter().rev() {
+                parts.push((offset + 1.0 - end, c1, c0));
+            }
+        } else {
+            for (start, _, c0, c1) in &segments {
+                parts.push((offset + start, c0, c1));
+            }
+        }
+    }
+
+    let len = (t1 - t0) as f32;
+    let mut functions = Vec::new();
+    let mut bounds = Vec::new();
+    let mut encode = Vec::new();
+    for (start, c0, c1) in parts {
+        let id = ctx.alloc_ref();
+        ctx.pdf
+            .exponential_function(id)
+            .domain([0.0, 1.0])
+            .c0(color_components(c0, alpha))
+            .c1(color_components(c1, alpha))
+            .n(1.0);
+
+        if !functions.is_empty() {
+            bounds.push((start - t0 as f32) / len);
+        }
+        functions.push(id);
+        encode.extend([0.0, 1.0]);
+    }
+
+    if functions.len() == 1 {
+        return functions[0];
+    }
+
+    let id = ctx.alloc_ref();
+    ctx.pdf
+        .stitching_function(id)
+        .domain([0.0, 1.0])
+        .functions(functions)
+        .bounds(bounds)
+        .encode(encode);
+
+    id
+}
+
+/// Splits gradient stops into non-empty segments covering the `0..1` range.
+fn gradient_segments(
+    stops: &[GradientStop],
+) -> Vec<(f32, f32, tiny_skia::Color, tiny_skia::Color)> {
+    let mut points: Vec<(f32, tiny_skia::Color)> =
+        stops.iter().map(|stop| (stop.offset, stop.color)).collect();
+
+    if let Some(first) = points.first().copied() {
+        if first.0 > 0.0 {
+            points.insert(0, (0.0, first.1));
+        }
+    }
+
+    if let Some(last) = points.last().copied() {
+        if last.0 < 1.0 {
+            points.push((1.0, last.1));
+        }
+    }
+
+    let last_idx = points.len().saturating_sub(2);
+    let mut segments = Vec::new();
+    for (i, pair) in points.windows(2).enumerate() {
+        let (start, c0) = pair[0];
+        let (end, c1) = pair[1];
+        // Stops with the same offset produce a hard edge, not a segment.
+        // Unless they are at the gradient edges, where they define the padding color.
+        if end > start || i == 0 || i == last_idx {
+            segments.push((start, end, c0, c1));
+        }
+    }
+
+    // PDF functions cannot have empty subdomains, therefore edge segments
+    // must be a bit wider.
+    const EDGE: f32 = 0.0001;
+    if segments.len() > 1 {
+        if segments[0].1 == 0.0 {
+            segments[0].1 = EDGE;
+            segments[1].0 = EDGE;
+        }
+
+        let len = segments.len();
+        if segments[len - 1].0 == 1.0 {
+            segments[len - 1].0 = 1.0 - EDGE;
+            segments[len - 2].1 = 1.0 - EDGE;
+        }
+    }
+
+    if segments.is_empty() {
+        if let Some(last) = points.last() {
+            segments.push((0.0, 1.0, last.1, last.1));
+        }
+    }
+
+    segments
+}
+
+fn color_components(c: &tiny_skia::Color, alpha: bool) -> Vec<f32> {
+    if alpha {
+        vec![c.alpha()]
+    } else {
+        vec![c.red(), c.green(), c.blue()]
+    }
+}
+
+fn write_tiling_pattern(pattern: &Pattern, ctx: &mut Context, ts: tiny_skia::Transform) -> Ref {
+    let mut content_ts = tiny_skia::Transform::default();
+    if let Some(vbox) = pattern.view_box {
+        let vbox_ts =
+            usvg::utils::view_box_to_transform(vbox.rect, vbox.aspect, pattern.rect.size());
+        content_ts = content_ts.pre_concat(vbox_ts);
+    }
+    content_ts = content_ts.pre_concat(pattern.content_transform);
+
+    // Pattern cell has its own coordinate space.
+    let mut stream = Stream::default();
+    render_nodes(&pattern.children, ctx, content_ts, &mut stream);
+
+    let pattern_ts = ts
+        .pre_concat(pattern.transform)
+        .pre_translate(pattern.rect.x(), pattern.rect.y());
+
+    let id = ctx.alloc_ref();
+    let (data, resources) = stream.finish();
+    let mut tiling = ctx.pdf.tiling_pattern(id, &data);
+    tiling.filter(Filter::FlateDecode);
+    tiling
+        .paint_type(PaintType::Colored)
+        .tiling_type(TilingType::ConstantSpacing)
+        .bbox(Rect::new(
+            0.0,
+            0.0,
+            pattern.rect.width(),
+            pattern.rect.height(),
+        ))
+        .x_step(pattern.rect.width())
+        .y_step(pattern.rect.height())
+        .matrix(convert_transform(pattern_ts));
+    resources.write(tiling.resources());
+    tiling.finish();
+
+    id
+}
+
+fn render_image(image: &Image, ctx: &mut Context, ts: tiny_skia::Transform, s: &mut Stream) {
+    match image.kind {
+        #[cfg(feature = "raster-images")]
+        ImageKind::Raster(ref raster) => {
+            render_raster_image(image, raster, ctx, ts, s);
+        }
+        ImageKind::Vector(ref tree) => {
+            render_vector_image(image, tree, ctx, ts, s);
+        }
+    }
+}
+
+#[cfg(feature = "raster-images")]
+fn render_raster_image(
+    image: &Image,
+    raster: &tiny_skia::Pixmap,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    s: &mut Stream,
+) -> Option<()> {
+    let img_size = tiny_skia::IntSize::from_wh(raster.width(), raster.height())?;
+    let rect = crate::image::raster_images::image_rect(&image.view_box, img_size);
+
+    let interpolate = image.quality != tiny_skia::FilterQuality::Nearest;
+    let id = write_raster_image(raster, interpolate, ctx);
+
+    s.content.save_state();
+    if image.view_box.aspect.slice {
+        clip_rect(image.view_box.rect.to_rect(), ts, &mut s.content);
+    }
+
+    // Images are drawn into a unit square with the first row at the top.
+    let image_ts = ts.pre_concat(tiny_skia::Transform::from_row(
+        rect.width(),
+        0.0,
+        0.0,
+        -rect.height(),
+        rect.x(),
+        rect.y() + rect.height(),
+    ));
+    s.content.transform(convert_transform(image_ts));
+    s.draw_x_object(id);
+    s.content.restore_state();
+
+    Some(())
+}
+
+fn render_vector_image(
+    image: &Image,
+    tree: &Tree,
+    ctx: &mut Context,
+    ts: tiny_skia::Transform,
+    s: &mut Stream,
+) {
+    let img_size = tree.size.to_int_size();
+    let (image_ts, clip) = crate::geom::view_box_to_transform_with_clip(&image.view_box, img_size);
+
+    let view_box_ts =
+        usvg::utils::view_box_to_transform(tree.view_box.rect, tree.view_box.aspect, tree.size);
+    let tree_ts = ts.pre_concat(image_ts).pre_concat(view_box_ts);
+
+    s.content.save_state();
+    if let Some(clip) = clip {
+        clip_rect(clip.to_rect(), ts, &mut s.content);
+    }
+    render_nodes(&tree.children, ctx, tree_ts, s);
+    s.content.restore_state();
+}
+
+fn clip_rect(rect: tiny_skia::Rect, ts: tiny_skia::Transform, content: &mut Content) {
+    // The clip path is written in the stream coordinates,
+    // so we would not have to keep a `cm` operator active.
+    if let Some(path) = tiny_skia::PathBuilder::from_rect(rect).transform(ts) {
+        write_path(&path, content);
+        content.clip_nonzero();
+        content.end_path();
+    }
+}
+
+fn write_raster_image(pixmap: &tiny_skia::Pixmap, interpolate: bool, ctx: &mut Context) -> Ref {
+    let mut color = Vec::with_capacity(pixmap.pixels().len() * 3);
+    let mut alpha = Vec::with_capacity(pixmap.pixels().len());
+    for p in pixmap.pixels() {
+        let c = p.demultiply();
+        color.extend([c.red(), c.green(), c.blue()]);
+        alpha.push(c.alpha());
+    }
+
+    let mask_id = if alpha.iter().any(|a| *a != 255) {
+        let id = ctx.alloc_ref();
+        let data = miniz_oxide::deflate::compress_to_vec_zlib(&alpha, 6);
+        let mut mask = ctx.pdf.image_xobject(id, &data);
+        mask.filter(Filter::FlateDecode);
+        mask.width(pixmap.width() as i32)
+            .height(pixmap.height() as i32)
+            .bits_per_component(8)
+            .interpolate(interpolate);
+        mask.color_space().device_gray();
+        mask.finish();
+        Some(id)
+    } else {
+        None
+    };
+
+    let id = ctx.alloc_ref();
+    let data = miniz_oxide::deflate::compress_to_vec_zlib(&color, 6);
+    let mut image = ctx.pdf.image_xobject(id, &data);
+    image.filter(Filter::FlateDecode);
+    image
+        .width(pixmap.width() as i32)
+        .height(pixmap.height() as i32)
+        .bits_per_component(8)
+        .interpolate(interpolate);
+    image.color_space().device_rgb();
+    if let Some(mask_id) = mask_id {
+        image.s_mask(mask_id);
+    }
+    image.finish();
+
+    id
+}
+
+fn write_path(path: &tiny_skia::Path, content: &mut Content) {
+    let mut last = tiny_skia::Point::zero();
+    for segment in path.segments() {
+        match segment {
+            tiny_skia::PathSegment::MoveTo(p) => {
+                content.move_to(p.x, p.y);
+                last = p;
+            }
+            tiny_skia::PathSegment::LineTo(p) => {
+                content.line_to(p.x, p.y);
+                last = p;
+            }
+            tiny_skia::PathSegment::QuadTo(p1, p) => {
+                // PDF doesn't support quadratic curves.
+                let c1x = last.x + 2.0 / 3.0 * (p1.x - last.x);
+                let c1y = last.y + 2.0 / 3.0 * (p1.y - last.y);
+                let c2x = p.x + 2.0 / 3.0 * (p1.x - p.x);
+                let c2y = p.y + 2.0 / 3.0 * (p1.y - p.y);
+                content.cubic_to(c1x, c1y, c2x, c2y, p.x, p.y);
+                last = p;
+            }
+            tiny_skia::PathSegment::CubicTo(p1, p2, p) => {
+                content.cubic_to(p1.x, p1.y, p2.x, p2.y, p.x, p.y);
+                last = p;
+            }
+            tiny_skia::PathSegment::Close => {
+                content.close_path();
+            }
+        }
+    }
+}
+
+fn convert_transform(ts: tiny_skia::Transform) -> [f32; 6] {
+    [ts.sx, ts.ky, ts.kx, ts.sy, ts.tx, ts.ty]
+}
+
+fn convert_rect(r: tiny_skia::Rect) -> Rect {
+    Rect::new(r.left(), r.top(), r.right(), r.bottom())
+}
+
+fn convert_blend_mode(mode: tiny_skia::BlendMode) -> BlendMode {
+    match mode {
+        tiny_skia::BlendMode::Multiply => BlendMode::Multiply,
+        tiny_skia::BlendMode::Screen => BlendMode::Screen,
+        tiny_skia::BlendMode::Overlay => BlendMode::Overlay,
+        tiny_skia::BlendMode::Darken => BlendMode::Darken,
+        tiny_skia::BlendMode::Lighten => BlendMode::Lighten,
+        tiny_skia::BlendMode::ColorDodge => BlendMode::ColorDodge,
+        tiny_skia::BlendMode::ColorBurn => BlendMode::ColorBurn,
+        tiny_skia::BlendMode::HardLight => BlendMode::HardLight,
+        tiny_skia::BlendMode::SoftLight => BlendMode::SoftLight,
+        tiny_skia::BlendMode::Difference => BlendMode::Difference,
+        tiny_skia::BlendMode::Exclusion => BlendMode::Exclusion,
+        tiny_skia::BlendMode::Hue => BlendMode::Hue,
+        tiny_skia::BlendMode::Saturation => BlendMode::Saturation,
+        tiny_skia::BlendMode::Color => BlendMode::Color,
+        tiny_skia::BlendMode::Luminosity => BlendMode::Luminosity,
+        _ => BlendMode::Normal,
+    }
+}
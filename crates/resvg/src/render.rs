@@ -2,10 +2,80 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::filter::PixmapExt;
 use crate::tree::{Group, Node, OptionLog, Tree};
 
-pub struct Context {
+/// [`Tree::render_tiled`] splits canvases taller than this into horizontal tiles of this height.
+const TILE_HEIGHT: u32 = 512;
+
+#[derive(Clone, Copy)]
+pub struct Context<'a> {
     pub max_bbox: tiny_skia::IntRect,
+    /// A canvas tile we are rendering onto.
+    ///
+    /// `None` when rendering onto the whole canvas or onto a separate pixmap, like a layer.
+    pub tile: Option<Tile<'a>>,
+}
+
+impl Context<'_> {
+    /// Returns a context for rendering onto a separate pixmap, like a layer.
+    pub fn for_layer(&self) -> Self {
+        Context {
+            tile: None,
+            ..*self
+        }
+    }
+
+    /// Converts a canvas transform into a pixmap one.
+    fn pixmap_transform(&self, transform: tiny_skia::Transform) -> tiny_skia::Transform {
+        match self.tile {
+            Some(tile) => {
+                tiny_skia::Transform::from_translate(0.0, -(tile.y as f32)).pre_concat(transform)
+            }
+            None => transform,
+        }
+    }
+}
+
+/// A horizontal tile of the canvas.
+///
+/// Transforms are always in the canvas coordinates, so layers and filter regions
+/// are the same in each tile. The offset is applied only when drawing onto the tile.
+#[derive(Clone, Copy)]
+pub struct Tile<'a> {
+    /// The vertical position of the tile on the canvas.
+    pub y: i32,
+    /// Layers that are not cropped to a tile.
+    pub shared_layers: &'a SharedLayers,
+}
+
+type SharedLayer = Arc<Mutex<Option<Option<Arc<tiny_skia::Pixmap>>>>>;
+
+/// Group layers that are rendered only once and are shared between tiles.
+///
+/// Layers without filters are cropped to a tile, but filter results depend
+/// on the whole filter region, therefore groups with filters inside are rendered
+/// in full by the first tile that needs them.
+#[derive(Default)]
+pub struct SharedLayers {
+    layers: Mutex<HashMap<usize, SharedLayer>>,
+}
+
+impl SharedLayers {
+    fn get_or_render<F>(&self, group: &Group, render: F) -> Option<Arc<tiny_skia::Pixmap>>
+    where
+        F: FnOnce() -> Option<tiny_skia::Pixmap>,
+    {
+        let key = group as *const Group as usize;
+        let layer = self.layers.lock().unwrap().entry(key).or_default().clone();
+
+        // Other tiles will wait here until the layer is rendered.
+        let mut layer = layer.lock().unwrap();
+        layer.get_or_insert_with(|| render().map(Arc::new)).clone()
+    }
 }
 
 impl Tree {
@@ -16,12 +86,87 @@ impl Tree {
     ///
     /// The produced content is in the sRGB color space.
     pub fn render(&self, transform: tiny_skia::Transform, pixmap: &mut tiny_skia::PixmapMut) {
-        let target_size = tiny_skia::IntSize::from_wh(pixmap.width(), pixmap.height()).unwrap();
+        let canvas_size = tiny_skia::IntSize::from_wh(pixmap.width(), pixmap.height()).unwrap();
+        self.render_tile(transform, canvas_size, None, pixmap);
+    }
+
+    /// Renders the tree onto the pixmap using multiple threads.
+    ///
+    /// The pixmap is split into horizontal tiles, which are rendered in place
+    /// by up to `threads` threads. Each tile renders only the content that intersects it,
+    /// while groups with filters are rendered once and shared between tiles,
+    /// so filter results are the same as with [`Tree::render`].
+    ///
+    /// Anti-aliasing of edges crossing tile boundaries can still differ slightly,
+    /// because tiny-skia clips such edges to the tile.
+    pub fn render_tiled(
+        &self,
+        transform: tiny_skia::Transform,
+        threads: usize,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) {
+        let canvas_size = tiny_skia::IntSize::from_wh(pixmap.width(), pixmap.height()).unwrap();
+        if canvas_size.height() <= TILE_HEIGHT {
+            self.render_tile(transform, canvas_size, None, pixmap);
+            return;
+        }
+
+        let stride = canvas_size.width() as usize * tiny_skia::BYTES_PER_PIXEL;
+        let mut tiles = pixmap
+            .data_mut()
+            .chunks_mut(stride * TILE_HEIGHT as usize)
+            .enumerate();
+        let tiles_count = tiles.len();
+
+        let shared_layers = SharedLayers::default();
+        let render_tile = |idx: usize, data: &mut [u8]| {
+            let height = (data.len() / stride) as u32;
+            let mut pixmap =
+                tiny_skia::PixmapMut::from_bytes(data, canvas_size.width(), height).unwrap();
+            let tile = Tile {
+                y: (idx as u32 * TILE_HEIGHT) as i32,
+                shared_layers: &shared_layers,
+            };
+            self.render_tile(transform, canvas_size, Some(tile), &mut pixmap);
+        };
+
+        let threads = threads.clamp(1, tiles_count);
+        if threads == 1 {
+            for (idx, data) in tiles {
+                render_tile(idx, data);
+            }
+
+            return;
+        }
+
+        let tiles = Mutex::new(&mut tiles);
+        std::thread::scope(|s| {
+            for _ in 0..threads {
+                s.spawn(|| loop {
+                    // Do not hold the lock while rendering.
+                    let next = tiles.lock().unwrap().next();
+                    match next {
+                        Some((idx, data)) => render_tile(idx, data),
+                        None => break,
+                    }
+                });
+            }
+        });
+    }
+
+    /// Renders the tree onto a canvas tile or onto the whole canvas when `tile` is `None`.
+    fn render_tile(
+        &self,
+        transform: tiny_skia::Transform,
+        canvas_size: tiny_skia::IntSize,
+        tile: Option<Tile>,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) {
         let max_bbox = tiny_skia::IntRect::from_xywh(
-            -(target_size.width() as i32) * 2,
-            -(target_size.height() as i32) * 2,
-            target_size.width() * 4,
-            target_size.height() * 4,
+            -(canvas_size.width() as i32) * 2,
+            -(canvas_size.height() as i32) * 2,
+            canvas_size.width() * 4,
+            canvas_size.height() * 4,
         )
         .unwrap();
 
@@ -30,7 +175,7 @@ impl Tree {
 
         let root_transform = transform.pre_concat(ts);
 
-        let ctx = Context { max_bbox };
+        let ctx = Context { max_bbox, tile };
         render_nodes(&self.children, &ctx, root_transform, pixmap);
     }
 }
@@ -60,8 +205,8 @@ fn render_node(
             crate::path::render_fill_path(
                 path,
                 tiny_skia::BlendMode::SourceOver,
-                ctx,
-                transform,
+                &ctx.for_layer(),
+                ctx.pixmap_transform(transform),
                 pixmap,
             );
         }
@@ -69,17 +214,54 @@ fn render_node(
             crate::path::render_stroke_path(
                 path,
                 tiny_skia::BlendMode::SourceOver,
-                ctx,
-                transform,
+                &ctx.for_layer(),
+                ctx.pixmap_transform(transform),
                 pixmap,
             );
         }
         Node::Image(ref image) => {
-            crate::image::render_image(image, transform, pixmap);
+            crate::image::render_image(image, ctx.pixmap_transform(transform), pixmap);
         }
     }
 }
 
+/// Renders group's children into a group layer.
+///
+/// With `color-interpolation="linearRGB"`, each child is rendered separately
+/// and composited in the linearRGB color space.
+pub(crate) fn render_group_children(
+    group: &Group,
+    ctx: &Context,
+    transform: tiny_skia::Transform,
+    layer: &mut tiny_skia::Pixmap,
+) -> Option<()> {
+    if group.color_interpolation == usvg::filter::ColorInterpolation::SRGB {
+        render_nodes(&group.children, ctx, transform, &mut layer.as_mut());
+        return Some(());
+    }
+
+    let mut child_layer = tiny_skia::Pixmap::new(layer.width(), layer.height())
+        .log_none(|| log::warn!("Failed to allocate a group layer."))?;
+
+    for node in &group.children {
+        child_layer.fill(tiny_skia::Color::TRANSPARENT);
+        render_node(node, ctx, transform, &mut child_layer.as_mut());
+        child_layer.into_linear_rgb();
+
+        layer.draw_pixmap(
+            0,
+            0,
+            child_layer.as_ref(),
+            &tiny_skia::PixmapPaint::default(),
+            tiny_skia::Transform::identity(),
+            None,
+        );
+    }
+    layer.into_srgb();
+
+    Some(())
+}
+
 fn render_group(
     group: &Group,
     ctx: &Context,
@@ -93,7 +275,7 @@ fn render_group(
         return Some(());
     }
 
-    let bbox = group.bbox.transform(transform)?;
+    let bbox = group.layer_bbox(transform)?;
 
     let mut ibbox = if group.filters.is_empty() {
         // Convert group bbox into an integer one, expanding each side outwards by 2px
@@ -120,6 +302,17 @@ fn render_group(
         ibbox = crate::geom::fit_to_rect(ibbox, ctx.max_bbox)?;
     }
 
+    // Only the visible part of a layer without filters affects the result.
+    // Layers with filters inside are shared between tiles instead.
+    let tile = ctx.tile;
+    if let Some(tile) = tile {
+        if !group.contains_filters {
+            let tile_rect =
+                tiny_skia::IntRect::from_xywh(0, tile.y, pixmap.width(), pixmap.height())?;
+            ibbox = ibbox.intersect(&tile_rect)?;
+        }
+    }
+
     let shift_ts = {
         // Original shift.
         let mut dx = bbox.x();
@@ -134,10 +327,32 @@ fn render_group(
 
     let transform = shift_ts.pre_concat(transform);
 
+    match tile {
+        Some(tile) if group.contains_filters => {
+            let layer = tile
+                .shared_layers
+                .get_or_render(group, || render_layer(group, ctx, transform, ibbox))?;
+            draw_layer(group, &layer, ibbox, ctx, pixmap)
+        }
+        _ => {
+            let layer = render_layer(group, ctx, transform, ibbox)?;
+            draw_layer(group, &layer, ibbox, ctx, pixmap)
+        }
+    }
+}
+
+/// Renders group's content, filters, clip path and masks into a new layer.
+fn render_layer(
+    group: &Group,
+    ctx: &Context,
+    transform: tiny_skia::Transform,
+    ibbox: tiny_skia::IntRect,
+) -> Option<tiny_skia::Pixmap> {
     let mut sub_pixmap = tiny_skia::Pixmap::new(ibbox.width(), ibbox.height())
         .log_none(|| log::warn!("Failed to allocate a group layer for: {:?}.", ibbox))?;
 
-    render_nodes(&group.children, ctx, transform, &mut sub_pixmap.as_mut());
+    let layer_ctx = ctx.for_layer();
+    render_group_children(group, &layer_ctx, transform, &mut sub_pixmap);
 
     if !group.filters.is_empty() {
         for filter in &group.filters {
@@ -149,24 +364,69 @@ fn render_group(
         crate::clip::apply(clip_path, transform, &mut sub_pixmap);
     }
 
-    if let Some(ref mask) = group.mask {
-        crate::mask::apply(mask, ctx, transform, &mut sub_pixmap);
+    if !group.masks.is_empty() {
+        crate::mask::apply(&group.masks, &layer_ctx, transform, &mut sub_pixmap);
     }
 
-    let paint = tiny_skia::PixmapPaint {
-        opacity: group.opacity.get(),
+    Some(sub_pixmap)
+}
+
+/// Draws a group layer positioned at `ibbox` on the canvas.
+fn draw_layer(
+    group: &Group,
+    layer: &tiny_skia::Pixmap,
+    ibbox: tiny_skia::IntRect,
+    ctx: &Context,
+    pixmap: &mut tiny_skia::PixmapMut,
+) -> Option<()> {
+    let tile_y = match ctx.tile {
+        Some(tile) => tile.y,
+        None => {
+            let paint = tiny_skia::PixmapPaint {
+                opacity: group.opacity.get(),
+                blend_mode: group.blend_mode,
+                quality: tiny_skia::FilterQuality::Nearest,
+            };
+
+            pixmap.draw_pixmap(
+                ibbox.x(),
+                ibbox.y(),
+                layer.as_ref(),
+                &paint,
+                tiny_skia::Transform::identity(),
+                None,
+            );
+
+            return Some(());
+        }
+    };
+
+    // `PixmapMut::draw_pixmap` fills the layer rect with a pattern.
+    // Use the same rounding as `PixmapMut::draw_pixmap` in the canvas coordinates
+    // and only then move the rect onto the tile. Otherwise the result would depend on tiling,
+    // because tiny-skia rounds negative coordinates towards zero.
+    let rect = ibbox.to_rect().round()?;
+    let pixmap_rect = tiny_skia::IntRect::from_xywh(0, 0, pixmap.width(), pixmap.height())?;
+    let rect = rect
+        .translate(0, -tile_y)?
+        .intersect(&pixmap_rect)?
+        .to_rect();
+
+    let (x, y) = (ibbox.x() as f32, (ibbox.y() - tile_y) as f32);
+    let paint = tiny_skia::Paint {
+        shader: tiny_skia::Pattern::new(
+            layer.as_ref(),
+            tiny_skia::SpreadMode::Pad,
+            tiny_skia::FilterQuality::Nearest,
+            group.opacity.get(),
+            tiny_skia::Transform::from_translate(x, y),
+        ),
         blend_mode: group.blend_mode,
-        quality: tiny_skia::FilterQuality::Nearest,
+        anti_alias: false,
+        force_hq_pipeline: false,
     };
 
-    pixmap.draw_pixmap(
-        ibbox.x(),
-        ibbox.y(),
-        sub_pixmap.as_ref(),
-        &paint,
-        tiny_skia::Transform::identity(),
-        None,
-    );
+    pixmap.fill_rect(rect, &paint, tiny_skia::Transform::identity(), None);
 
     Some(())
 }
@@ -0,0 +1,672 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The SVG 2 `arcs` line join.
+//!
+//! tiny-skia doesn't support it, so we stroke a path using the `bevel` join first
+//! and then fill the gap between the bevel and the extended outer edges ourselves.
+//!
+//! https://www.w3.org/TR/SVG2/painting.html#LineJoin
+
+use std::f64::consts::PI;
+
+use tiny_skia::{Path, PathBuilder, PathSegment};
+
+/// Strokes a path using the `arcs` line join.
+///
+/// Returns an outline that must be filled using the `nonzero` fill rule.
+///
+/// `stroke.line_join` is ignored.
+pub fn stroke_arcs(path: &Path, stroke: &tiny_skia::Stroke, res_scale: f32) -> Option<Path> {
+    // Joins must be calculated on a dashed path.
+    let dashed_path;
+    let path = match stroke.dash {
+        Some(ref dash) => {
+            dashed_path = path.dash(dash, res_scale)?;
+            &dashed_path
+        }
+        None => path,
+    };
+
+    let bevel_stroke = tiny_skia::Stroke {
+        line_join: tiny_skia::LineJoin::Bevel,
+        dash: None,
+        ..stroke.clone()
+    };
+    let outline = path.stroke(&bevel_stroke, res_scale)?;
+
+    let half_width = f64::from(stroke.width) / 2.0;
+    let miter_limit = f64::from(stroke.miter_limit).max(1.0);
+
+    let mut builder = PathBuilder::new();
+    builder.push_path(&outline);
+    for contour in collect_contours(path) {
+        let edges = &contour.edges;
+        for pair in edges.windows(2) {
+            push_join(&pair[0], &pair[1], half_width, miter_limit, &mut builder);
+        }
+
+        if contour.closed && edges.len() > 1 {
+            push_join(
+                &edges[edges.len() - 1],
+                &edges[0],
+                half_width,
+                miter_limit,
+                &mut builder,
+            );
+        }
+    }
+
+    builder.finish()
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Vector {
+    x: f64,
+    y: f64,
+}
+
+impl Vector {
+    fn new(x: f64, y: f64) -> Self {
+        Vector { x, y }
+    }
+
+    fn from_point(p: tiny_skia::Point) -> Self {
+        Vector::new(f64::from(p.x), f64::from(p.y))
+    }
+
+    fn length(self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    fn normalized(self) -> Option<Self> {
+        let len = self.length();
+        if len.is_finite() && len > f64::EPSILON {
+            Some(self * (1.0 / len))
+        } else {
+            None
+        }
+    }
+
+    fn dot(self, other: Self) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    fn cross(self, other: Self) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Returns the same normal as tiny-skia's stroker.
+    ///
+    /// The stroker traverses this side of the stroke forward and the opposite one backward.
+    fn normal(self) -> Self {
+        Vector::new(self.y, -self.x)
+    }
+}
+
+impl std::ops::Add for Vector {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Vector::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl std::ops::Sub for Vector {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl std::ops::Mul<f64> for Vector {
+    type Output = Self;
+
+    fn mul(self, k: f64) -> Self {
+        Vector::new(self.x * k, self.y * k)
+    }
+}
+
+/// A path segment end.
+#[derive(Clone, Copy, Debug)]
+struct EdgeEnd {
+    point: Vector,
+    /// A unit tangent in the path direction.
+    tangent: Vector,
+    /// A signed curvature.
+    ///
+    /// The curvature center is at `point + tangent.normal() / curvature`.
+    curvature: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Edge {
+    start: EdgeEnd,
+    end: EdgeEnd,
+}
+
+struct Contour {
+    edges: Vec<Edge>,
+    closed: bool,
+}
+
+fn collect_contours(path: &Path) -> Vec<Contour> {
+    let mut contours = Vec::new();
+    let mut edges = Vec::new();
+    let mut start = Vector::new(0.0, 0.0);
+    let mut prev = start;
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                if !edges.is_empty() {
+                    contours.push(Contour {
+                        edges: std::mem::take(&mut edges),
+                        closed: false,
+                    });
+                }
+
+                start = Vector::from_point(p);
+                prev = start;
+            }
+            PathSegment::LineTo(p) => {
+                let p = Vector::from_point(p);
+                edges.extend(line_edge(prev, p));
+                prev = p;
+            }
+            PathSegment::QuadTo(p1, p) => {
+                let p1 = Vector::from_point(p1);
+                let p = Vector::from_point(p);
+                edges.extend(quad_edge(prev, p1, p));
+                prev = p;
+            }
+            PathSegment::CubicTo(p1, p2, p) => {
+                let p1 = Vector::from_point(p1);
+                let p2 = Vector::from_point(p2);
+                let p = Vector::from_point(p);
+                edges.extend(cubic_edge(prev, p1, p2, p));
+                prev = p;
+            }
+            PathSegment::Close => {
+                edges.extend(line_edge(prev, start));
+                contours.push(Contour {
+                    edges: std::mem::take(&mut edges),
+                    closed: true,
+                });
+                prev = start;
+            }
+        }
+    }
+
+    if !edges.is_empty() {
+        contours.push(Contour {
+            edges,
+            closed: false,
+        });
+    }
+
+    contours
+}
+
+fn line_edge(p0: Vector, p1: Vector) -> Option<Edge> {
+    let tangent = (p1 - p0).normalized()?;
+    Some(Edge {
+        start: EdgeEnd {
+            point: p0,
+            tangent,
+            curvature: 0.0,
+        },
+        end: EdgeEnd {
+            point: p1,
+            tangent,
+            curvature: 0.0,
+        },
+    })
+}
+
+fn quad_edge(p0: Vector, p1: Vector, p2: Vector) -> Option<Edge> {
+    let second = (p2 - p1 * 2.0 + p0) * 2.0;
+    Some(Edge {
+        start: curve_end(p0, (p1 - p0) * 2.0, &[p2 - p0], second)?,
+        end: curve_end(p2, (p2 - p1) * 2.0, &[p2 - p0], second)?,
+    })
+}
+
+fn cubic_edge(p0: Vector, p1: Vector, p2: Vector, p3: Vector) -> Option<Edge> {
+    Some(Edge {
+        start: curve_end(
+            p0,
+            (p1 - p0) * 3.0,
+            &[p2 - p0, p3 - p0],
+            (p2 - p1 * 2.0 + p0) * 6.0,
+        )?,
+        end: curve_end(
+            p3,
+            (p3 - p2) * 3.0,
+            &[p3 - p1, p3 - p0],
+            (p3 - p2 * 2.0 + p1) * 6.0,
+        )?,
+    })
+}
+
+/// `fallbacks` are used when a control point coincides with the end point.
+fn curve_end(
+    point: Vector,
+    first_derivative: Vector,
+    fallbacks: &[Vector],
+    second_derivative: Vector,
+) -> Option<EdgeEnd> {
+    if let Some(tangent) = first_derivative.normalized() {
+        let len = first_derivative.length();
+        let curvature = -first_derivative.cross(second_derivative) / (len * len * len);
+        return Some(EdgeEnd {
+            point,
+            tangent,
+            curvature: if curvature.is_finite() {
+                curvature
+            } else {
+                0.0
+            },
+        });
+    }
+
+    // The curvature is undefined at a degenerate end, so we treat it as a line.
+    let tangent = fallbacks.iter().find_map(|d| d.normalized())?;
+    Some(EdgeEnd {
+        point,
+        tangent,
+        curvature: 0.0,
+    })
+}
+
+/// An outer stroke edge extended beyond the join point.
+#[derive(Clone, Copy, Debug)]
+enum Extension {
+    Line {
+        origin: Vector,
+        direction: Vector,
+    },
+    Arc {
+        center: Vector,
+        radius: f64,
+        /// `1.0` when the angle increases in the path direction and `-1.0` otherwise.
+        sweep: f64,
+    },
+}
+
+impl Extension {
+    fn new(end: &EdgeEnd, offset: f64) -> Self {
+        let origin = end.point + end.tangent.normal() * offset;
+        let line = Extension::line(end, offset);
+
+        // The offset curve curvature.
+        let denom = 1.0 - end.curvature * offset;
+        if end.curvature == 0.0 || denom <= 1e-6 {
+            return line;
+        }
+
+        let curvature = end.curvature / denom;
+        let radius = 1.0 / curvature.abs();
+        if !radius.is_finite() || radius > 1e6 {
+            return line;
+        }
+
+        let center = origin + end.tangent.normal() * (1.0 / curvature);
+        let sweep = if (origin - center).cross(end.tangent) > 0.0 {
+            1.0
+        } else {
+            -1.0
+        };
+
+        Extension::Arc {
+            center,
+            radius,
+            sweep,
+        }
+    }
+
+    fn line(end: &EdgeEnd, offset: f64) -> Self {
+        Extension::Line {
+            origin: end.point + end.tangent.normal() * offset,
+            direction: end.tangent,
+        }
+    }
+
+    /// Returns a distance along the extension from `from` to `to` in the path direction.
+    ///
+    /// For lines, the distance can be negative.
+    fn distance(&self, from: Vector, to: Vector) -> f64 {
+        match *self {
+            Extension::Line { direction, .. } => (to - from).dot(direction),
+            Extension::Arc {
+                center,
+                radius,
+                sweep,
+            } => sweep_angle(center, from, to, sweep) * radius,
+        }
+    }
+}
+
+fn sweep_angle(center: Vector, from: Vector, to: Vector, sweep: f64) -> f64 {
+    let a0 = (from.y - center.y).atan2(from.x - center.x);
+    let a1 = (to.y - center.y).atan2(to.x - center.x);
+    let mut angle = (a1 - a0) * sweep;
+    while angle < 0.0 {
+        angle += 2.0 * PI;
+    }
+
+    while angle >= 2.0 * PI {
+        angle -= 2.0 * PI;
+    }
+
+    angle
+}
+
+fn intersect(e1: &Extension, e2: &Extension) -> Vec<Vector> {
+    match (*e1, *e2) {
+        (
+            Extension::Line {
+                origin: o1,
+                direction: d1,
+            },
+            Extension::Line {
+                origin: o2,
+                direction: d2,
+            },
+        ) => {
+            let denom = d1.cross(d2);
+            if denom.abs() <= f64::EPSILON {
+                return Vec::new();
+            }
+
+            let t = (o2 - o1).cross(d2) / denom;
+            vec![o1 + d1 * t]
+        }
+        (Extension::Line { origin, direction }, Extension::Arc { center, radius, .. })
+        | (Extension::Arc { center, radius, .. }, Extension::Line { origin, direction }) => {
+            intersect_line_circle(origin, direction, center, radius)
+        }
+        (
+            Extension::Arc {
+                center: c1,
+                radius: r1,
+                ..
+            },
+            Extension::Arc {
+                center: c2,
+                radius: r2,
+                ..
+            },
+        ) => {
+            let d = (c2 - c1).length();
+            if d <= f64::EPSILON || d > r1 + r2 || d < (r1 - r2).abs() {
+                return Vec::new();
+            }
+
+            let a = (r1 * r1 - r2 * r2 + d * d) / (2.0 * d);
+            let h = (r1 * r1 - a * a).max(0.0).sqrt();
+            let dir = (c2 - c1) * (1.0 / d);
+            let mid = c1 + dir * a;
+            let normal = dir.normal();
+            vec![mid + normal * h, mid - normal * h]
+        }
+    }
+}
+
+fn intersect_line_circle(
+    origin: Vector,
+    direction: Vector,
+    center: Vector,
+    radius: f64,
+) -> Vec<Vector> {
+    // `direction` is a unit vector.
+    let oc = origin - center;
+    let b = oc.dot(direction);
+    let c = oc.dot(oc) - radius * radius;
+    let disc = b * b - c;
+    if disc < 0.0 {
+        return Vec::new();
+    }
+
+    let sq = disc.sqrt();
+    vec![
+        origin + direction * (-b - sq),
+        origin + direction * (-b + sq),
+    ]
+}
+
+/// Fills the gap between the bevel and the `arcs` join on the outer side of the join.
+fn push_join(
+    incoming: &Edge,
+    outgoing: &Edge,
+    half_width: f64,
+    miter_limit: f64,
+    builder: &mut PathBuilder,
+) {
+    let end0 = incoming.end;
+    let end1 = outgoing.start;
+    let pivot = end0.point;
+
+    let cross = end0.tangent.cross(end1.tangent);
+    if cross.abs() <= 1e-6 {
+        // Either a straight line, where no join is needed,
+        // or a complete turn, where the bevel is the best we can do.
+        return;
+    }
+
+    // The outer side of the join.
+    let side = cross.signum();
+    let offset = side * half_width;
+    let start = pivot + end0.tangent.normal() * offset;
+    let end = pivot + end1.tangent.normal() * offset;
+
+    // The direction from the pivot to the join tip.
+    let bisector = match ((end0.tangent.normal() + end1.tangent.normal()) * side).normalized() {
+        Some(v) => v,
+        None => return,
+    };
+
+    let mut ext0 = Extension::new(&end0, offset);
+    let mut ext1 = Extension::new(&end1, offset);
+    let tip = match find_tip(&ext0, &ext1, start, end, pivot, bisector) {
+        Some(tip) => tip,
+        None => {
+            // When the extended edges do not intersect, fallback to `miter-clip`.
+            ext0 = Extension::line(&end0, offset);
+            ext1 = Extension::line(&end1, offset);
+            match find_tip(&ext0, &ext1, start, end, pivot, bisector) {
+                Some(tip) => tip,
+                None => return,
+            }
+        }
+    };
+
+    // Clip the join just like `miter-clip` does.
+    let clip_distance = miter_limit * half_width;
+    let (clip0, clip1) = if (tip - pivot).dot(bisector) > clip_distance {
+        let clip0 = clip_point(&ext0, start, tip, pivot, bisector, clip_distance, false);
+        let clip1 = clip_point(&ext1, end, tip, pivot, bisector, clip_distance, true);
+        match (clip0, clip1) {
+            (Some(c0), Some(c1)) => (c0, c1),
+            _ => return,
+        }
+    } else {
+        (tip, tip)
+    };
+
+    // The join has to be traversed in the same direction as the stroke outline,
+    // otherwise it would produce a hole when filled using the `nonzero` rule.
+    if side > 0.0 {
+        move_to(builder, start);
+        push_extension(builder, &ext0, start, clip0);
+        line_to(builder, clip1);
+        push_extension(builder, &ext1, clip1, end);
+    } else {
+        move_to(builder, end);
+        push_extension_reversed(builder, &ext1, end, clip1);
+        line_to(builder, clip0);
+        push_extension_reversed(builder, &ext0, clip0, start);
+    }
+    builder.close();
+}
+
+/// Finds the nearest point where both extended edges meet.
+fn find_tip(
+    ext0: &Extension,
+    ext1: &Extension,
+    start: Vector,
+    end: Vector,
+    pivot: Vector,
+    bisector: Vector,
+) -> Option<Vector> {
+    let mut best: Option<(Vector, f64)> = None;
+    for p in intersect(ext0, ext1) {
+        if (p - pivot).dot(bisector) <= 0.0 {
+            continue;
+        }
+
+        let d0 = ext0.distance(start, p);
+        let d1 = ext1.distance(p, end);
+        if d0 < 0.0 || d1 < 0.0 {
+            continue;
+        }
+
+        // Do not allow an extension to wrap around its circle.
+        if !is_short_arc(ext0, d0) || !is_short_arc(ext1, d1) {
+            continue;
+        }
+
+        let len = d0 + d1;
+        if best.map(|(_, l)| len < l).unwrap_or(true) {
+            best = Some((p, len));
+        }
+    }
+
+    best.map(|(p, _)| p)
+}
+
+fn is_short_arc(ext: &Extension, distance: f64) -> bool {
+    match *ext {
+        Extension::Line { .. } => true,
+        Extension::Arc { radius, .. } => distance / radius <= PI,
+    }
+}
+
+/// Finds where the extended edge crosses the clip line.
+///
+/// When `backward` is set, the edge is traversed from `end` to `tip` instead.
+fn clip_point(
+    ext: &Extension,
+    end: Vector,
+    tip: Vector,
+    pivot: Vector,
+    bisector: Vector,
+    clip_distance: f64,
+    backward: bool,
+) -> Option<Vector> {
+    let clip_origin = pivot + bisector * clip_distance;
+    let clip_direction = bisector.normal();
+    match *ext {
+        Extension::Line { direction, .. } => {
+            let denom = direction.dot(bisector);
+            if denom.abs() <= f64::EPSILON {
+                return None;
+            }
+
+            let t = (clip_origin - end).dot(bisector) / denom;
+            Some(end + direction * t)
+        }
+        Extension::Arc { center, radius, .. } => {
+            let mut best: Option<(Vector, f64)> = None;
+            for p in intersect_line_circle(clip_origin, clip_direction, center, radius) {
+                let d = if backward {
+                    ext.distance(p, end)
+                } else {
+                    ext.distance(end, p)
+                };
+
+                let max = if backward {
+                    ext.distance(tip, end)
+                } else {
+                    ext.distance(end, tip)
+                };
+
+                if d <= max && best.map(|(_, l)| d < l).unwrap_or(true) {
+                    best = Some((p, d));
+                }
+            }
+
+            best.map(|(p, _)| p)
+        }
+    }
+}
+
+fn move_to(builder: &mut PathBuilder, p: Vector) {
+    builder.move_to(p.x as f32, p.y as f32);
+}
+
+fn line_to(builder: &mut PathBuilder, p: Vector) {
+    builder.line_to(p.x as f32, p.y as f32);
+}
+
+/// Appends an extension part in the path direction.
+fn push_extension(builder: &mut PathBuilder, ext: &Extension, from: Vector, to: Vector) {
+    match *ext {
+        Extension::Line { .. } => line_to(builder, to),
+        Extension::Arc {
+            center,
+            radius,
+            sweep,
+        } => push_arc(builder, center, radius, from, to, sweep),
+    }
+}
+
+/// Appends an extension part against the path direction.
+fn push_extension_reversed(builder: &mut PathBuilder, ext: &Extension, from: Vector, to: Vector) {
+    match *ext {
+        Extension::Line { .. } => line_to(builder, to),
+        Extension::Arc {
+            center,
+            radius,
+            sweep,
+        } => push_arc(builder, center, radius, from, to, -sweep),
+    }
+}
+
+/// Appends a circular arc using cubic curves.
+fn push_arc(
+    builder: &mut PathBuilder,
+    center: Vector,
+    radius: f64,
+    from: Vector,
+    to: Vector,
+    sweep: f64,
+) {
+    let total = sweep_angle(center, from, to, sweep) * sweep;
+    let start_angle = (from.y - center.y).atan2(from.x - center.x);
+
+    let count = (total.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+    let step = total / count as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    for i in 0..count {
+        let a0 = start_angle + step * i as f64;
+        let a1 = a0 + step;
+        let p0 = center + Vector::new(a0.cos(), a0.sin()) * radius;
+        let p3 = if i + 1 == count {
+            to
+        } else {
+            center + Vector::new(a1.cos(), a1.sin()) * radius
+        };
+        let p1 = p0 + Vector::new(-a0.sin(), a0.cos()) * (radius * k);
+        let p2 = p3 - Vector::new(-a1.sin(), a1.cos()) * (radius * k);
+        builder.cubic_to(
+            p1.x as f32,
+            p1.y as f32,
+            p2.x as f32,
+            p2.y as f32,
+            p3.x as f32,
+            p3.y as f32,
+        );
+    }
+}
@@ -6,7 +6,7 @@ use usvg::NodeExt;
 
 use crate::clip::ClipPath;
 use crate::image::Image;
-use crate::mask::Mask;
+use crate::mask::{MaskLayer, MaskSource};
 use crate::path::{FillPath, StrokePath};
 
 pub struct Group {
@@ -14,11 +14,18 @@ pub struct Group {
     pub opacity: usvg::Opacity,
     pub blend_mode: tiny_skia::BlendMode,
     pub clip_path: Option<ClipPath>,
-    pub mask: Option<Mask>,
+    pub masks: Vec<MaskLayer>,
     pub filters: Vec<crate::filter::Filter>,
     pub isolate: bool,
+    pub color_interpolation: usvg::filter::ColorInterpolation,
+    /// Whether the group, its masks or any of its descendants have filters.
+    pub contains_filters: bool,
     /// Group's layer bounding box in object coordinates.
     pub bbox: tiny_skia::Rect,
+    /// Layer bounding box padding in canvas pixels.
+    ///
+    /// See [`BBoxes::canvas_padding`].
+    pub canvas_padding: f32,
 
     pub children: Vec<Node>,
 }
@@ -28,9 +35,20 @@ impl Group {
         self.opacity == usvg::Opacity::ONE
             && self.blend_mode == tiny_skia::BlendMode::SourceOver
             && self.clip_path.is_none()
-            && self.mask.is_none()
+            && self.masks.is_empty()
             && self.filters.is_empty()
             && !self.isolate
+            && self.color_interpolation == usvg::filter::ColorInterpolation::SRGB
+    }
+
+    /// Returns group's layer bounding box in canvas coordinates.
+    pub fn layer_bbox(&self, transform: tiny_skia::Transform) -> Option<tiny_skia::Rect> {
+        let bbox = self.bbox.transform(transform)?;
+        if self.canvas_padding > 0.0 {
+            bbox.outset(self.canvas_padding, self.canvas_padding)
+        } else {
+            Some(bbox)
+        }
     }
 }
 
@@ -50,6 +68,8 @@ pub enum Node {
 // - No `objectBoundingBox` units.
 
 /// A render tree.
+///
+/// The tree is immutable and can be rendered from multiple threads at the same time.
 pub struct Tree {
     /// Image size.
     ///
@@ -137,6 +157,12 @@ pub struct BBoxes {
 
     /// Similar to `object`, but expanded to fit the stroke as well.
     pub layer: usvg::BBox,
+
+    /// How far the content can extend beyond the `layer` bbox, in canvas pixels.
+    ///
+    /// The width of a non-scaling stroke depends on the rendering transform,
+    /// therefore its `layer` bbox is only an estimation.
+    pub canvas_padding: f32,
 }
 
 fn convert_node_inner(
@@ -148,7 +174,9 @@ fn convert_node_inner(
         usvg::NodeKind::Group(ref ugroup) => {
             convert_group(node.clone(), ugroup, text_bbox, children)
         }
-        usvg::NodeKind::Path(ref upath) => crate::path::convert(upath, text_bbox, children),
+        usvg::NodeKind::Path(ref upath) => {
+            crate::path::convert(upath, node.abs_transform(), text_bbox, children)
+        }
         usvg::NodeKind::Image(ref uimage) => crate::image::convert(uimage, children),
         usvg::NodeKind::Text(ref utext) => {
             if let (Some(bbox), Some(flattened)) = (utext.bounding_box, &utext.flattened) {
@@ -183,17 +211,25 @@ fn convert_group(
 
     if let Some(filter_bbox) = filter_bbox {
         bboxes.layer = usvg::BBox::from(filter_bbox);
+        // The filter region acts as a clipping region.
+        bboxes.canvas_padding = 0.0;
     }
 
+    let masks = crate::mask::convert(&ugroup.masks, bboxes.object.to_rect()?);
     let group = Group {
         transform: ugroup.transform,
         opacity: ugroup.opacity,
         blend_mode: convert_blend_mode(ugroup.blend_mode),
         clip_path: crate::clip::convert(ugroup.clip_path.clone(), bboxes.object.to_rect()?),
-        mask: crate::mask::convert(ugroup.mask.clone(), bboxes.object.to_rect()?),
         isolate: ugroup.isolate,
+        color_interpolation: ugroup.color_interpolation,
+        contains_filters: !filters.is_empty()
+            || contains_filters(&group_children)
+            || masks_contain_filters(&masks),
+        masks,
         filters,
         bbox: bboxes.layer.to_rect()?,
+        canvas_padding: bboxes.canvas_padding,
         children: group_children,
     };
 
@@ -217,10 +253,13 @@ fn convert_empty_group(ugroup: &usvg::Group, children: &mut Vec<Node>) -> Option
         opacity: ugroup.opacity,
         blend_mode: convert_blend_mode(ugroup.blend_mode),
         clip_path: None,
-        mask: None,
+        masks: Vec::new(),
         isolate: ugroup.isolate,
+        color_interpolation: ugroup.color_interpolation,
+        contains_filters: true,
         filters,
         bbox: layer_bbox,
+        canvas_padding: 0.0,
         children: Vec::new(),
     };
 
@@ -228,12 +267,28 @@ fn convert_empty_group(ugroup: &usvg::Group, children: &mut Vec<Node>) -> Option
         // TODO: find a better solution
         object: usvg::BBox::default(),
         layer: usvg::BBox::from(layer_bbox),
+        canvas_padding: 0.0,
     };
 
     children.push(Node::Group(group));
     Some(bboxes)
 }
 
+fn contains_filters(nodes: &[Node]) -> bool {
+    nodes
+        .iter()
+        .any(|node| matches!(node, Node::Group(ref group) if group.contains_filters))
+}
+
+fn masks_contain_filters(masks: &[MaskLayer]) -> bool {
+    masks.iter().any(|layer| match layer.source {
+        MaskSource::Mask(ref mask) => {
+            contains_filters(&mask.children) || masks_contain_filters(&mask.masks)
+        }
+        MaskSource::Image(_) => false,
+    })
+}
+
 fn convert_children(
     parent: usvg::Node,
     text_bbox: Option<tiny_skia::NonZeroRect>,
@@ -245,6 +300,7 @@ fn convert_children(
         if let Some(bboxes2) = convert_node_inner(node, text_bbox, children) {
             bboxes.object = bboxes.object.expand(bboxes2.object);
             bboxes.layer = bboxes.layer.expand(bboxes2.layer);
+            bboxes.canvas_padding = bboxes.canvas_padding.max(bboxes2.canvas_padding);
         }
     }
 
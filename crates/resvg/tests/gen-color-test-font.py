@@ -0,0 +1,125 @@
+#!/usr/bin/env python3
+
+# Generates `fonts/ColorTest.ttf`, a minimal font with color glyphs
+# from all supported tables:
+#
+# - A: COLRv0 layers
+# - B: COLRv1 linear gradient
+# - C: SVG document
+# - D: CBDT PNG bitmap
+#
+# Usage: ./gen-color-test-font.py fonts/ColorTest.ttf
+
+import struct, zlib, sys
+
+def png(w, h, rgba):
+    raw = b''.join(b'\x00' + bytes(rgba) * w for _ in range(h))
+    def chunk(t, d):
+        c = struct.pack('>I', len(d)) + t + d
+        return c + struct.pack('>I', zlib.crc32(t + d) & 0xffffffff)
+    return (b'\x89PNG\r\n\x1a\n' + chunk(b'IHDR', struct.pack('>IIBBBBB', w, h, 8, 6, 0, 0, 0))
+            + chunk(b'IDAT', zlib.compress(raw)) + chunk(b'IEND', b''))
+
+def square(x0, y0, x1, y1):
+    pts = [(x0, y0), (x0, y1), (x1, y1), (x1, y0)]
+    d = struct.pack('>hhhhh', 1, x0, y0, x1, y1) + struct.pack('>H', 3) + struct.pack('>H', 0)
+    d += bytes([0x01] * 4)
+    px, py = 0, 0
+    xs = b''; ys = b''
+    for x, y in pts:
+        xs += struct.pack('>h', x - px); ys += struct.pack('>h', y - py)
+        px, py = x, y
+    d += xs + ys
+    while len(d) % 4: d += b'\x00'
+    return d
+
+SQ = square(100, 0, 900, 800)
+glyphs = [b'', SQ, square(300, 200, 700, 600), SQ, b'', b'', b'']
+N = len(glyphs)
+
+glyf = b''; loca = []
+for g in glyphs:
+    loca.append(len(glyf)); glyf += g
+loca.append(len(glyf))
+loca = b''.join(struct.pack('>I', o) for o in loca)
+
+head = struct.pack('>IIIIHHqqhhhhHHhhh', 0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0x000B, 1000,
+                   0, 0, 0, -200, 1000, 800, 0, 8, 2, 1, 0)
+hhea = struct.pack('>IhhhHhhhhhhhhhhhH', 0x00010000, 800, -200, 0, 1000, 0, 0, 1000, 1, 0, 0,
+                   0, 0, 0, 0, 0, N)
+maxp = struct.pack('>IHHHHHHHHHHHHHH', 0x00010000, N, 4, 1, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0)
+os2 = struct.pack('>HhHHHhhhhhhhhhhh10s4I4sHHHhhhHH2IhhHHH', 4, 1000, 400, 5, 0,
+                  650, 600, 0, 75, 650, 600, 0, 350, 50, 300, 0, b'\x00' * 10, 0, 0, 0, 0,
+                  b'NONE', 0x40, 0x41, 0x44, 800, -200, 0, 800, 200, 1, 0, 500, 800, 0, 32, 0)
+assert len(os2) == 96, len(os2)
+hmtx = b''.join(struct.pack('>Hh', 1000, 0) for _ in range(N))
+
+seg_end = [0x44, 0xFFFF]; seg_start = [0x41, 0xFFFF]; seg_delta = [(3 - 0x41) & 0xFFFF, 1]
+sub = struct.pack('>HHHHHHH', 4, 0, 0, 4, 4, 1, 0)
+sub += b''.join(struct.pack('>H', v) for v in seg_end) + b'\x00\x00'
+sub += b''.join(struct.pack('>H', v) for v in seg_start)
+sub += b''.join(struct.pack('>H', v) for v in seg_delta)
+sub += b'\x00\x00' * 2
+sub = sub[:2] + struct.pack('>H', len(sub)) + sub[4:]
+cmap = struct.pack('>HHHHI', 0, 1, 3, 1, 12) + sub
+
+names = {1: 'Color Test', 2: 'Regular', 4: 'Color Test Regular', 6: 'ColorTest-Regular'}
+recs = b''; strs = b''
+for nid, text in names.items():
+    enc = text.encode('utf-16-be')
+    recs += struct.pack('>HHHHHH', 3, 1, 0x409, nid, len(enc), len(strs)); strs += enc
+name = struct.pack('>HHH', 0, len(names), 6 + len(recs)) + recs + strs
+
+post = struct.pack('>IIhhIIIII', 0x00030000, 0, -100, 50, 0, 0, 0, 0, 0)
+
+# CPAL: green, red, blue.
+colors = [(0, 128, 0), (255, 0, 0), (0, 0, 255)]
+cpal = struct.pack('>HHHHIH', 0, 3, 1, 3, 14, 0)
+cpal += b''.join(struct.pack('>BBBB', b, g, r, 255) for r, g, b in colors)
+
+# COLR v1 with a v0 glyph 3 and a v1 glyph 4.
+hdr_size = 34
+base_off = hdr_size
+layers_off = base_off + 6
+list_off = layers_off + 8
+colr = struct.pack('>HHIIHIIIII', 1, 1, base_off, layers_off, 2, list_off, 0, 0, 0, 0)
+colr += struct.pack('>HHH', 3, 0, 2)
+colr += struct.pack('>HH', 1, 0) + struct.pack('>HH', 2, 0xFFFF)
+bgl = struct.pack('>I', 1) + struct.pack('>HI', 4, 10)
+paint_glyph = struct.pack('>B', 10) + (6).to_bytes(3, 'big') + struct.pack('>H', 1)
+lin = struct.pack('>B', 4) + (16).to_bytes(3, 'big') + struct.pack('>hhhhhh', 100, 0, 900, 0, 100, 800)
+cline = struct.pack('>BH', 0, 2) + struct.pack('>hHh', 0, 1, 0x4000) + struct.pack('>hHh', 0x4000, 2, 0x4000)
+colr += bgl + paint_glyph + lin + cline
+
+doc = b'<svg xmlns="http://www.w3.org/2000/svg"><rect id="glyph5" x="100" y="-800" width="800" height="800" fill="#ff00ff"/></svg>'
+svg = struct.pack('>HII', 0, 10, 0) + struct.pack('>H', 1) + struct.pack('>HHII', 5, 5, 14, len(doc)) + doc
+
+img = png(8, 8, (255, 128, 0, 255))
+cbdt_glyph = struct.pack('>BBbbB', 8, 8, 1, 8, 10) + struct.pack('>I', len(img)) + img
+cbdt = struct.pack('>HH', 3, 0) + cbdt_glyph
+line = struct.pack('>bbBbbbbbbbbb', 8, -2, 10, 0, 0, 0, 0, 0, 8, -2, 0, 0)
+cblc = struct.pack('>HHI', 3, 0, 1)
+cblc += struct.pack('>IIII', 56, 24, 1, 0) + line + line + struct.pack('>HHBBBb', 6, 6, 10, 10, 32, 1)
+cblc += struct.pack('>HHI', 6, 6, 8)
+cblc += struct.pack('>HHI', 1, 17, 4) + struct.pack('>II', 0, len(cbdt_glyph))
+
+tables = {b'CBDT': cbdt, b'CBLC': cblc, b'COLR': colr, b'CPAL': cpal, b'OS/2': os2, b'SVG ': svg,
+          b'cmap': cmap, b'glyf': glyf, b'head': head, b'hhea': hhea, b'hmtx': hmtx, b'loca': loca,
+          b'maxp': maxp, b'name': name, b'post': post}
+
+def checksum(d):
+    d = d + b'\x00' * ((4 - len(d) % 4) % 4)
+    return sum(struct.unpack('>%dI' % (len(d) // 4), d)) & 0xffffffff
+
+n = len(tables)
+es = n.bit_length() - 1
+sr = (1 << es) * 16
+out = struct.pack('>IHHHH', 0x00010000, n, sr, es, n * 16 - sr)
+offset = 12 + 16 * n
+body = b''
+for tag in sorted(tables):
+    d = tables[tag]
+    out += struct.pack('>4sIII', tag, checksum(d), offset + len(body), len(d))
+    body += d + b'\x00' * ((4 - len(d) % 4) % 4)
+font = out + body
+open(sys.argv[1], 'wb').write(font)
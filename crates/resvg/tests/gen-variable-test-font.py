@@ -0,0 +1,141 @@
+#!/usr/bin/env python3
+
+# Generates `fonts/VariableTest.ttf`, a minimal variable font.
+# Each glyph is a square that reacts to a single axis:
+#
+# - A: `wght` 100..400..900, the square becomes wider/narrower
+# - B: `wdth` 50..100..200, the square and its advance are stretched
+# - C: `opsz` 8..12..72, the square becomes shorter
+# - D: `TEST` 0..0..100, a custom axis, the square moves up
+#
+# Usage: ./gen-variable-test-font.py fonts/VariableTest.ttf
+
+import struct, sys
+
+def square(x0, y0, x1, y1):
+    pts = [(x0, y0), (x0, y1), (x1, y1), (x1, y0)]
+    d = struct.pack('>hhhhh', 1, x0, y0, x1, y1) + struct.pack('>H', 3) + struct.pack('>H', 0)
+    d += bytes([0x01] * 4)
+    px, py = 0, 0
+    xs = b''; ys = b''
+    for x, y in pts:
+        xs += struct.pack('>h', x - px); ys += struct.pack('>h', y - py)
+        px, py = x, y
+    d += xs + ys
+    while len(d) % 4: d += b'\x00'
+    return d
+
+AXES = [(b'wght', 100, 400, 900), (b'wdth', 50, 100, 200), (b'opsz', 8, 12, 72), (b'TEST', 0, 0, 100)]
+
+# Points order is: bottom-left, top-left, top-right, bottom-right
+# and 4 phantom points: left, right (advance), top, bottom.
+# Each variation is (axis index, peak, x deltas, y deltas).
+GLYPHS = [
+    (b'', []),
+    (square(400, 0, 600, 700), [
+        (0, 1, [-150, -150, 150, 150, 0, 0, 0, 0], [0] * 8),
+        (0, -1, [75, 75, -75, -75, 0, 0, 0, 0], [0] * 8),
+    ]),
+    (square(100, 0, 900, 700), [
+        (1, 1, [0, 0, 800, 800, 0, 800, 0, 0], [0] * 8),
+        (1, -1, [0, 0, -400, -400, 0, -400, 0, 0], [0] * 8),
+    ]),
+    (square(100, 0, 900, 700), [
+        (2, 1, [0] * 8, [0, -400, -400, 0, 0, 0, 0, 0]),
+    ]),
+    (square(100, 0, 900, 700), [
+        (3, 1, [0] * 8, [300, 300, 300, 300, 0, 0, 0, 0]),
+    ]),
+]
+N = len(GLYPHS)
+
+glyf = b''; loca = []
+for g, _ in GLYPHS:
+    loca.append(len(glyf)); glyf += g
+loca.append(len(glyf))
+loca = b''.join(struct.pack('>I', o) for o in loca)
+
+head = struct.pack('>IIIIHHqqhhhhHHhhh', 0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0x000B, 1000,
+                   0, 0, 0, -200, 1000, 800, 0, 8, 2, 1, 0)
+hhea = struct.pack('>IhhhHhhhhhhhhhhhH', 0x00010000, 800, -200, 0, 1000, 0, 0, 1000, 1, 0, 0,
+                   0, 0, 0, 0, 0, N)
+maxp = struct.pack('>IHHHHHHHHHHHHHH', 0x00010000, N, 4, 1, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0)
+os2 = struct.pack('>HhHHHhhhhhhhhhhh10s4I4sHHHhhhHH2IhhHHH', 4, 1000, 400, 5, 0,
+                  650, 600, 0, 75, 650, 600, 0, 350, 50, 300, 0, b'\x00' * 10, 0, 0, 0, 0,
+                  b'NONE', 0x40, 0x41, 0x44, 800, -200, 0, 800, 200, 1, 0, 500, 800, 0, 32, 0)
+assert len(os2) == 96, len(os2)
+hmtx = b''.join(struct.pack('>Hh', 1000, 0) for _ in range(N))
+
+seg_end = [0x44, 0xFFFF]; seg_start = [0x41, 0xFFFF]; seg_delta = [(1 - 0x41) & 0xFFFF, 1]
+sub = struct.pack('>HHHHHHH', 4, 0, 0, 4, 4, 1, 0)
+sub += b''.join(struct.pack('>H', v) for v in seg_end) + b'\x00\x00'
+sub += b''.join(struct.pack('>H', v) for v in seg_start)
+sub += b''.join(struct.pack('>H', v) for v in seg_delta)
+sub += b'\x00\x00' * 2
+sub = sub[:2] + struct.pack('>H', len(sub)) + sub[4:]
+cmap = struct.pack('>HHHHI', 0, 1, 3, 1, 12) + sub
+
+names = {1: 'Variable Test', 2: 'Regular', 4: 'Variable Test Regular', 6: 'VariableTest-Regular',
+         256: 'Weight', 257: 'Width', 258: 'Optical size', 259: 'Test'}
+recs = b''; strs = b''
+for nid, text in names.items():
+    enc = text.encode('utf-16-be')
+    recs += struct.pack('>HHHHHH', 3, 1, 0x409, nid, len(enc), len(strs)); strs += enc
+name = struct.pack('>HHH', 0, len(names), 6 + len(recs)) + recs + strs
+
+post = struct.pack('>IIhhIIIII', 0x00030000, 0, -100, 50, 0, 0, 0, 0, 0)
+
+fvar = struct.pack('>HHHHHHHH', 1, 0, 16, 2, len(AXES), 20, 0, 4 + 4 * len(AXES))
+for i, (tag, lo, default, hi) in enumerate(AXES):
+    fvar += struct.pack('>4siiiHH', tag, lo << 16, default << 16, hi << 16, 0, 256 + i)
+
+def packed_words(values):
+    assert len(values) <= 64
+    return struct.pack('>B', 0x40 | (len(values) - 1)) + struct.pack('>%dh' % len(values), *values)
+
+def glyph_variations(variations):
+    if not variations:
+        return b''
+    headers = b''
+    # Shared point numbers: 0 means all points.
+    data = b'\x00'
+    for axis, peak, dx, dy in variations:
+        tuple_data = packed_words(dx) + packed_words(dy)
+        coords = [0] * len(AXES)
+        coords[axis] = peak * 0x4000
+        headers += struct.pack('>HH', len(tuple_data), 0x8000)
+        headers += b''.join(struct.pack('>h', c) for c in coords)
+        data += tuple_data
+    offset = 4 + len(headers)
+    return struct.pack('>HH', 0x8000 | len(variations), offset) + headers + data
+
+var_data = b''; var_offsets = []
+for _, variations in GLYPHS:
+    var_offsets.append(len(var_data))
+    var_data += glyph_variations(variations)
+    while len(var_data) % 2: var_data += b'\x00'
+var_offsets.append(len(var_data))
+gvar_header_size = 20 + 4 * (N + 1)
+gvar = struct.pack('>HHHHIHHI', 1, 0, len(AXES), 0, gvar_header_size, N, 1, gvar_header_size)
+gvar += b''.join(struct.pack('>I', o) for o in var_offsets) + var_data
+
+tables = {b'OS/2': os2, b'cmap': cmap, b'fvar': fvar, b'glyf': glyf, b'gvar': gvar,
+          b'head': head, b'hhea': hhea, b'hmtx': hmtx, b'loca': loca, b'maxp': maxp,
+          b'name': name, b'post': post}
+
+def checksum(d):
+    d = d + b'\x00' * ((4 - len(d) % 4) % 4)
+    return sum(struct.unpack('>%dI' % (len(d) // 4), d)) & 0xffffffff
+
+n = len(tables)
+es = n.bit_length() - 1
+sr = (1 << es) * 16
+out = struct.pack('>IHHHH', 0x00010000, n, sr, es, n * 16 - sr)
+offset = 12 + 16 * n
+body = b''
+for tag in sorted(tables):
+    d = tables[tag]
+    out += struct.pack('>4sIII', tag, checksum(d), offset + len(body), len(d))
+    body += d + b'\x00' * ((4 - len(d) % 4) % 4)
+font = out + body
+open(sys.argv[1], 'wb').write(font)
@@ -0,0 +1,294 @@
+#!/usr/bin/env python3
+
+# Generates `resources/woff-test.woff` and `resources/woff-test.woff2`,
+# a minimal TrueType font packed into WOFF and WOFF2 containers:
+#
+# - A: a circle made of quadratic curves with a square hole
+# - B: a triangle
+# - C: a composite glyph made of a scaled B and a shifted A
+#
+# WOFF2 uses the `glyf`, `loca` and `hmtx` transforms. Since Python has no Brotli encoder
+# in the standard library, the data is stored as uncompressed Brotli meta-blocks.
+#
+# Usage: ./gen-woff-test-fonts.py resources/woff-test
+
+import struct, zlib, sys
+
+# Contours are lists of (on_curve, x, y).
+NOTDEF = [
+    [(1, 50, 0), (1, 50, 800), (1, 550, 800), (1, 550, 0)],
+    [(1, 100, 50), (1, 500, 50), (1, 500, 750), (1, 100, 750)],
+]
+CIRCLE = [
+    [(1, 500, 50), (0, 150, 50), (1, 150, 400), (0, 150, 750),
+     (1, 500, 750), (0, 850, 750), (1, 850, 400), (0, 850, 50)],
+    # Small deltas, to test all triplet encodings.
+    [(1, 470, 370), (1, 530, 370), (1, 530, 430), (1, 470, 430)],
+]
+TRIANGLE = [
+    [(1, 100, 0), (1, 500, 800), (1, 900, 0)],
+]
+
+ARG_1_AND_2_ARE_WORDS = 0x0001
+ARGS_ARE_XY_VALUES = 0x0002
+WE_HAVE_A_SCALE = 0x0008
+MORE_COMPONENTS = 0x0020
+# Glyph 2 scaled by 0.5, then glyph 1 shifted by (300, 400).
+COMPOSITE = (
+    struct.pack('>HHbbh', ARGS_ARE_XY_VALUES | WE_HAVE_A_SCALE | MORE_COMPONENTS, 2, 0, 0, 0x2000)
+    + struct.pack('>HHhh', ARGS_ARE_XY_VALUES | ARG_1_AND_2_ARE_WORDS, 1, 300, 400)
+)
+COMPOSITE_BBOX = (50, 0, 1150, 1150)
+
+# .notdef, A, B, C, space
+GLYPHS = [NOTDEF, CIRCLE, TRIANGLE, COMPOSITE, None]
+N = len(GLYPHS)
+
+
+def bbox(glyph):
+    if glyph is None:
+        return (0, 0, 0, 0)
+    if isinstance(glyph, bytes):
+        return COMPOSITE_BBOX
+    xs = [p[1] for c in glyph for p in c]
+    ys = [p[2] for c in glyph for p in c]
+    return (min(xs), min(ys), max(xs), max(ys))
+
+
+def simple_glyph(contours):
+    d = struct.pack('>h4h', len(contours), *bbox(contours))
+    end = -1
+    for c in contours:
+        end += len(c)
+        d += struct.pack('>H', end)
+    d += struct.pack('>H', 0)
+    pts = [p for c in contours for p in c]
+    d += bytes(p[0] for p in pts)
+    px, py = 0, 0
+    xs = b''; ys = b''
+    for _, x, y in pts:
+        xs += struct.pack('>h', x - px); ys += struct.pack('>h', y - py)
+        px, py = x, y
+    return d + xs + ys
+
+
+def glyph_data(glyph):
+    if glyph is None:
+        return b''
+    if isinstance(glyph, bytes):
+        d = struct.pack('>h4h', -1, *COMPOSITE_BBOX) + glyph
+    else:
+        d = simple_glyph(glyph)
+    while len(d) % 4: d += b'\x00'
+    return d
+
+
+def build_ttf_tables():
+    glyf = b''; loca = []
+    for g in GLYPHS:
+        loca.append(len(glyf)); glyf += glyph_data(g)
+    loca.append(len(glyf))
+    loca = b''.join(struct.pack('>H', o // 2) for o in loca)
+
+    head = struct.pack('>IIIIHHqqhhhhHHhhh', 0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0x000B, 1000,
+                       0, 0, 0, -200, 1150, 1150, 0, 8, 2, 0, 0)
+    hhea = struct.pack('>IhhhHhhhhhhhhhhhH', 0x00010000, 800, -200, 0, 1000, 0, 0, 1000, 1, 0, 0,
+                       0, 0, 0, 0, 0, N)
+    maxp = struct.pack('>IHHHHHHHHHHHHHH', 0x00010000, N, 8, 2, 11, 4, 2, 0, 0, 0, 0, 0, 0, 2, 1)
+    os2 = struct.pack('>HhHHHhhhhhhhhhhh10s4I4sHHHhhhHH2IhhHHH', 4, 1000, 400, 5, 0,
+                      650, 600, 0, 75, 650, 600, 0, 350, 50, 300, 0, b'\x00' * 10, 0, 0, 0, 0,
+                      b'NONE', 0x40, 0x20, 0x43, 800, -200, 0, 800, 200, 1, 0, 500, 800, 0, 32, 0)
+    assert len(os2) == 96, len(os2)
+    # Left side bearings are equal to xMin, so WOFF2 can omit them.
+    hmtx = b''.join(struct.pack('>Hh', 1000, bbox(g)[0]) for g in GLYPHS)
+
+    seg_end = [0x20, 0x43, 0xFFFF]; seg_start = [0x20, 0x41, 0xFFFF]
+    seg_delta = [(4 - 0x20) & 0xFFFF, (1 - 0x41) & 0xFFFF, 1]
+    segs = len(seg_end)
+    sub = struct.pack('>HHHHHHH', 4, 0, 0, segs * 2, 4, 1, segs * 2 - 4)
+    sub += b''.join(struct.pack('>H', v) for v in seg_end) + b'\x00\x00'
+    sub += b''.join(struct.pack('>H', v) for v in seg_start)
+    sub += b''.join(struct.pack('>H', v) for v in seg_delta)
+    sub += b'\x00\x00' * segs
+    sub = sub[:2] + struct.pack('>H', len(sub)) + sub[4:]
+    cmap = struct.pack('>HHHHI', 0, 1, 3, 1, 12) + sub
+
+    names = {1: 'WOFF Test', 2: 'Regular', 4: 'WOFF Test Regular', 6: 'WOFFTest-Regular'}
+    recs = b''; strs = b''
+    for nid, text in names.items():
+        enc = text.encode('utf-16-be')
+        recs += struct.pack('>HHHHHH', 3, 1, 0x409, nid, len(enc), len(strs)); strs += enc
+    name = struct.pack('>HHH', 0, len(names), 6 + len(recs)) + recs + strs
+
+    post = struct.pack('>IIhhIIIII', 0x00030000, 0, -100, 50, 0, 0, 0, 0, 0)
+
+    return {b'OS/2': os2, b'cmap': cmap, b'glyf': glyf, b'head': head, b'hhea': hhea,
+            b'hmtx': hmtx, b'loca': loca, b'maxp': maxp, b'name': name, b'post': post}
+
+
+def checksum(d):
+    d = d + b'\x00' * ((4 - len(d) % 4) % 4)
+    return sum(struct.unpack('>%dI' % (len(d) // 4), d)) & 0xffffffff
+
+
+def pad4(d):
+    return d + b'\x00' * ((4 - len(d) % 4) % 4)
+
+
+def sfnt_size(tables):
+    return 12 + 16 * len(tables) + sum(len(pad4(d)) for d in tables.values())
+
+
+def build_woff(tables):
+    n = len(tables)
+    offset = 44 + 20 * n
+    directory = b''; body = b''
+    for tag in sorted(tables):
+        d = tables[tag]
+        comp = zlib.compress(d)
+        if len(comp) >= len(d):
+            comp = d
+        directory += struct.pack('>4sIIII', tag, offset + len(body), len(comp), len(d), checksum(d))
+        body += pad4(comp)
+    length = offset + len(body)
+    header = struct.pack('>4sIIHHIHHIIIII', b'wOFF', 0x00010000, length, n, 0, sfnt_size(tables),
+                         1, 0, 0, 0, 0, 0, 0)
+    return header + directory + body
+
+
+def base128(v):
+    out = [v & 0x7F]
+    v >>= 7
+    while v:
+        out.insert(0, 0x80 | (v & 0x7F))
+        v >>= 7
+    return bytes(out)
+
+
+def u255(v):
+    if v < 253:
+        return bytes([v])
+    if v < 506:
+        return bytes([255, v - 253])
+    if v < 762:
+        return bytes([254, v - 506])
+    return bytes([253]) + struct.pack('>H', v)
+
+
+def triplet(on_curve, x, y):
+    ax, ay = abs(x), abs(y)
+    on = 0 if on_curve else 128
+    xs = 0 if x < 0 else 1
+    ys = 0 if y < 0 else 1
+    xys = xs + 2 * ys
+    if x == 0 and ay < 1280:
+        return on + ((ay & 0xF00) >> 7) + ys, bytes([ay & 0xFF])
+    if y == 0 and ax < 1280:
+        return on + 10 + ((ax & 0xF00) >> 7) + xs, bytes([ax & 0xFF])
+    if ax < 65 and ay < 65:
+        return (on + 20 + ((ax - 1) & 0x30) + (((ay - 1) & 0x30) >> 2) + xys,
+                bytes([(((ax - 1) & 0xF) << 4) | ((ay - 1) & 0xF)]))
+    if ax < 769 and ay < 769:
+        return (on + 84 + 12 * (((ax - 1) & 0x300) >> 8) + (((ay - 1) & 0x300) >> 6) + xys,
+                bytes([(ax - 1) & 0xFF, (ay - 1) & 0xFF]))
+    if ax < 4096 and ay < 4096:
+        return on + 120 + xys, bytes([ax >> 4, ((ax & 0xF) << 4) | (ay >> 8), ay & 0xFF])
+    return on + 124 + xys, struct.pack('>HH', ax, ay)
+
+
+def transform_glyf():
+    n_contours = b''; n_points = b''; flags = b''; glyphs = b''; composites = b''
+    bitmap = bytearray(((N + 31) >> 5) << 2); bboxes = b''
+    for idx, g in enumerate(GLYPHS):
+        if g is None:
+            n_contours += struct.pack('>h', 0)
+        elif isinstance(g, bytes):
+            n_contours += struct.pack('>h', -1)
+            composites += g
+            bitmap[idx >> 3] |= 0x80 >> (idx & 7)
+            bboxes += struct.pack('>4h', *COMPOSITE_BBOX)
+        else:
+            n_contours += struct.pack('>h', len(g))
+            px, py = 0, 0
+            for c in g:
+                n_points += u255(len(c))
+                for on, x, y in c:
+                    flag, data = triplet(on, x - px, y - py)
+                    flags += bytes([flag]); glyphs += data
+                    px, py = x, y
+            glyphs += u255(0)  # instructionLength
+            # Store an explicit bounding box for .notdef only.
+            if idx == 0:
+                bitmap[0] |= 0x80
+                bboxes += struct.pack('>4h', *bbox(g))
+    streams = [n_contours, n_points, flags, glyphs, composites, bytes(bitmap) + bboxes, b'']
+    header = struct.pack('>HHHH', 0, 0, N, 0) + b''.join(struct.pack('>I', len(s)) for s in streams)
+    return header + b''.join(streams)
+
+
+def brotli_uncompressed(data):
+    bits = []
+    def write(value, count):
+        for i in range(count):
+            bits.append((value >> i) & 1)
+    def align():
+        while len(bits) % 8:
+            bits.append(0)
+    def flush():
+        out = bytearray()
+        for i in range(0, len(bits), 8):
+            out.append(sum(b << j for j, b in enumerate(bits[i:i + 8])))
+        bits.clear()
+        return bytes(out)
+
+    out = b''
+    write(0, 1)  # WBITS = 16
+    for i in range(0, len(data), 65536):
+        chunk = data[i:i + 65536]
+        write(0, 1)  # ISLAST
+        write(0, 2)  # MNIBBLES = 4
+        write(len(chunk) - 1, 16)
+        write(1, 1)  # ISUNCOMPRESSED
+        align()
+        out += flush() + chunk
+    write(1, 1)  # ISLAST
+    write(1, 1)  # ISLASTEMPTY
+    align()
+    return out + flush()
+
+
+KNOWN_TAGS = [b'cmap', b'head', b'hhea', b'hmtx', b'maxp', b'name', b'OS/2', b'post', b'cvt ',
+              b'fpgm', b'glyf', b'loca']
+
+
+def build_woff2(tables):
+    glyf = transform_glyf()
+    hmtx = b'\x03' + b''.join(struct.pack('>H', 1000) for _ in GLYPHS)
+
+    # `glyf` must be followed by `loca`.
+    order = [t for t in sorted(tables) if t != b'loca']
+    order.insert(order.index(b'glyf') + 1, b'loca')
+
+    directory = b''; stream = b''
+    for tag in order:
+        d = tables[tag]
+        flags = KNOWN_TAGS.index(tag)
+        if tag == b'glyf':
+            directory += bytes([flags]) + base128(len(d)) + base128(len(glyf)); stream += glyf
+        elif tag == b'loca':
+            directory += bytes([flags]) + base128(len(d)) + base128(0)
+        elif tag == b'hmtx':
+            directory += bytes([flags | 0x40]) + base128(len(d)) + base128(len(hmtx)); stream += hmtx
+        else:
+            directory += bytes([flags]) + base128(len(d)); stream += d
+
+    compressed = brotli_uncompressed(stream)
+    length = len(pad4(b'\x00' * (48 + len(directory)) + compressed))
+    header = struct.pack('>4sIIHHIIHHIIIII', b'wOF2', 0x00010000, length, len(tables), 0,
+                         sfnt_size(tables), len(compressed), 1, 0, 0, 0, 0, 0, 0)
+    return pad4(header + directory + compressed)
+
+
+tables = build_ttf_tables()
+open(sys.argv[1] + '.woff', 'wb').write(build_woff(tables))
+open(sys.argv[1] + '.woff2', 'wb').write(build_woff2(tables))
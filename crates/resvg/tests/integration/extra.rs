@@ -67,3 +67,79 @@ fn filter_region_precision() {
 fn translate_outside_viewbox() {
     assert_eq!(render_extra("extra/translate-outside-viewbox"), 0);
 }
+
+#[test]
+fn non_scaling_stroke_in_group_with_opacity() {
+    assert_eq!(
+        render_extra_with_scale("extra/non-scaling-stroke-in-group-with-opacity", 0.5),
+        0
+    );
+}
+
+#[test]
+fn render_from_multiple_threads() {
+    use usvg::TreeParsing;
+
+    let svg_data =
+        std::fs::read("tests/tests/filters/feImage/link-on-an-element-with-transform.svg").unwrap();
+    let mut opt = usvg::Options::default();
+    opt.resources_dir = Some(std::path::PathBuf::from("tests/tests/filters/feImage"));
+    let tree = usvg::Tree::from_data(&svg_data, &opt).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let render = || {
+        let size = rtree.size.to_int_size();
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height()).unwrap();
+        rtree.render(tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap
+    };
+
+    let expected = render();
+    std::thread::scope(|s| {
+        let threads: Vec<_> = (0..4).map(|_| s.spawn(render)).collect();
+        for thread in threads {
+            assert!(thread.join().unwrap() == expected);
+        }
+    });
+}
+
+#[test]
+fn render_tiled() {
+    use usvg::TreeParsing;
+
+    let files = [
+        "filters/feFlood/subregion-inheritance",
+        "filters/feGaussianBlur/simple-case",
+        "masking/mask/color-interpolation=linearRGB",
+        "paint-servers/pattern/pattern-on-child",
+        "painting/mix-blend-mode/color-burn",
+        "shapes/line/simple-case",
+        "structure/style/resolve-order",
+    ];
+
+    for name in files {
+        let path = format!("tests/tests/{}.svg", name);
+        let svg_data = std::fs::read(&path).unwrap();
+        let mut opt = usvg::Options::default();
+        opt.resources_dir = std::path::Path::new(&path).parent().map(|p| p.to_owned());
+        let tree = usvg::Tree::from_data(&svg_data, &opt).unwrap();
+        let rtree = resvg::Tree::from_usvg(&tree);
+
+        // Large enough to be split into multiple tiles,
+        // so paths, groups and filter regions are crossing tile boundaries.
+        let size = rtree.size.to_int_size().scale_to_width(1200).unwrap();
+        let transform = tiny_skia::Transform::from_scale(
+            size.width() as f32 / rtree.size.width(),
+            size.height() as f32 / rtree.size.height(),
+        );
+
+        let mut expected = tiny_skia::Pixmap::new(size.width(), size.height()).unwrap();
+        rtree.render(transform, &mut expected.as_mut());
+
+        for threads in [1, 2, 4] {
+            let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height()).unwrap();
+            rtree.render_tiled(transform, threads, &mut pixmap.as_mut());
+            assert!(pixmap == expected, "{} with {} threads", name, threads);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::field_reassign_with_default)]
+
 use once_cell::sync::Lazy;
 use rgb::{FromSlice, RGBA8};
 use usvg::{fontdb, TreeParsing, TreeTextToPath};
@@ -6,6 +8,8 @@ use usvg::{fontdb, TreeParsing, TreeTextToPath};
 mod render;
 
 mod extra;
+#[cfg(feature = "pdf")]
+mod pdf;
 
 const IMAGE_SIZE: u32 = 300;
 
@@ -36,7 +40,7 @@ pub fn render(name: &str) -> usize {
         let svg_data = std::fs::read(&svg_path).unwrap();
         let mut tree = usvg::Tree::from_data(&svg_data, &opt).unwrap();
         let db = GLOBAL_FONTDB.lock().unwrap();
-        tree.convert_text(&db);
+        tree.convert_text_with_svg_glyphs(&db, &usvg::parse_svg_glyph, None);
         tree
     };
 
@@ -197,7 +201,7 @@ fn gen_diff(name: &str, img1: &[u8], img2: &[u8]) -> Result<(), png::EncodingErr
 
     let path = std::path::PathBuf::from(format!("tests/{}-diff.png", name));
     let file = std::fs::File::create(path)?;
-    let ref mut w = std::io::BufWriter::new(file);
+    let w = &mut std::io::BufWriter::new(file);
 
     let mut encoder = png::Encoder::new(w, IMAGE_SIZE, IMAGE_SIZE);
     encoder.set_color(png::ColorType::Rgb);
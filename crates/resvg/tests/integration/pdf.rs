@@ -0,0 +1,89 @@
+use usvg::TreeParsing;
+
+fn render_pdf(svg: &str) -> Vec<u8> {
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+    let size = rtree.size;
+    rtree.render_pdf(tiny_skia::Transform::default(), size)
+}
+
+fn contains(data: &[u8], needle: &str) -> bool {
+    data.windows(needle.len()).any(|w| w == needle.as_bytes())
+}
+
+#[test]
+fn pdf_simple_shapes() {
+    let data = render_pdf(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+            <rect x='20' y='20' width='160' height='160' fill='green' stroke='black'/>
+        </svg>",
+    );
+
+    assert!(data.starts_with(b"%PDF-"));
+    assert!(contains(&data, "/MediaBox [0 0 200 200]"));
+    assert!(!contains(&data, "/Subtype /Image"));
+}
+
+#[test]
+fn pdf_gradient_is_vector() {
+    let data = render_pdf(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+            <linearGradient id='lg1' spreadMethod='reflect' x2='0.3'>
+                <stop offset='0' stop-color='white' stop-opacity='0.5'/>
+                <stop offset='1' stop-color='black'/>
+            </linearGradient>
+            <rect x='20' y='20' width='160' height='160' fill='url(#lg1)'/>
+        </svg>",
+    );
+
+    assert!(contains(&data, "/ShadingType 2"));
+    assert!(contains(&data, "/SMask"));
+    assert!(!contains(&data, "/Subtype /Image"));
+}
+
+#[test]
+fn pdf_filter_fallback() {
+    let data = render_pdf(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+            <filter id='filter1'>
+                <feGaussianBlur stdDeviation='5'/>
+            </filter>
+            <rect x='20' y='20' width='160' height='160' fill='green' filter='url(#filter1)'/>
+        </svg>",
+    );
+
+    assert!(contains(&data, "/Subtype /Image"));
+}
+
+#[test]
+fn pdf_mask_is_vector() {
+    let data = render_pdf(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+            <mask id='mask1'>
+                <rect x='40' y='40' width='120' height='120' fill='white'/>
+            </mask>
+            <rect x='20' y='20' width='160' height='160' fill='green' mask='url(#mask1)'/>
+        </svg>",
+    );
+
+    assert!(contains(&data, "/SMask"));
+    assert!(!contains(&data, "/Subtype /Image"));
+}
+
+#[test]
+fn pdf_mask_layers_fallback() {
+    let data = render_pdf(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+            <mask id='mask1'>
+                <rect x='40' y='40' width='120' height='120' fill='white'/>
+            </mask>
+            <mask id='mask2'>
+                <circle cx='100' cy='100' r='50' fill='white'/>
+            </mask>
+            <rect x='20' y='20' width='160' height='160' fill='green'
+                  style='mask:url(#mask1), url(#mask2) subtract'/>
+        </svg>",
+    );
+
+    assert!(contains(&data, "/Subtype /Image"));
+}
@@ -397,6 +397,12 @@ use crate::render;
 #[test] fn filters_flood_opacity_simple_case() { assert_eq!(render("tests/filters/flood-opacity/simple-case"), 0); }
 #[test] fn masking_clip_simple_case() { assert_eq!(render("tests/masking/clip/simple-case"), 0); }
 #[test] fn masking_clip_rule_clip_rule_eq_evenodd() { assert_eq!(render("tests/masking/clip-rule/clip-rule=evenodd"), 0); }
+#[test] fn masking_clipPath_circle_shorthand_on_group() { assert_eq!(render("tests/masking/clipPath/circle-shorthand-on-group"), 0); }
+#[test] fn masking_clipPath_circle_shorthand_with_farthest_side() { assert_eq!(render("tests/masking/clipPath/circle-shorthand-with-farthest-side"), 0); }
+#[test] fn masking_clipPath_circle_shorthand_with_fill_box() { assert_eq!(render("tests/masking/clipPath/circle-shorthand-with-fill-box"), 0); }
+#[test] fn masking_clipPath_circle_shorthand_with_four_value_position() { assert_eq!(render("tests/masking/clipPath/circle-shorthand-with-four-value-position"), 0); }
+#[test] fn masking_clipPath_circle_shorthand_with_position() { assert_eq!(render("tests/masking/clipPath/circle-shorthand-with-position"), 0); }
+#[test] fn masking_clipPath_circle_shorthand_with_radius() { assert_eq!(render("tests/masking/clipPath/circle-shorthand-with-radius"), 0); }
 #[test] fn masking_clipPath_circle_shorthand_with_stroke_box() { assert_eq!(render("tests/masking/clipPath/circle-shorthand-with-stroke-box"), 0); }
 #[test] fn masking_clipPath_circle_shorthand_with_view_box() { assert_eq!(render("tests/masking/clipPath/circle-shorthand-with-view-box"), 0); }
 #[test] fn masking_clipPath_circle_shorthand() { assert_eq!(render("tests/masking/clipPath/circle-shorthand"), 0); }
@@ -414,13 +420,24 @@ use crate::render;
 #[test] fn masking_clipPath_clipping_with_complex_text_2() { assert_eq!(render("tests/masking/clipPath/clipping-with-complex-text-2"), 0); }
 #[test] fn masking_clipPath_clipping_with_complex_text_and_clip_rule() { assert_eq!(render("tests/masking/clipPath/clipping-with-complex-text-and-clip-rule"), 0); }
 #[test] fn masking_clipPath_clipping_with_text() { assert_eq!(render("tests/masking/clipPath/clipping-with-text"), 0); }
+#[test] fn masking_clipPath_ellipse_shorthand_with_radii() { assert_eq!(render("tests/masking/clipPath/ellipse-shorthand-with-radii"), 0); }
+#[test] fn masking_clipPath_ellipse_shorthand() { assert_eq!(render("tests/masking/clipPath/ellipse-shorthand"), 0); }
+#[test] fn masking_clipPath_fill_box_shorthand() { assert_eq!(render("tests/masking/clipPath/fill-box-shorthand"), 0); }
 #[test] fn masking_clipPath_fill_has_no_effect() { assert_eq!(render("tests/masking/clipPath/fill-has-no-effect"), 0); }
 #[test] fn masking_clipPath_filter_has_no_effect() { assert_eq!(render("tests/masking/clipPath/filter-has-no-effect"), 0); }
 #[test] fn masking_clipPath_g_is_not_a_valid_child() { assert_eq!(render("tests/masking/clipPath/g-is-not-a-valid-child"), 0); }
 #[test] fn masking_clipPath_image_is_not_a_valid_child() { assert_eq!(render("tests/masking/clipPath/image-is-not-a-valid-child"), 0); }
+#[test] fn masking_clipPath_inset_shorthand_collapsed() { assert_eq!(render("tests/masking/clipPath/inset-shorthand-collapsed"), 0); }
+#[test] fn masking_clipPath_inset_shorthand_on_element_with_transform() { assert_eq!(render("tests/masking/clipPath/inset-shorthand-on-element-with-transform"), 0); }
+#[test] fn masking_clipPath_inset_shorthand_with_elliptical_round() { assert_eq!(render("tests/masking/clipPath/inset-shorthand-with-elliptical-round"), 0); }
+#[test] fn masking_clipPath_inset_shorthand_with_large_round() { assert_eq!(render("tests/masking/clipPath/inset-shorthand-with-large-round"), 0); }
+#[test] fn masking_clipPath_inset_shorthand_with_percentages() { assert_eq!(render("tests/masking/clipPath/inset-shorthand-with-percentages"), 0); }
+#[test] fn masking_clipPath_inset_shorthand_with_round() { assert_eq!(render("tests/masking/clipPath/inset-shorthand-with-round"), 0); }
+#[test] fn masking_clipPath_inset_shorthand() { assert_eq!(render("tests/masking/clipPath/inset-shorthand"), 0); }
 #[test] fn masking_clipPath_invalid_FuncIRI() { assert_eq!(render("tests/masking/clipPath/invalid-FuncIRI"), 0); }
 #[test] fn masking_clipPath_invalid_clip_path_on_child() { assert_eq!(render("tests/masking/clipPath/invalid-clip-path-on-child"), 0); }
 #[test] fn masking_clipPath_invalid_clip_path_on_self() { assert_eq!(render("tests/masking/clipPath/invalid-clip-path-on-self"), 0); }
+#[test] fn masking_clipPath_invalid_shorthand() { assert_eq!(render("tests/masking/clipPath/invalid-shorthand"), 0); }
 #[test] fn masking_clipPath_invalid_transform_on_clipPath() { assert_eq!(render("tests/masking/clipPath/invalid-transform-on-clipPath"), 0); }
 #[test] fn masking_clipPath_invisible_child_1() { assert_eq!(render("tests/masking/clipPath/invisible-child-1"), 0); }
 #[test] fn masking_clipPath_invisible_child_2() { assert_eq!(render("tests/masking/clipPath/invisible-child-2"), 0); }
@@ -437,6 +454,10 @@ use crate::render;
 #[test] fn masking_clipPath_on_the_root_svg_without_size() { assert_eq!(render("tests/masking/clipPath/on-the-root-svg-without-size"), 0); }
 #[test] fn masking_clipPath_opacity_has_no_effect() { assert_eq!(render("tests/masking/clipPath/opacity-has-no-effect"), 0); }
 #[test] fn masking_clipPath_overlapped_shapes_with_evenodd() { assert_eq!(render("tests/masking/clipPath/overlapped-shapes-with-evenodd"), 0); }
+#[test] fn masking_clipPath_path_shorthand_with_evenodd() { assert_eq!(render("tests/masking/clipPath/path-shorthand-with-evenodd"), 0); }
+#[test] fn masking_clipPath_path_shorthand() { assert_eq!(render("tests/masking/clipPath/path-shorthand"), 0); }
+#[test] fn masking_clipPath_polygon_shorthand_with_evenodd() { assert_eq!(render("tests/masking/clipPath/polygon-shorthand-with-evenodd"), 0); }
+#[test] fn masking_clipPath_polygon_shorthand() { assert_eq!(render("tests/masking/clipPath/polygon-shorthand"), 0); }
 #[test] fn masking_clipPath_recursive_on_child() { assert_eq!(render("tests/masking/clipPath/recursive-on-child"), 0); }
 #[test] fn masking_clipPath_recursive_on_self() { assert_eq!(render("tests/masking/clipPath/recursive-on-self"), 0); }
 #[test] fn masking_clipPath_recursive() { assert_eq!(render("tests/masking/clipPath/recursive"), 0); }
@@ -446,6 +467,7 @@ use crate::render;
 #[test] fn masking_clipPath_switch_is_not_a_valid_child() { assert_eq!(render("tests/masking/clipPath/switch-is-not-a-valid-child"), 0); }
 #[test] fn masking_clipPath_symbol_via_use_is_not_a_valid_child() { assert_eq!(render("tests/masking/clipPath/symbol-via-use-is-not-a-valid-child"), 0); }
 #[test] fn masking_clipPath_transform_on_clipPath() { assert_eq!(render("tests/masking/clipPath/transform-on-clipPath"), 0); }
+#[test] fn masking_clipPath_unknown_shorthand() { assert_eq!(render("tests/masking/clipPath/unknown-shorthand"), 0); }
 #[test] fn masking_clipPath_with_invalid_child_via_use() { assert_eq!(render("tests/masking/clipPath/with-invalid-child-via-use"), 0); }
 #[test] fn masking_clipPath_with_marker_on_clip() { assert_eq!(render("tests/masking/clipPath/with-marker-on-clip"), 0); }
 #[test] fn masking_clipPath_with_use_child() { assert_eq!(render("tests/masking/clipPath/with-use-child"), 0); }
@@ -453,8 +475,10 @@ use crate::render;
 #[test] fn masking_mask_half_width_region_with_rotation() { assert_eq!(render("tests/masking/mask/half-width-region-with-rotation"), 0); }
 #[test] fn masking_mask_invalid_FuncIRI() { assert_eq!(render("tests/masking/mask/invalid-FuncIRI"), 0); }
 #[test] fn masking_mask_invalid_child() { assert_eq!(render("tests/masking/mask/invalid-child"), 0); }
+#[test] fn masking_mask_invalid_shorthand() { assert_eq!(render("tests/masking/mask/invalid-shorthand"), 0); }
 #[test] fn masking_mask_invisible_child_1() { assert_eq!(render("tests/masking/mask/invisible-child-1"), 0); }
 #[test] fn masking_mask_invisible_child_2() { assert_eq!(render("tests/masking/mask/invisible-child-2"), 0); }
+#[test] fn masking_mask_longhand_overrides_shorthand() { assert_eq!(render("tests/masking/mask/longhand-overrides-shorthand"), 0); }
 #[test] fn masking_mask_mask_on_child() { assert_eq!(render("tests/masking/mask/mask-on-child"), 0); }
 #[test] fn masking_mask_mask_on_self_with_mask_type_eq_alpha() { assert_eq!(render("tests/masking/mask/mask-on-self-with-mask-type=alpha"), 0); }
 #[test] fn masking_mask_mask_on_self_with_mixed_mask_type() { assert_eq!(render("tests/masking/mask/mask-on-self-with-mixed-mask-type"), 0); }
@@ -468,16 +492,21 @@ use crate::render;
 #[test] fn masking_mask_maskUnits_eq_userSpaceOnUse_with_rect() { assert_eq!(render("tests/masking/mask/maskUnits=userSpaceOnUse-with-rect"), 0); }
 #[test] fn masking_mask_maskUnits_eq_userSpaceOnUse_with_width_only() { assert_eq!(render("tests/masking/mask/maskUnits=userSpaceOnUse-with-width-only"), 0); }
 #[test] fn masking_mask_maskUnits_eq_userSpaceOnUse_without_rect() { assert_eq!(render("tests/masking/mask/maskUnits=userSpaceOnUse-without-rect"), 0); }
+#[test] fn masking_mask_multiple_masks() { assert_eq!(render("tests/masking/mask/multiple-masks"), 0); }
+#[test] fn masking_mask_nested_layers() { assert_eq!(render("tests/masking/mask/nested-layers"), 0); }
 #[test] fn masking_mask_nested_objectBoundingBox() { assert_eq!(render("tests/masking/mask/nested-objectBoundingBox"), 0); }
 #[test] fn masking_mask_no_children() { assert_eq!(render("tests/masking/mask/no-children"), 0); }
 #[test] fn masking_mask_none() { assert_eq!(render("tests/masking/mask/none"), 0); }
 #[test] fn masking_mask_on_a_horizontal_line() { assert_eq!(render("tests/masking/mask/on-a-horizontal-line"), 0); }
 #[test] fn masking_mask_on_a_small_object() { assert_eq!(render("tests/masking/mask/on-a-small-object"), 0); }
 #[test] fn masking_mask_on_group_with_transform() { assert_eq!(render("tests/masking/mask/on-group-with-transform"), 0); }
+#[test] fn masking_mask_recursive_layers() { assert_eq!(render("tests/masking/mask/recursive-layers"), 0); }
 #[test] fn masking_mask_recursive_on_child() { assert_eq!(render("tests/masking/mask/recursive-on-child"), 0); }
 #[test] fn masking_mask_recursive_on_self() { assert_eq!(render("tests/masking/mask/recursive-on-self"), 0); }
 #[test] fn masking_mask_recursive() { assert_eq!(render("tests/masking/mask/recursive"), 0); }
 #[test] fn masking_mask_self_recursive() { assert_eq!(render("tests/masking/mask/self-recursive"), 0); }
+#[test] fn masking_mask_shorthand_with_geometry_boxes() { assert_eq!(render("tests/masking/mask/shorthand-with-geometry-boxes"), 0); }
+#[test] fn masking_mask_shorthand_with_image() { assert_eq!(render("tests/masking/mask/shorthand-with-image"), 0); }
 #[test] fn masking_mask_simple_case() { assert_eq!(render("tests/masking/mask/simple-case"), 0); }
 #[test] fn masking_mask_transform_has_no_effect() { assert_eq!(render("tests/masking/mask/transform-has-no-effect"), 0); }
 #[test] fn masking_mask_transform_on_shape() { assert_eq!(render("tests/masking/mask/transform-on-shape"), 0); }
@@ -487,11 +516,41 @@ use crate::render;
 #[test] fn masking_mask_with_opacity_1() { assert_eq!(render("tests/masking/mask/with-opacity-1"), 0); }
 #[test] fn masking_mask_with_opacity_2() { assert_eq!(render("tests/masking/mask/with-opacity-2"), 0); }
 #[test] fn masking_mask_with_opacity_3() { assert_eq!(render("tests/masking/mask/with-opacity-3"), 0); }
+#[test] fn masking_mask_composite_add() { assert_eq!(render("tests/masking/mask-composite/add"), 0); }
+#[test] fn masking_mask_composite_exclude() { assert_eq!(render("tests/masking/mask-composite/exclude"), 0); }
+#[test] fn masking_mask_composite_intersect() { assert_eq!(render("tests/masking/mask-composite/intersect"), 0); }
+#[test] fn masking_mask_composite_on_last_layer() { assert_eq!(render("tests/masking/mask-composite/on-last-layer"), 0); }
+#[test] fn masking_mask_composite_subtract() { assert_eq!(render("tests/masking/mask-composite/subtract"), 0); }
+#[test] fn masking_mask_composite_three_layers() { assert_eq!(render("tests/masking/mask-composite/three-layers"), 0); }
+#[test] fn masking_mask_image_css_gradient() { assert_eq!(render("tests/masking/mask-image/css-gradient"), 0); }
+#[test] fn masking_mask_image_external_png() { assert_eq!(render("tests/masking/mask-image/external-png"), 0); }
+#[test] fn masking_mask_image_mask_position_with_lengths() { assert_eq!(render("tests/masking/mask-image/mask-position-with-lengths"), 0); }
+#[test] fn masking_mask_image_mask_position_eq_center() { assert_eq!(render("tests/masking/mask-image/mask-position=center"), 0); }
+#[test] fn masking_mask_image_mask_position_eq_right_bottom() { assert_eq!(render("tests/masking/mask-image/mask-position=right-bottom"), 0); }
+#[test] fn masking_mask_image_mask_repeat_eq_no_repeat_repeat() { assert_eq!(render("tests/masking/mask-image/mask-repeat=no-repeat-repeat"), 0); }
+#[test] fn masking_mask_image_mask_repeat_eq_no_repeat() { assert_eq!(render("tests/masking/mask-image/mask-repeat=no-repeat"), 0); }
+#[test] fn masking_mask_image_mask_repeat_eq_repeat_x() { assert_eq!(render("tests/masking/mask-image/mask-repeat=repeat-x"), 0); }
+#[test] fn masking_mask_image_mask_size_in_percent() { assert_eq!(render("tests/masking/mask-image/mask-size-in-percent"), 0); }
+#[test] fn masking_mask_image_mask_size_with_two_values() { assert_eq!(render("tests/masking/mask-image/mask-size-with-two-values"), 0); }
+#[test] fn masking_mask_image_mask_size_eq_contain() { assert_eq!(render("tests/masking/mask-image/mask-size=contain"), 0); }
+#[test] fn masking_mask_image_mask_size_eq_cover() { assert_eq!(render("tests/masking/mask-image/mask-size=cover"), 0); }
+#[test] fn masking_mask_image_multiple_images() { assert_eq!(render("tests/masking/mask-image/multiple-images"), 0); }
+#[test] fn masking_mask_image_none() { assert_eq!(render("tests/masking/mask-image/none"), 0); }
+#[test] fn masking_mask_image_on_group() { assert_eq!(render("tests/masking/mask-image/on-group"), 0); }
+#[test] fn masking_mask_image_simple_case() { assert_eq!(render("tests/masking/mask-image/simple-case"), 0); }
+#[test] fn masking_mask_image_with_mask_element() { assert_eq!(render("tests/masking/mask-image/with-mask-element"), 0); }
+#[test] fn masking_mask_image_with_transform() { assert_eq!(render("tests/masking/mask-image/with-transform"), 0); }
+#[test] fn masking_mask_mode_alpha_on_mask_element() { assert_eq!(render("tests/masking/mask-mode/alpha-on-mask-element"), 0); }
+#[test] fn masking_mask_mode_luminance_on_image() { assert_eq!(render("tests/masking/mask-mode/luminance-on-image"), 0); }
+#[test] fn masking_mask_mode_luminance_on_mask_with_mask_type_eq_alpha() { assert_eq!(render("tests/masking/mask-mode/luminance-on-mask-with-mask-type=alpha"), 0); }
+#[test] fn masking_mask_mode_match_source_on_image() { assert_eq!(render("tests/masking/mask-mode/match-source-on-image"), 0); }
 #[test] fn paint_servers_linearGradient_attributes_via_xlink_href_complex_order() { assert_eq!(render("tests/paint-servers/linearGradient/attributes-via-xlink-href-complex-order"), 0); }
 #[test] fn paint_servers_linearGradient_attributes_via_xlink_href_from_radialGradient() { assert_eq!(render("tests/paint-servers/linearGradient/attributes-via-xlink-href-from-radialGradient"), 0); }
 #[test] fn paint_servers_linearGradient_attributes_via_xlink_href_from_rect() { assert_eq!(render("tests/paint-servers/linearGradient/attributes-via-xlink-href-from-rect"), 0); }
 #[test] fn paint_servers_linearGradient_attributes_via_xlink_href_only_required() { assert_eq!(render("tests/paint-servers/linearGradient/attributes-via-xlink-href-only-required"), 0); }
 #[test] fn paint_servers_linearGradient_attributes_via_xlink_href() { assert_eq!(render("tests/paint-servers/linearGradient/attributes-via-xlink-href"), 0); }
+#[test] fn paint_servers_linearGradient_color_interpolation_on_parent() { assert_eq!(render("tests/paint-servers/linearGradient/color-interpolation-on-parent"), 0); }
+#[test] fn paint_servers_linearGradient_color_interpolation_eq_linearRGB() { assert_eq!(render("tests/paint-servers/linearGradient/color-interpolation=linearRGB"), 0); }
 #[test] fn paint_servers_linearGradient_default_attributes() { assert_eq!(render("tests/paint-servers/linearGradient/default-attributes"), 0); }
 #[test] fn paint_servers_linearGradient_gradientTransform_and_transform() { assert_eq!(render("tests/paint-servers/linearGradient/gradientTransform-and-transform"), 0); }
 #[test] fn paint_servers_linearGradient_gradientTransform() { assert_eq!(render("tests/paint-servers/linearGradient/gradientTransform"), 0); }
@@ -562,6 +621,13 @@ use crate::render;
 #[test] fn paint_servers_radialGradient_attributes_via_xlink_href_only_required() { assert_eq!(render("tests/paint-servers/radialGradient/attributes-via-xlink-href-only-required"), 0); }
 #[test] fn paint_servers_radialGradient_attributes_via_xlink_href() { assert_eq!(render("tests/paint-servers/radialGradient/attributes-via-xlink-href"), 0); }
 #[test] fn paint_servers_radialGradient_default_attributes() { assert_eq!(render("tests/paint-servers/radialGradient/default-attributes"), 0); }
+#[test] fn paint_servers_radialGradient_fr_on_stroke() { assert_eq!(render("tests/paint-servers/radialGradient/fr-on-stroke"), 0); }
+#[test] fn paint_servers_radialGradient_fr_outside_of_the_end_circle() { assert_eq!(render("tests/paint-servers/radialGradient/fr-outside-of-the-end-circle"), 0); }
+#[test] fn paint_servers_radialGradient_fr_via_xlink_href() { assert_eq!(render("tests/paint-servers/radialGradient/fr-via-xlink-href"), 0); }
+#[test] fn paint_servers_radialGradient_fr_with_focal_point() { assert_eq!(render("tests/paint-servers/radialGradient/fr-with-focal-point"), 0); }
+#[test] fn paint_servers_radialGradient_fr_with_gradientTransform() { assert_eq!(render("tests/paint-servers/radialGradient/fr-with-gradientTransform"), 0); }
+#[test] fn paint_servers_radialGradient_fr_with_spreadMethod_eq_reflect() { assert_eq!(render("tests/paint-servers/radialGradient/fr-with-spreadMethod=reflect"), 0); }
+#[test] fn paint_servers_radialGradient_fr_with_spreadMethod_eq_repeat() { assert_eq!(render("tests/paint-servers/radialGradient/fr-with-spreadMethod=repeat"), 0); }
 #[test] fn paint_servers_radialGradient_fr_eq__1() { assert_eq!(render("tests/paint-servers/radialGradient/fr=-1"), 0); }
 #[test] fn paint_servers_radialGradient_fr_eq_0_2() { assert_eq!(render("tests/paint-servers/radialGradient/fr=0.2"), 0); }
 #[test] fn paint_servers_radialGradient_fr_eq_0_5() { assert_eq!(render("tests/paint-servers/radialGradient/fr=0.5"), 0); }
@@ -635,8 +701,26 @@ use crate::render;
 #[test] fn paint_servers_stop_color_simple_case() { assert_eq!(render("tests/paint-servers/stop-color/simple-case"), 0); }
 #[test] fn paint_servers_stop_opacity_50percent() { assert_eq!(render("tests/paint-servers/stop-opacity/50percent"), 0); }
 #[test] fn paint_servers_stop_opacity_simple_case() { assert_eq!(render("tests/paint-servers/stop-opacity/simple-case"), 0); }
+#[test] fn painting_color_color_display_p3() { assert_eq!(render("tests/painting/color/color-display-p3"), 0); }
 #[test] fn painting_color_inherit() { assert_eq!(render("tests/painting/color/inherit"), 0); }
+#[test] fn painting_color_lab() { assert_eq!(render("tests/painting/color/lab"), 0); }
+#[test] fn painting_color_lch() { assert_eq!(render("tests/painting/color/lch"), 0); }
+#[test] fn painting_color_oklab() { assert_eq!(render("tests/painting/color/oklab"), 0); }
+#[test] fn painting_color_oklch() { assert_eq!(render("tests/painting/color/oklch"), 0); }
 #[test] fn painting_color_simple_case() { assert_eq!(render("tests/painting/color/simple-case"), 0); }
+#[test] fn painting_color_interpolation_linearRGB_on_group() { assert_eq!(render("tests/painting/color-interpolation/linearRGB-on-group"), 0); }
+#[test] fn painting_color_interpolation_linearRGB_on_root_svg() { assert_eq!(render("tests/painting/color-interpolation/linearRGB-on-root-svg"), 0); }
+#[test] fn painting_color_interpolation_linearRGB_on_shape() { assert_eq!(render("tests/painting/color-interpolation/linearRGB-on-shape"), 0); }
+#[test] fn painting_color_interpolation_sRGB_on_group() { assert_eq!(render("tests/painting/color-interpolation/sRGB-on-group"), 0); }
+#[test] fn painting_context_in_marker_with_context_fill() { assert_eq!(render("tests/painting/context/in-marker-with-context-fill"), 0); }
+#[test] fn painting_context_in_marker_with_stroke_opacity() { assert_eq!(render("tests/painting/context/in-marker-with-stroke-opacity"), 0); }
+#[test] fn painting_context_in_marker_without_stroke() { assert_eq!(render("tests/painting/context/in-marker-without-stroke"), 0); }
+#[test] fn painting_context_in_marker() { assert_eq!(render("tests/painting/context/in-marker"), 0); }
+#[test] fn painting_context_in_nested_use() { assert_eq!(render("tests/painting/context/in-nested-use"), 0); }
+#[test] fn painting_context_in_use_with_gradient() { assert_eq!(render("tests/painting/context/in-use-with-gradient"), 0); }
+#[test] fn painting_context_in_use() { assert_eq!(render("tests/painting/context/in-use"), 0); }
+#[test] fn painting_context_on_text_in_use() { assert_eq!(render("tests/painting/context/on-text-in-use"), 0); }
+#[test] fn painting_context_without_context_element() { assert_eq!(render("tests/painting/context/without-context-element"), 0); }
 #[test] fn painting_display_bBox_impact() { assert_eq!(render("tests/painting/display/bBox-impact"), 0); }
 #[test] fn painting_display_none_on_clipPath() { assert_eq!(render("tests/painting/display/none-on-clipPath"), 0); }
 #[test] fn painting_display_none_on_defs() { assert_eq!(render("tests/painting/display/none-on-defs"), 0); }
@@ -892,6 +976,12 @@ use crate::render;
 #[test] fn painting_stroke_linecap_zero_length_path_with_butt() { assert_eq!(render("tests/painting/stroke-linecap/zero-length-path-with-butt"), 0); }
 #[test] fn painting_stroke_linecap_zero_length_path_with_round() { assert_eq!(render("tests/painting/stroke-linecap/zero-length-path-with-round"), 0); }
 #[test] fn painting_stroke_linecap_zero_length_path_with_square() { assert_eq!(render("tests/painting/stroke-linecap/zero-length-path-with-square"), 0); }
+#[test] fn painting_stroke_linejoin_arcs_on_closed_path() { assert_eq!(render("tests/painting/stroke-linejoin/arcs-on-closed-path"), 0); }
+#[test] fn painting_stroke_linejoin_arcs_on_counterclockwise_path() { assert_eq!(render("tests/painting/stroke-linejoin/arcs-on-counterclockwise-path"), 0); }
+#[test] fn painting_stroke_linejoin_arcs_on_lines() { assert_eq!(render("tests/painting/stroke-linejoin/arcs-on-lines"), 0); }
+#[test] fn painting_stroke_linejoin_arcs_with_dasharray() { assert_eq!(render("tests/painting/stroke-linejoin/arcs-with-dasharray"), 0); }
+#[test] fn painting_stroke_linejoin_arcs_with_miterlimit() { assert_eq!(render("tests/painting/stroke-linejoin/arcs-with-miterlimit"), 0); }
+#[test] fn painting_stroke_linejoin_arcs_with_opacity() { assert_eq!(render("tests/painting/stroke-linejoin/arcs-with-opacity"), 0); }
 #[test] fn painting_stroke_linejoin_arcs() { assert_eq!(render("tests/painting/stroke-linejoin/arcs"), 0); }
 #[test] fn painting_stroke_linejoin_bevel() { assert_eq!(render("tests/painting/stroke-linejoin/bevel"), 0); }
 #[test] fn painting_stroke_linejoin_miter_clip() { assert_eq!(render("tests/painting/stroke-linejoin/miter-clip"), 0); }
@@ -915,6 +1005,15 @@ use crate::render;
 #[test] fn painting_stroke_width_negative() { assert_eq!(render("tests/painting/stroke-width/negative"), 0); }
 #[test] fn painting_stroke_width_percentage() { assert_eq!(render("tests/painting/stroke-width/percentage"), 0); }
 #[test] fn painting_stroke_width_zero() { assert_eq!(render("tests/painting/stroke-width/zero"), 0); }
+#[test] fn painting_vector_effect_in_group_with_opacity() { assert_eq!(render("tests/painting/vector-effect/in-group-with-opacity"), 0); }
+#[test] fn painting_vector_effect_non_scaling_stroke() { assert_eq!(render("tests/painting/vector-effect/non-scaling-stroke"), 0); }
+#[test] fn painting_vector_effect_none() { assert_eq!(render("tests/painting/vector-effect/none"), 0); }
+#[test] fn painting_vector_effect_not_inherited() { assert_eq!(render("tests/painting/vector-effect/not-inherited"), 0); }
+#[test] fn painting_vector_effect_on_text() { assert_eq!(render("tests/painting/vector-effect/on-text"), 0); }
+#[test] fn painting_vector_effect_with_dasharray() { assert_eq!(render("tests/painting/vector-effect/with-dasharray"), 0); }
+#[test] fn painting_vector_effect_with_gradient() { assert_eq!(render("tests/painting/vector-effect/with-gradient"), 0); }
+#[test] fn painting_vector_effect_with_pattern() { assert_eq!(render("tests/painting/vector-effect/with-pattern"), 0); }
+#[test] fn painting_vector_effect_with_rotation_and_skew() { assert_eq!(render("tests/painting/vector-effect/with-rotation-and-skew"), 0); }
 #[test] fn painting_visibility_bbox_impact_1() { assert_eq!(render("tests/painting/visibility/bbox-impact-1"), 0); }
 #[test] fn painting_visibility_bbox_impact_2() { assert_eq!(render("tests/painting/visibility/bbox-impact-2"), 0); }
 #[test] fn painting_visibility_bbox_impact_3() { assert_eq!(render("tests/painting/visibility/bbox-impact-3"), 0); }
@@ -1115,22 +1214,45 @@ use crate::render;
 #[test] fn structure_image_with_zero_width_and_height() { assert_eq!(render("tests/structure/image/with-zero-width-and-height"), 0); }
 #[test] fn structure_image_zero_height() { assert_eq!(render("tests/structure/image/zero-height"), 0); }
 #[test] fn structure_image_zero_width() { assert_eq!(render("tests/structure/image/zero-width"), 0); }
+#[test] fn structure_style_attribute_selector_operators() { assert_eq!(render("tests/structure/style/attribute-selector-operators"), 0); }
 #[test] fn structure_style_attribute_selector() { assert_eq!(render("tests/structure/style/attribute-selector"), 0); }
 #[test] fn structure_style_class_selector() { assert_eq!(render("tests/structure/style/class-selector"), 0); }
 #[test] fn structure_style_combined_selectors() { assert_eq!(render("tests/structure/style/combined-selectors"), 0); }
 #[test] fn structure_style_external_CSS() { assert_eq!(render("tests/structure/style/external-CSS"), 0); }
 #[test] fn structure_style_iD_selector() { assert_eq!(render("tests/structure/style/iD-selector"), 0); }
+#[test] fn structure_style_important_in_style_attribute_and_style_element() { assert_eq!(render("tests/structure/style/important-in-style-attribute-and-style-element"), 0); }
+#[test] fn structure_style_important_in_style_attribute() { assert_eq!(render("tests/structure/style/important-in-style-attribute"), 0); }
 #[test] fn structure_style_important() { assert_eq!(render("tests/structure/style/important"), 0); }
 #[test] fn structure_style_invalid_type() { assert_eq!(render("tests/structure/style/invalid-type"), 0); }
+#[test] fn structure_style_is_and_where_pseudo_classes() { assert_eq!(render("tests/structure/style/is-and-where-pseudo-classes"), 0); }
+#[test] fn structure_style_media_not() { assert_eq!(render("tests/structure/style/media-not"), 0); }
+#[test] fn structure_style_media_prefers_color_scheme() { assert_eq!(render("tests/structure/style/media-prefers-color-scheme"), 0); }
+#[test] fn structure_style_media_range_syntax() { assert_eq!(render("tests/structure/style/media-range-syntax"), 0); }
+#[test] fn structure_style_media_unsupported_feature() { assert_eq!(render("tests/structure/style/media-unsupported-feature"), 0); }
+#[test] fn structure_style_media_width() { assert_eq!(render("tests/structure/style/media-width"), 0); }
 #[test] fn structure_style_non_presentational_attribute() { assert_eq!(render("tests/structure/style/non-presentational-attribute"), 0); }
+#[test] fn structure_style_not_pseudo_class() { assert_eq!(render("tests/structure/style/not-pseudo-class"), 0); }
+#[test] fn structure_style_nth_child_pseudo_class() { assert_eq!(render("tests/structure/style/nth-child-pseudo-class"), 0); }
+#[test] fn structure_style_nth_last_child_pseudo_class() { assert_eq!(render("tests/structure/style/nth-last-child-pseudo-class"), 0); }
+#[test] fn structure_style_of_type_pseudo_classes() { assert_eq!(render("tests/structure/style/of-type-pseudo-classes"), 0); }
 #[test] fn structure_style_resolve_order() { assert_eq!(render("tests/structure/style/resolve-order"), 0); }
 #[test] fn structure_style_rule_specificity() { assert_eq!(render("tests/structure/style/rule-specificity"), 0); }
 #[test] fn structure_style_style_after_usage() { assert_eq!(render("tests/structure/style/style-after-usage"), 0); }
 #[test] fn structure_style_style_inside_CDATA() { assert_eq!(render("tests/structure/style/style-inside-CDATA"), 0); }
+#[test] fn structure_style_subsequent_sibling_combinator() { assert_eq!(render("tests/structure/style/subsequent-sibling-combinator"), 0); }
 #[test] fn structure_style_transform() { assert_eq!(render("tests/structure/style/transform"), 0); }
 #[test] fn structure_style_type_selector() { assert_eq!(render("tests/structure/style/type-selector"), 0); }
 #[test] fn structure_style_universal_selector() { assert_eq!(render("tests/structure/style/universal-selector"), 0); }
 #[test] fn structure_style_unresolved_class_selector() { assert_eq!(render("tests/structure/style/unresolved-class-selector"), 0); }
+#[test] fn structure_style_unsupported_selector() { assert_eq!(render("tests/structure/style/unsupported-selector"), 0); }
+#[test] fn structure_style_var_cyclic() { assert_eq!(render("tests/structure/style/var-cyclic"), 0); }
+#[test] fn structure_style_var_in_presentation_attribute() { assert_eq!(render("tests/structure/style/var-in-presentation-attribute"), 0); }
+#[test] fn structure_style_var_in_style_attribute() { assert_eq!(render("tests/structure/style/var-in-style-attribute"), 0); }
+#[test] fn structure_style_var_referencing_var() { assert_eq!(render("tests/structure/style/var-referencing-var"), 0); }
+#[test] fn structure_style_var_undefined() { assert_eq!(render("tests/structure/style/var-undefined"), 0); }
+#[test] fn structure_style_var_with_fallback() { assert_eq!(render("tests/structure/style/var-with-fallback"), 0); }
+#[test] fn structure_style_var_with_use() { assert_eq!(render("tests/structure/style/var-with-use"), 0); }
+#[test] fn structure_style_var() { assert_eq!(render("tests/structure/style/var"), 0); }
 #[test] fn structure_style_attribute_comments() { assert_eq!(render("tests/structure/style-attribute/comments"), 0); }
 #[test] fn structure_style_attribute_non_presentational_attribute() { assert_eq!(render("tests/structure/style-attribute/non-presentational-attribute"), 0); }
 #[test] fn structure_style_attribute_simple_case() { assert_eq!(render("tests/structure/style-attribute/simple-case"), 0); }
@@ -1212,6 +1334,17 @@ use crate::render;
 #[test] fn structure_systemLanguage_on_tspan() { assert_eq!(render("tests/structure/systemLanguage/on-tspan"), 0); }
 #[test] fn structure_systemLanguage_ru_Ru() { assert_eq!(render("tests/structure/systemLanguage/ru-Ru"), 0); }
 #[test] fn structure_systemLanguage_ru_en() { assert_eq!(render("tests/structure/systemLanguage/ru-en"), 0); }
+#[test] fn structure_transform_css_angle_units() { assert_eq!(render("tests/structure/transform/css-angle-units"), 0); }
+#[test] fn structure_transform_css_invalid_function() { assert_eq!(render("tests/structure/transform/css-invalid-function"), 0); }
+#[test] fn structure_transform_css_none() { assert_eq!(render("tests/structure/transform/css-none"), 0); }
+#[test] fn structure_transform_css_on_gradient() { assert_eq!(render("tests/structure/transform/css-on-gradient"), 0); }
+#[test] fn structure_transform_css_on_pattern() { assert_eq!(render("tests/structure/transform/css-on-pattern"), 0); }
+#[test] fn structure_transform_css_overrides_attribute() { assert_eq!(render("tests/structure/transform/css-overrides-attribute"), 0); }
+#[test] fn structure_transform_css_overrides_gradientTransform() { assert_eq!(render("tests/structure/transform/css-overrides-gradientTransform"), 0); }
+#[test] fn structure_transform_css_rotate_in_style_attribute() { assert_eq!(render("tests/structure/transform/css-rotate-in-style-attribute"), 0); }
+#[test] fn structure_transform_css_skew() { assert_eq!(render("tests/structure/transform/css-skew"), 0); }
+#[test] fn structure_transform_css_translate_and_scale_in_style_element() { assert_eq!(render("tests/structure/transform/css-translate-and-scale-in-style-element"), 0); }
+#[test] fn structure_transform_css_translate_with_percentages() { assert_eq!(render("tests/structure/transform/css-translate-with-percentages"), 0); }
 #[test] fn structure_transform_default() { assert_eq!(render("tests/structure/transform/default"), 0); }
 #[test] fn structure_transform_direct_transform() { assert_eq!(render("tests/structure/transform/direct-transform"), 0); }
 #[test] fn structure_transform_empty() { assert_eq!(render("tests/structure/transform/empty"), 0); }
@@ -1231,6 +1364,18 @@ use crate::render;
 #[test] fn structure_transform_translate_without_Y() { assert_eq!(render("tests/structure/transform/translate-without-Y"), 0); }
 #[test] fn structure_transform_translate() { assert_eq!(render("tests/structure/transform/translate"), 0); }
 #[test] fn structure_transform_zeroed_matrix() { assert_eq!(render("tests/structure/transform/zeroed-matrix"), 0); }
+#[test] fn structure_transform_box_attribute() { assert_eq!(render("tests/structure/transform-box/attribute"), 0); }
+#[test] fn structure_transform_box_border_box() { assert_eq!(render("tests/structure/transform-box/border-box"), 0); }
+#[test] fn structure_transform_box_content_box() { assert_eq!(render("tests/structure/transform-box/content-box"), 0); }
+#[test] fn structure_transform_box_fill_box_on_group() { assert_eq!(render("tests/structure/transform-box/fill-box-on-group"), 0); }
+#[test] fn structure_transform_box_fill_box_on_text() { assert_eq!(render("tests/structure/transform-box/fill-box-on-text"), 0); }
+#[test] fn structure_transform_box_fill_box_with_default_origin() { assert_eq!(render("tests/structure/transform-box/fill-box-with-default-origin"), 0); }
+#[test] fn structure_transform_box_fill_box_with_translate_percentages() { assert_eq!(render("tests/structure/transform-box/fill-box-with-translate-percentages"), 0); }
+#[test] fn structure_transform_box_fill_box() { assert_eq!(render("tests/structure/transform-box/fill-box"), 0); }
+#[test] fn structure_transform_box_in_style_element() { assert_eq!(render("tests/structure/transform-box/in-style-element"), 0); }
+#[test] fn structure_transform_box_invalid_value() { assert_eq!(render("tests/structure/transform-box/invalid-value"), 0); }
+#[test] fn structure_transform_box_stroke_box() { assert_eq!(render("tests/structure/transform-box/stroke-box"), 0); }
+#[test] fn structure_transform_box_view_box() { assert_eq!(render("tests/structure/transform-box/view-box"), 0); }
 #[test] fn structure_transform_origin_bottom() { assert_eq!(render("tests/structure/transform-origin/bottom"), 0); }
 #[test] fn structure_transform_origin_center() { assert_eq!(render("tests/structure/transform-origin/center"), 0); }
 #[test] fn structure_transform_origin_keyword_length() { assert_eq!(render("tests/structure/transform-origin/keyword-length"), 0); }
@@ -1285,6 +1430,7 @@ use crate::render;
 #[test] fn structure_use_with_size() { assert_eq!(render("tests/structure/use/with-size"), 0); }
 #[test] fn structure_use_xlink_to_a_child_of_a_non_SVG_element() { assert_eq!(render("tests/structure/use/xlink-to-a-child-of-a-non-SVG-element"), 0); }
 #[test] fn structure_use_xlink_to_a_child_of_an_invalid_element() { assert_eq!(render("tests/structure/use/xlink-to-a-child-of-an-invalid-element"), 0); }
+#[test] fn structure_use_xlink_to_an_external_file_with_resources() { assert_eq!(render("tests/structure/use/xlink-to-an-external-file-with-resources"), 0); }
 #[test] fn structure_use_xlink_to_an_external_file() { assert_eq!(render("tests/structure/use/xlink-to-an-external-file"), 0); }
 #[test] fn structure_use_xlink_to_an_invalid_element_1() { assert_eq!(render("tests/structure/use/xlink-to-an-invalid-element-1"), 0); }
 #[test] fn structure_use_xlink_to_an_invalid_element_2() { assert_eq!(render("tests/structure/use/xlink-to-an-invalid-element-2"), 0); }
@@ -1340,8 +1486,10 @@ use crate::render;
 #[test] fn text_direction_rtl() { assert_eq!(render("tests/text/direction/rtl"), 0); }
 #[test] fn text_dominant_baseline_alignment_baseline_and_baseline_shift_on_tspans() { assert_eq!(render("tests/text/dominant-baseline/alignment-baseline-and-baseline-shift-on-tspans"), 0); }
 #[test] fn text_dominant_baseline_alignment_baseline_eq_baseline_on_tspan() { assert_eq!(render("tests/text/dominant-baseline/alignment-baseline=baseline-on-tspan"), 0); }
+#[test] fn text_dominant_baseline_alphabetic_on_vertical() { assert_eq!(render("tests/text/dominant-baseline/alphabetic-on-vertical"), 0); }
 #[test] fn text_dominant_baseline_alphabetic() { assert_eq!(render("tests/text/dominant-baseline/alphabetic"), 0); }
 #[test] fn text_dominant_baseline_auto() { assert_eq!(render("tests/text/dominant-baseline/auto"), 0); }
+#[test] fn text_dominant_baseline_central_on_vertical() { assert_eq!(render("tests/text/dominant-baseline/central-on-vertical"), 0); }
 #[test] fn text_dominant_baseline_central() { assert_eq!(render("tests/text/dominant-baseline/central"), 0); }
 #[test] fn text_dominant_baseline_complex() { assert_eq!(render("tests/text/dominant-baseline/complex"), 0); }
 #[test] fn text_dominant_baseline_different_alignment_baseline_on_tspan() { assert_eq!(render("tests/text/dominant-baseline/different-alignment-baseline-on-tspan"), 0); }
@@ -1360,6 +1508,13 @@ use crate::render;
 #[test] fn text_dominant_baseline_text_before_edge() { assert_eq!(render("tests/text/dominant-baseline/text-before-edge"), 0); }
 #[test] fn text_dominant_baseline_use_script() { assert_eq!(render("tests/text/dominant-baseline/use-script"), 0); }
 #[test] fn text_font_simple_case() { assert_eq!(render("tests/text/font/simple-case"), 0); }
+#[test] fn text_font_face_data_url() { assert_eq!(render("tests/text/font-face/data-url"), 0); }
+#[test] fn text_font_face_invalid_source() { assert_eq!(render("tests/text/font-face/invalid-source"), 0); }
+#[test] fn text_font_face_multiple_sources() { assert_eq!(render("tests/text/font-face/multiple-sources"), 0); }
+#[test] fn text_font_face_overrides_system_font() { assert_eq!(render("tests/text/font-face/overrides-system-font"), 0); }
+#[test] fn text_font_face_with_other_rules() { assert_eq!(render("tests/text/font-face/with-other-rules"), 0); }
+#[test] fn text_font_face_woff() { assert_eq!(render("tests/text/font-face/woff"), 0); }
+#[test] fn text_font_face_woff2() { assert_eq!(render("tests/text/font-face/woff2"), 0); }
 #[test] fn text_font_family_bold_sans_serif() { assert_eq!(render("tests/text/font-family/bold-sans-serif"), 0); }
 #[test] fn text_font_family_cursive() { assert_eq!(render("tests/text/font-family/cursive"), 0); }
 #[test] fn text_font_family_double_quoted() { assert_eq!(render("tests/text/font-family/double-quoted"), 0); }
@@ -1372,9 +1527,20 @@ use crate::render;
 #[test] fn text_font_family_sans_serif() { assert_eq!(render("tests/text/font-family/sans-serif"), 0); }
 #[test] fn text_font_family_serif() { assert_eq!(render("tests/text/font-family/serif"), 0); }
 #[test] fn text_font_family_source_sans_pro() { assert_eq!(render("tests/text/font-family/source-sans-pro"), 0); }
+#[test] fn text_font_feature_settings_double_quotes() { assert_eq!(render("tests/text/font-feature-settings/double-quotes"), 0); }
+#[test] fn text_font_feature_settings_inheritance() { assert_eq!(render("tests/text/font-feature-settings/inheritance"), 0); }
+#[test] fn text_font_feature_settings_invalid_value() { assert_eq!(render("tests/text/font-feature-settings/invalid-value"), 0); }
+#[test] fn text_font_feature_settings_normal() { assert_eq!(render("tests/text/font-feature-settings/normal"), 0); }
+#[test] fn text_font_feature_settings_numeric_value() { assert_eq!(render("tests/text/font-feature-settings/numeric-value"), 0); }
+#[test] fn text_font_feature_settings_on_and_off() { assert_eq!(render("tests/text/font-feature-settings/on-and-off"), 0); }
+#[test] fn text_font_feature_settings_overrides_font_variant() { assert_eq!(render("tests/text/font-feature-settings/overrides-font-variant"), 0); }
+#[test] fn text_font_feature_settings_simple_case() { assert_eq!(render("tests/text/font-feature-settings/simple-case"), 0); }
 #[test] fn text_font_kerning_arabic_script() { assert_eq!(render("tests/text/font-kerning/arabic-script"), 0); }
 #[test] fn text_font_kerning_as_property() { assert_eq!(render("tests/text/font-kerning/as-property"), 0); }
 #[test] fn text_font_kerning_none() { assert_eq!(render("tests/text/font-kerning/none"), 0); }
+#[test] fn text_font_optical_sizing_auto() { assert_eq!(render("tests/text/font-optical-sizing/auto"), 0); }
+#[test] fn text_font_optical_sizing_none() { assert_eq!(render("tests/text/font-optical-sizing/none"), 0); }
+#[test] fn text_font_optical_sizing_overridden_by_font_variation_settings() { assert_eq!(render("tests/text/font-optical-sizing/overridden-by-font-variation-settings"), 0); }
 #[test] fn text_font_size_em_nested_and_mixed() { assert_eq!(render("tests/text/font-size/em-nested-and-mixed"), 0); }
 #[test] fn text_font_size_em_on_the_root_element() { assert_eq!(render("tests/text/font-size/em-on-the-root-element"), 0); }
 #[test] fn text_font_size_em() { assert_eq!(render("tests/text/font-size/em"), 0); }
@@ -1398,15 +1564,51 @@ use crate::render;
 #[test] fn text_font_size_adjust_simple_case() { assert_eq!(render("tests/text/font-size-adjust/simple-case"), 0); }
 #[test] fn text_font_stretch_extra_condensed() { assert_eq!(render("tests/text/font-stretch/extra-condensed"), 0); }
 #[test] fn text_font_stretch_inherit() { assert_eq!(render("tests/text/font-stretch/inherit"), 0); }
+#[test] fn text_font_stretch_keyword_on_variable_font() { assert_eq!(render("tests/text/font-stretch/keyword-on-variable-font"), 0); }
 #[test] fn text_font_stretch_narrower() { assert_eq!(render("tests/text/font-stretch/narrower"), 0); }
+#[test] fn text_font_stretch_percentage_on_variable_font() { assert_eq!(render("tests/text/font-stretch/percentage-on-variable-font"), 0); }
+#[test] fn text_font_stretch_percentage() { assert_eq!(render("tests/text/font-stretch/percentage"), 0); }
 #[test] fn text_font_style_inherit() { assert_eq!(render("tests/text/font-style/inherit"), 0); }
 #[test] fn text_font_style_italic() { assert_eq!(render("tests/text/font-style/italic"), 0); }
 #[test] fn text_font_style_oblique() { assert_eq!(render("tests/text/font-style/oblique"), 0); }
 #[test] fn text_font_variant_inherit() { assert_eq!(render("tests/text/font-variant/inherit"), 0); }
+#[test] fn text_font_variant_longhand_overrides_shorthand() { assert_eq!(render("tests/text/font-variant/longhand-overrides-shorthand"), 0); }
+#[test] fn text_font_variant_none() { assert_eq!(render("tests/text/font-variant/none"), 0); }
+#[test] fn text_font_variant_shorthand_with_multiple_values() { assert_eq!(render("tests/text/font-variant/shorthand-with-multiple-values"), 0); }
 #[test] fn text_font_variant_small_caps() { assert_eq!(render("tests/text/font-variant/small-caps"), 0); }
+#[test] fn text_font_variant_caps_all_small_caps() { assert_eq!(render("tests/text/font-variant-caps/all-small-caps"), 0); }
+#[test] fn text_font_variant_caps_small_caps() { assert_eq!(render("tests/text/font-variant-caps/small-caps"), 0); }
+#[test] fn text_font_variant_caps_titling_caps() { assert_eq!(render("tests/text/font-variant-caps/titling-caps"), 0); }
+#[test] fn text_font_variant_ligatures_common_ligatures() { assert_eq!(render("tests/text/font-variant-ligatures/common-ligatures"), 0); }
+#[test] fn text_font_variant_ligatures_no_common_ligatures() { assert_eq!(render("tests/text/font-variant-ligatures/no-common-ligatures"), 0); }
+#[test] fn text_font_variant_ligatures_none() { assert_eq!(render("tests/text/font-variant-ligatures/none"), 0); }
+#[test] fn text_font_variant_numeric_diagonal_fractions() { assert_eq!(render("tests/text/font-variant-numeric/diagonal-fractions"), 0); }
+#[test] fn text_font_variant_numeric_in_style() { assert_eq!(render("tests/text/font-variant-numeric/in-style"), 0); }
+#[test] fn text_font_variant_numeric_inheritance() { assert_eq!(render("tests/text/font-variant-numeric/inheritance"), 0); }
+#[test] fn text_font_variant_numeric_multiple_values() { assert_eq!(render("tests/text/font-variant-numeric/multiple-values"), 0); }
+#[test] fn text_font_variant_numeric_normal() { assert_eq!(render("tests/text/font-variant-numeric/normal"), 0); }
+#[test] fn text_font_variant_numeric_oldstyle_nums() { assert_eq!(render("tests/text/font-variant-numeric/oldstyle-nums"), 0); }
+#[test] fn text_font_variant_numeric_slashed_zero() { assert_eq!(render("tests/text/font-variant-numeric/slashed-zero"), 0); }
+#[test] fn text_font_variant_numeric_tabular_nums() { assert_eq!(render("tests/text/font-variant-numeric/tabular-nums"), 0); }
+#[test] fn text_font_variant_position_sub() { assert_eq!(render("tests/text/font-variant-position/sub"), 0); }
+#[test] fn text_font_variant_position_super() { assert_eq!(render("tests/text/font-variant-position/super"), 0); }
+#[test] fn text_font_variation_settings_custom_axis() { assert_eq!(render("tests/text/font-variation-settings/custom-axis"), 0); }
+#[test] fn text_font_variation_settings_fractional_value() { assert_eq!(render("tests/text/font-variation-settings/fractional-value"), 0); }
+#[test] fn text_font_variation_settings_in_style() { assert_eq!(render("tests/text/font-variation-settings/in-style"), 0); }
+#[test] fn text_font_variation_settings_inheritance() { assert_eq!(render("tests/text/font-variation-settings/inheritance"), 0); }
+#[test] fn text_font_variation_settings_invalid_value() { assert_eq!(render("tests/text/font-variation-settings/invalid-value"), 0); }
+#[test] fn text_font_variation_settings_multiple_axes() { assert_eq!(render("tests/text/font-variation-settings/multiple-axes"), 0); }
+#[test] fn text_font_variation_settings_on_tspan() { assert_eq!(render("tests/text/font-variation-settings/on-tspan"), 0); }
+#[test] fn text_font_variation_settings_out_of_range_value() { assert_eq!(render("tests/text/font-variation-settings/out-of-range-value"), 0); }
+#[test] fn text_font_variation_settings_overrides_font_weight() { assert_eq!(render("tests/text/font-variation-settings/overrides-font-weight"), 0); }
+#[test] fn text_font_variation_settings_simple_case() { assert_eq!(render("tests/text/font-variation-settings/simple-case"), 0); }
+#[test] fn text_font_variation_settings_unknown_axis() { assert_eq!(render("tests/text/font-variation-settings/unknown-axis"), 0); }
+#[test] fn text_font_weight_1() { assert_eq!(render("tests/text/font-weight/1"), 0); }
 #[test] fn text_font_weight_650() { assert_eq!(render("tests/text/font-weight/650"), 0); }
 #[test] fn text_font_weight_700() { assert_eq!(render("tests/text/font-weight/700"), 0); }
+#[test] fn text_font_weight_arbitrary_number_on_variable_font() { assert_eq!(render("tests/text/font-weight/arbitrary-number-on-variable-font"), 0); }
 #[test] fn text_font_weight_bold() { assert_eq!(render("tests/text/font-weight/bold"), 0); }
+#[test] fn text_font_weight_bolder_on_variable_font() { assert_eq!(render("tests/text/font-weight/bolder-on-variable-font"), 0); }
 #[test] fn text_font_weight_bolder_with_clamping() { assert_eq!(render("tests/text/font-weight/bolder-with-clamping"), 0); }
 #[test] fn text_font_weight_bolder_without_parent() { assert_eq!(render("tests/text/font-weight/bolder-without-parent"), 0); }
 #[test] fn text_font_weight_bolder() { assert_eq!(render("tests/text/font-weight/bolder"), 0); }
@@ -1417,7 +1619,19 @@ use crate::render;
 #[test] fn text_font_weight_lighter() { assert_eq!(render("tests/text/font-weight/lighter"), 0); }
 #[test] fn text_font_weight_normal() { assert_eq!(render("tests/text/font-weight/normal"), 0); }
 #[test] fn text_glyph_orientation_horizontal_simple_case() { assert_eq!(render("tests/text/glyph-orientation-horizontal/simple-case"), 0); }
+#[test] fn text_glyph_orientation_vertical_0deg() { assert_eq!(render("tests/text/glyph-orientation-vertical/0deg"), 0); }
+#[test] fn text_glyph_orientation_vertical_90() { assert_eq!(render("tests/text/glyph-orientation-vertical/90"), 0); }
+#[test] fn text_glyph_orientation_vertical_auto() { assert_eq!(render("tests/text/glyph-orientation-vertical/auto"), 0); }
 #[test] fn text_glyph_orientation_vertical_simple_case() { assert_eq!(render("tests/text/glyph-orientation-vertical/simple-case"), 0); }
+#[test] fn text_inline_size_in_style() { assert_eq!(render("tests/text/inline-size/in-style"), 0); }
+#[test] fn text_inline_size_long_word() { assert_eq!(render("tests/text/inline-size/long-word"), 0); }
+#[test] fn text_inline_size_percentage() { assert_eq!(render("tests/text/inline-size/percentage"), 0); }
+#[test] fn text_inline_size_simple_case() { assert_eq!(render("tests/text/inline-size/simple-case"), 0); }
+#[test] fn text_inline_size_with_dx() { assert_eq!(render("tests/text/inline-size/with-dx"), 0); }
+#[test] fn text_inline_size_with_text_anchor_end() { assert_eq!(render("tests/text/inline-size/with-text-anchor-end"), 0); }
+#[test] fn text_inline_size_with_text_anchor_middle() { assert_eq!(render("tests/text/inline-size/with-text-anchor-middle"), 0); }
+#[test] fn text_inline_size_with_tspan() { assert_eq!(render("tests/text/inline-size/with-tspan"), 0); }
+#[test] fn text_inline_size_zero() { assert_eq!(render("tests/text/inline-size/zero"), 0); }
 #[test] fn text_kerning_0() { assert_eq!(render("tests/text/kerning/0"), 0); }
 #[test] fn text_kerning_10percent() { assert_eq!(render("tests/text/kerning/10percent"), 0); }
 #[test] fn text_lengthAdjust_spacingAndGlyphs() { assert_eq!(render("tests/text/lengthAdjust/spacingAndGlyphs"), 0); }
@@ -1436,7 +1650,29 @@ use crate::render;
 #[test] fn text_letter_spacing_non_ASCII_character() { assert_eq!(render("tests/text/letter-spacing/non-ASCII-character"), 0); }
 #[test] fn text_letter_spacing_normal() { assert_eq!(render("tests/text/letter-spacing/normal"), 0); }
 #[test] fn text_letter_spacing_on_Arabic() { assert_eq!(render("tests/text/letter-spacing/on-Arabic"), 0); }
+#[test] fn text_line_height_inherit() { assert_eq!(render("tests/text/line-height/inherit"), 0); }
+#[test] fn text_line_height_length() { assert_eq!(render("tests/text/line-height/length"), 0); }
+#[test] fn text_line_height_number() { assert_eq!(render("tests/text/line-height/number"), 0); }
+#[test] fn text_line_height_percentage() { assert_eq!(render("tests/text/line-height/percentage"), 0); }
+#[test] fn text_shape_inside_basic_shape() { assert_eq!(render("tests/text/shape-inside/basic-shape"), 0); }
+#[test] fn text_shape_inside_circle() { assert_eq!(render("tests/text/shape-inside/circle"), 0); }
+#[test] fn text_shape_inside_invalid_reference() { assert_eq!(render("tests/text/shape-inside/invalid-reference"), 0); }
+#[test] fn text_shape_inside_overflow() { assert_eq!(render("tests/text/shape-inside/overflow"), 0); }
+#[test] fn text_shape_inside_path() { assert_eq!(render("tests/text/shape-inside/path"), 0); }
+#[test] fn text_shape_inside_rect() { assert_eq!(render("tests/text/shape-inside/rect"), 0); }
+#[test] fn text_shape_inside_with_inline_size() { assert_eq!(render("tests/text/shape-inside/with-inline-size"), 0); }
+#[test] fn text_shape_inside_with_transform() { assert_eq!(render("tests/text/shape-inside/with-transform"), 0); }
+#[test] fn text_svg_font_hkern() { assert_eq!(render("tests/text/svg-font/hkern"), 0); }
+#[test] fn text_svg_font_ligature() { assert_eq!(render("tests/text/svg-font/ligature"), 0); }
+#[test] fn text_svg_font_missing_glyph() { assert_eq!(render("tests/text/svg-font/missing-glyph"), 0); }
+#[test] fn text_svg_font_simple_case() { assert_eq!(render("tests/text/svg-font/simple-case"), 0); }
+#[test] fn text_svg_font_with_fallback_family() { assert_eq!(render("tests/text/svg-font/with-fallback-family"), 0); }
+#[test] fn text_svg_font_without_font_face() { assert_eq!(render("tests/text/svg-font/without-font-face"), 0); }
 #[test] fn text_text_bidi_reordering() { assert_eq!(render("tests/text/text/bidi-reordering"), 0); }
+#[test] fn text_text_color_glyph_CBDT() { assert_eq!(render("tests/text/text/color-glyph-CBDT"), 0); }
+#[test] fn text_text_color_glyph_COLRv0() { assert_eq!(render("tests/text/text/color-glyph-COLRv0"), 0); }
+#[test] fn text_text_color_glyph_COLRv1() { assert_eq!(render("tests/text/text/color-glyph-COLRv1"), 0); }
+#[test] fn text_text_color_glyph_SVG() { assert_eq!(render("tests/text/text/color-glyph-SVG"), 0); }
 #[test] fn text_text_complex_grapheme_split_by_tspan() { assert_eq!(render("tests/text/text/complex-grapheme-split-by-tspan"), 0); }
 #[test] fn text_text_complex_graphemes_and_coordinates_list() { assert_eq!(render("tests/text/text/complex-graphemes-and-coordinates-list"), 0); }
 #[test] fn text_text_complex_graphemes() { assert_eq!(render("tests/text/text/complex-graphemes"), 0); }
@@ -1480,6 +1716,10 @@ use crate::render;
 #[test] fn text_text_xml_lang_eq_ja() { assert_eq!(render("tests/text/text/xml-lang=ja"), 0); }
 #[test] fn text_text_xml_space() { assert_eq!(render("tests/text/text/xml-space"), 0); }
 #[test] fn text_text_zalgo() { assert_eq!(render("tests/text/text/zalgo"), 0); }
+#[test] fn text_text_align_center() { assert_eq!(render("tests/text/text-align/center"), 0); }
+#[test] fn text_text_align_end() { assert_eq!(render("tests/text/text-align/end"), 0); }
+#[test] fn text_text_align_justify() { assert_eq!(render("tests/text/text-align/justify"), 0); }
+#[test] fn text_text_align_start() { assert_eq!(render("tests/text/text-align/start"), 0); }
 #[test] fn text_text_anchor_coordinates_list() { assert_eq!(render("tests/text/text-anchor/coordinates-list"), 0); }
 #[test] fn text_text_anchor_end_on_text() { assert_eq!(render("tests/text/text-anchor/end-on-text"), 0); }
 #[test] fn text_text_anchor_end_with_letter_spacing() { assert_eq!(render("tests/text/text-anchor/end-with-letter-spacing"), 0); }
@@ -1514,6 +1754,18 @@ use crate::render;
 #[test] fn text_text_decoration_underline_with_y_list() { assert_eq!(render("tests/text/text-decoration/underline-with-y-list"), 0); }
 #[test] fn text_text_decoration_underline() { assert_eq!(render("tests/text/text-decoration/underline"), 0); }
 #[test] fn text_text_decoration_with_textLength_on_a_single_character() { assert_eq!(render("tests/text/text-decoration/with-textLength-on-a-single-character"), 0); }
+#[test] fn text_text_orientation_in_style() { assert_eq!(render("tests/text/text-orientation/in-style"), 0); }
+#[test] fn text_text_orientation_inheritance() { assert_eq!(render("tests/text/text-orientation/inheritance"), 0); }
+#[test] fn text_text_orientation_invalid_value() { assert_eq!(render("tests/text/text-orientation/invalid-value"), 0); }
+#[test] fn text_text_orientation_mixed() { assert_eq!(render("tests/text/text-orientation/mixed"), 0); }
+#[test] fn text_text_orientation_on_horizontal_text() { assert_eq!(render("tests/text/text-orientation/on-horizontal-text"), 0); }
+#[test] fn text_text_orientation_on_tspan() { assert_eq!(render("tests/text/text-orientation/on-tspan"), 0); }
+#[test] fn text_text_orientation_sideways_right() { assert_eq!(render("tests/text/text-orientation/sideways-right"), 0); }
+#[test] fn text_text_orientation_sideways() { assert_eq!(render("tests/text/text-orientation/sideways"), 0); }
+#[test] fn text_text_orientation_upright() { assert_eq!(render("tests/text/text-orientation/upright"), 0); }
+#[test] fn text_text_orientation_with_glyph_orientation_vertical() { assert_eq!(render("tests/text/text-orientation/with-glyph-orientation-vertical"), 0); }
+#[test] fn text_text_orientation_with_punctuation() { assert_eq!(render("tests/text/text-orientation/with-punctuation"), 0); }
+#[test] fn text_text_orientation_with_text_decoration() { assert_eq!(render("tests/text/text-orientation/with-text-decoration"), 0); }
 #[test] fn text_text_rendering_geometricPrecision() { assert_eq!(render("tests/text/text-rendering/geometricPrecision"), 0); }
 #[test] fn text_text_rendering_on_tspan() { assert_eq!(render("tests/text/text-rendering/on-tspan"), 0); }
 #[test] fn text_text_rendering_optimizeLegibility() { assert_eq!(render("tests/text/text-rendering/optimizeLegibility"), 0); }
@@ -1618,6 +1870,11 @@ use crate::render;
 #[test] fn text_tspan_xml_space_1() { assert_eq!(render("tests/text/tspan/xml-space-1"), 0); }
 #[test] fn text_tspan_xml_space_2() { assert_eq!(render("tests/text/tspan/xml-space-2"), 0); }
 #[test] fn text_unicode_bidi_bidi_override() { assert_eq!(render("tests/text/unicode-bidi/bidi-override"), 0); }
+#[test] fn text_white_space_normal() { assert_eq!(render("tests/text/white-space/normal"), 0); }
+#[test] fn text_white_space_nowrap() { assert_eq!(render("tests/text/white-space/nowrap"), 0); }
+#[test] fn text_white_space_pre_line() { assert_eq!(render("tests/text/white-space/pre-line"), 0); }
+#[test] fn text_white_space_pre_wrap() { assert_eq!(render("tests/text/white-space/pre-wrap"), 0); }
+#[test] fn text_white_space_pre() { assert_eq!(render("tests/text/white-space/pre"), 0); }
 #[test] fn text_word_spacing__5() { assert_eq!(render("tests/text/word-spacing/-5"), 0); }
 #[test] fn text_word_spacing_0() { assert_eq!(render("tests/text/word-spacing/0"), 0); }
 #[test] fn text_word_spacing_10() { assert_eq!(render("tests/text/word-spacing/10"), 0); }
@@ -17,13 +17,10 @@ struct Map<V: 'static> {
 
 impl<V: PartialEq> Map<V> {
     fn get(&self, key: &str) -> Option<&V> {
-        use std::borrow::Borrow;
-
         let hash = hash(key, self.key);
         let index = get_index(hash, self.disps, self.entries.len());
         let entry = &self.entries[index as usize];
-        let b = entry.0.borrow();
-        if b == key {
+        if entry.0 == key {
             Some(&entry.1)
         } else {
             None
@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use usvg_tree::{ImageKind, Tree};
+
+use crate::{svgtree, Options};
+
+/// SMIL animations of an SVG document.
+///
+/// The parsed SVG tree is kept, so only the conversion is performed for each frame.
+pub(crate) struct SmilAnimation {
+    doc: svgtree::Document<'static>,
+    opt: Options,
+    duration: Option<f32>,
+    /// Images are resolved only once, during the initial conversion.
+    images: RefCell<HashMap<String, Option<ImageKind>>>,
+}
+
+impl SmilAnimation {
+    pub(crate) fn new(
+        doc: svgtree::Document<'static>,
+        opt: &Options,
+        duration: Option<f32>,
+        images: HashMap<String, Option<ImageKind>>,
+    ) -> Self {
+        SmilAnimation {
+            doc,
+            opt: opt.clone(),
+            duration,
+            images: RefCell::new(images),
+        }
+    }
+}
+
+impl usvg_tree::Animation for SmilAnimation {
+    fn duration(&self) -> Option<f32> {
+        self.duration
+    }
+
+    fn at_time(&self, time: f32) -> Option<Tree> {
+        let doc = self.doc.at_time(time);
+        let mut images = self.images.borrow_mut();
+        crate::converter::convert_doc_with_images(&doc, &self.opt, &mut images).ok()
+    }
+}
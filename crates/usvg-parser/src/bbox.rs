@@ -0,0 +1,158 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use svgtypes::Length;
+use usvg_tree::{tiny_skia_path, BBox, LineCap, LineJoin, NonZeroRect, Rect, Transform};
+
+use crate::converter;
+use crate::svgtree::{AId, EId, FromValue, SvgNode};
+
+/// A CSS reference box.
+///
+/// SVG elements do not have a CSS layout box, therefore CSS boxes are mapped onto SVG ones.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum ReferenceBox {
+    Fill,
+    Stroke,
+    View,
+}
+
+impl<'a, 'input: 'a> FromValue<'a, 'input> for ReferenceBox {
+    fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
+        ReferenceBox::from_keyword(value)
+    }
+}
+
+impl ReferenceBox {
+    pub(crate) fn from_keyword(text: &str) -> Option<Self> {
+        match text {
+            "content-box" | "padding-box" | "fill-box" => Some(ReferenceBox::Fill),
+            "border-box" | "margin-box" | "stroke-box" => Some(ReferenceBox::Stroke),
+            "view-box" => Some(ReferenceBox::View),
+            _ => None,
+        }
+    }
+}
+
+/// Resolves element's reference box in its user space.
+///
+/// Unlike `usvg_tree::NodeExt::calculate_bbox`, works on the SVG tree,
+/// therefore can be used before the element itself is converted.
+/// Text bounding boxes are not supported, since text is not laid out yet.
+pub(crate) fn resolve(
+    node: SvgNode,
+    reference: ReferenceBox,
+    state: &converter::State,
+) -> Option<NonZeroRect> {
+    match reference {
+        ReferenceBox::Fill => calc_bbox(node, false, state)?.to_non_zero_rect(),
+        ReferenceBox::Stroke => calc_bbox(node, true, state)?.to_non_zero_rect(),
+        ReferenceBox::View => Some(view_box(state)),
+    }
+}
+
+/// Returns the `view-box` reference box.
+///
+/// It is positioned at the origin of the coordinate system established by the `viewBox`.
+pub(crate) fn view_box(state: &converter::State) -> NonZeroRect {
+    state.view_box.size().to_non_zero_rect(0.0, 0.0)
+}
+
+fn calc_bbox(node: SvgNode, with_stroke: bool, state: &converter::State) -> Option<BBox> {
+    match node.tag_name()? {
+        EId::Rect
+        | EId::Circle
+        | EId::Ellipse
+        | EId::Line
+        | EId::Polyline
+        | EId::Polygon
+        | EId::Path => {
+            let path = crate::shapes::convert(node, state)?;
+            if with_stroke {
+                if let Some(stroke) = resolve_stroke(node, state) {
+                    if let Some(outline) = path.stroke(&stroke, 1.0) {
+                        return outline.compute_tight_bounds().map(BBox::from);
+                    }
+                }
+            }
+
+            path.compute_tight_bounds().map(BBox::from)
+        }
+        EId::Image => {
+            let x = node.convert_user_length(AId::X, state, Length::zero());
+            let y = node.convert_user_length(AId::Y, state, Length::zero());
+            let width = node.convert_user_length(AId::Width, state, Length::zero());
+            let height = node.convert_user_length(AId::Height, state, Length::zero());
+            Rect::from_xywh(x, y, width, height).map(BBox::from)
+        }
+        EId::Text => {
+            log::warn!("Reference boxes of text elements are not supported.");
+            None
+        }
+        // `use` elements have the referenced element as a child.
+        // And their `x` and `y` are already a part of the group transform.
+        EId::G | EId::Svg | EId::Switch | EId::Symbol | EId::Use => {
+            let mut bbox = BBox::default();
+            for child in node.children() {
+                match child.tag_name() {
+                    Some(EId::G | EId::Svg | EId::Symbol) => {}
+                    Some(tag_name) if tag_name.is_graphic() => {}
+                    _ => continue,
+                }
+
+                if !child.is_visible_element(state.opt) {
+                    continue;
+                }
+
+                let ts: Transform = child.resolve_transform(AId::Transform, state);
+                if let Some(child_bbox) = calc_bbox(child, with_stroke, state) {
+                    if let Some(child_bbox) = child_bbox.transform(ts) {
+                        bbox = bbox.expand(child_bbox);
+                    }
+                }
+
+                // Only the first valid child of `switch` is rendered.
+                if node.tag_name() == Some(EId::Switch) {
+                    break;
+                }
+            }
+
+            if bbox.is_default() {
+                None
+            } else {
+                Some(bbox)
+            }
+        }
+        _ => None,
+    }
+}
+
+fn resolve_stroke(node: SvgNode, state: &converter::State) -> Option<tiny_skia_path::Stroke> {
+    let paint_node = node.ancestors().find(|n| n.has_attribute(AId::Stroke))?;
+    if paint_node.attribute(AId::Stroke) == Some("none") {
+        return None;
+    }
+
+    let width = node.resolve_valid_length(AId::StrokeWidth, state, 1.0)?;
+    let miterlimit = node.find_attribute(AId::StrokeMiterlimit).unwrap_or(4.0f32);
+    let linecap: LineCap = node.find_attribute(AId::StrokeLinecap).unwrap_or_default();
+    let linejoin: LineJoin = node.find_attribute(AId::StrokeLinejoin).unwrap_or_default();
+
+    Some(tiny_skia_path::Stroke {
+        width: width.get(),
+        miter_limit: miterlimit.max(1.0),
+        line_cap: match linecap {
+            LineCap::Butt => tiny_skia_path::LineCap::Butt,
+            LineCap::Round => tiny_skia_path::LineCap::Round,
+            LineCap::Square => tiny_skia_path::LineCap::Square,
+        },
+        line_join: match linejoin {
+            LineJoin::Miter => tiny_skia_path::LineJoin::Miter,
+            LineJoin::MiterClip | LineJoin::Arcs => tiny_skia_path::LineJoin::MiterClip,
+            LineJoin::Round => tiny_skia_path::LineJoin::Round,
+            LineJoin::Bevel => tiny_skia_path::LineJoin::Bevel,
+        },
+        dash: None,
+    })
+}
@@ -3,12 +3,17 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::rc::Rc;
-use std::str::FromStr;
+use std::sync::Arc;
 
-use usvg_tree::{ClipPath, Group, Node, NodeKind, Transform, Units};
+use svgtypes::{Length, LengthUnit as Unit};
+use usvg_tree::{
+    tiny_skia_path, ClipPath, Fill, FillRule, Group, Node, NodeExt, NodeKind, NonZeroRect, Path,
+    Transform, Units,
+};
 
-use crate::converter;
-use crate::svgtree::{AId, EId, SvgNode};
+use crate::bbox::ReferenceBox;
+use crate::svgtree::{self, AId, EId, SvgNode};
+use crate::{converter, OptionLog};
 
 pub(crate) fn convert(
     node: SvgNode,
@@ -68,7 +73,7 @@ pub(crate) fn convert(
 }
 
 fn resolve_clip_path_transform(node: SvgNode, state: &converter::State) -> Option<Transform> {
-    // Do not use Node::attribute::<Transform>, because it will always
+    // Do not use `SvgNode::resolve_transform`, because it will always
     // return a valid transform.
 
     let value: &str = match node.attribute(AId::Transform) {
@@ -76,26 +81,429 @@ fn resolve_clip_path_transform(node: SvgNode, state: &converter::State) -> Optio
         None => return Some(Transform::default()),
     };
 
-    let ts = match svgtypes::Transform::from_str(value) {
-        Ok(v) => v,
-        Err(_) => {
+    let ts = match svgtree::parse_transform(value) {
+        Some(v) => v,
+        None => {
             log::warn!("Failed to parse {} value: '{}'.", AId::Transform, value);
             return None;
         }
     };
 
-    let ts = Transform::from_row(
-        ts.a as f32,
-        ts.b as f32,
-        ts.c as f32,
-        ts.d as f32,
-        ts.e as f32,
-        ts.f as f32,
-    );
-
     if ts.is_valid() {
         Some(node.resolve_transform(AId::Transform, state))
     } else {
         None
     }
 }
+
+/// Converts a CSS `clip-path` value, like `circle(40%) fill-box`, into a clip path.
+///
+/// Unlike `clipPath` elements, CSS shapes depend on the element's reference box,
+/// therefore they are resolved into user space paths and not cached.
+pub(crate) fn convert_shape(node: SvgNode, state: &converter::State) -> Option<Rc<ClipPath>> {
+    let value: &str = node.attribute(AId::ClipPath)?;
+    if value == "none" {
+        return None;
+    }
+
+    let (shape, reference) = parse_value(value)
+        .log_none(|| log::warn!("Failed to parse {} value: '{}'.", AId::ClipPath, value))?;
+
+    // `border-box` is the default one and maps onto `stroke-box` for SVG elements.
+    let reference = reference.unwrap_or(ReferenceBox::Stroke);
+    let rect = crate::bbox::resolve(node, reference, state).log_none(|| {
+        log::warn!(
+            "Failed to resolve a reference box for '{}'. Clipping is ignored.",
+            value
+        )
+    })?;
+
+    let (path, rule) = match shape {
+        Some(shape) => convert_basic_shape(shape, rect, node, state)
+            .log_none(|| log::warn!("Failed to parse {} value: '{}'.", AId::ClipPath, value))?,
+        None => (
+            Some(tiny_skia_path::PathBuilder::from_rect(rect.to_rect())),
+            FillRule::NonZero,
+        ),
+    };
+
+    // A collapsed shape clips the whole element.
+    let clip = ClipPath::default();
+    if let Some(path) = path {
+        let mut path = Path::new(Arc::new(path));
+        path.fill = Some(Fill {
+            rule,
+            ..Fill::default()
+        });
+        clip.root.append_kind(NodeKind::Path(path));
+    }
+
+    Some(Rc::new(clip))
+}
+
+/// Splits a value into a basic shape and a reference box. Both are optional.
+fn parse_value(value: &str) -> Option<(Option<&str>, Option<ReferenceBox>)> {
+    let mut shape = None;
+    let mut reference = None;
+    for token in svgtree::split_css_list_by(value, |c| c.is_ascii_whitespace()) {
+        if token.is_empty() {
+            continue;
+        }
+
+        if let Some(r) = ReferenceBox::from_keyword(token) {
+            if reference.replace(r).is_some() {
+                return None;
+            }
+        } else if token.ends_with(')') {
+            if shape.replace(token).is_some() {
+                return None;
+            }
+        } else {
+            return None;
+        }
+    }
+
+    if shape.is_none() && reference.is_none() {
+        return None;
+    }
+
+    Some((shape, reference))
+}
+
+/// Converts a `<basic-shape>` into a path.
+///
+/// Returns `None` for invalid values and a `None` path for collapsed shapes.
+fn convert_basic_shape(
+    text: &str,
+    rect: NonZeroRect,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<(Option<tiny_skia_path::Path>, FillRule)> {
+    let (name, args) = text.strip_suffix(')')?.split_once('(')?;
+    let args = args.trim();
+    match name.trim() {
+        "inset" => convert_inset(args, rect, node, state).map(|p| (p, FillRule::NonZero)),
+        "circle" => convert_circle(args, rect, node, state).map(|p| (p, FillRule::NonZero)),
+        "ellipse" => convert_ellipse(args, rect, node, state).map(|p| (p, FillRule::NonZero)),
+        "polygon" => convert_polygon(args, rect, node, state),
+        "path" => convert_path(args, rect),
+        _ => None,
+    }
+}
+
+fn convert_inset(
+    args: &str,
+    rect: NonZeroRect,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<Option<tiny_skia_path::Path>> {
+    let (offsets, radii) = match args.split_once("round") {
+        Some((offsets, radii)) => (offsets, Some(radii)),
+        None => (args, None),
+    };
+
+    let offsets: Vec<_> = offsets.split_ascii_whitespace().collect();
+    let [top, right, bottom, left] = expand_sides(&offsets)?;
+    let top = parse_length(top, rect.height(), node, state)?;
+    let right = parse_length(right, rect.width(), node, state)?;
+    let bottom = parse_length(bottom, rect.height(), node, state)?;
+    let left = parse_length(left, rect.width(), node, state)?;
+
+    let radii = match radii {
+        Some(text) => parse_border_radius(text, rect, node, state)?,
+        None => [(0.0, 0.0); 4],
+    };
+
+    let inset_rect = match NonZeroRect::from_xywh(
+        rect.x() + left,
+        rect.y() + top,
+        rect.width() - left - right,
+        rect.height() - top - bottom,
+    ) {
+        Some(v) => v,
+        None => return Some(None),
+    };
+
+    // Scale radii down when they do not fit, like CSS borders do.
+    let [tl, tr, br, bl] = radii;
+    let mut scale = 1.0f32;
+    for (side, sum) in [
+        (inset_rect.width(), tl.0 + tr.0),
+        (inset_rect.width(), bl.0 + br.0),
+        (inset_rect.height(), tl.1 + bl.1),
+        (inset_rect.height(), tr.1 + br.1),
+    ] {
+        if sum > side {
+            scale = scale.min(side / sum);
+        }
+    }
+    let radii = radii.map(|(rx, ry)| (rx * scale, ry * scale));
+
+    Some(crate::shapes::rounded_rect_to_path(inset_rect, radii))
+}
+
+fn parse_border_radius(
+    text: &str,
+    rect: NonZeroRect,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<[(f32, f32); 4]> {
+    let (horizontal, vertical) = match text.split_once('/') {
+        Some((horizontal, vertical)) => (horizontal, vertical),
+        None => (text, text),
+    };
+
+    let parse_corners = |text: &str, base: f32| -> Option<[f32; 4]> {
+        let tokens: Vec<_> = text.split_ascii_whitespace().collect();
+        let [tl, tr, br, bl] = expand_sides(&tokens)?;
+        let mut radii = [0.0; 4];
+        for (radius, text) in radii.iter_mut().zip([tl, tr, br, bl]) {
+            *radius = parse_length(text, base, node, state)?;
+            if *radius < 0.0 {
+                return None;
+            }
+        }
+
+        Some(radii)
+    };
+
+    let horizontal = parse_corners(horizontal, rect.width())?;
+    let vertical = parse_corners(vertical, rect.height())?;
+    Some([
+        (horizontal[0], vertical[0]),
+        (horizontal[1], vertical[1]),
+        (horizontal[2], vertical[2]),
+        (horizontal[3], vertical[3]),
+    ])
+}
+
+/// Expands a CSS 1-4 values shorthand into top, right, bottom and left values.
+fn expand_sides<'a>(tokens: &[&'a str]) -> Option<[&'a str; 4]> {
+    match *tokens {
+        [all] => Some([all, all, all, all]),
+        [vertical, horizontal] => Some([vertical, horizontal, vertical, horizontal]),
+        [top, horizontal, bottom] => Some([top, horizontal, bottom, horizontal]),
+        [top, right, bottom, left] => Some([top, right, bottom, left]),
+        _ => None,
+    }
+}
+
+fn convert_circle(
+    args: &str,
+    rect: NonZeroRect,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<Option<tiny_skia_path::Path>> {
+    let (radius, position) = split_at_position(args);
+    let (cx, cy) = parse_position(&position, rect, node, state)?;
+
+    let sides = [
+        (cx - rect.left()).abs(),
+        (rect.right() - cx).abs(),
+        (cy - rect.top()).abs(),
+        (rect.bottom() - cy).abs(),
+    ];
+    let base = ((rect.width().powi(2) + rect.height().powi(2)) / 2.0).sqrt();
+    let r = match *radius {
+        [] => parse_radius("closest-side", &sides, base, node, state)?,
+        [r] => parse_radius(r, &sides, base, node, state)?,
+        _ => return None,
+    };
+
+    Some(ellipse_path(cx, cy, r, r))
+}
+
+fn convert_ellipse(
+    args: &str,
+    rect: NonZeroRect,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<Option<tiny_skia_path::Path>> {
+    let (radii, position) = split_at_position(args);
+    let (cx, cy) = parse_position(&position, rect, node, state)?;
+
+    let x_sides = [(cx - rect.left()).abs(), (rect.right() - cx).abs()];
+    let y_sides = [(cy - rect.top()).abs(), (rect.bottom() - cy).abs()];
+    let (rx, ry) = match *radii {
+        [] => ("closest-side", "closest-side"),
+        [rx, ry] => (rx, ry),
+        _ => return None,
+    };
+    let rx = parse_radius(rx, &x_sides, rect.width(), node, state)?;
+    let ry = parse_radius(ry, &y_sides, rect.height(), node, state)?;
+
+    Some(ellipse_path(cx, cy, rx, ry))
+}
+
+fn ellipse_path(cx: f32, cy: f32, rx: f32, ry: f32) -> Option<tiny_skia_path::Path> {
+    if rx > 0.0 && ry > 0.0 {
+        crate::shapes::ellipse_to_path(cx, cy, rx, ry).map(|p| (*p).clone())
+    } else {
+        None
+    }
+}
+
+/// Splits `circle()` and `ellipse()` arguments at the `at` keyword.
+fn split_at_position(args: &str) -> (Vec<&str>, Vec<&str>) {
+    let tokens: Vec<_> = args.split_ascii_whitespace().collect();
+    match tokens.iter().position(|t| *t == "at") {
+        Some(idx) => (tokens[..idx].to_vec(), tokens[idx + 1..].to_vec()),
+        None => (tokens, Vec::new()),
+    }
+}
+
+fn parse_radius(
+    text: &str,
+    sides: &[f32],
+    base: f32,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<f32> {
+    match text {
+        "closest-side" => sides.iter().copied().reduce(f32::min),
+        "farthest-side" => sides.iter().copied().reduce(f32::max),
+        _ => parse_length(text, base, node, state).filter(|r| *r >= 0.0),
+    }
+}
+
+/// Resolves a `<position>` with up to four values. Defaults to the center.
+fn parse_position(
+    tokens: &[&str],
+    rect: NonZeroRect,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<(f32, f32)> {
+    let x_component = |text: &str| match text {
+        "left" => Some(rect.left()),
+        "center" => Some(rect.x() + rect.width() / 2.0),
+        "right" => Some(rect.right()),
+        _ => parse_length(text, rect.width(), node, state).map(|n| rect.x() + n),
+    };
+
+    let y_component = |text: &str| match text {
+        "top" => Some(rect.top()),
+        "center" => Some(rect.y() + rect.height() / 2.0),
+        "bottom" => Some(rect.bottom()),
+        _ => parse_length(text, rect.height(), node, state).map(|n| rect.y() + n),
+    };
+
+    match *tokens {
+        [] => Some((x_component("center")?, y_component("center")?)),
+        [token] if matches!(token, "top" | "bottom") => {
+            Some((x_component("center")?, y_component(token)?))
+        }
+        [token] => Some((x_component(token)?, y_component("center")?)),
+        [first, second]
+            if matches!(first, "top" | "bottom") || matches!(second, "left" | "right") =>
+        {
+            Some((x_component(second)?, y_component(first)?))
+        }
+        [first, second] => Some((x_component(first)?, y_component(second)?)),
+        [first, first_offset, second, second_offset] => {
+            let ((x_keyword, x_offset), (y_keyword, y_offset)) =
+                if matches!(first, "top" | "bottom") {
+                    ((second, second_offset), (first, first_offset))
+                } else {
+                    ((first, first_offset), (second, second_offset))
+                };
+
+            let x_offset = parse_length(x_offset, rect.width(), node, state)?;
+            let y_offset = parse_length(y_offset, rect.height(), node, state)?;
+            let x = match x_keyword {
+                "left" => rect.left() + x_offset,
+                "right" => rect.right() - x_offset,
+                _ => return None,
+            };
+            let y = match y_keyword {
+                "top" => rect.top() + y_offset,
+                "bottom" => rect.bottom() - y_offset,
+                _ => return None,
+            };
+
+            Some((x, y))
+        }
+        _ => None,
+    }
+}
+
+fn convert_polygon(
+    args: &str,
+    rect: NonZeroRect,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<(Option<tiny_skia_path::Path>, FillRule)> {
+    let (rule, points) = split_fill_rule(args);
+
+    let mut builder = tiny_skia_path::PathBuilder::new();
+    for point in points {
+        let (x, y) = match *point.split_ascii_whitespace().collect::<Vec<_>>() {
+            [x, y] => (x, y),
+            _ => return None,
+        };
+
+        let x = rect.x() + parse_length(x, rect.width(), node, state)?;
+        let y = rect.y() + parse_length(y, rect.height(), node, state)?;
+        if builder.is_empty() {
+            builder.move_to(x, y);
+        } else {
+            builder.line_to(x, y);
+        }
+    }
+
+    if builder.is_empty() {
+        return None;
+    }
+
+    builder.close();
+    Some((builder.finish(), rule))
+}
+
+fn convert_path(args: &str, rect: NonZeroRect) -> Option<(Option<tiny_skia_path::Path>, FillRule)> {
+    let (rule, rest) = split_fill_rule(args);
+    let data = match *rest {
+        [data] => data,
+        _ => return None,
+    };
+
+    let data = data
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| data.strip_prefix('"').and_then(|s| s.strip_suffix('"')))?;
+
+    // Path coordinates are relative to the reference box.
+    let path = crate::shapes::parse_path_data(data)?
+        .transform(Transform::from_translate(rect.x(), rect.y()));
+    Some((path, rule))
+}
+
+/// Splits comma-separated arguments and extracts an optional leading `<fill-rule>`.
+fn split_fill_rule(args: &str) -> (FillRule, Vec<&str>) {
+    let mut list: Vec<_> = svgtree::split_css_list(args, b',')
+        .into_iter()
+        .map(str::trim)
+        .collect();
+
+    let rule = match list.first().copied() {
+        Some("nonzero") => FillRule::NonZero,
+        Some("evenodd") => FillRule::EvenOdd,
+        _ => return (FillRule::NonZero, list),
+    };
+
+    list.remove(0);
+    (rule, list)
+}
+
+/// Resolves a `<length-percentage>` relative to `base`.
+fn parse_length(text: &str, base: f32, node: SvgNode, state: &converter::State) -> Option<f32> {
+    let length: Length = text.parse().ok()?;
+    if length.unit == Unit::Percent {
+        Some(base * length.number as f32 / 100.0)
+    } else {
+        Some(crate::units::convert_user_length(
+            length,
+            node,
+            AId::ClipPath,
+            state,
+        ))
+    }
+}
@@ -0,0 +1,260 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::str::FromStr;
+
+/// Parses a color.
+///
+/// In addition to colors supported by `svgtypes`, accepts
+/// `lab()`, `lch()`, `oklab()`, `oklch()` and `color()` with
+/// `srgb`, `srgb-linear` and `display-p3` color spaces from CSS Color Module Level 4.
+///
+/// All colors are converted into sRGB. Out of gamut colors are clipped.
+pub(crate) fn parse_color(text: &str) -> Option<svgtypes::Color> {
+    if let Ok(color) = svgtypes::Color::from_str(text) {
+        return Some(color);
+    }
+
+    let text = text.trim();
+    let open = text.find('(')?;
+    if !text.ends_with(')') {
+        return None;
+    }
+
+    let name = text[..open].trim().to_ascii_lowercase();
+    let args = &text[open + 1..text.len() - 1];
+
+    let (args, alpha) = match args.split_once('/') {
+        Some((args, alpha)) => (args, parse_alpha(alpha.trim())?),
+        None => (args, 1.0),
+    };
+
+    let mut args = args.split_ascii_whitespace();
+    let [r, g, b] = match name.as_str() {
+        "lab" => {
+            let (l, a, b) = parse_triple(&mut args, 100.0, 125.0, 125.0)?;
+            lab_to_linear_srgb(l, a, b)
+        }
+        "lch" => {
+            let l = parse_component(args.next()?, 100.0)?;
+            let c = parse_component(args.next()?, 150.0)?;
+            let h = parse_hue(args.next()?)?;
+            let (a, b) = polar_to_rect(c, h);
+            lab_to_linear_srgb(l, a, b)
+        }
+        "oklab" => {
+            let (l, a, b) = parse_triple(&mut args, 1.0, 0.4, 0.4)?;
+            oklab_to_linear_srgb(l, a, b)
+        }
+        "oklch" => {
+            let l = parse_component(args.next()?, 1.0)?;
+            let c = parse_component(args.next()?, 0.4)?;
+            let h = parse_hue(args.next()?)?;
+            let (a, b) = polar_to_rect(c, h);
+            oklab_to_linear_srgb(l, a, b)
+        }
+        "color" => {
+            let space = args.next()?.to_ascii_lowercase();
+            let (c1, c2, c3) = parse_triple(&mut args, 1.0, 1.0, 1.0)?;
+            match space.as_str() {
+                "srgb" => [srgb_to_linear(c1), srgb_to_linear(c2), srgb_to_linear(c3)],
+                "srgb-linear" => [c1, c2, c3],
+                "display-p3" => {
+                    let p3 = [srgb_to_linear(c1), srgb_to_linear(c2), srgb_to_linear(c3)];
+                    xyz_d65_to_linear_srgb(mul(&P3_TO_XYZ_D65, p3))
+                }
+                _ => {
+                    log::warn!("Unsupported color space: '{}'.", space);
+                    return None;
+                }
+            }
+        }
+        _ => return None,
+    };
+
+    if args.next().is_some() {
+        return None;
+    }
+
+    Some(svgtypes::Color::new_rgba(
+        to_u8(linear_to_srgb(r)),
+        to_u8(linear_to_srgb(g)),
+        to_u8(linear_to_srgb(b)),
+        to_u8(alpha),
+    ))
+}
+
+fn parse_triple<'a>(
+    args: &mut impl Iterator<Item = &'a str>,
+    percent1: f64,
+    percent2: f64,
+    percent3: f64,
+) -> Option<(f64, f64, f64)> {
+    Some((
+        parse_component(args.next()?, percent1)?,
+        parse_component(args.next()?, percent2)?,
+        parse_component(args.next()?, percent3)?,
+    ))
+}
+
+/// Parses a number, a percentage or `none`.
+///
+/// `percent` is the value that corresponds to `100%`.
+fn parse_component(text: &str, percent: f64) -> Option<f64> {
+    if text.eq_ignore_ascii_case("none") {
+        Some(0.0)
+    } else if let Some(n) = text.strip_suffix('%') {
+        Some(f64::from_str(n).ok()? / 100.0 * percent)
+    } else {
+        f64::from_str(text).ok()
+    }
+}
+
+fn parse_alpha(text: &str) -> Option<f64> {
+    parse_component(text, 1.0).map(|a| a.clamp(0.0, 1.0))
+}
+
+/// Parses a hue angle into degrees.
+fn parse_hue(text: &str) -> Option<f64> {
+    let text = text.to_ascii_lowercase();
+    if text == "none" {
+        return Some(0.0);
+    }
+
+    // Units with a common suffix must be checked first.
+    let units = [
+        ("grad", 0.9),
+        ("deg", 1.0),
+        ("rad", 180.0 / std::f64::consts::PI),
+        ("turn", 360.0),
+    ];
+    for (unit, scale) in units {
+        if let Some(n) = text.strip_suffix(unit) {
+            return Some(f64::from_str(n).ok()? * scale);
+        }
+    }
+
+    f64::from_str(&text).ok()
+}
+
+fn polar_to_rect(chroma: f64, hue: f64) -> (f64, f64) {
+    let hue = hue.to_radians();
+    (chroma * hue.cos(), chroma * hue.sin())
+}
+
+type Matrix = [[f64; 3]; 3];
+
+// Matrices are taken from the CSS Color Module Level 4 sample code
+// and from the original OKLab description.
+
+const D50_TO_D65: Matrix = [
+    [0.955473421488075, -0.02309845494876471, 0.06325924320057072],
+    [
+        -0.0283697093338637,
+        1.0099953980813041,
+        0.021041441191917323,
+    ],
+    [
+        0.012314014864481998,
+        -0.020507649298898964,
+        1.330365926242124,
+    ],
+];
+
+const XYZ_D65_TO_LINEAR_SRGB: Matrix = [
+    [3.2409699419045226, -1.537383177570094, -0.4986107602930034],
+    [-0.9692436362808796, 1.8759675015077202, 0.04155505740717559],
+    [
+        0.05563007969699366,
+        -0.20397695888897652,
+        1.0569715142428786,
+    ],
+];
+
+const P3_TO_XYZ_D65: Matrix = [
+    [0.4865709486482162, 0.26566769316909306, 0.1982172852343625],
+    [0.2289745640697488, 0.6917385218365064, 0.079286914093745],
+    [0.0, 0.04511338185890264, 1.043944368900976],
+];
+
+const OKLAB_TO_LMS: Matrix = [
+    [1.0, 0.3963377774, 0.2158037573],
+    [1.0, -0.1055613458, -0.0638541728],
+    [1.0, -0.0894841775, -1.2914855480],
+];
+
+const LMS_TO_LINEAR_SRGB: Matrix = [
+    [4.0767416621, -3.3077115913, 0.2309699292],
+    [-1.2684380046, 2.6097574011, -0.3413193965],
+    [-0.0041960863, -0.7034186147, 1.7076147010],
+];
+
+fn mul(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+fn lab_to_linear_srgb(l: f64, a: f64, b: f64) -> [f64; 3] {
+    const KAPPA: f64 = 24389.0 / 27.0;
+    const EPSILON: f64 = 216.0 / 24389.0;
+    const D50_WHITE: [f64; 3] = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+
+    let f1 = (l + 16.0) / 116.0;
+    let f0 = a / 500.0 + f1;
+    let f2 = f1 - b / 200.0;
+
+    let x = if f0.powi(3) > EPSILON {
+        f0.powi(3)
+    } else {
+        (116.0 * f0 - 16.0) / KAPPA
+    };
+    let y = if l > KAPPA * EPSILON {
+        f1.powi(3)
+    } else {
+        l / KAPPA
+    };
+    let z = if f2.powi(3) > EPSILON {
+        f2.powi(3)
+    } else {
+        (116.0 * f2 - 16.0) / KAPPA
+    };
+
+    let xyz_d50 = [x * D50_WHITE[0], y * D50_WHITE[1], z * D50_WHITE[2]];
+    xyz_d65_to_linear_srgb(mul(&D50_TO_D65, xyz_d50))
+}
+
+fn oklab_to_linear_srgb(l: f64, a: f64, b: f64) -> [f64; 3] {
+    let lms = mul(&OKLAB_TO_LMS, [l, a, b]);
+    let lms = [lms[0].powi(3), lms[1].powi(3), lms[2].powi(3)];
+    mul(&LMS_TO_LINEAR_SRGB, lms)
+}
+
+fn xyz_d65_to_linear_srgb(xyz: [f64; 3]) -> [f64; 3] {
+    mul(&XYZ_D65_TO_LINEAR_SRGB, xyz)
+}
+
+fn srgb_to_linear(c: f64) -> f64 {
+    let abs = c.abs();
+    if abs <= 0.04045 {
+        c / 12.92
+    } else {
+        c.signum() * ((abs + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> f64 {
+    let abs = c.abs();
+    if abs <= 0.0031308 {
+        c * 12.92
+    } else {
+        c.signum() * (1.055 * abs.powf(1.0 / 2.4) - 0.055)
+    }
+}
+
+fn to_u8(c: f64) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
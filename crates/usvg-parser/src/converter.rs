@@ -4,13 +4,13 @@
 
 use std::collections::HashMap;
 use std::rc::Rc;
-use std::str::FromStr;
+use std::sync::Arc;
 
 use svgtypes::{Length, LengthUnit as Unit, PaintOrderKind, TransformOrigin};
 use usvg_tree::*;
 
 use crate::svgtree::{self, AId, EId, FromValue, SvgNode};
-use crate::units::{self, convert_length};
+use crate::units;
 use crate::{Error, Options};
 
 #[derive(Clone)]
@@ -24,15 +24,30 @@ pub struct State<'a> {
     /// Used only during nested `svg` size resolving.
     /// Width and height can be set independently.
     pub(crate) use_size: (Option<f32>, Option<f32>),
+    /// Paints referenced by `context-fill` and `context-stroke`.
+    ///
+    /// Set by markers and `use` elements.
+    pub(crate) context_element: Option<ContextElement>,
     pub(crate) opt: &'a Options,
 }
 
+/// Paints of an element that is referenced by `context-fill` and `context-stroke`.
+#[derive(Clone, Debug)]
+pub struct ContextElement {
+    pub fill: Option<(Paint, Opacity)>,
+    pub stroke: Option<(Paint, Opacity)>,
+}
+
 #[derive(Default)]
 pub struct Cache {
     pub clip_paths: HashMap<String, Rc<ClipPath>>,
     pub masks: HashMap<String, Rc<Mask>>,
     pub filters: HashMap<String, Rc<usvg_tree::filter::Filter>>,
     pub paint: HashMap<String, Paint>,
+    /// Resolved `xlink:href` of `image` and `feImage` elements.
+    pub images: HashMap<String, Option<ImageKind>>,
+    /// All SVG fonts defined in the document.
+    pub svg_fonts: Vec<Rc<SvgFont>>,
 }
 
 impl<'a, 'input: 'a> SvgNode<'a, 'input> {
@@ -92,7 +107,7 @@ impl<'a, 'input: 'a> SvgNode<'a, 'input> {
     }
 
     pub fn has_valid_transform(&self, aid: AId) -> bool {
-        // Do not use Node::attribute::<Transform>, because it will always
+        // Do not use `SvgNode::resolve_transform`, because it will always
         // return a valid transform.
 
         let attr = match self.attribute(aid) {
@@ -100,20 +115,10 @@ impl<'a, 'input: 'a> SvgNode<'a, 'input> {
             None => return true,
         };
 
-        let ts = match svgtypes::Transform::from_str(attr) {
-            Ok(v) => v,
-            Err(_) => return true,
-        };
-
-        let ts = Transform::from_row(
-            ts.a as f32,
-            ts.b as f32,
-            ts.c as f32,
-            ts.d as f32,
-            ts.e as f32,
-            ts.f as f32,
-        );
-        ts.is_valid()
+        match svgtree::parse_transform(attr) {
+            Some(list) => list.is_valid(),
+            None => true,
+        }
     }
 
     pub fn is_visible_element(&self, opt: &crate::Options) -> bool {
@@ -143,6 +148,17 @@ impl SvgColorExt for svgtypes::Color {
 /// - If `Document` doesn't have an SVG node - returns an empty tree.
 /// - If `Document` doesn't have a valid size - returns `Error::InvalidSize`.
 pub(crate) fn convert_doc(svg_doc: &svgtree::Document, opt: &Options) -> Result<Tree, Error> {
+    convert_doc_with_images(svg_doc, opt, &mut HashMap::new())
+}
+
+/// Converts an input `Document` into a `Tree` reusing already resolved images.
+///
+/// Newly resolved images will be added to `images`.
+pub(crate) fn convert_doc_with_images(
+    svg_doc: &svgtree::Document,
+    opt: &Options,
+    images: &mut HashMap<String, Option<ImageKind>>,
+) -> Result<Tree, Error> {
     let svg = svg_doc.root_element();
     let (size, restore_viewbox) = resolve_svg_size(&svg, opt);
     let size = size?;
@@ -157,6 +173,8 @@ pub(crate) fn convert_doc(svg_doc: &svgtree::Document, opt: &Options) -> Result<
         size,
         view_box,
         root: Node::new(NodeKind::Group(Group::default())),
+        animation: None,
+        font_faces: Vec::new(),
     };
 
     if !svg.is_visible_element(opt) {
@@ -169,11 +187,19 @@ pub(crate) fn convert_doc(svg_doc: &svgtree::Document, opt: &Options) -> Result<
         fe_image_link: false,
         view_box: view_box.rect,
         use_size: (None, None),
+        context_element: None,
         opt,
     };
 
-    let mut cache = Cache::default();
+    tree.font_faces = crate::font_face::load(svg_doc, opt);
+
+    let mut cache = Cache {
+        images: std::mem::take(images),
+        svg_fonts: crate::font::collect(svg_doc),
+        ..Cache::default()
+    };
     convert_children(svg_doc.root(), &state, &mut cache, &mut tree.root);
+    *images = cache.images;
 
     remove_empty_groups(&mut tree);
     tree.calculate_abs_transforms();
@@ -192,6 +218,7 @@ fn resolve_svg_size(svg: &SvgNode, opt: &Options) -> (Result<Size, Error>, bool)
         fe_image_link: false,
         view_box: NonZeroRect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap(),
         use_size: (None, None),
+        context_element: None,
         opt,
     };
 
@@ -334,7 +361,7 @@ pub(crate) fn convert_element(
             }
         }
         EId::Image => {
-            crate::image::convert(node, state, parent);
+            crate::image::convert(node, state, cache, parent);
         }
         EId::Text => {
             crate::text::convert(node, state, cache, parent);
@@ -408,18 +435,13 @@ pub(crate) fn convert_clip_path_elements(
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
 enum Isolation {
+    #[default]
     Auto,
     Isolate,
 }
 
-impl Default for Isolation {
-    fn default() -> Self {
-        Self::Auto
-    }
-}
-
 impl<'a, 'input: 'a> FromValue<'a, 'input> for Isolation {
     fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
         match value {
@@ -477,14 +499,23 @@ pub(crate) fn convert_group(
     // `mask` and `filter` cannot be set on `clipPath` children.
     // But `clip-path` can.
 
-    let clip_path = resolve_link!(AId::ClipPath, crate::clippath::convert);
-
-    let mask = if state.parent_clip_path.is_none() {
-        resolve_link!(AId::Mask, crate::mask::convert)
+    // `clip-path` can be either a link or a CSS shape.
+    let is_link = matches!(node.attribute::<&str>(AId::ClipPath), Some(v) if v.starts_with("url("));
+    let clip_path = if is_link {
+        resolve_link!(AId::ClipPath, crate::clippath::convert)
     } else {
-        None
+        crate::clippath::convert_shape(node, state)
     };
 
+    let mut masks = Vec::new();
+    if state.parent_clip_path.is_none() {
+        match crate::mask::convert_layers(node, state, cache) {
+            Ok(v) => masks = v,
+            // If a mask layer is linked to an invalid element - skip this group completely.
+            Err(_) => return GroupKind::Ignore,
+        }
+    }
+
     let filters = {
         let mut filters = Vec::new();
         if state.parent_clip_path.is_none() {
@@ -519,15 +550,28 @@ pub(crate) fn convert_group(
     let isolation: Isolation = node.attribute(AId::Isolation).unwrap_or_default();
     let isolate = isolation == Isolation::Isolate;
 
+    // `color-interpolation` affects only the compositing of container's children.
+    let is_container = matches!(
+        node.tag_name(),
+        Some(EId::Svg) | Some(EId::G) | Some(EId::Use) | Some(EId::Switch) | Some(EId::A)
+    );
+    let color_interpolation = if is_container && state.parent_clip_path.is_none() {
+        node.attribute(AId::ColorInterpolation)
+            .unwrap_or(filter::ColorInterpolation::SRGB)
+    } else {
+        filter::ColorInterpolation::SRGB
+    };
+
     // TODO: ignore just transform
     let is_g_or_use = matches!(node.tag_name(), Some(EId::G) | Some(EId::Use));
     let required = opacity.get().approx_ne_ulps(&1.0, 4)
         || clip_path.is_some()
-        || mask.is_some()
+        || !masks.is_empty()
         || !filters.is_empty()
         || !transform.is_identity()
         || blend_mode != BlendMode::Normal
         || isolate
+        || color_interpolation == filter::ColorInterpolation::LinearRGB
         || is_g_or_use
         || force;
 
@@ -546,8 +590,9 @@ pub(crate) fn convert_group(
             opacity,
             blend_mode,
             isolate,
+            color_interpolation,
             clip_path,
-            mask,
+            masks,
             filters,
         }));
 
@@ -597,7 +642,7 @@ fn remove_empty_groups(tree: &mut Tree) {
 
 fn convert_path(
     node: SvgNode,
-    path: Rc<tiny_skia_path::Path>,
+    path: Arc<tiny_skia_path::Path>,
     state: &State,
     cache: &mut Cache,
     parent: &mut Node,
@@ -611,6 +656,7 @@ fn convert_path(
     let fill = crate::style::resolve_fill(node, has_bbox, state, cache);
     let stroke = crate::style::resolve_stroke(node, has_bbox, state, cache);
     let mut visibility: Visibility = node.find_attribute(AId::Visibility).unwrap_or_default();
+    let pointer_events = node.find_attribute(AId::PointerEvents).unwrap_or_default();
     let rendering_mode: ShapeRendering = node
         .find_attribute(AId::ShapeRendering)
         .unwrap_or(state.opt.shape_rendering);
@@ -643,6 +689,7 @@ fn convert_path(
     let path = Path {
         id,
         visibility,
+        pointer_events,
         fill,
         stroke,
         paint_order,
@@ -668,21 +715,17 @@ fn convert_path(
         [first, PaintOrderKind::Markers, last] => {
             let append_single_paint_path = |paint_order_kind: PaintOrderKind| match paint_order_kind
             {
-                PaintOrderKind::Fill => {
-                    if path.fill.is_some() {
-                        let mut fill_path = path.clone();
-                        fill_path.stroke = None;
-                        fill_path.id = String::new();
-                        parent.append(Node::new(NodeKind::Path(fill_path)));
-                    }
+                PaintOrderKind::Fill if path.fill.is_some() => {
+                    let mut fill_path = path.clone();
+                    fill_path.stroke = None;
+                    fill_path.id = String::new();
+                    parent.append(Node::new(NodeKind::Path(fill_path)));
                 }
-                PaintOrderKind::Stroke => {
-                    if path.stroke.is_some() {
-                        let mut stroke_path = path.clone();
-                        stroke_path.fill = None;
-                        stroke_path.id = String::new();
-                        parent.append(Node::new(NodeKind::Path(stroke_path)));
-                    }
+                PaintOrderKind::Stroke if path.stroke.is_some() => {
+                    let mut stroke_path = path.clone();
+                    stroke_path.fill = None;
+                    stroke_path.id = String::new();
+                    parent.append(Node::new(NodeKind::Path(stroke_path)));
                 }
                 _ => {}
             };
@@ -711,24 +754,50 @@ pub fn svg_paint_order_to_usvg(order: svgtypes::PaintOrder) -> PaintOrder {
 
 impl SvgNode<'_, '_> {
     pub(crate) fn resolve_transform(&self, transform_aid: AId, state: &State) -> Transform {
-        let mut transform: Transform = self.attribute(transform_aid).unwrap_or_default();
-        let transform_origin: Option<TransformOrigin> = self.attribute(AId::TransformOrigin);
+        let list: svgtree::TransformList = match self.attribute(transform_aid) {
+            Some(v) => v,
+            None => return Transform::default(),
+        };
+
+        // `transform-box` doesn't affect `gradientTransform` and `patternTransform`.
+        let reference = if transform_aid == AId::Transform {
+            self.attribute(AId::TransformBox)
+                .unwrap_or(crate::bbox::ReferenceBox::View)
+        } else {
+            crate::bbox::ReferenceBox::View
+        };
+        let rect = crate::bbox::resolve(*self, reference, state)
+            .unwrap_or_else(|| crate::bbox::view_box(state));
+
+        // Percentages are relative to the reference box.
+        let resolve_length = |length: Length, horizontal: bool| {
+            if length.unit == Unit::Percent {
+                let side = if horizontal {
+                    rect.width()
+                } else {
+                    rect.height()
+                };
+                side * length.number as f32 / 100.0
+            } else {
+                let aid = if horizontal { AId::Width } else { AId::Height };
+                units::convert_user_length(length, *self, aid, state)
+            }
+        };
 
+        let mut transform = list.resolve(resolve_length);
+        if !transform.is_valid() {
+            return Transform::default();
+        }
+
+        // The initial `transform-origin` is the top-left corner of the reference box.
+        let (mut dx, mut dy) = (rect.x(), rect.y());
+        let transform_origin: Option<TransformOrigin> = self.attribute(AId::TransformOrigin);
         if let Some(transform_origin) = transform_origin {
-            let dx = convert_length(
-                transform_origin.x_offset,
-                *self,
-                AId::Width,
-                Units::UserSpaceOnUse,
-                state,
-            );
-            let dy = convert_length(
-                transform_origin.y_offset,
-                *self,
-                AId::Height,
-                Units::UserSpaceOnUse,
-                state,
-            );
+            dx += resolve_length(transform_origin.x_offset, true);
+            dy += resolve_length(transform_origin.y_offset, false);
+        }
+
+        if dx != 0.0 || dy != 0.0 {
             transform = Transform::default()
                 .pre_translate(dx, dy)
                 .pre_concat(transform)
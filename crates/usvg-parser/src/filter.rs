@@ -6,7 +6,6 @@
 
 use std::collections::HashSet;
 use std::rc::Rc;
-use std::str::FromStr;
 
 use strict_num::PositiveF32;
 use svgtypes::{Length, LengthUnit as Unit};
@@ -734,7 +733,7 @@ fn convert_image(fe: SvgNode, state: &converter::State, cache: &mut converter::C
         }
     };
 
-    let href = crate::image::get_href_data(href, state.opt);
+    let href = crate::image::get_href_data_cached(href, state, cache);
     let img_data = match href {
         Some(data) => data,
         None => return create_dummy_primitive(),
@@ -791,7 +790,7 @@ fn convert_lighting_color(node: SvgNode) -> Color {
                 .0
         }
         Some(value) => {
-            if let Ok(c) = svgtypes::Color::from_str(value) {
+            if let Some(c) = crate::color::parse_color(value) {
                 c.split_alpha().0
             } else {
                 log::warn!("Failed to parse lighting-color value: '{}'.", value);
@@ -0,0 +1,229 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::rc::Rc;
+
+use strict_num::NonZeroPositiveF32;
+use usvg_tree::{strict_num, SvgFont, SvgGlyph, SvgKerningPair};
+
+use crate::shapes;
+use crate::svgtree::{self, AId, EId, SvgNode};
+
+/// Collects all SVG fonts defined in the document.
+pub(crate) fn collect(doc: &svgtree::Document) -> Vec<Rc<SvgFont>> {
+    doc.descendants()
+        .filter(|n| n.tag_name() == Some(EId::Font))
+        .filter_map(convert)
+        .map(Rc::new)
+        .collect()
+}
+
+fn convert(node: SvgNode) -> Option<SvgFont> {
+    let face = match node
+        .children()
+        .find(|n| n.tag_name() == Some(EId::FontFace))
+    {
+        Some(v) => v,
+        None => {
+            log::warn!(
+                "Font '{}' has no 'font-face' element. Skipped.",
+                node.element_id()
+            );
+            return None;
+        }
+    };
+
+    let family = parse_family(face.attribute(AId::FontFamily).unwrap_or(""));
+    if family.is_empty() {
+        log::warn!(
+            "Font '{}' has no 'font-family'. Skipped.",
+            node.element_id()
+        );
+        return None;
+    }
+
+    let units_per_em = face
+        .attribute(AId::UnitsPerEm)
+        .and_then(NonZeroPositiveF32::new)
+        .unwrap_or_else(|| NonZeroPositiveF32::new(1000.0).unwrap());
+
+    // SVG uses a positive descent, unlike TrueType.
+    let ascent: f32 = face.attribute(AId::Ascent).unwrap_or(units_per_em.get());
+    let descent: f32 = face.attribute(AId::Descent).unwrap_or(0.0);
+
+    let horiz_adv_x: f32 = node.attribute(AId::HorizAdvX).unwrap_or(0.0);
+
+    let mut glyphs = Vec::new();
+    let mut missing_glyph = None;
+    for child in node.children() {
+        match child.tag_name() {
+            Some(EId::Glyph) => {
+                let unicode: &str = child.attribute(AId::Unicode).unwrap_or("");
+                // Glyphs without `unicode` can still be referenced by `hkern`.
+                glyphs.push(convert_glyph(child, unicode, horiz_adv_x));
+            }
+            Some(EId::MissingGlyph) => {
+                missing_glyph = Some(convert_glyph(child, "", horiz_adv_x));
+            }
+            _ => {}
+        }
+    }
+
+    let kerning_pairs = node
+        .children()
+        .filter(|n| n.tag_name() == Some(EId::Hkern))
+        .filter_map(|n| convert_kerning_pair(n, &glyphs))
+        .collect();
+
+    // Use a blank glyph when `missing-glyph` is not set.
+    let missing_glyph = missing_glyph.unwrap_or_else(|| SvgGlyph {
+        name: String::new(),
+        unicode: String::new(),
+        horiz_adv_x,
+        path: None,
+    });
+
+    Some(SvgFont {
+        id: node.element_id().to_string(),
+        family,
+        style: face.attribute(AId::FontStyle).unwrap_or_default(),
+        weight: parse_weight(face.attribute(AId::FontWeight).unwrap_or("")),
+        units_per_em,
+        ascent,
+        descent: -descent,
+        x_height: face.attribute(AId::XHeight),
+        glyphs,
+        missing_glyph,
+        kerning_pairs,
+    })
+}
+
+fn convert_glyph(node: SvgNode, unicode: &str, font_horiz_adv_x: f32) -> SvgGlyph {
+    SvgGlyph {
+        name: node.attribute(AId::GlyphName).unwrap_or("").to_string(),
+        unicode: unicode.to_string(),
+        horiz_adv_x: node.attribute(AId::HorizAdvX).unwrap_or(font_horiz_adv_x),
+        path: shapes::convert_path(node),
+    }
+}
+
+fn convert_kerning_pair(node: SvgNode, glyphs: &[SvgGlyph]) -> Option<SvgKerningPair> {
+    let kerning: f32 = node.attribute(AId::K)?;
+
+    let first = resolve_kerning_glyphs(node, AId::U1, AId::G1, glyphs);
+    let second = resolve_kerning_glyphs(node, AId::U2, AId::G2, glyphs);
+    if first.is_empty() || second.is_empty() {
+        return None;
+    }
+
+    Some(SvgKerningPair {
+        first,
+        second,
+        kerning,
+    })
+}
+
+/// Returns indices of glyphs referenced by `u1`/`u2` and `g1`/`g2` attributes.
+fn resolve_kerning_glyphs(
+    node: SvgNode,
+    unicode_aid: AId,
+    name_aid: AId,
+    glyphs: &[SvgGlyph],
+) -> Vec<usize> {
+    let unicode: &str = node.attribute(unicode_aid).unwrap_or("");
+    let names: &str = node.attribute(name_aid).unwrap_or("");
+
+    let mut indices = Vec::new();
+    for (idx, glyph) in glyphs.iter().enumerate() {
+        let by_unicode = !glyph.unicode.is_empty()
+            && unicode
+                .split(',')
+                .any(|range| unicode_range_matches(range, &glyph.unicode));
+
+        let by_name =
+            !glyph.name.is_empty() && names.split(',').any(|name| name.trim() == glyph.name);
+
+        if by_unicode || by_name {
+            indices.push(idx);
+        }
+    }
+
+    indices
+}
+
+/// Checks that a `u1`/`u2` list item matches the specified glyph characters.
+///
+/// An item can be either a sequence of characters or a Unicode range,
+/// like `U+0041`, `U+0041-005A` or `U+00??`.
+fn unicode_range_matches(range: &str, text: &str) -> bool {
+    // Keep a single whitespace character as is.
+    let range = if range.trim().is_empty() {
+        range
+    } else {
+        range.trim()
+    };
+
+    if range.len() > 2 && (range.starts_with("U+") || range.starts_with("u+")) {
+        let mut chars = text.chars();
+        let c = match (chars.next(), chars.next()) {
+            (Some(c), None) => c as u32,
+            _ => return false,
+        };
+
+        let range = &range[2..];
+        let (start, end) = if let Some((start, end)) = range.split_once('-') {
+            (
+                u32::from_str_radix(start, 16).ok(),
+                u32::from_str_radix(end, 16).ok(),
+            )
+        } else if range.contains('?') {
+            (
+                u32::from_str_radix(&range.replace('?', "0"), 16).ok(),
+                u32::from_str_radix(&range.replace('?', "F"), 16).ok(),
+            )
+        } else {
+            let c = u32::from_str_radix(range, 16).ok();
+            (c, c)
+        };
+
+        match (start, end) {
+            (Some(start), Some(end)) => c >= start && c <= end,
+            _ => false,
+        }
+    } else {
+        range == text
+    }
+}
+
+pub(crate) fn parse_family(value: &str) -> String {
+    // `font-face` can have only a single family.
+    let mut family = value.trim();
+    if family.starts_with(['\'', '"']) {
+        family = &family[1..];
+    }
+
+    if family.ends_with(['\'', '"']) {
+        family = &family[..family.len() - 1];
+    }
+
+    family.trim().to_string()
+}
+
+pub(crate) fn parse_weight(value: &str) -> u16 {
+    match value {
+        "bold" => 700,
+        _ => value
+            .parse()
+            .ok()
+            .filter(|w| (1..=1000).contains(w))
+            .unwrap_or(400),
+    }
+}
+
+/// Checks that a font matches one of the specified families.
+pub(crate) fn matches_families(font: &SvgFont, families: &[String]) -> bool {
+    families
+        .iter()
+        .any(|family| family.eq_ignore_ascii_case(&font.family))
+}
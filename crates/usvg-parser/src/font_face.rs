@@ -0,0 +1,112 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+
+use usvg_tree::{FontFace, FontStyle};
+
+use crate::svgtree::{self, FontFaceRule};
+use crate::{woff, Options};
+
+/// Loads all fonts referenced by `@font-face` rules.
+pub(crate) fn load(doc: &svgtree::Document, opt: &Options) -> Vec<FontFace> {
+    doc.font_faces()
+        .iter()
+        .filter_map(|rule| convert(rule, opt))
+        .collect()
+}
+
+fn convert(rule: &FontFaceRule, opt: &Options) -> Option<FontFace> {
+    let family = crate::font::parse_family(&rule.family);
+    if family.is_empty() {
+        log::warn!("@font-face rule with an empty 'font-family'. Skipped.");
+        return None;
+    }
+
+    // The first source that can be loaded wins.
+    let data = match svgtree::split_css_list(&rule.src, b',')
+        .into_iter()
+        .find_map(|source| load_source(source.trim(), opt))
+    {
+        Some(v) => v,
+        None => {
+            log::warn!("Failed to load any font for the '{}' font family.", family);
+            return None;
+        }
+    };
+
+    // Font ranges, like `100 900`, are not supported. Use the first value.
+    let weight = rule
+        .weight
+        .as_deref()
+        .and_then(|v| v.split_whitespace().next())
+        .map(crate::font::parse_weight)
+        .unwrap_or(400);
+
+    let style = match rule.style.as_deref().map(str::trim) {
+        Some("italic") => FontStyle::Italic,
+        // An angle, like in `oblique 10deg`, is ignored.
+        Some(v) if v.starts_with("oblique") => FontStyle::Oblique,
+        _ => FontStyle::Normal,
+    };
+
+    Some(FontFace {
+        family,
+        style,
+        weight,
+        data: Arc::new(data),
+    })
+}
+
+/// Loads a single `src` list item.
+///
+/// Only `url()` sources are supported, since local fonts are resolved during text layout anyway.
+fn load_source(source: &str, opt: &Options) -> Option<Vec<u8>> {
+    if !source.starts_with("url(") {
+        return None;
+    }
+
+    let end = source.find(')')?;
+    let url = unquote(&source[4..end]);
+
+    let format = source[end + 1..].trim();
+    if let Some(format) = format.strip_prefix("format(") {
+        let format = unquote(format.trim_end_matches(')'));
+        if !matches!(format, "woff" | "woff2" | "truetype" | "opentype") {
+            return None;
+        }
+    }
+
+    let data = if let Ok(url) = data_url::DataUrl::process(url) {
+        url.decode_to_vec().ok()?.0
+    } else {
+        let path = opt.get_abs_path(std::path::Path::new(url));
+        match std::fs::read(&path) {
+            Ok(v) => v,
+            Err(_) => {
+                log::warn!("Failed to load '{}'.", path.display());
+                return None;
+            }
+        }
+    };
+
+    let data = woff::decode(&data);
+    if data.is_none() {
+        log::warn!("Failed to decode a font from '{}'.", shorten(url));
+    }
+
+    data
+}
+
+fn unquote(text: &str) -> &str {
+    text.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+/// Makes sure that data URLs do not flood the log.
+fn shorten(url: &str) -> &str {
+    match url.char_indices().nth(64) {
+        Some((idx, _)) => &url[..idx],
+        None => url,
+    }
+}
@@ -12,15 +12,18 @@ use crate::{converter, OptionLog, Options, TreeParsing};
 
 /// A shorthand for [ImageHrefResolver]'s data function.
 pub type ImageHrefDataResolverFn =
-    Box<dyn Fn(&str, Arc<Vec<u8>>, &Options) -> Option<ImageKind> + Send + Sync>;
+    Arc<dyn Fn(&str, Arc<Vec<u8>>, &Options) -> Option<ImageKind> + Send + Sync>;
 /// A shorthand for [ImageHrefResolver]'s string function.
-pub type ImageHrefStringResolverFn = Box<dyn Fn(&str, &Options) -> Option<ImageKind> + Send + Sync>;
+pub type ImageHrefStringResolverFn = Arc<dyn Fn(&str, &Options) -> Option<ImageKind> + Send + Sync>;
 
 /// An `xlink:href` resolver for `<image>` elements.
 ///
 /// This type can be useful if you want to have an alternative `xlink:href` handling
 /// to the default one. For example, you can forbid access to local files (which is allowed by default)
 /// or add support for resolving actual URLs (usvg doesn't do any network requests).
+///
+/// Resolvers are reference-counted, therefore cloning is cheap.
+#[derive(Clone)]
 pub struct ImageHrefResolver {
     /// Resolver function that will be used when `xlink:href` contains a
     /// [Data URL](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/Data_URIs).
@@ -52,7 +55,7 @@ impl ImageHrefResolver {
     /// Note that it will simply match the `mime` or data's magic.
     /// The actual images would not be decoded. It's up to the renderer.
     pub fn default_data_resolver() -> ImageHrefDataResolverFn {
-        Box::new(
+        Arc::new(
             move |mime: &str, data: Arc<Vec<u8>>, opts: &Options| match mime {
                 "image/jpg" | "image/jpeg" => Some(ImageKind::JPEG(data)),
                 "image/png" => Some(ImageKind::PNG(data)),
@@ -77,7 +80,7 @@ impl ImageHrefResolver {
     /// Paths have to be absolute or relative to the input SVG file or relative to
     /// [Options::resources_dir](crate::Options::resources_dir).
     pub fn default_string_resolver() -> ImageHrefStringResolverFn {
-        Box::new(move |href: &str, opts: &Options| {
+        Arc::new(move |href: &str, opts: &Options| {
             let path = opts.get_abs_path(std::path::Path::new(href));
 
             if path.exists() {
@@ -121,14 +124,20 @@ enum ImageFormat {
     SVG,
 }
 
-pub(crate) fn convert(node: SvgNode, state: &converter::State, parent: &mut Node) -> Option<()> {
+pub(crate) fn convert(
+    node: SvgNode,
+    state: &converter::State,
+    cache: &mut converter::Cache,
+    parent: &mut Node,
+) -> Option<()> {
     let href = node
         .attribute(AId::Href)
         .log_none(|| log::warn!("Image lacks the 'xlink:href' attribute. Skipped."))?;
 
-    let kind = get_href_data(href, state.opt)?;
+    let kind = get_href_data_cached(href, state, cache)?;
 
     let visibility = node.find_attribute(AId::Visibility).unwrap_or_default();
+    let pointer_events = node.find_attribute(AId::PointerEvents).unwrap_or_default();
     let rendering_mode = node
         .find_attribute(AId::ImageRendering)
         .unwrap_or(state.opt.image_rendering);
@@ -174,6 +183,7 @@ pub(crate) fn convert(node: SvgNode, state: &converter::State, parent: &mut Node
     parent.append_kind(NodeKind::Image(Image {
         id,
         visibility,
+        pointer_events,
         view_box,
         rendering_mode,
         kind,
@@ -182,6 +192,21 @@ pub(crate) fn convert(node: SvgNode, state: &converter::State, parent: &mut Node
     Some(())
 }
 
+/// Like [`get_href_data`], but resolves each `href` only once.
+pub(crate) fn get_href_data_cached(
+    href: &str,
+    state: &converter::State,
+    cache: &mut converter::Cache,
+) -> Option<ImageKind> {
+    if let Some(kind) = cache.images.get(href) {
+        return kind.clone();
+    }
+
+    let kind = get_href_data(href, state.opt);
+    cache.images.insert(href.to_string(), kind.clone());
+    kind
+}
+
 pub(crate) fn get_href_data(href: &str, opt: &Options) -> Option<ImageKind> {
     if let Ok(url) = data_url::DataUrl::process(href) {
         let (data, _) = url.decode_to_vec().ok()?;
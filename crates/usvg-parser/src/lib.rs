@@ -15,15 +15,21 @@
 #![warn(missing_copy_implementations)]
 #![allow(clippy::collapsible_else_if)]
 #![allow(clippy::collapsible_if)]
+#![allow(clippy::derivable_impls)]
 #![allow(clippy::field_reassign_with_default)]
 #![allow(clippy::identity_op)]
 #![allow(clippy::question_mark)]
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::upper_case_acronyms)]
 
+mod animation;
+mod bbox;
 mod clippath;
+mod color;
 mod converter;
 mod filter;
+mod font;
+mod font_face;
 mod image;
 mod marker;
 mod mask;
@@ -36,11 +42,13 @@ mod switch;
 mod text;
 mod units;
 mod use_node;
+mod woff;
 
 pub use crate::options::*;
 pub use image::ImageHrefResolver;
 pub use roxmltree;
 pub use svgtree::{AId, EId};
+pub use use_node::{UseHrefCache, UseHrefResolver};
 
 /// List of all errors.
 #[derive(Debug)]
@@ -152,9 +160,22 @@ impl TreeParsing for usvg_tree::Tree {
     }
 
     /// Parses `Tree` from `roxmltree::Document`.
+    ///
+    /// SMIL animations will be stored in [`Tree::animation`](usvg_tree::Tree::animation).
     fn from_xmltree(doc: &roxmltree::Document, opt: &Options) -> Result<Self, Error> {
-        let doc = svgtree::Document::parse_tree(doc)?;
-        crate::converter::convert_doc(&doc, opt)
+        let externals = use_node::load_external_documents(doc, opt);
+        let doc = svgtree::Document::parse_tree(doc, &externals, opt)?;
+        if !doc.has_animations() {
+            return crate::converter::convert_doc(&doc, opt);
+        }
+
+        let mut images = std::collections::HashMap::new();
+        let mut tree = crate::converter::convert_doc_with_images(&doc, opt, &mut images)?;
+        let duration = doc.animations_duration();
+
+        let animation = animation::SmilAnimation::new(doc.into_owned(), opt, duration, images);
+        tree.animation = Some(std::rc::Rc::new(animation));
+        Ok(tree)
     }
 }
 
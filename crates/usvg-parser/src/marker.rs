@@ -3,6 +3,7 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::rc::Rc;
+use std::sync::Arc;
 
 use strict_num::NonZeroPositiveF32;
 use svgtypes::Length;
@@ -46,6 +47,14 @@ pub(crate) fn convert(
     cache: &mut converter::Cache,
     parent: &mut Node,
 ) {
+    // Marker content can reference the shape paint via `context-fill` and `context-stroke`.
+    let has_bbox = path.bounds().width() > 0.0 && path.bounds().height() > 0.0;
+    let mut state = state.clone();
+    state.context_element = Some(crate::style::resolve_context_element(
+        node, has_bbox, &state, cache,
+    ));
+    let state = &state;
+
     let list = [
         (AId::MarkerStart, MarkerKind::Start),
         (AId::MarkerMid, MarkerKind::Middle),
@@ -121,7 +130,7 @@ fn resolve(
 
         let clip_path = ClipPath::default();
 
-        let mut path = Path::new(Rc::new(tiny_skia_path::PathBuilder::from_rect(
+        let mut path = Path::new(Arc::new(tiny_skia_path::PathBuilder::from_rect(
             clip_rect.to_rect(),
         )));
         path.fill = Some(usvg_tree::Fill::default());
@@ -5,9 +5,12 @@
 use std::rc::Rc;
 
 use svgtypes::{Length, LengthUnit as Unit};
-use usvg_tree::{Group, Mask, MaskType, Node, NodeKind, NonZeroRect, Units};
+use usvg_tree::{
+    Group, Mask, MaskComposite, MaskLayer, MaskLength, MaskMode, MaskPosition, MaskRepeat,
+    MaskSize, MaskSource, MaskType, Node, NodeKind, NonZeroRect, Units,
+};
 
-use crate::svgtree::{AId, EId, SvgNode};
+use crate::svgtree::{self, AId, EId, SvgNode};
 use crate::{converter, OptionLog};
 
 pub(crate) fn convert(
@@ -42,16 +45,8 @@ pub(crate) fn convert(
     let rect =
         rect.log_none(|| log::warn!("Mask '{}' has an invalid size. Skipped.", node.element_id()))?;
 
-    // Resolve linked mask.
-    let mut mask = None;
-    if let Some(link) = node.attribute::<SvgNode>(AId::Mask) {
-        mask = convert(link, state, cache);
-
-        // Linked `mask` must be valid.
-        if mask.is_none() {
-            return None;
-        }
-    }
+    // Resolve linked masks. They must be valid.
+    let masks = convert_layers(node, state, cache).ok()?;
 
     let kind = if node.attribute(AId::MaskType) == Some("alpha") {
         MaskType::Alpha
@@ -65,7 +60,7 @@ pub(crate) fn convert(
         content_units,
         rect,
         kind,
-        mask,
+        masks,
         root: Node::new(NodeKind::Group(Group::default())),
     };
 
@@ -82,3 +77,417 @@ pub(crate) fn convert(
         None
     }
 }
+
+/// Converts `mask` and `mask-*` properties into a list of mask layers.
+///
+/// Longhands always override the corresponding `mask` shorthand values,
+/// since we do not preserve the declarations order.
+///
+/// Returns an error when one of the layers references an invalid `mask` element.
+/// Such elements should not be rendered.
+pub(crate) fn convert_layers(
+    node: SvgNode,
+    state: &converter::State,
+    cache: &mut converter::Cache,
+) -> Result<Vec<MaskLayer>, ()> {
+    let mut layers = Vec::new();
+    if let Some(value) = node.attribute::<&str>(AId::Mask) {
+        layers = split_layers(value)
+            .map(|text| parse_layer(text, node, state))
+            .collect::<Option<Vec<_>>>()
+            .log_none(|| log::warn!("Failed to parse mask value: '{}'.", value))
+            .unwrap_or_default();
+    }
+
+    if let Some(images) = parse_longhand(node, AId::MaskImage, |tokens| match tokens {
+        [token] => parse_image(token),
+        _ => None,
+    }) {
+        layers.resize(images.len(), LayerValue::default());
+        for (layer, image) in layers.iter_mut().zip(images) {
+            layer.image = image;
+        }
+    }
+
+    macro_rules! apply_longhand {
+        ($aid:expr, $field:ident, $parser:expr) => {
+            if let Some(values) = parse_longhand(node, $aid, $parser) {
+                // Missing values are repeated.
+                for (layer, value) in layers.iter_mut().zip(values.iter().cycle()) {
+                    layer.$field = *value;
+                }
+            }
+        };
+    }
+
+    apply_longhand!(AId::MaskMode, mode, |tokens| match tokens {
+        [token] => parse_mode(token),
+        _ => None,
+    });
+    apply_longhand!(AId::MaskComposite, composite, |tokens| match tokens {
+        [token] => parse_composite(token),
+        _ => None,
+    });
+    apply_longhand!(AId::MaskPosition, position, |tokens| {
+        parse_position(tokens, node, state)
+    });
+    apply_longhand!(AId::MaskSize, size, |tokens| parse_size(
+        tokens, node, state
+    ));
+    apply_longhand!(AId::MaskRepeat, repeat, parse_repeat);
+
+    let mut mask_layers = Vec::new();
+    for layer in layers {
+        let source = match layer.image {
+            // `none` layers are transparent and do not affect the result.
+            ImageValue::None => continue,
+            ImageValue::Link(id) => {
+                let link = match node.document().element_by_id(id) {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                // If a layer is linked to an invalid element - skip the element completely.
+                MaskSource::Mask(convert(link, state, cache).ok_or(())?)
+            }
+            ImageValue::Url(href) => match crate::image::get_href_data_cached(href, state, cache) {
+                Some(kind) => MaskSource::Image(kind),
+                None => {
+                    log::warn!("Failed to load a mask image: '{}'.", href);
+                    continue;
+                }
+            },
+        };
+
+        mask_layers.push(MaskLayer {
+            source,
+            mode: layer.mode,
+            composite: layer.composite,
+            position: layer.position,
+            size: layer.size,
+            repeat: layer.repeat,
+        });
+    }
+
+    Ok(mask_layers)
+}
+
+/// Returns all elements referenced by `mask` or `mask-image`.
+pub(crate) fn links<'a, 'input: 'a>(
+    node: SvgNode<'a, 'input>,
+    aid: AId,
+) -> Vec<SvgNode<'a, 'input>> {
+    let value = match node.attribute::<&str>(aid) {
+        Some(v) => v,
+        None => return Vec::new(),
+    };
+
+    split_layers(value)
+        .flat_map(tokenize)
+        .filter_map(|token| match parse_image(token)? {
+            ImageValue::Link(id) => node.document().element_by_id(id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum ImageValue<'a> {
+    None,
+    Link(&'a str),
+    Url(&'a str),
+}
+
+#[derive(Clone, Copy)]
+struct LayerValue<'a> {
+    image: ImageValue<'a>,
+    mode: MaskMode,
+    composite: MaskComposite,
+    position: MaskPosition,
+    size: MaskSize,
+    repeat: MaskRepeat,
+}
+
+impl Default for LayerValue<'_> {
+    fn default() -> Self {
+        LayerValue {
+            image: ImageValue::None,
+            mode: MaskMode::default(),
+            composite: MaskComposite::default(),
+            position: MaskPosition::default(),
+            size: MaskSize::default(),
+            repeat: MaskRepeat::default(),
+        }
+    }
+}
+
+fn parse_longhand<'a, T>(
+    node: SvgNode<'a, '_>,
+    aid: AId,
+    parser: impl Fn(&[&'a str]) -> Option<T>,
+) -> Option<Vec<T>> {
+    let value: &str = node.attribute(aid)?;
+    split_layers(value)
+        .map(|text| parser(&tokenize(text)))
+        .collect::<Option<Vec<_>>>()
+        .log_none(|| log::warn!("Failed to parse {} value: '{}'.", aid, value))
+}
+
+/// Parses a single `mask` shorthand layer.
+///
+/// `mask-clip` and `mask-origin` are not supported and the object bounding box is used instead.
+fn parse_layer<'a>(
+    text: &'a str,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<LayerValue<'a>> {
+    let tokens = tokenize(text);
+
+    let mut layer = LayerValue::default();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+
+        if let Some(image) = parse_image(token) {
+            layer.image = image;
+        } else if let Some(mode) = parse_mode(token) {
+            layer.mode = mode;
+        } else if let Some(composite) = parse_composite(token) {
+            layer.composite = composite;
+        } else if is_geometry_box(token) {
+            // Ignore.
+        } else if is_repeat_keyword(token) {
+            let len = count_tokens(&tokens[i..], 2, is_repeat_keyword);
+            layer.repeat = parse_repeat(&tokens[i..i + len])?;
+            i += len;
+            continue;
+        } else if is_position_token(token) {
+            let len = count_tokens(&tokens[i..], 2, is_position_token);
+            layer.position = parse_position(&tokens[i..i + len], node, state)?;
+            i += len;
+
+            if tokens.get(i) == Some(&"/") {
+                i += 1;
+                let len = count_tokens(&tokens[i..], 2, is_size_token);
+                layer.size = parse_size(&tokens[i..i + len], node, state)?;
+                i += len;
+            }
+
+            continue;
+        } else {
+            return None;
+        }
+
+        i += 1;
+    }
+
+    Some(layer)
+}
+
+fn parse_image(text: &str) -> Option<ImageValue<'_>> {
+    if text == "none" {
+        return Some(ImageValue::None);
+    }
+
+    if let Some(url) = text.strip_prefix("url(").and_then(|s| s.strip_suffix(')')) {
+        let url = url.trim();
+        let url = url
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .or_else(|| url.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+            .unwrap_or(url);
+
+        return Some(match url.strip_prefix('#') {
+            Some(id) => ImageValue::Link(id),
+            None => ImageValue::Url(url),
+        });
+    }
+
+    if text.ends_with(')') {
+        log::warn!("'{}' is not supported as a mask image.", text);
+        return Some(ImageValue::None);
+    }
+
+    None
+}
+
+fn parse_mode(text: &str) -> Option<MaskMode> {
+    match text {
+        "alpha" => Some(MaskMode::Alpha),
+        "luminance" => Some(MaskMode::Luminance),
+        "match-source" => Some(MaskMode::MatchSource),
+        _ => None,
+    }
+}
+
+fn parse_composite(text: &str) -> Option<MaskComposite> {
+    match text {
+        "add" => Some(MaskComposite::Add),
+        "subtract" => Some(MaskComposite::Subtract),
+        "intersect" => Some(MaskComposite::Intersect),
+        "exclude" => Some(MaskComposite::Exclude),
+        _ => None,
+    }
+}
+
+fn is_geometry_box(text: &str) -> bool {
+    matches!(
+        text,
+        "content-box"
+            | "padding-box"
+            | "border-box"
+            | "margin-box"
+            | "fill-box"
+            | "stroke-box"
+            | "view-box"
+            | "no-clip"
+    )
+}
+
+fn is_repeat_keyword(text: &str) -> bool {
+    matches!(
+        text,
+        "repeat-x" | "repeat-y" | "repeat" | "space" | "round" | "no-repeat"
+    )
+}
+
+/// `space` and `round` are not supported and will fallback to `repeat`.
+fn parse_repeat(tokens: &[&str]) -> Option<MaskRepeat> {
+    let is_repeat = |text: &str| match text {
+        "repeat" | "space" | "round" => Some(true),
+        "no-repeat" => Some(false),
+        _ => None,
+    };
+
+    match *tokens {
+        ["repeat-x"] => Some(MaskRepeat { x: true, y: false }),
+        ["repeat-y"] => Some(MaskRepeat { x: false, y: true }),
+        [token] => {
+            let v = is_repeat(token)?;
+            Some(MaskRepeat { x: v, y: v })
+        }
+        [x, y] => Some(MaskRepeat {
+            x: is_repeat(x)?,
+            y: is_repeat(y)?,
+        }),
+        _ => None,
+    }
+}
+
+fn is_position_token(text: &str) -> bool {
+    matches!(text, "left" | "center" | "right" | "top" | "bottom") || text.parse::<Length>().is_ok()
+}
+
+/// Parses a one or two values `mask-position`.
+fn parse_position(
+    tokens: &[&str],
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<MaskPosition> {
+    let (x, y) = match *tokens {
+        [token] if matches!(token, "top" | "bottom") => ("center", token),
+        [token] => (token, "center"),
+        [first, second]
+            if matches!(first, "top" | "bottom") || matches!(second, "left" | "right") =>
+        {
+            (second, first)
+        }
+        [first, second] => (first, second),
+        _ => return None,
+    };
+
+    Some(MaskPosition {
+        x: parse_position_component(x, "left", "right", node, state)?,
+        y: parse_position_component(y, "top", "bottom", node, state)?,
+    })
+}
+
+fn parse_position_component(
+    text: &str,
+    start: &str,
+    end: &str,
+    node: SvgNode,
+    state: &converter::State,
+) -> Option<MaskLength> {
+    if text == start {
+        Some(MaskLength::Relative(0.0))
+    } else if text == end {
+        Some(MaskLength::Relative(1.0))
+    } else if text == "center" {
+        Some(MaskLength::Relative(0.5))
+    } else {
+        parse_length(text, node, state)
+    }
+}
+
+fn is_size_token(text: &str) -> bool {
+    matches!(text, "auto" | "cover" | "contain") || text.parse::<Length>().is_ok()
+}
+
+fn parse_size(tokens: &[&str], node: SvgNode, state: &converter::State) -> Option<MaskSize> {
+    let parse_side = |text: &str| {
+        if text == "auto" {
+            Some(None)
+        } else {
+            parse_length(text, node, state).map(Some)
+        }
+    };
+
+    match *tokens {
+        ["cover"] => Some(MaskSize::Cover),
+        ["contain"] => Some(MaskSize::Contain),
+        [width] => Some(MaskSize::Explicit {
+            width: parse_side(width)?,
+            height: None,
+        }),
+        [width, height] => Some(MaskSize::Explicit {
+            width: parse_side(width)?,
+            height: parse_side(height)?,
+        }),
+        _ => None,
+    }
+}
+
+fn parse_length(text: &str, node: SvgNode, state: &converter::State) -> Option<MaskLength> {
+    let length: Length = text.parse().ok()?;
+    if length.unit == Unit::Percent {
+        Some(MaskLength::Relative(length.number as f32 / 100.0))
+    } else {
+        Some(MaskLength::Absolute(crate::units::convert_user_length(
+            length,
+            node,
+            AId::MaskPosition,
+            state,
+        )))
+    }
+}
+
+fn count_tokens(tokens: &[&str], max: usize, f: impl Fn(&str) -> bool) -> usize {
+    tokens.iter().take(max).take_while(|t| f(t)).count()
+}
+
+/// Splits a value into comma-separated layers.
+fn split_layers(text: &str) -> impl Iterator<Item = &str> {
+    svgtree::split_css_list(text, b',')
+        .into_iter()
+        .map(str::trim)
+}
+
+/// Splits a layer into whitespace-separated tokens.
+///
+/// `/` is always a separate token.
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    for token in svgtree::split_css_list_by(text, |c| c.is_ascii_whitespace()) {
+        for (i, part) in svgtree::split_css_list(token, b'/').into_iter().enumerate() {
+            if i != 0 {
+                tokens.push("/");
+            }
+
+            tokens.push(part);
+        }
+    }
+
+    tokens.retain(|t| !t.is_empty());
+    tokens
+}
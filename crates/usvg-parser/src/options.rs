@@ -2,12 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use usvg_tree::{ImageRendering, ShapeRendering, Size, TextRendering};
+use usvg_tree::{Color, ImageRendering, ShapeRendering, Size, TextRendering};
 
-use crate::ImageHrefResolver;
+use crate::{ImageHrefResolver, UseHrefResolver};
 
 /// Processing options.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Options {
     /// Directory that will be used during relative paths resolving.
     ///
@@ -78,6 +78,36 @@ pub struct Options {
     ///
     /// Default: see type's documentation for details
     pub image_href_resolver: ImageHrefResolver,
+
+    /// Specifies the way `xlink:href` in `<use>` elements referencing external files
+    /// should be handled.
+    ///
+    /// Default: see type's documentation for details
+    pub use_href_resolver: UseHrefResolver,
+
+    /// A user style sheet.
+    ///
+    /// Applied with the user origin precedence. Meaning that its normal declarations
+    /// are overridden by the document's style sheets and presentation attributes,
+    /// while `!important` ones override everything.
+    ///
+    /// Default: `None`
+    pub style_sheet: Option<String>,
+
+    /// A preferred color scheme.
+    ///
+    /// Will be used to resolve the `prefers-color-scheme` CSS media feature.
+    ///
+    /// Default: Light
+    pub color_scheme: ColorScheme,
+
+    /// An initial `color` property value, which is referenced by `currentColor`.
+    ///
+    /// Acts like a color inherited from an embedding document,
+    /// therefore the document's own `color` has a higher priority.
+    ///
+    /// Default: `None`, which means black
+    pub current_color: Option<Color>,
 }
 
 impl Default for Options {
@@ -94,6 +124,10 @@ impl Default for Options {
             image_rendering: ImageRendering::default(),
             default_size: Size::from_wh(100.0, 100.0).unwrap(),
             image_href_resolver: ImageHrefResolver::default(),
+            use_href_resolver: UseHrefResolver::default(),
+            style_sheet: None,
+            color_scheme: ColorScheme::default(),
+            current_color: None,
         }
     }
 }
@@ -109,3 +143,19 @@ impl Options {
         }
     }
 }
+
+/// A color scheme.
+///
+/// `prefers-color-scheme` in CSS.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self::Light
+    }
+}
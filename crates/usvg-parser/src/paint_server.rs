@@ -3,7 +3,6 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::rc::Rc;
-use std::str::FromStr;
 
 use strict_num::PositiveF32;
 use svgtypes::{Length, LengthUnit as Unit};
@@ -71,6 +70,7 @@ fn convert_linear(node: SvgNode, state: &converter::State) -> Option<ServerOrCol
             units,
             transform,
             spread_method: convert_spread_method(node),
+            color_interpolation: convert_color_interpolation(node),
             stops,
         },
     };
@@ -119,6 +119,9 @@ fn convert_radial(node: SvgNode, state: &converter::State) -> Option<ServerOrCol
     );
     let fx = resolve_number(node, AId::Fx, units, state, Length::new_number(cx as f64));
     let fy = resolve_number(node, AId::Fy, units, state, Length::new_number(cy as f64));
+    // A negative value is an error.
+    let fr = resolve_number(node, AId::Fr, units, state, Length::zero());
+    let fr = PositiveF32::new(fr).unwrap_or(PositiveF32::ZERO);
     let transform = node.resolve_transform(AId::GradientTransform, state);
 
     let gradient = RadialGradient {
@@ -127,11 +130,13 @@ fn convert_radial(node: SvgNode, state: &converter::State) -> Option<ServerOrCol
         r: PositiveF32::new(r).unwrap(),
         fx,
         fy,
+        fr,
         base: BaseGradient {
             id: node.element_id().to_string(),
             units,
             transform,
             spread_method,
+            color_interpolation: convert_color_interpolation(node),
             stops,
         },
     };
@@ -200,6 +205,13 @@ fn convert_spread_method(node: SvgNode) -> SpreadMethod {
     node.attribute(AId::SpreadMethod).unwrap_or_default()
 }
 
+fn convert_color_interpolation(node: SvgNode) -> filter::ColorInterpolation {
+    // `color-interpolation` is a property and not a gradient attribute,
+    // therefore it's not resolved via `xlink:href`.
+    node.find_attribute(AId::ColorInterpolation)
+        .unwrap_or(filter::ColorInterpolation::SRGB)
+}
+
 pub(crate) fn convert_units(node: SvgNode, name: AId, def: Units) -> Units {
     let node = resolve_attr(node, name);
     node.attribute(name).unwrap_or(def)
@@ -273,7 +285,7 @@ fn convert_stops(grad: SvgNode) -> Vec<Stop> {
                     .find_attribute(AId::Color)
                     .unwrap_or_else(svgtypes::Color::black),
                 Some(value) => {
-                    if let Ok(c) = svgtypes::Color::from_str(value) {
+                    if let Some(c) = crate::color::parse_color(value) {
                         c
                     } else {
                         log::warn!("Failed to parse stop-color value: '{}'.", value);
@@ -449,6 +461,7 @@ fn resolve_rg_attr<'a, 'input>(node: SvgNode<'a, 'input>, name: AId) -> SvgNode<
             | (AId::R,  EId::RadialGradient)
             | (AId::Fx, EId::RadialGradient)
             | (AId::Fy, EId::RadialGradient)
+            | (AId::Fr, EId::RadialGradient)
             // Other attributes can be resolved
             // from any kind of gradient.
             | (AId::GradientUnits, EId::LinearGradient)
@@ -2,16 +2,16 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use svgtypes::Length;
 use tiny_skia_path::Path;
-use usvg_tree::{tiny_skia_path, ApproxEqUlps, IsValidLength};
+use usvg_tree::{tiny_skia_path, ApproxEqUlps, IsValidLength, NonZeroRect};
 
 use crate::svgtree::{AId, EId, SvgNode};
 use crate::{converter, units};
 
-pub(crate) fn convert(node: SvgNode, state: &converter::State) -> Option<Rc<Path>> {
+pub(crate) fn convert(node: SvgNode, state: &converter::State) -> Option<Arc<Path>> {
     match node.tag_name()? {
         EId::Rect => convert_rect(node, state),
         EId::Circle => convert_circle(node, state),
@@ -24,8 +24,12 @@ pub(crate) fn convert(node: SvgNode, state: &converter::State) -> Option<Rc<Path
     }
 }
 
-pub(crate) fn convert_path(node: SvgNode) -> Option<Rc<Path>> {
-    let value: &str = node.attribute(AId::D)?;
+pub(crate) fn convert_path(node: SvgNode) -> Option<Arc<Path>> {
+    parse_path_data(node.attribute(AId::D)?).map(Arc::new)
+}
+
+/// Parses path data. Everything after the first error is ignored.
+pub(crate) fn parse_path_data(value: &str) -> Option<Path> {
     let mut builder = tiny_skia_path::PathBuilder::new();
     for segment in svgtypes::SimplifyingPathParser::from(value) {
         let segment = match segment {
@@ -61,10 +65,10 @@ pub(crate) fn convert_path(node: SvgNode) -> Option<Rc<Path>> {
         }
     }
 
-    builder.finish().map(Rc::new)
+    builder.finish()
 }
 
-fn convert_rect(node: SvgNode, state: &converter::State) -> Option<Rc<Path>> {
+fn convert_rect(node: SvgNode, state: &converter::State) -> Option<Arc<Path>> {
     // 'width' and 'height' attributes must be positive and non-zero.
     let width = node.convert_user_length(AId::Width, state, Length::zero());
     let height = node.convert_user_length(AId::Height, state, Length::zero());
@@ -122,7 +126,7 @@ fn convert_rect(node: SvgNode, state: &converter::State) -> Option<Rc<Path>> {
         builder.finish()?
     };
 
-    Some(Rc::new(path))
+    Some(Arc::new(path))
 }
 
 fn resolve_rx_ry(node: SvgNode, state: &converter::State) -> (f32, f32) {
@@ -160,7 +164,7 @@ fn resolve_rx_ry(node: SvgNode, state: &converter::State) -> (f32, f32) {
     }
 }
 
-fn convert_line(node: SvgNode, state: &converter::State) -> Option<Rc<Path>> {
+fn convert_line(node: SvgNode, state: &converter::State) -> Option<Arc<Path>> {
     let x1 = node.convert_user_length(AId::X1, state, Length::zero());
     let y1 = node.convert_user_length(AId::Y1, state, Length::zero());
     let x2 = node.convert_user_length(AId::X2, state, Length::zero());
@@ -169,18 +173,18 @@ fn convert_line(node: SvgNode, state: &converter::State) -> Option<Rc<Path>> {
     let mut builder = tiny_skia_path::PathBuilder::new();
     builder.move_to(x1, y1);
     builder.line_to(x2, y2);
-    builder.finish().map(Rc::new)
+    builder.finish().map(Arc::new)
 }
 
-fn convert_polyline(node: SvgNode) -> Option<Rc<Path>> {
+fn convert_polyline(node: SvgNode) -> Option<Arc<Path>> {
     let builder = points_to_path(node, "Polyline")?;
-    builder.finish().map(Rc::new)
+    builder.finish().map(Arc::new)
 }
 
-fn convert_polygon(node: SvgNode) -> Option<Rc<Path>> {
+fn convert_polygon(node: SvgNode) -> Option<Arc<Path>> {
     let mut builder = points_to_path(node, "Polygon")?;
     builder.close();
-    builder.finish().map(Rc::new)
+    builder.finish().map(Arc::new)
 }
 
 fn points_to_path(node: SvgNode, eid: &str) -> Option<tiny_skia_path::PathBuilder> {
@@ -220,7 +224,7 @@ fn points_to_path(node: SvgNode, eid: &str) -> Option<tiny_skia_path::PathBuilde
     Some(builder)
 }
 
-fn convert_circle(node: SvgNode, state: &converter::State) -> Option<Rc<Path>> {
+fn convert_circle(node: SvgNode, state: &converter::State) -> Option<Arc<Path>> {
     let cx = node.convert_user_length(AId::Cx, state, Length::zero());
     let cy = node.convert_user_length(AId::Cy, state, Length::zero());
     let r = node.convert_user_length(AId::R, state, Length::zero());
@@ -236,7 +240,7 @@ fn convert_circle(node: SvgNode, state: &converter::State) -> Option<Rc<Path>> {
     ellipse_to_path(cx, cy, r, r)
 }
 
-fn convert_ellipse(node: SvgNode, state: &converter::State) -> Option<Rc<Path>> {
+fn convert_ellipse(node: SvgNode, state: &converter::State) -> Option<Arc<Path>> {
     let cx = node.convert_user_length(AId::Cx, state, Length::zero());
     let cy = node.convert_user_length(AId::Cy, state, Length::zero());
     let (rx, ry) = resolve_rx_ry(node, state);
@@ -260,7 +264,7 @@ fn convert_ellipse(node: SvgNode, state: &converter::State) -> Option<Rc<Path>>
     ellipse_to_path(cx, cy, rx, ry)
 }
 
-fn ellipse_to_path(cx: f32, cy: f32, rx: f32, ry: f32) -> Option<Rc<Path>> {
+pub(crate) fn ellipse_to_path(cx: f32, cy: f32, rx: f32, ry: f32) -> Option<Arc<Path>> {
     let mut builder = tiny_skia_path::PathBuilder::new();
     builder.move_to(cx + rx, cy);
     builder.arc_to(rx, ry, 0.0, false, true, cx, cy + ry);
@@ -268,7 +272,34 @@ fn ellipse_to_path(cx: f32, cy: f32, rx: f32, ry: f32) -> Option<Rc<Path>> {
     builder.arc_to(rx, ry, 0.0, false, true, cx, cy - ry);
     builder.arc_to(rx, ry, 0.0, false, true, cx + rx, cy);
     builder.close();
-    builder.finish().map(Rc::new)
+    builder.finish().map(Arc::new)
+}
+
+/// Converts a rectangle with per-corner radii into a path.
+///
+/// Radii are in the top-left, top-right, bottom-right, bottom-left order
+/// and must be already clamped.
+pub(crate) fn rounded_rect_to_path(rect: NonZeroRect, radii: [(f32, f32); 4]) -> Option<Path> {
+    let (x, y, right, bottom) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+    let [tl, tr, br, bl] = radii;
+
+    let mut builder = tiny_skia_path::PathBuilder::new();
+    builder.move_to(x + tl.0, y);
+
+    builder.line_to(right - tr.0, y);
+    builder.arc_to(tr.0, tr.1, 0.0, false, true, right, y + tr.1);
+
+    builder.line_to(right, bottom - br.1);
+    builder.arc_to(br.0, br.1, 0.0, false, true, right - br.0, bottom);
+
+    builder.line_to(x + bl.0, bottom);
+    builder.arc_to(bl.0, bl.1, 0.0, false, true, x, bottom - bl.1);
+
+    builder.line_to(x, y + tl.1);
+    builder.arc_to(tl.0, tl.1, 0.0, false, true, x + tl.0, y);
+
+    builder.close();
+    builder.finish()
 }
 
 trait PathBuilderExt {
@@ -26,6 +26,7 @@ impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::LineJoin {
             "miter-clip" => Some(usvg_tree::LineJoin::MiterClip),
             "round" => Some(usvg_tree::LineJoin::Round),
             "bevel" => Some(usvg_tree::LineJoin::Bevel),
+            "arcs" => Some(usvg_tree::LineJoin::Arcs),
             _ => None,
         }
     }
@@ -112,6 +113,7 @@ pub(crate) fn resolve_stroke(
         width,
         linecap: node.find_attribute(AId::StrokeLinecap).unwrap_or_default(),
         linejoin: node.find_attribute(AId::StrokeLinejoin).unwrap_or_default(),
+        non_scaling: node.attribute(AId::VectorEffect) == Some("non-scaling-stroke"),
     };
 
     Some(stroke)
@@ -129,7 +131,10 @@ fn convert_paint(
     let paint = match svgtypes::Paint::from_str(value) {
         Ok(v) => v,
         Err(_) => {
-            if aid == AId::Fill {
+            // `svgtypes` doesn't support CSS Color Module Level 4 functions.
+            if let Some(c) = crate::color::parse_color(value) {
+                svgtypes::Paint::Color(c)
+            } else if aid == AId::Fill {
                 log::warn!(
                     "Failed to parse fill value: '{}'. Fallback to black.",
                     value
@@ -187,8 +192,51 @@ fn convert_paint(
                 from_fallback(node, fallback, opacity)
             }
         }
-        // Ignore `context-fill` and `context-stroke for now
-        _ => None,
+        svgtypes::Paint::ContextFill | svgtypes::Paint::ContextStroke => {
+            // Without a context element, context paints are equal to `none`.
+            let context = state.context_element.as_ref()?;
+            let (paint, context_opacity) = if paint == svgtypes::Paint::ContextFill {
+                context.fill.clone()?
+            } else {
+                context.stroke.clone()?
+            };
+
+            if !has_bbox && paint.units() == Some(Units::ObjectBoundingBox) {
+                return None;
+            }
+
+            *opacity = *opacity * context_opacity;
+            Some(paint)
+        }
+    }
+}
+
+/// Resolves paints that children of the `node` can reference
+/// via `context-fill` and `context-stroke`.
+pub(crate) fn resolve_context_element(
+    node: SvgNode,
+    has_bbox: bool,
+    state: &converter::State,
+    cache: &mut converter::Cache,
+) -> converter::ContextElement {
+    let mut resolve = |aid: AId, opacity_aid: AId| {
+        let mut sub_opacity = Opacity::ONE;
+        let paint = match node.ancestors().find(|n| n.has_attribute(aid)) {
+            Some(n) => convert_paint(n, aid, has_bbox, state, &mut sub_opacity, cache)?,
+            None if aid == AId::Fill => Paint::Color(Color::black()),
+            None => return None,
+        };
+
+        let opacity = node
+            .find_attribute::<Opacity>(opacity_aid)
+            .unwrap_or(Opacity::ONE);
+
+        Some((paint, sub_opacity * opacity))
+    };
+
+    converter::ContextElement {
+        fill: resolve(AId::Fill, AId::FillOpacity),
+        stroke: resolve(AId::Stroke, AId::StrokeOpacity),
     }
 }
 
@@ -0,0 +1,804 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! SMIL animations support.
+//!
+//! Animation elements are not stored in the tree. Instead, they are collected
+//! into a list and applied on demand, by overwriting the target element attributes
+//! with values calculated for a specific time.
+
+use std::str::FromStr;
+
+use super::{AId, Attribute, Document, EId, NodeId, NodeKind, ShortRange};
+
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+
+pub(crate) struct Animation {
+    target: Target,
+    attribute: AId,
+    kind: Kind,
+    values: Values,
+    calc_mode: CalcMode,
+    key_times: Vec<f32>,
+    key_splines: Vec<[f32; 4]>,
+    begin: Vec<f32>,
+    end: Vec<f32>,
+    dur: Option<f32>,
+    repeat_count: Option<f32>,
+    repeat_dur: Option<f32>,
+    freeze: bool,
+    additive: bool,
+    accumulate: bool,
+}
+
+enum Target {
+    Node(NodeId),
+    Link(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Kind {
+    Animate,
+    Set,
+    Transform(TransformKind),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TransformKind {
+    Translate,
+    Scale,
+    Rotate,
+    SkewX,
+    SkewY,
+}
+
+enum Values {
+    List(Vec<String>),
+    FromTo(Option<String>, Option<String>),
+    FromBy(Option<String>, String),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CalcMode {
+    Discrete,
+    Linear,
+    Paced,
+    Spline,
+}
+
+pub(crate) fn parse_animation(
+    node: roxmltree::Node,
+    parent_id: NodeId,
+    ignore_ids: bool,
+    doc: &mut Document,
+) {
+    let link = node
+        .attribute((XLINK_NS, "href"))
+        .or_else(|| node.attribute("href"));
+    let target = match link {
+        Some(link) => {
+            // Elements copied by `use` must not register linked animations again.
+            if ignore_ids {
+                return;
+            }
+
+            match svgtypes::IRI::from_str(link) {
+                Ok(iri) => Target::Link(iri.0.to_string()),
+                Err(_) => {
+                    log::warn!("Invalid animation target '{}'. Skipped.", link);
+                    return;
+                }
+            }
+        }
+        None => Target::Node(parent_id),
+    };
+
+    if let Some(animation) = convert_animation(node, target) {
+        doc.animations.push(animation);
+    }
+}
+
+fn convert_animation(node: roxmltree::Node, target: Target) -> Option<Animation> {
+    let tag_name = super::parse::parse_tag_name(node)?;
+
+    let name = node.attribute("attributeName");
+    let attribute = match name.and_then(AId::from_str) {
+        Some(AId::Id) | None => {
+            log::warn!(
+                "Animation of the '{}' attribute is not supported. Skipped.",
+                name.unwrap_or_default()
+            );
+            return None;
+        }
+        Some(aid) => aid,
+    };
+
+    let kind = match tag_name {
+        EId::Set => Kind::Set,
+        EId::AnimateTransform => {
+            let kind = match node.attribute("type").unwrap_or("translate") {
+                "translate" => TransformKind::Translate,
+                "scale" => TransformKind::Scale,
+                "rotate" => TransformKind::Rotate,
+                "skewX" => TransformKind::SkewX,
+                "skewY" => TransformKind::SkewY,
+                s => {
+                    log::warn!("Unknown animateTransform type '{}'. Skipped.", s);
+                    return None;
+                }
+            };
+
+            Kind::Transform(kind)
+        }
+        _ => Kind::Animate,
+    };
+
+    let values = if kind == Kind::Set {
+        Values::FromTo(None, Some(node.attribute("to")?.to_string()))
+    } else if let Some(list) = node.attribute("values") {
+        let list: Vec<String> = list
+            .split(';')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect();
+        if list.is_empty() {
+            return None;
+        }
+
+        Values::List(list)
+    } else {
+        let from = node.attribute("from").map(|s| s.trim().to_string());
+        match (node.attribute("to"), node.attribute("by")) {
+            (Some(to), _) => Values::FromTo(from, Some(to.trim().to_string())),
+            (None, Some(by)) => Values::FromBy(from, by.trim().to_string()),
+            (None, None) => return None,
+        }
+    };
+
+    let calc_mode = match node.attribute("calcMode") {
+        Some("discrete") => CalcMode::Discrete,
+        Some("paced") => CalcMode::Paced,
+        Some("spline") => CalcMode::Spline,
+        _ => CalcMode::Linear,
+    };
+
+    let key_times = node
+        .attribute("keyTimes")
+        .map(|list| parse_list(list, |s| s.parse::<f32>().ok()))
+        .unwrap_or_default()
+        .unwrap_or_default();
+
+    let key_splines = node
+        .attribute("keySplines")
+        .map(|list| {
+            parse_list(list, |s| {
+                let mut numbers = svgtypes::NumberListParser::from(s);
+                let mut spline = [0.0; 4];
+                for n in &mut spline {
+                    *n = numbers.next()?.ok()? as f32;
+                }
+
+                if numbers.next().is_some() {
+                    return None;
+                }
+
+                Some(spline)
+            })
+        })
+        .unwrap_or_default()
+        .unwrap_or_default();
+
+    let begin = match node.attribute("begin") {
+        Some(list) => parse_offsets(list),
+        None => vec![0.0],
+    };
+    if begin.is_empty() {
+        // An animation that never starts.
+        return None;
+    }
+
+    let end = node.attribute("end").map(parse_offsets).unwrap_or_default();
+
+    let dur = node.attribute("dur").and_then(parse_clock_value);
+    let dur = dur.filter(|d| *d > 0.0);
+
+    let repeat_count = match node.attribute("repeatCount") {
+        Some("indefinite") => Some(f32::INFINITY),
+        Some(s) => s.trim().parse::<f32>().ok().filter(|n| *n > 0.0),
+        None => None,
+    };
+
+    let repeat_dur = match node.attribute("repeatDur") {
+        Some("indefinite") => Some(f32::INFINITY),
+        Some(s) => parse_clock_value(s).filter(|n| *n > 0.0),
+        None => None,
+    };
+
+    Some(Animation {
+        target,
+        attribute,
+        kind,
+        values,
+        calc_mode,
+        key_times,
+        key_splines,
+        begin,
+        end,
+        dur,
+        repeat_count,
+        repeat_dur,
+        freeze: node.attribute("fill") == Some("freeze"),
+        additive: node.attribute("additive") == Some("sum"),
+        accumulate: node.attribute("accumulate") == Some("sum"),
+    })
+}
+
+/// Parses a semicolon-separated list.
+///
+/// Returns `None` when any of the items is invalid.
+fn parse_list<T>(list: &str, f: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    list.split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(f)
+        .collect()
+}
+
+/// Parses a `begin` or `end` list.
+///
+/// Only offset values are supported. Event, syncbase and wallclock values are ignored.
+fn parse_offsets(list: &str) -> Vec<f32> {
+    let mut offsets = Vec::new();
+    for item in list.split(';').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        if item == "indefinite" {
+            continue;
+        }
+
+        let (sign, value) = if let Some(s) = item.strip_prefix('-') {
+            (-1.0, s)
+        } else {
+            (1.0, item.strip_prefix('+').unwrap_or(item))
+        };
+
+        match parse_clock_value(value) {
+            Some(n) => offsets.push(n * sign),
+            None => log::warn!("Unsupported animation timing value '{}'.", item),
+        }
+    }
+
+    offsets.sort_by(|a, b| a.total_cmp(b));
+    offsets
+}
+
+/// Parses a [clock value](https://www.w3.org/TR/SMIL3/smil-timing.html#q22) in seconds.
+fn parse_clock_value(s: &str) -> Option<f32> {
+    let s = s.trim();
+
+    if s.contains(':') {
+        // Full or partial clock value.
+        let mut seconds = 0.0;
+        let parts: Vec<&str> = s.split(':').collect();
+        if parts.len() > 3 {
+            return None;
+        }
+
+        for part in parts {
+            if part.is_empty() || !part.bytes().all(|c| c.is_ascii_digit() || c == b'.') {
+                return None;
+            }
+
+            seconds = seconds * 60.0 + part.parse::<f32>().ok()?;
+        }
+
+        return Some(seconds);
+    }
+
+    let (number, scale) = if let Some(n) = s.strip_suffix("ms") {
+        (n, 0.001)
+    } else if let Some(n) = s.strip_suffix("min") {
+        (n, 60.0)
+    } else if let Some(n) = s.strip_suffix('h') {
+        (n, 3600.0)
+    } else if let Some(n) = s.strip_suffix('s') {
+        (n, 1.0)
+    } else {
+        (s, 1.0)
+    };
+
+    if number.is_empty() || !number.bytes().all(|c| c.is_ascii_digit() || c == b'.') {
+        return None;
+    }
+
+    number.parse::<f32>().ok().map(|n| n * scale)
+}
+
+impl Animation {
+    /// Returns the active duration of a single interval.
+    fn active_duration(&self) -> f32 {
+        let dur = match self.dur {
+            Some(dur) => dur,
+            None => return self.repeat_dur.unwrap_or(f32::INFINITY),
+        };
+
+        if self.repeat_count.is_none() && self.repeat_dur.is_none() {
+            return dur;
+        }
+
+        let by_count = self.repeat_count.map(|n| n * dur).unwrap_or(f32::INFINITY);
+        let by_dur = self.repeat_dur.unwrap_or(f32::INFINITY);
+        by_count.min(by_dur)
+    }
+
+    /// Returns an active interval that starts at `begin`.
+    fn interval(&self, idx: usize) -> (f32, f32) {
+        let begin = self.begin[idx];
+        let mut end = begin + self.active_duration();
+
+        if let Some(e) = self.end.iter().find(|e| **e >= begin) {
+            end = end.min(*e);
+        }
+
+        // A new interval restarts the animation.
+        if let Some(next) = self.begin.get(idx + 1) {
+            end = end.min(*next);
+        }
+
+        (begin, end)
+    }
+
+    /// Returns the time when this animation ends.
+    fn end_time(&self) -> f32 {
+        self.interval(self.begin.len() - 1).1
+    }
+
+    /// Returns a progress of the simple duration in a 0..1 range
+    /// and a current repeat iteration for the specified time.
+    ///
+    /// Returns `None` when the animation has no effect.
+    fn sample(&self, time: f32) -> Option<(f32, u32)> {
+        let idx = self.begin.iter().rposition(|b| *b <= time)?;
+        let (begin, end) = self.interval(idx);
+
+        let (local_time, frozen) = if time < end {
+            (time - begin, false)
+        } else if self.freeze {
+            (end - begin, true)
+        } else {
+            return None;
+        };
+
+        let dur = match self.dur {
+            Some(dur) => dur,
+            // An indefinite simple duration always stays at the beginning.
+            None => return Some((0.0, 0)),
+        };
+
+        let mut iteration = (local_time / dur).floor();
+        let mut progress = (local_time - iteration * dur) / dur;
+
+        // The frozen value of an animation that ended exactly at the end
+        // of an iteration is the last value, not the first one.
+        if frozen && iteration > 0.0 && progress.abs() < 1e-6 {
+            iteration -= 1.0;
+            progress = 1.0;
+        }
+
+        Some((progress.clamp(0.0, 1.0), iteration as u32))
+    }
+
+    /// Returns an animated value for the specified time.
+    fn value_at(&self, base: Option<&str>, time: f32) -> Option<String> {
+        let (progress, iteration) = self.sample(time)?;
+
+        if self.kind == Kind::Set {
+            return match self.values {
+                Values::FromTo(_, ref to) => to.clone(),
+                _ => None,
+            };
+        }
+
+        let mut additive = self.additive;
+        let values: Vec<String> = match self.values {
+            Values::List(ref list) => list.clone(),
+            Values::FromTo(ref from, ref to) => {
+                let to = to.clone()?;
+                match from {
+                    Some(from) => vec![from.clone(), to],
+                    None => {
+                        // A to-animation starts from the base value and is never additive.
+                        additive = false;
+                        match base {
+                            Some(base) if !self.is_transform() => vec![base.to_string(), to],
+                            _ => vec![to],
+                        }
+                    }
+                }
+            }
+            Values::FromBy(ref from, ref by) => match from {
+                Some(from) => {
+                    let to = self.mix(from, by, 1.0, 1.0)?;
+                    vec![from.clone(), to]
+                }
+                None => {
+                    // A by-animation is always additive.
+                    additive = true;
+                    vec![self.zero(by)?, by.clone()]
+                }
+            },
+        };
+
+        let mut value = self.interpolate(&values, progress)?;
+
+        if self.accumulate && iteration > 0 {
+            if let Some(last) = values.last() {
+                value = self.mix(&value, last, 1.0, iteration as f32)?;
+            }
+        }
+
+        if self.is_transform() {
+            value = self.to_transform(&value)?;
+        }
+
+        if additive {
+            if let Some(base) = base {
+                value = if self.is_transform() {
+                    format!("{} {}", base, value)
+                } else {
+                    self.mix(base, &value, 1.0, 1.0).unwrap_or(value)
+                };
+            }
+        }
+
+        Some(value)
+    }
+
+    fn interpolate(&self, values: &[String], progress: f32) -> Option<String> {
+        let n = values.len();
+        if n == 1 || self.calc_mode == CalcMode::Discrete {
+            return Some(self.discrete(values, progress));
+        }
+
+        let key_times = if self.key_times.len() == n {
+            self.key_times.clone()
+        } else {
+            (0..n).map(|i| i as f32 / (n - 1) as f32).collect()
+        };
+
+        let idx = key_times
+            .iter()
+            .rposition(|t| *t <= progress)
+            .unwrap_or(0)
+            .min(n - 2);
+        let start = key_times[idx];
+        let end = key_times[idx + 1];
+        let mut t = if end > start {
+            ((progress - start) / (end - start)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        if self.calc_mode == CalcMode::Spline {
+            if let Some(spline) = self.key_splines.get(idx) {
+                t = ease(*spline, t);
+            }
+        }
+
+        // Values that cannot be interpolated use the discrete mode.
+        self.mix(&values[idx], &values[idx + 1], 1.0 - t, t)
+            .or_else(|| Some(self.discrete(values, progress)))
+    }
+
+    fn discrete(&self, values: &[String], progress: f32) -> String {
+        let n = values.len();
+        let idx = if self.key_times.len() == n {
+            self.key_times
+                .iter()
+                .rposition(|t| *t <= progress)
+                .unwrap_or(0)
+        } else {
+            ((progress * n as f32) as usize).min(n - 1)
+        };
+
+        values[idx].clone()
+    }
+
+    fn is_transform(&self) -> bool {
+        matches!(self.kind, Kind::Transform(_))
+    }
+
+    /// Returns `a * ka + b * kb`.
+    fn mix(&self, a: &str, b: &str, ka: f32, kb: f32) -> Option<String> {
+        if let Kind::Transform(kind) = self.kind {
+            let a = transform_values(kind, a)?;
+            let b = transform_values(kind, b)?;
+            let list: Vec<String> = a
+                .iter()
+                .zip(b.iter())
+                .map(|(a, b)| fmt_number(a * ka + b * kb))
+                .collect();
+            return Some(list.join(" "));
+        }
+
+        if is_color_attribute(self.attribute) {
+            if let (Ok(a), Ok(b)) = (svgtypes::Color::from_str(a), svgtypes::Color::from_str(b)) {
+                let f = |a: u8, b: u8| (a as f32 * ka + b as f32 * kb).round().clamp(0.0, 255.0);
+                return Some(format!(
+                    "rgba({}, {}, {}, {})",
+                    f(a.red, b.red),
+                    f(a.green, b.green),
+                    f(a.blue, b.blue),
+                    fmt_number(f(a.alpha, b.alpha) / 255.0)
+                ));
+            }
+        }
+
+        // Links and hex colors are not interpolatable.
+        if a.contains('#') || b.contains('#') {
+            return None;
+        }
+
+        let (a_skeleton, a_numbers) = split_numbers(a);
+        let (b_skeleton, b_numbers) = split_numbers(b);
+        if a_numbers.is_empty() || a_skeleton != b_skeleton {
+            return None;
+        }
+
+        let mut numbers = a_numbers
+            .iter()
+            .zip(b_numbers.iter())
+            .map(|(a, b)| a * ka + b * kb);
+
+        let mut s = String::new();
+        for (i, part) in a_skeleton.iter().enumerate() {
+            if i > 0 {
+                s.push_str(&fmt_number(numbers.next()?));
+            }
+            s.push_str(part);
+        }
+
+        Some(s)
+    }
+
+    /// Returns a zero value with the same structure as `value`.
+    fn zero(&self, value: &str) -> Option<String> {
+        self.mix(value, value, 0.0, 0.0)
+    }
+
+    fn to_transform(&self, values: &str) -> Option<String> {
+        let kind = match self.kind {
+            Kind::Transform(kind) => kind,
+            _ => return None,
+        };
+
+        let name = match kind {
+            TransformKind::Translate => "translate",
+            TransformKind::Scale => "scale",
+            TransformKind::Rotate => "rotate",
+            TransformKind::SkewX => "skewX",
+            TransformKind::SkewY => "skewY",
+        };
+
+        Some(format!("{}({})", name, values))
+    }
+}
+
+fn is_color_attribute(aid: AId) -> bool {
+    matches!(
+        aid,
+        AId::Color
+            | AId::Fill
+            | AId::FloodColor
+            | AId::LightingColor
+            | AId::StopColor
+            | AId::Stroke
+    )
+}
+
+/// Parses `animateTransform` values into a list with all optional values resolved.
+fn transform_values(kind: TransformKind, s: &str) -> Option<Vec<f32>> {
+    let mut list = Vec::new();
+    for n in svgtypes::NumberListParser::from(s) {
+        list.push(n.ok()? as f32);
+    }
+
+    let list = match (kind, list.as_slice()) {
+        (TransformKind::Translate, [x]) => vec![*x, 0.0],
+        (TransformKind::Translate, [x, y]) => vec![*x, *y],
+        (TransformKind::Scale, [x]) => vec![*x, *x],
+        (TransformKind::Scale, [x, y]) => vec![*x, *y],
+        (TransformKind::Rotate, [a]) => vec![*a, 0.0, 0.0],
+        (TransformKind::Rotate, [a, x, y]) => vec![*a, *x, *y],
+        (TransformKind::SkewX, [a]) | (TransformKind::SkewY, [a]) => vec![*a],
+        _ => return None,
+    };
+
+    Some(list)
+}
+
+/// Splits a string into numbers and everything in between them.
+///
+/// The first list always has one more item than the second one.
+fn split_numbers(s: &str) -> (Vec<&str>, Vec<f32>) {
+    let mut skeleton = Vec::new();
+    let mut numbers = Vec::new();
+
+    let bytes = s.as_bytes();
+    let mut part_start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let is_number_start = c.is_ascii_digit()
+            || (c == b'.' && matches!(bytes.get(i + 1), Some(c) if c.is_ascii_digit()))
+            || ((c == b'-' || c == b'+')
+                && matches!(bytes.get(i + 1), Some(c) if c.is_ascii_digit() || *c == b'.'));
+
+        if !is_number_start {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+            i += 1;
+        }
+
+        // An exponent.
+        if i + 1 < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+            let mut j = i + 1;
+            if bytes[j] == b'-' || bytes[j] == b'+' {
+                j += 1;
+            }
+
+            if matches!(bytes.get(j), Some(c) if c.is_ascii_digit()) {
+                i = j;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+        }
+
+        if let Ok(n) = s[start..i].parse::<f32>() {
+            skeleton.push(&s[part_start..start]);
+            numbers.push(n);
+            part_start = i;
+        }
+    }
+
+    skeleton.push(&s[part_start..]);
+    (skeleton, numbers)
+}
+
+fn fmt_number(n: f32) -> String {
+    // Prevent `-0` and long fractional parts.
+    let n = (n * 10000.0).round() / 10000.0;
+    if n == 0.0 {
+        "0".to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+/// Applies a cubic Bézier easing function to `t`.
+fn ease(spline: [f32; 4], t: f32) -> f32 {
+    let [x1, y1, x2, y2] = spline;
+    let bezier = |p1: f32, p2: f32, t: f32| {
+        let mt = 1.0 - t;
+        3.0 * mt * mt * t * p1 + 3.0 * mt * t * t * p2 + t * t * t
+    };
+
+    // Find a curve parameter for `t` on the X axis using bisection.
+    let mut low = 0.0;
+    let mut high = 1.0;
+    let mut mid = t;
+    for _ in 0..32 {
+        mid = (low + high) / 2.0;
+        if bezier(x1, x2, mid) < t {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    bezier(y1, y2, mid)
+}
+
+impl<'input> Document<'input> {
+    /// Checks that the document has any animations.
+    pub fn has_animations(&self) -> bool {
+        !self.animations.is_empty()
+    }
+
+    /// Returns the time in seconds when all animations are finished.
+    ///
+    /// Returns `None` when there are no animations or when they never end.
+    pub fn animations_duration(&self) -> Option<f32> {
+        let mut duration: Option<f32> = None;
+        for animation in &self.animations {
+            let end = animation.end_time();
+            if !end.is_finite() {
+                return None;
+            }
+
+            duration = Some(duration.map_or(end, |d| d.max(end)));
+        }
+
+        duration
+    }
+
+    /// Returns a static copy of the document, with animated attributes
+    /// overwritten by their values at the specified time in seconds.
+    pub fn at_time(&self, time: f32) -> Document<'input> {
+        let mut doc = Document {
+            nodes: self.nodes.clone(),
+            attrs: self.attrs.clone(),
+            links: self.links.clone(),
+            animations: Vec::new(),
+            font_faces: self.font_faces.clone(),
+            custom_properties: self.custom_properties.clone(),
+        };
+
+        for animation in &self.animations {
+            let node_id = match animation.target {
+                Target::Node(id) => id,
+                Target::Link(ref link) => match doc.links.get(link) {
+                    Some(id) => *id,
+                    None => continue,
+                },
+            };
+
+            let base = doc.animation_base_value(node_id, animation.attribute);
+            if let Some(value) = animation.value_at(base.as_deref(), time) {
+                doc.set_attribute(node_id, animation.attribute, value);
+            }
+        }
+
+        doc
+    }
+
+    fn animation_base_value(&self, node_id: NodeId, aid: AId) -> Option<String> {
+        let node = self.get(node_id);
+        let node = if aid.is_inheritable() {
+            node.ancestors().find(|n| n.has_attribute(aid))?
+        } else {
+            node
+        };
+
+        node.attribute::<&str>(aid).map(|s| s.to_string())
+    }
+
+    fn set_attribute(&mut self, node_id: NodeId, aid: AId, value: String) {
+        let range = match self.nodes[node_id.get_usize()].kind {
+            NodeKind::Element { attributes, .. } => attributes,
+            _ => return,
+        };
+
+        let value = roxmltree::StringStorage::new_owned(value);
+
+        if let Some(attr) = self.attrs[range.to_urange()]
+            .iter_mut()
+            .find(|a| a.name == aid)
+        {
+            attr.value = value;
+            return;
+        }
+
+        // Attributes of an element must be stored continuously,
+        // therefore we have to move them to the end of the list.
+        let start = self.attrs.len();
+        for idx in range.to_urange() {
+            let attr = self.attrs[idx].clone();
+            self.attrs.push(attr);
+        }
+        self.attrs.push(Attribute { name: aid, value });
+
+        if let NodeKind::Element {
+            ref mut attributes, ..
+        } = self.nodes[node_id.get_usize()].kind
+        {
+            *attributes = ShortRange::new(start as u32, self.attrs.len() as u32);
+        }
+    }
+}
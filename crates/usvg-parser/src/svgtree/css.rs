@@ -0,0 +1,1242 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A minimal CSS cascade implementation.
+//!
+//! Supports:
+//! - Selectors Level 3 and `:not()`, `:is()` and `:where()` from Level 4.
+//! - Specificity and `!important`.
+//! - `@media` rules with `prefers-color-scheme`, `width`, `height` and `orientation` features.
+//! - `@font-face` rules.
+//! - Custom properties and `var()`.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use svgtypes::{Length, LengthUnit as Unit};
+
+use super::FontFaceRule;
+use crate::{ColorScheme, Options};
+
+/// A CSS declaration.
+#[derive(Clone, Debug)]
+pub(crate) struct Declaration<'a> {
+    pub name: &'a str,
+    pub value: Cow<'a, str>,
+    pub important: bool,
+}
+
+struct Rule<'a> {
+    selector: Selector<'a>,
+    declarations: Vec<Declaration<'a>>,
+}
+
+/// A style sheet.
+///
+/// Rules are sorted by specificity, with the source order preserved for equal ones.
+pub(crate) struct StyleSheet<'a> {
+    rules: Vec<Rule<'a>>,
+    pub font_faces: Vec<FontFaceRule>,
+}
+
+impl<'a> StyleSheet<'a> {
+    pub fn new() -> Self {
+        StyleSheet {
+            rules: Vec::new(),
+            font_faces: Vec::new(),
+        }
+    }
+
+    /// Parses a style sheet from a text to the current style sheet.
+    ///
+    /// `@media` rules that do not match `media` are skipped.
+    pub fn parse_more(&mut self, text: &'a str, media: &Media) {
+        self.parse_rules(text, media);
+        self.rules.sort_by_key(|rule| rule.selector.specificity());
+    }
+
+    fn parse_rules(&mut self, text: &'a str, media: &Media) {
+        let bytes = text.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                    i = skip_css_comment(bytes, i);
+                    continue;
+                }
+                // CDO and CDC tokens are allowed at the top level.
+                b'<' if text[i..].starts_with("<!--") => {
+                    i += 4;
+                    continue;
+                }
+                b'-' if text[i..].starts_with("-->") => {
+                    i += 3;
+                    continue;
+                }
+                c if c.is_ascii_whitespace() => {
+                    i += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            // A rule prelude ends at a block or, for at-rules, at a semicolon.
+            let is_at_rule = bytes[i] == b'@';
+            let start = i;
+            let mut block = None;
+            let mut prelude_end = bytes.len();
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                        i = skip_css_comment(bytes, i);
+                        continue;
+                    }
+                    b'"' | b'\'' => {
+                        i = skip_css_string(bytes, i);
+                        continue;
+                    }
+                    b';' if is_at_rule => {
+                        prelude_end = i;
+                        break;
+                    }
+                    b'{' => {
+                        prelude_end = i;
+                        let end = find_block_end(bytes, i + 1).unwrap_or(bytes.len());
+                        block = Some(&text[i + 1..end]);
+                        i = end;
+                        break;
+                    }
+                    _ => {}
+                }
+
+                i += 1;
+            }
+
+            let prelude = text[start..prelude_end].trim();
+            i += 1;
+
+            if is_at_rule {
+                self.parse_at_rule(prelude, block, media);
+            } else if let Some(block) = block {
+                self.parse_rule_set(prelude, block);
+            }
+        }
+    }
+
+    fn parse_at_rule(&mut self, prelude: &'a str, block: Option<&'a str>, media: &Media) {
+        let name_end = prelude
+            .find(|c: char| c.is_ascii_whitespace() || c == '(' || c == '"' || c == '\'')
+            .unwrap_or(prelude.len());
+        let (name, query) = prelude[1..].split_at(name_end - 1);
+
+        match (name.to_ascii_lowercase().as_str(), block) {
+            ("media", Some(block)) => {
+                if media.matches(query) {
+                    self.parse_rules(block, media);
+                }
+            }
+            ("font-face", Some(block)) => {
+                self.font_faces.extend(parse_font_face_rule(block));
+            }
+            _ => log::warn!("The @{} rule is not supported. Skipped.", name),
+        }
+    }
+
+    fn matched_declarations(&self, node: roxmltree::Node) -> Vec<Declaration<'a>> {
+        let mut declarations = Vec::new();
+        for rule in &self.rules {
+            if rule.selector.matches(node) {
+                declarations.extend(rule.declarations.iter().cloned());
+            }
+        }
+
+        declarations
+    }
+
+    fn parse_rule_set(&mut self, prelude: &'a str, block: &'a str) {
+        // A single invalid selector invalidates the whole rule.
+        let selectors = match parse_selector_list(prelude) {
+            Some(v) => v,
+            None => {
+                log::warn!("Unsupported CSS selector: '{}'. Skipped.", prelude);
+                return;
+            }
+        };
+
+        let declarations = parse_declarations(block);
+        if declarations.is_empty() {
+            return;
+        }
+
+        for selector in selectors {
+            self.rules.push(Rule {
+                selector,
+                declarations: declarations.clone(),
+            });
+        }
+    }
+}
+
+/// Style sheets of different origins.
+#[derive(Clone, Copy)]
+pub(crate) struct StyleSheets<'a> {
+    /// A style sheet set via `Options`.
+    pub user: &'a StyleSheet<'a>,
+    /// Document's style sheets.
+    pub author: &'a StyleSheet<'a>,
+}
+
+/// Returns declarations that apply to an element, in the cascade order.
+///
+/// Later declarations take precedence over earlier ones. The order is:
+///
+/// 1. Normal user declarations.
+/// 2. Normal author declarations, followed by the `style` attribute.
+/// 3. Important author declarations, followed by the `style` attribute.
+/// 4. Important user declarations.
+///
+/// Presentation attributes must be placed between the first and the second groups,
+/// therefore the number of normal user declarations is returned as well.
+pub(crate) fn cascade<'a>(
+    style_sheets: StyleSheets<'a>,
+    node: roxmltree::Node<'a, '_>,
+) -> (Vec<Declaration<'a>>, usize) {
+    let mut author = style_sheets.author.matched_declarations(node);
+    if let Some(value) = node.attribute("style") {
+        author.extend(parse_declarations(value));
+    }
+
+    // The sort is stable, so the order of declarations with the same importance is preserved.
+    author.sort_by_key(|d| d.important);
+
+    let (important, mut declarations): (Vec<_>, Vec<_>) = style_sheets
+        .user
+        .matched_declarations(node)
+        .into_iter()
+        .partition(|d| d.important);
+
+    let user_len = declarations.len();
+    declarations.extend(author);
+    declarations.extend(important);
+    (declarations, user_len)
+}
+
+/// Parses a declarations block or a `style` attribute.
+pub(crate) fn parse_declarations(text: &str) -> Vec<Declaration<'_>> {
+    let mut declarations = Vec::new();
+
+    // We cannot use a simple split here, because values can contain semicolons
+    // inside strings and URLs, like `data:` ones.
+    for declaration in split_css_list(text, b';') {
+        let (name, value) = match declaration.split_once(':') {
+            Some(v) => v,
+            None => continue,
+        };
+
+        // Skip comments before the name.
+        let name = name.rsplit("*/").next().unwrap_or(name).trim();
+        let mut value = value.trim();
+        let mut important = false;
+        if let Some(idx) = value.rfind('!') {
+            if value[idx + 1..]
+                .trim_start()
+                .eq_ignore_ascii_case("important")
+            {
+                value = value[..idx].trim_end();
+                important = true;
+            }
+        }
+
+        if name.is_empty() {
+            continue;
+        }
+
+        declarations.push(Declaration {
+            name,
+            value: remove_css_comments(value),
+            important,
+        });
+    }
+
+    declarations
+}
+
+fn parse_font_face_rule(body: &str) -> Option<FontFaceRule> {
+    let mut family = None;
+    let mut src = None;
+    let mut weight = None;
+    let mut style = None;
+
+    for declaration in parse_declarations(body) {
+        let value = declaration.value.into_owned();
+        match declaration.name.to_ascii_lowercase().as_str() {
+            "font-family" => family = Some(value),
+            "src" => src = Some(value),
+            "font-weight" => weight = Some(value),
+            "font-style" => style = Some(value),
+            _ => {}
+        }
+    }
+
+    match (family, src) {
+        (Some(family), Some(src)) => Some(FontFaceRule {
+            family,
+            src,
+            weight,
+            style,
+        }),
+        _ => {
+            log::warn!("@font-face rule without 'font-family' or 'src'. Skipped.");
+            None
+        }
+    }
+}
+
+/// Custom properties of an element, including inherited ones.
+pub(crate) type CustomProperties = HashMap<String, String>;
+
+/// Substitutes `var()` functions in a value.
+///
+/// Returns `None` when a custom property is not defined and there is no fallback.
+pub(crate) fn substitute_vars(value: &str, properties: &CustomProperties) -> Option<String> {
+    substitute_vars_impl(value, properties, 0)
+}
+
+fn substitute_vars_impl(value: &str, properties: &CustomProperties, depth: u32) -> Option<String> {
+    // Prevent infinite recursion on cyclic references.
+    if depth > 16 {
+        return None;
+    }
+
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("var(") {
+        result.push_str(&rest[..start]);
+
+        let args_start = start + 4;
+        let args_end = find_closing_paren(rest.as_bytes(), args_start)?;
+        let args = &rest[args_start..args_end];
+        let (name, fallback) = match split_css_list(args, b',').first() {
+            Some(name) if name.len() < args.len() => (name.trim(), Some(&args[name.len() + 1..])),
+            _ => (args.trim(), None),
+        };
+
+        let resolved = match properties.get(name) {
+            Some(v) => substitute_vars_impl(v, properties, depth + 1)?,
+            None => substitute_vars_impl(fallback?.trim(), properties, depth + 1)?,
+        };
+        result.push_str(&resolved);
+
+        rest = &rest[args_end + 1..];
+    }
+
+    result.push_str(rest);
+    Some(result)
+}
+
+/// Returns the position of the `)` that closes a function starting at `pos`.
+fn find_closing_paren(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    let mut depth = 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'"' | b'\'' => {
+                pos = skip_css_string(bytes, pos);
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos);
+                }
+            }
+            _ => {}
+        }
+
+        pos += 1;
+    }
+
+    None
+}
+
+/// An environment `@media` queries are evaluated against.
+pub(crate) struct Media {
+    width: f32,
+    height: f32,
+    dpi: f32,
+    font_size: f32,
+    dark: bool,
+}
+
+impl Media {
+    /// Creates a media environment for a document.
+    ///
+    /// The viewport size is the size of the root `svg` element.
+    pub fn new(xml: &roxmltree::Document, opt: &Options) -> Self {
+        let root = xml.root_element();
+        let view_box = root
+            .attribute("viewBox")
+            .and_then(|v| svgtypes::ViewBox::from_str(v).ok());
+
+        let mut media = Media {
+            width: 0.0,
+            height: 0.0,
+            dpi: opt.dpi,
+            font_size: opt.font_size,
+            dark: opt.color_scheme == ColorScheme::Dark,
+        };
+
+        media.width = media.resolve_size(
+            root.attribute("width"),
+            view_box.map(|vb| vb.w as f32),
+            opt.default_size.width(),
+        );
+        media.height = media.resolve_size(
+            root.attribute("height"),
+            view_box.map(|vb| vb.h as f32),
+            opt.default_size.height(),
+        );
+
+        media
+    }
+
+    fn resolve_size(&self, value: Option<&str>, view_box: Option<f32>, default: f32) -> f32 {
+        let length = value
+            .and_then(|v| Length::from_str(v).ok())
+            .unwrap_or(Length::new(100.0, Unit::Percent));
+
+        match length.unit {
+            Unit::Percent => view_box.unwrap_or(default) * length.number as f32 / 100.0,
+            _ => self.convert_length(length).unwrap_or(default),
+        }
+    }
+
+    fn convert_length(&self, length: Length) -> Option<f32> {
+        let n = length.number as f32;
+        let n = match length.unit {
+            Unit::None | Unit::Px => n,
+            Unit::Em => n * self.font_size,
+            Unit::Ex => n * self.font_size / 2.0,
+            Unit::In => n * self.dpi,
+            Unit::Cm => n * self.dpi / 2.54,
+            Unit::Mm => n * self.dpi / 25.4,
+            Unit::Pt => n * self.dpi / 72.0,
+            Unit::Pc => n * self.dpi / 6.0,
+            Unit::Percent => return None,
+        };
+
+        Some(n)
+    }
+
+    /// Checks that a media query list matches the environment.
+    ///
+    /// An empty list matches everything.
+    fn matches(&self, query_list: &str) -> bool {
+        let query_list = query_list.trim();
+        if query_list.is_empty() {
+            return true;
+        }
+
+        split_css_list(query_list, b',')
+            .iter()
+            .any(|query| self.matches_query(query).unwrap_or(false))
+    }
+
+    /// Evaluates a single media query.
+    ///
+    /// Returns `None` for unsupported or malformed queries, which never match.
+    fn matches_query(&self, query: &str) -> Option<bool> {
+        let mut rest = query.trim();
+        let mut negate = false;
+        if let Some(tail) = strip_keyword(rest, "not") {
+            negate = true;
+            rest = tail;
+        } else if let Some(tail) = strip_keyword(rest, "only") {
+            rest = tail;
+        }
+
+        let mut result = true;
+        let mut is_first = true;
+        while !rest.is_empty() {
+            if !is_first {
+                rest = strip_keyword(rest, "and")?;
+            }
+            is_first = false;
+
+            if rest.starts_with('(') {
+                let end = find_closing_paren(rest.as_bytes(), 1)?;
+                result &= self.matches_feature(&rest[1..end])?;
+                rest = rest[end + 1..].trim_start();
+            } else {
+                let end = rest
+                    .find(|c: char| c.is_ascii_whitespace())
+                    .unwrap_or(rest.len());
+                result &= match rest[..end].to_ascii_lowercase().as_str() {
+                    "all" | "screen" => true,
+                    "print" | "speech" => false,
+                    _ => return None,
+                };
+                rest = rest[end..].trim_start();
+            }
+        }
+
+        Some(result != negate)
+    }
+
+    fn matches_feature(&self, feature: &str) -> Option<bool> {
+        let feature = feature.trim();
+
+        // A range syntax, like `width >= 400px` or `400px <= width <= 800px`.
+        if let Some(idx) = feature.find(['<', '>', '=']) {
+            let (name, op, rest) = split_range(feature, idx)?;
+            return match rest.find(['<', '>', '=']) {
+                Some(idx) => {
+                    let (name2, op2, value) = split_range(rest, idx)?;
+                    // The feature name is in the middle, so the first comparison is flipped.
+                    let lhs = self.compare(name2, op.flip(), name)?;
+                    let rhs = self.compare(name2, op2, value)?;
+                    Some(lhs && rhs)
+                }
+                None => match self.compare(name, op, rest) {
+                    Some(v) => Some(v),
+                    None => self.compare(rest, op.flip(), name),
+                },
+            };
+        }
+
+        let (name, value) = match feature.split_once(':') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (feature, None),
+        };
+
+        let name = name.to_ascii_lowercase();
+        let value = match value {
+            Some(v) => v,
+            // A boolean context.
+            None => {
+                return match name.as_str() {
+                    "width" | "height" | "prefers-color-scheme" | "orientation" => Some(true),
+                    _ => None,
+                };
+            }
+        };
+
+        match name.as_str() {
+            "prefers-color-scheme" => match value {
+                "light" => Some(!self.dark),
+                "dark" => Some(self.dark),
+                _ => None,
+            },
+            "orientation" => match value {
+                "portrait" => Some(self.height >= self.width),
+                "landscape" => Some(self.width > self.height),
+                _ => None,
+            },
+            _ => {
+                let (op, name) = if let Some(name) = name.strip_prefix("min-") {
+                    (RangeOp::GreaterOrEqual, name)
+                } else if let Some(name) = name.strip_prefix("max-") {
+                    (RangeOp::LessOrEqual, name)
+                } else {
+                    (RangeOp::Equal, name.as_str())
+                };
+
+                self.compare(name, op, value)
+            }
+        }
+    }
+
+    fn compare(&self, name: &str, op: RangeOp, value: &str) -> Option<bool> {
+        let actual = match name.trim().to_ascii_lowercase().as_str() {
+            "width" => self.width,
+            "height" => self.height,
+            _ => return None,
+        };
+
+        let length = Length::from_str(value.trim()).ok()?;
+        let value = self.convert_length(length)?;
+
+        Some(match op {
+            RangeOp::Less => actual < value,
+            RangeOp::LessOrEqual => actual <= value,
+            RangeOp::Greater => actual > value,
+            RangeOp::GreaterOrEqual => actual >= value,
+            RangeOp::Equal => actual == value,
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RangeOp {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl RangeOp {
+    fn flip(self) -> Self {
+        match self {
+            RangeOp::Less => RangeOp::Greater,
+            RangeOp::LessOrEqual => RangeOp::GreaterOrEqual,
+            RangeOp::Greater => RangeOp::Less,
+            RangeOp::GreaterOrEqual => RangeOp::LessOrEqual,
+            RangeOp::Equal => RangeOp::Equal,
+        }
+    }
+}
+
+/// Splits a range media feature at an operator.
+fn split_range(text: &str, idx: usize) -> Option<(&str, RangeOp, &str)> {
+    let tail = &text[idx..];
+    let (op, len) = if tail.starts_with("<=") {
+        (RangeOp::LessOrEqual, 2)
+    } else if tail.starts_with(">=") {
+        (RangeOp::GreaterOrEqual, 2)
+    } else if tail.starts_with('<') {
+        (RangeOp::Less, 1)
+    } else if tail.starts_with('>') {
+        (RangeOp::Greater, 1)
+    } else if tail.starts_with('=') {
+        (RangeOp::Equal, 1)
+    } else {
+        return None;
+    };
+
+    Some((text[..idx].trim(), op, tail[len..].trim()))
+}
+
+/// Strips a case-insensitive keyword followed by a whitespace or a parenthesis.
+fn strip_keyword<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+    let head = text.get(..keyword.len())?;
+    let tail = &text[keyword.len()..];
+    if !head.eq_ignore_ascii_case(keyword) {
+        return None;
+    }
+
+    if !tail.starts_with(|c: char| c.is_ascii_whitespace() || c == '(') {
+        return None;
+    }
+
+    Some(tail.trim_start())
+}
+
+/// A complex selector, like `g > .icon:not(:first-child)`.
+#[derive(Clone, Debug)]
+struct Selector<'a> {
+    /// Compound selectors with combinators that precede them.
+    /// The first combinator is always `None`.
+    components: Vec<(Combinator, Compound<'a>)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Combinator {
+    None,
+    Descendant,
+    Child,
+    NextSibling,
+    SubsequentSibling,
+}
+
+#[derive(Clone, Debug)]
+struct Compound<'a> {
+    /// `None` for a universal selector.
+    tag_name: Option<&'a str>,
+    subselectors: Vec<SubSelector<'a>>,
+}
+
+#[derive(Clone, Debug)]
+enum SubSelector<'a> {
+    Id(&'a str),
+    Class(&'a str),
+    Attribute(&'a str, AttributeOperator<'a>),
+    PseudoClass(PseudoClass<'a>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum AttributeOperator<'a> {
+    /// `[attr]`
+    Exists,
+    /// `[attr=value]`
+    Matches(&'a str),
+    /// `[attr~=value]`
+    Contains(&'a str),
+    /// `[attr|=value]`
+    DashMatches(&'a str),
+    /// `[attr^=value]`
+    StartsWith(&'a str),
+    /// `[attr$=value]`
+    EndsWith(&'a str),
+    /// `[attr*=value]`
+    Substring(&'a str),
+}
+
+#[derive(Clone, Debug)]
+enum PseudoClass<'a> {
+    Root,
+    Empty,
+    FirstChild,
+    LastChild,
+    OnlyChild,
+    FirstOfType,
+    LastOfType,
+    OnlyOfType,
+    NthChild(Nth),
+    NthLastChild(Nth),
+    NthOfType(Nth),
+    NthLastOfType(Nth),
+    Not(Vec<Selector<'a>>),
+    Is(Vec<Selector<'a>>),
+    Where(Vec<Selector<'a>>),
+    /// Dynamic pseudo-classes, like `:hover`, which never match a static image.
+    Never,
+}
+
+/// An `An+B` expression.
+#[derive(Clone, Copy, Debug)]
+struct Nth {
+    a: i32,
+    b: i32,
+}
+
+impl Nth {
+    /// Checks that a 1-based index matches the expression.
+    fn matches(&self, index: i32) -> bool {
+        if self.a == 0 {
+            return index == self.b;
+        }
+
+        // Huge `A` and `B` values must not overflow.
+        let n = match index.checked_sub(self.b) {
+            Some(n) => n,
+            None => return false,
+        };
+
+        match (n.checked_rem(self.a), n.checked_div(self.a)) {
+            (Some(rem), Some(div)) => rem == 0 && div >= 0,
+            _ => false,
+        }
+    }
+}
+
+type Specificity = [u32; 3];
+
+impl Selector<'_> {
+    /// Computes the selector's specificity.
+    ///
+    /// Cf. https://www.w3.org/TR/selectors-4/#specificity-rules
+    fn specificity(&self) -> Specificity {
+        let mut spec = [0; 3];
+        for (_, compound) in &self.components {
+            if compound.tag_name.is_some() {
+                spec[2] += 1;
+            }
+
+            for sub in &compound.subselectors {
+                let sub_spec = match sub {
+                    SubSelector::Id(_) => [1, 0, 0],
+                    SubSelector::PseudoClass(PseudoClass::Where(_)) => [0, 0, 0],
+                    SubSelector::PseudoClass(PseudoClass::Not(list) | PseudoClass::Is(list)) => {
+                        list.iter()
+                            .map(Selector::specificity)
+                            .max()
+                            .unwrap_or_default()
+                    }
+                    _ => [0, 1, 0],
+                };
+
+                for (a, b) in spec.iter_mut().zip(sub_spec) {
+                    *a += b;
+                }
+            }
+        }
+
+        spec
+    }
+
+    fn matches(&self, node: roxmltree::Node) -> bool {
+        self.matches_impl(self.components.len() - 1, node)
+    }
+
+    fn matches_impl(&self, idx: usize, node: roxmltree::Node) -> bool {
+        let (combinator, ref compound) = self.components[idx];
+        if !compound.matches(node) {
+            return false;
+        }
+
+        match combinator {
+            Combinator::None => true,
+            Combinator::Descendant => node
+                .ancestors()
+                .skip(1)
+                .filter(|n| n.is_element())
+                .any(|n| self.matches_impl(idx - 1, n)),
+            Combinator::Child => match node.parent_element() {
+                Some(parent) => self.matches_impl(idx - 1, parent),
+                None => false,
+            },
+            Combinator::NextSibling => match node.prev_sibling_element() {
+                Some(prev) => self.matches_impl(idx - 1, prev),
+                None => false,
+            },
+            Combinator::SubsequentSibling => node
+                .prev_siblings()
+                .skip(1)
+                .filter(|n| n.is_element())
+                .any(|n| self.matches_impl(idx - 1, n)),
+        }
+    }
+}
+
+impl Compound<'_> {
+    fn matches(&self, node: roxmltree::Node) -> bool {
+        if let Some(tag_name) = self.tag_name {
+            if node.tag_name().name() != tag_name {
+                return false;
+            }
+        }
+
+        self.subselectors.iter().all(|sub| match sub {
+            SubSelector::Id(id) => node.attribute("id") == Some(*id),
+            SubSelector::Class(class) => match node.attribute("class") {
+                Some(value) => value.split_ascii_whitespace().any(|c| c == *class),
+                None => false,
+            },
+            SubSelector::Attribute(name, operator) => match node.attribute(*name) {
+                Some(value) => operator.matches(value),
+                None => false,
+            },
+            SubSelector::PseudoClass(class) => class.matches(node),
+        })
+    }
+}
+
+impl AttributeOperator<'_> {
+    fn matches(&self, value: &str) -> bool {
+        match *self {
+            AttributeOperator::Exists => true,
+            AttributeOperator::Matches(v) => value == v,
+            AttributeOperator::Contains(v) => value.split_ascii_whitespace().any(|s| s == v),
+            AttributeOperator::DashMatches(v) => {
+                value == v || (value.starts_with(v) && value[v.len()..].starts_with('-'))
+            }
+            AttributeOperator::StartsWith(v) => !v.is_empty() && value.starts_with(v),
+            AttributeOperator::EndsWith(v) => !v.is_empty() && value.ends_with(v),
+            AttributeOperator::Substring(v) => !v.is_empty() && value.contains(v),
+        }
+    }
+}
+
+impl PseudoClass<'_> {
+    fn matches(&self, node: roxmltree::Node) -> bool {
+        let is_same_type = |n: &roxmltree::Node| n.is_element() && n.tag_name() == node.tag_name();
+        let index = || node.prev_siblings().filter(|n| n.is_element()).count() as i32;
+        let last_index = || node.next_siblings().filter(|n| n.is_element()).count() as i32;
+        let type_index = || node.prev_siblings().filter(is_same_type).count() as i32;
+        let last_type_index = || node.next_siblings().filter(is_same_type).count() as i32;
+
+        match self {
+            PseudoClass::Root => node.parent_element().is_none(),
+            PseudoClass::Empty => !node
+                .children()
+                .any(|n| n.is_element() || (n.is_text() && !n.text().unwrap_or("").is_empty())),
+            PseudoClass::FirstChild => index() == 1,
+            PseudoClass::LastChild => last_index() == 1,
+            PseudoClass::OnlyChild => index() == 1 && last_index() == 1,
+            PseudoClass::FirstOfType => type_index() == 1,
+            PseudoClass::LastOfType => last_type_index() == 1,
+            PseudoClass::OnlyOfType => type_index() == 1 && last_type_index() == 1,
+            PseudoClass::NthChild(nth) => nth.matches(index()),
+            PseudoClass::NthLastChild(nth) => nth.matches(last_index()),
+            PseudoClass::NthOfType(nth) => nth.matches(type_index()),
+            PseudoClass::NthLastOfType(nth) => nth.matches(last_type_index()),
+            PseudoClass::Not(list) => !list.iter().any(|s| s.matches(node)),
+            PseudoClass::Is(list) | PseudoClass::Where(list) => {
+                list.iter().any(|s| s.matches(node))
+            }
+            PseudoClass::Never => false,
+        }
+    }
+}
+
+/// Parses a comma-separated list of complex selectors.
+///
+/// Returns `None` when any of the selectors is invalid or unsupported.
+fn parse_selector_list(text: &str) -> Option<Vec<Selector<'_>>> {
+    split_css_list(text, b',')
+        .into_iter()
+        .map(parse_selector)
+        .collect()
+}
+
+fn parse_selector(text: &str) -> Option<Selector<'_>> {
+    let mut s = SelectorStream {
+        text: text.trim(),
+        pos: 0,
+    };
+    let mut components = Vec::new();
+    let mut combinator = Combinator::None;
+    loop {
+        let compound = s.parse_compound()?;
+        components.push((combinator, compound));
+
+        let had_spaces = s.skip_spaces();
+        if s.at_end() {
+            break;
+        }
+
+        combinator = match s.curr_byte() {
+            b'>' => Combinator::Child,
+            b'+' => Combinator::NextSibling,
+            b'~' => Combinator::SubsequentSibling,
+            _ if had_spaces => Combinator::Descendant,
+            _ => return None,
+        };
+
+        if combinator != Combinator::Descendant {
+            s.pos += 1;
+            s.skip_spaces();
+        }
+    }
+
+    Some(Selector { components })
+}
+
+struct SelectorStream<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> SelectorStream<'a> {
+    fn at_end(&self) -> bool {
+        self.pos >= self.text.len()
+    }
+
+    fn curr_byte(&self) -> u8 {
+        self.text.as_bytes()[self.pos]
+    }
+
+    fn skip_spaces(&mut self) -> bool {
+        let start = self.pos;
+        while !self.at_end() && self.curr_byte().is_ascii_whitespace() {
+            self.pos += 1;
+        }
+
+        self.pos != start
+    }
+
+    fn consume_byte(&mut self, c: u8) -> Option<()> {
+        if !self.at_end() && self.curr_byte() == c {
+            self.pos += 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn consume_ident(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        while !self.at_end() {
+            let c = self.curr_byte();
+            if c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || !c.is_ascii() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+
+        if self.pos == start {
+            None
+        } else {
+            Some(&self.text[start..self.pos])
+        }
+    }
+
+    /// Consumes function arguments, including the closing parenthesis.
+    fn consume_args(&mut self) -> Option<&'a str> {
+        let end = find_closing_paren(self.text.as_bytes(), self.pos)?;
+        let args = &self.text[self.pos..end];
+        self.pos = end + 1;
+        Some(args)
+    }
+
+    fn parse_compound(&mut self) -> Option<Compound<'a>> {
+        let mut compound = Compound {
+            tag_name: None,
+            subselectors: Vec::new(),
+        };
+
+        let start = self.pos;
+        if self.consume_byte(b'*').is_none() {
+            compound.tag_name = self.consume_ident();
+        }
+
+        while !self.at_end() {
+            let sub = match self.curr_byte() {
+                b'#' => {
+                    self.pos += 1;
+                    SubSelector::Id(self.consume_ident()?)
+                }
+                b'.' => {
+                    self.pos += 1;
+                    SubSelector::Class(self.consume_ident()?)
+                }
+                b'[' => {
+                    self.pos += 1;
+                    self.parse_attribute()?
+                }
+                b':' => {
+                    self.pos += 1;
+                    SubSelector::PseudoClass(self.parse_pseudo_class()?)
+                }
+                _ => break,
+            };
+
+            compound.subselectors.push(sub);
+        }
+
+        if self.pos == start {
+            None
+        } else {
+            Some(compound)
+        }
+    }
+
+    fn parse_attribute(&mut self) -> Option<SubSelector<'a>> {
+        self.skip_spaces();
+        let name = self.consume_ident()?;
+        self.skip_spaces();
+
+        let operator = if self.consume_byte(b']').is_some() {
+            return Some(SubSelector::Attribute(name, AttributeOperator::Exists));
+        } else if self.consume_byte(b'=').is_some() {
+            AttributeOperator::Matches
+        } else {
+            let op = match self.curr_byte() {
+                b'~' => AttributeOperator::Contains,
+                b'|' => AttributeOperator::DashMatches,
+                b'^' => AttributeOperator::StartsWith,
+                b'$' => AttributeOperator::EndsWith,
+                b'*' => AttributeOperator::Substring,
+                _ => return None,
+            };
+            self.pos += 1;
+            self.consume_byte(b'=')?;
+            op
+        };
+
+        self.skip_spaces();
+        let value = if self.at_end() {
+            return None;
+        } else if matches!(self.curr_byte(), b'"' | b'\'') {
+            let end = skip_css_string(self.text.as_bytes(), self.pos);
+            let value = self.text.get(self.pos + 1..end - 1)?;
+            self.pos = end;
+            value
+        } else {
+            self.consume_ident()?
+        };
+
+        self.skip_spaces();
+        self.consume_byte(b']')?;
+
+        Some(SubSelector::Attribute(name, operator(value)))
+    }
+
+    fn parse_pseudo_class(&mut self) -> Option<PseudoClass<'a>> {
+        // Pseudo-elements are not supported.
+        let name = self.consume_ident()?;
+        let name = name.to_ascii_lowercase();
+
+        if self.consume_byte(b'(').is_some() {
+            let args = self.consume_args()?;
+            let class = match name.as_str() {
+                "nth-child" => PseudoClass::NthChild(parse_nth(args)?),
+                "nth-last-child" => PseudoClass::NthLastChild(parse_nth(args)?),
+                "nth-of-type" => PseudoClass::NthOfType(parse_nth(args)?),
+                "nth-last-of-type" => PseudoClass::NthLastOfType(parse_nth(args)?),
+                "not" => PseudoClass::Not(parse_selector_list(args.trim())?),
+                "is" | "matches" => PseudoClass::Is(parse_selector_list(args.trim())?),
+                "where" => PseudoClass::Where(parse_selector_list(args.trim())?),
+                // TODO: lang
+                "lang" => PseudoClass::Never,
+                _ => return None,
+            };
+
+            return Some(class);
+        }
+
+        let class = match name.as_str() {
+            "root" => PseudoClass::Root,
+            "empty" => PseudoClass::Empty,
+            "first-child" => PseudoClass::FirstChild,
+            "last-child" => PseudoClass::LastChild,
+            "only-child" => PseudoClass::OnlyChild,
+            "first-of-type" => PseudoClass::FirstOfType,
+            "last-of-type" => PseudoClass::LastOfType,
+            "only-of-type" => PseudoClass::OnlyOfType,
+            // Since we are querying a static SVG we can ignore dynamic pseudo-classes.
+            "link" | "visited" | "any-link" | "hover" | "active" | "focus" | "focus-within"
+            | "focus-visible" | "target" => PseudoClass::Never,
+            _ => return None,
+        };
+
+        Some(class)
+    }
+}
+
+/// Parses an `An+B` expression.
+///
+/// The `of S` syntax is not supported.
+fn parse_nth(text: &str) -> Option<Nth> {
+    let text: String = text
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    match text.as_str() {
+        "odd" => return Some(Nth { a: 2, b: 1 }),
+        "even" => return Some(Nth { a: 2, b: 0 }),
+        _ => {}
+    }
+
+    let (a, b) = match text.split_once('n') {
+        Some((a, b)) => {
+            let a = match a {
+                "" | "+" => 1,
+                "-" => -1,
+                _ => a.parse().ok()?,
+            };
+
+            let b = match b {
+                "" => 0,
+                _ if b.starts_with('+') || b.starts_with('-') => b.parse().ok()?,
+                _ => return None,
+            };
+
+            (a, b)
+        }
+        None => (0, text.parse().ok()?),
+    };
+
+    Some(Nth { a, b })
+}
+
+pub(crate) fn skip_css_comment(bytes: &[u8], pos: usize) -> usize {
+    match bytes[pos + 2..].windows(2).position(|w| w == b"*/") {
+        Some(end) => pos + 2 + end + 2,
+        None => bytes.len(),
+    }
+}
+
+pub(crate) fn skip_css_string(bytes: &[u8], pos: usize) -> usize {
+    let quote = bytes[pos];
+    let mut i = pos + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            c if c == quote => return i + 1,
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    bytes.len()
+}
+
+/// Returns the position of the `}` that closes a block starting at `pos`.
+fn find_block_end(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    let mut depth = 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                pos = skip_css_comment(bytes, pos);
+                continue;
+            }
+            b'"' | b'\'' => {
+                pos = skip_css_string(bytes, pos);
+                continue;
+            }
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos);
+                }
+            }
+            _ => {}
+        }
+
+        pos += 1;
+    }
+
+    None
+}
+
+fn remove_css_comments(text: &str) -> Cow<'_, str> {
+    if !text.contains("/*") {
+        return Cow::Borrowed(text);
+    }
+
+    let bytes = text.as_bytes();
+    let mut result = String::with_capacity(text.len());
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                result.push_str(&text[start..i]);
+                i = skip_css_comment(bytes, i);
+                start = i;
+            }
+            b'"' | b'\'' => i = skip_css_string(bytes, i),
+            _ => i += 1,
+        }
+    }
+
+    result.push_str(&text[start..]);
+    Cow::Owned(result.trim().to_string())
+}
+
+/// Splits a CSS text by a separator outside of strings and parentheses.
+pub(crate) fn split_css_list(text: &str, separator: u8) -> Vec<&str> {
+    split_css_list_by(text, |c| c == separator)
+}
+
+/// Like [`split_css_list`], but with a custom separator predicate.
+pub(crate) fn split_css_list_by(text: &str, is_separator: impl Fn(u8) -> bool) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let mut declarations = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_css_comment(bytes, i);
+                continue;
+            }
+            b'"' | b'\'' => {
+                i = skip_css_string(bytes, i);
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            c if depth == 0 && is_separator(c) => {
+                declarations.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    declarations.push(&text[start..]);
+    declarations
+}
@@ -4,13 +4,20 @@
 
 use std::collections::HashMap;
 use std::num::NonZeroU32;
+use std::rc::Rc;
 use std::str::FromStr;
 
+mod animation;
+mod css;
 #[rustfmt::skip] mod names;
 mod parse;
 mod text;
+mod transform;
 
+pub(crate) use css::{split_css_list, split_css_list_by};
 pub use names::{AId, EId};
+pub(crate) use parse::{resolve_external_url, split_external_href};
+pub(crate) use transform::{parse_transform, TransformList};
 
 /// An SVG tree container.
 ///
@@ -20,9 +27,48 @@ pub struct Document<'input> {
     nodes: Vec<NodeData>,
     attrs: Vec<Attribute<'input>>,
     links: HashMap<String, NodeId>,
+    animations: Vec<animation::Animation>,
+    font_faces: Vec<FontFaceRule>,
+    /// Custom properties declared by elements. Used only during parsing.
+    custom_properties: HashMap<NodeId, Rc<css::CustomProperties>>,
+}
+
+/// A CSS `@font-face` rule.
+///
+/// Descriptors are stored as is and parsed during conversion.
+#[derive(Clone, Debug)]
+pub struct FontFaceRule {
+    pub family: String,
+    pub src: String,
+    pub weight: Option<String>,
+    pub style: Option<String>,
 }
 
 impl<'input> Document<'input> {
+    /// Copies all attribute values, so the document no longer borrows the input.
+    pub fn into_owned(self) -> Document<'static> {
+        let attrs = self
+            .attrs
+            .into_iter()
+            .map(|attr| Attribute {
+                name: attr.name,
+                value: match attr.value {
+                    roxmltree::StringStorage::Borrowed(s) => roxmltree::StringStorage::new_owned(s),
+                    roxmltree::StringStorage::Owned(s) => roxmltree::StringStorage::Owned(s),
+                },
+            })
+            .collect();
+
+        Document {
+            nodes: self.nodes,
+            attrs,
+            links: self.links,
+            animations: self.animations,
+            font_faces: self.font_faces,
+            custom_properties: self.custom_properties,
+        }
+    }
+
     /// Returns the root node.
     #[inline]
     pub fn root<'a>(&'a self) -> SvgNode<'a, 'input> {
@@ -58,6 +104,12 @@ impl<'input> Document<'input> {
         Some(self.get(*node_id))
     }
 
+    /// Returns `@font-face` rules from the document's style sheets.
+    #[inline]
+    pub fn font_faces(&self) -> &[FontFaceRule] {
+        &self.font_faces
+    }
+
     #[inline]
     fn get<'a>(&'a self, id: NodeId) -> SvgNode<'a, 'input> {
         SvgNode {
@@ -144,13 +196,13 @@ impl ShortRange {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub(crate) struct NodeId(NonZeroU32);
 
 impl NodeId {
     #[inline]
     fn new(id: u32) -> Self {
-        debug_assert!(id < core::u32::MAX);
+        debug_assert!(id < u32::MAX);
 
         // We are using `NonZeroU32` to reduce overhead of `Option<NodeId>`.
         NodeId(NonZeroU32::new(id + 1).unwrap())
@@ -171,11 +223,12 @@ impl From<usize> for NodeId {
     #[inline]
     fn from(id: usize) -> Self {
         // We already checked that `id` is limited by u32::MAX.
-        debug_assert!(id <= core::u32::MAX as usize);
+        debug_assert!(id <= u32::MAX as usize);
         NodeId::new(id as u32)
     }
 }
 
+#[derive(Clone)]
 pub(crate) enum NodeKind {
     Root,
     Element {
@@ -185,6 +238,7 @@ pub(crate) enum NodeKind {
     Text(String),
 }
 
+#[derive(Clone)]
 struct NodeData {
     parent: Option<NodeId>,
     next_sibling: Option<NodeId>,
@@ -648,6 +702,12 @@ impl EId {
             EId::LinearGradient | EId::RadialGradient | EId::Pattern
         )
     }
+
+    /// Checks if this is an
+    /// [animation element](https://www.w3.org/TR/SVG11/intro.html#TermAnimationElement).
+    pub fn is_animation(&self) -> bool {
+        matches!(self, EId::Animate | EId::AnimateTransform | EId::Set)
+    }
 }
 
 impl AId {
@@ -672,27 +732,45 @@ impl AId {
                 | AId::FloodColor
                 | AId::FloodOpacity
                 | AId::FontFamily
+                | AId::FontFeatureSettings
                 | AId::FontKerning // technically not presentation
+                | AId::FontOpticalSizing
                 | AId::FontSize
                 | AId::FontSizeAdjust
                 | AId::FontStretch
                 | AId::FontStyle
                 | AId::FontVariant
+                | AId::FontVariantCaps
+                | AId::FontVariantEastAsian
+                | AId::FontVariantLigatures
+                | AId::FontVariantNumeric
+                | AId::FontVariantPosition
+                | AId::FontVariationSettings
                 | AId::FontWeight
                 | AId::GlyphOrientationHorizontal
                 | AId::GlyphOrientationVertical
                 | AId::ImageRendering
+                | AId::InlineSize
                 | AId::Isolation // technically not presentation
                 | AId::LetterSpacing
                 | AId::LightingColor
+                | AId::LineHeight
                 | AId::MarkerEnd
                 | AId::MarkerMid
                 | AId::MarkerStart
                 | AId::Mask
+                | AId::MaskComposite // technically not presentation
+                | AId::MaskImage // technically not presentation
+                | AId::MaskMode // technically not presentation
+                | AId::MaskPosition // technically not presentation
+                | AId::MaskRepeat // technically not presentation
+                | AId::MaskSize // technically not presentation
                 | AId::MixBlendMode // technically not presentation
                 | AId::Opacity
                 | AId::Overflow
                 | AId::PaintOrder
+                | AId::PointerEvents
+                | AId::ShapeInside
                 | AId::ShapeRendering
                 | AId::StopColor
                 | AId::StopOpacity
@@ -704,11 +782,14 @@ impl AId {
                 | AId::StrokeMiterlimit
                 | AId::StrokeOpacity
                 | AId::StrokeWidth
+                | AId::TextAlign
                 | AId::TextAnchor
                 | AId::TextDecoration
+                | AId::TextOrientation
                 | AId::TextOverflow
                 | AId::TextRendering
                 | AId::Transform
+                | AId::TransformBox
                 | AId::TransformOrigin
                 | AId::UnicodeBidi
                 | AId::VectorEffect
@@ -736,6 +817,7 @@ impl AId {
                 | AId::ClipPath
                 | AId::ClipRule
                 | AId::Color
+                | AId::ColorInterpolation
                 | AId::ColorInterpolationFilters
                 | AId::Direction
                 | AId::Display
@@ -747,21 +829,37 @@ impl AId {
                 | AId::FloodColor
                 | AId::FloodOpacity
                 | AId::FontFamily
+                | AId::FontFeatureSettings
                 | AId::FontKerning
+                | AId::FontOpticalSizing
                 | AId::FontSize
                 | AId::FontStretch
                 | AId::FontStyle
                 | AId::FontVariant
+                | AId::FontVariantCaps
+                | AId::FontVariantEastAsian
+                | AId::FontVariantLigatures
+                | AId::FontVariantNumeric
+                | AId::FontVariantPosition
+                | AId::FontVariationSettings
                 | AId::FontWeight
                 | AId::ImageRendering
                 | AId::Kerning
                 | AId::LetterSpacing
+                | AId::LineHeight
                 | AId::MarkerEnd
                 | AId::MarkerMid
                 | AId::MarkerStart
                 | AId::Mask
+                | AId::MaskComposite
+                | AId::MaskImage
+                | AId::MaskMode
+                | AId::MaskPosition
+                | AId::MaskRepeat
+                | AId::MaskSize
                 | AId::Opacity
                 | AId::Overflow
+                | AId::PointerEvents
                 | AId::ShapeRendering
                 | AId::StopColor
                 | AId::StopOpacity
@@ -773,10 +871,14 @@ impl AId {
                 | AId::StrokeMiterlimit
                 | AId::StrokeOpacity
                 | AId::StrokeWidth
+                | AId::TextAlign
                 | AId::TextAnchor
                 | AId::TextDecoration
+                | AId::TextOrientation
                 | AId::TextRendering
+                | AId::TransformBox
                 | AId::Visibility
+                | AId::WhiteSpace
                 | AId::WordSpacing
                 | AId::WritingMode
         )
@@ -794,15 +896,25 @@ fn is_non_inheritable(id: AId) -> bool {
             | AId::Filter
             | AId::FloodColor
             | AId::FloodOpacity
+            | AId::InlineSize
             | AId::Mask
+            | AId::MaskComposite
+            | AId::MaskImage
+            | AId::MaskMode
+            | AId::MaskPosition
+            | AId::MaskRepeat
+            | AId::MaskSize
             | AId::Opacity
             | AId::Overflow
             | AId::LightingColor
+            | AId::ShapeInside
             | AId::StopColor
             | AId::StopOpacity
             | AId::TextDecoration
             | AId::Transform
+            | AId::TransformBox
             | AId::TransformOrigin
+            | AId::VectorEffect
     )
 }
 
@@ -849,27 +961,9 @@ impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::Opacity {
     }
 }
 
-impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::Transform {
+impl<'a, 'input: 'a> FromValue<'a, 'input> for TransformList {
     fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
-        let ts = match svgtypes::Transform::from_str(value) {
-            Ok(v) => v,
-            Err(_) => return None,
-        };
-
-        let ts = usvg_tree::Transform::from_row(
-            ts.a as f32,
-            ts.b as f32,
-            ts.c as f32,
-            ts.d as f32,
-            ts.e as f32,
-            ts.f as f32,
-        );
-
-        if ts.is_valid() {
-            Some(ts)
-        } else {
-            Some(usvg_tree::Transform::default())
-        }
+        parse_transform(value)
     }
 }
 
@@ -909,7 +1003,7 @@ impl<'a, 'input: 'a> FromValue<'a, 'input> for svgtypes::PaintOrder {
 
 impl<'a, 'input: 'a> FromValue<'a, 'input> for svgtypes::Color {
     fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
-        Self::from_str(value).ok()
+        crate::color::parse_color(value)
     }
 }
 
@@ -953,6 +1047,26 @@ impl<'a, 'input: 'a> FromValue<'a, 'input> for Vec<svgtypes::Length> {
     }
 }
 
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::PointerEvents {
+    fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
+        match value {
+            "visiblePainted" => Some(usvg_tree::PointerEvents::VisiblePainted),
+            "visibleFill" => Some(usvg_tree::PointerEvents::VisibleFill),
+            "visibleStroke" => Some(usvg_tree::PointerEvents::VisibleStroke),
+            "visible" => Some(usvg_tree::PointerEvents::Visible),
+            "painted" => Some(usvg_tree::PointerEvents::Painted),
+            "fill" => Some(usvg_tree::PointerEvents::Fill),
+            "stroke" => Some(usvg_tree::PointerEvents::Stroke),
+            "all" => Some(usvg_tree::PointerEvents::All),
+            "bounding-box" => Some(usvg_tree::PointerEvents::BoundingBox),
+            "none" => Some(usvg_tree::PointerEvents::None),
+            // `auto` is the same as `visiblePainted` for SVG elements.
+            "auto" => Some(usvg_tree::PointerEvents::VisiblePainted),
+            _ => None,
+        }
+    }
+}
+
 impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::Visibility {
     fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
         match value {
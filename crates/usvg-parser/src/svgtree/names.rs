@@ -6,6 +6,8 @@
 #[derive(Clone, Copy, PartialEq)]
 pub enum EId {
     A,
+    Animate,
+    AnimateTransform,
     Circle,
     ClipPath,
     Defs,
@@ -36,18 +38,24 @@ pub enum EId {
     FeTile,
     FeTurbulence,
     Filter,
+    Font,
+    FontFace,
     G,
+    Glyph,
+    Hkern,
     Image,
     Line,
     LinearGradient,
     Marker,
     Mask,
+    MissingGlyph,
     Path,
     Pattern,
     Polygon,
     Polyline,
     RadialGradient,
     Rect,
+    Set,
     Stop,
     Style,
     Svg,
@@ -57,78 +65,88 @@ pub enum EId {
     TextPath,
     Tref,
     Tspan,
-    Use,
+    Use
 }
 
 static ELEMENTS: Map<EId> = Map {
-    key: 732231254413039614,
+    key: 3213172566270843353,
     disps: &[
-        (0, 12),
-        (1, 11),
-        (10, 26),
-        (2, 42),
-        (1, 19),
-        (0, 5),
-        (1, 13),
-        (8, 50),
+        (0, 32),
+        (0, 17),
+        (0, 56),
         (0, 0),
-        (1, 0),
-        (7, 45),
+        (2, 24),
+        (1, 1),
+        (11, 42),
+        (10, 5),
+        (8, 46),
+        (0, 0),
+        (2, 17),
+        (1, 55),
+        (0, 9),
     ],
     entries: &[
-        ("feFlood", EId::FeFlood),
-        ("radialGradient", EId::RadialGradient),
-        ("feImage", EId::FeImage),
-        ("stop", EId::Stop),
-        ("fePointLight", EId::FePointLight),
-        ("feConvolveMatrix", EId::FeConvolveMatrix),
-        ("feComposite", EId::FeComposite),
-        ("clipPath", EId::ClipPath),
-        ("feMerge", EId::FeMerge),
-        ("defs", EId::Defs),
-        ("mask", EId::Mask),
-        ("svg", EId::Svg),
-        ("symbol", EId::Symbol),
-        ("linearGradient", EId::LinearGradient),
-        ("feSpecularLighting", EId::FeSpecularLighting),
-        ("feFuncB", EId::FeFuncB),
-        ("filter", EId::Filter),
+        ("feMorphology", EId::FeMorphology),
         ("feFuncG", EId::FeFuncG),
-        ("circle", EId::Circle),
+        ("feSpotLight", EId::FeSpotLight),
         ("g", EId::G),
-        ("tref", EId::Tref),
         ("feFuncA", EId::FeFuncA),
-        ("image", EId::Image),
-        ("text", EId::Text),
-        ("line", EId::Line),
-        ("pattern", EId::Pattern),
+        ("animate", EId::Animate),
+        ("feFuncB", EId::FeFuncB),
+        ("feFuncR", EId::FeFuncR),
+        ("symbol", EId::Symbol),
+        ("font", EId::Font),
+        ("clipPath", EId::ClipPath),
+        ("ellipse", EId::Ellipse),
         ("use", EId::Use),
-        ("feDropShadow", EId::FeDropShadow),
-        ("feSpotLight", EId::FeSpotLight),
+        ("a", EId::A),
+        ("image", EId::Image),
+        ("feConvolveMatrix", EId::FeConvolveMatrix),
         ("marker", EId::Marker),
-        ("style", EId::Style),
-        ("switch", EId::Switch),
-        ("tspan", EId::Tspan),
-        ("feColorMatrix", EId::FeColorMatrix),
+        ("circle", EId::Circle),
+        ("stop", EId::Stop),
+        ("feComponentTransfer", EId::FeComponentTransfer),
+        ("tref", EId::Tref),
+        ("set", EId::Set),
+        ("text", EId::Text),
         ("feOffset", EId::FeOffset),
+        ("font-face", EId::FontFace),
+        ("feDisplacementMap", EId::FeDisplacementMap),
+        ("feComposite", EId::FeComposite),
+        ("fePointLight", EId::FePointLight),
+        ("missing-glyph", EId::MissingGlyph),
+        ("feDiffuseLighting", EId::FeDiffuseLighting),
+        ("filter", EId::Filter),
+        ("polyline", EId::Polyline),
+        ("radialGradient", EId::RadialGradient),
+        ("feMerge", EId::FeMerge),
+        ("linearGradient", EId::LinearGradient),
+        ("defs", EId::Defs),
+        ("feDistantLight", EId::FeDistantLight),
+        ("feImage", EId::FeImage),
         ("path", EId::Path),
-        ("feGaussianBlur", EId::FeGaussianBlur),
-        ("feTile", EId::FeTile),
+        ("feFlood", EId::FeFlood),
+        ("feDropShadow", EId::FeDropShadow),
+        ("feBlend", EId::FeBlend),
+        ("feSpecularLighting", EId::FeSpecularLighting),
+        ("polygon", EId::Polygon),
         ("feTurbulence", EId::FeTurbulence),
-        ("feMergeNode", EId::FeMergeNode),
-        ("feMorphology", EId::FeMorphology),
-        ("a", EId::A),
         ("textPath", EId::TextPath),
-        ("ellipse", EId::Ellipse),
-        ("feComponentTransfer", EId::FeComponentTransfer),
-        ("feDistantLight", EId::FeDistantLight),
-        ("polyline", EId::Polyline),
-        ("polygon", EId::Polygon),
-        ("feBlend", EId::FeBlend),
-        ("feDisplacementMap", EId::FeDisplacementMap),
-        ("feDiffuseLighting", EId::FeDiffuseLighting),
         ("rect", EId::Rect),
-        ("feFuncR", EId::FeFuncR),
+        ("tspan", EId::Tspan),
+        ("switch", EId::Switch),
+        ("style", EId::Style),
+        ("animateTransform", EId::AnimateTransform),
+        ("glyph", EId::Glyph),
+        ("feGaussianBlur", EId::FeGaussianBlur),
+        ("mask", EId::Mask),
+        ("hkern", EId::Hkern),
+        ("pattern", EId::Pattern),
+        ("svg", EId::Svg),
+        ("feColorMatrix", EId::FeColorMatrix),
+        ("line", EId::Line),
+        ("feTile", EId::FeTile),
+        ("feMergeNode", EId::FeMergeNode),
     ],
 };
 
@@ -162,6 +180,7 @@ impl std::fmt::Display for EId {
 pub enum AId {
     AlignmentBaseline,
     Amplitude,
+    Ascent,
     Azimuth,
     BaseFrequency,
     BaselineShift,
@@ -179,6 +198,7 @@ pub enum AId {
     Cx,
     Cy,
     D,
+    Descent,
     DiffuseConstant,
     Direction,
     Display,
@@ -201,6 +221,7 @@ pub enum AId {
     FontFamily,
     FontFeatureSettings,
     FontKerning,
+    FontOpticalSizing,
     FontSize,
     FontSizeAdjust,
     FontStretch,
@@ -212,15 +233,20 @@ pub enum AId {
     FontVariantLigatures,
     FontVariantNumeric,
     FontVariantPosition,
+    FontVariationSettings,
     FontWeight,
     Fr,
     Fx,
     Fy,
+    G1,
+    G2,
+    GlyphName,
     GlyphOrientationHorizontal,
     GlyphOrientationVertical,
     GradientTransform,
     GradientUnits,
     Height,
+    HorizAdvX,
     Href,
     Id,
     ImageRendering,
@@ -229,6 +255,7 @@ pub enum AId {
     InlineSize,
     Intercept,
     Isolation,
+    K,
     K1,
     K2,
     K3,
@@ -261,6 +288,7 @@ pub enum AId {
     MaskMode,
     MaskOrigin,
     MaskPosition,
+    MaskRepeat,
     MaskSize,
     MaskType,
     MaskContentUnits,
@@ -280,6 +308,7 @@ pub enum AId {
     PatternContentUnits,
     PatternTransform,
     PatternUnits,
+    PointerEvents,
     Points,
     PointsAtX,
     PointsAtY,
@@ -349,8 +378,12 @@ pub enum AId {
     TransformBox,
     TransformOrigin,
     Type,
+    U1,
+    U2,
+    Unicode,
     UnicodeBidi,
     UnicodeRange,
+    UnitsPerEm,
     Values,
     VectorEffect,
     ViewBox,
@@ -360,6 +393,7 @@ pub enum AId {
     WordSpacing,
     WritingMode,
     X,
+    XHeight,
     X1,
     X2,
     XChannelSelector,
@@ -367,270 +401,283 @@ pub enum AId {
     Y1,
     Y2,
     YChannelSelector,
-    Z,
+    Z
 }
 
 static ATTRIBUTES: Map<AId> = Map {
-    key: 732231254413039614,
+    key: 6925680744564340301,
     disps: &[
-        (63, 17),
+        (0, 11),
+        (0, 59),
         (0, 1),
-        (0, 113),
-        (0, 21),
-        (0, 8),
-        (0, 23),
-        (0, 15),
-        (0, 3),
-        (40, 203),
-        (0, 3),
-        (0, 6),
-        (1, 31),
-        (0, 68),
-        (11, 161),
-        (0, 41),
-        (2, 58),
-        (7, 10),
-        (0, 0),
-        (0, 39),
-        (7, 73),
-        (0, 5),
-        (65, 30),
-        (0, 71),
-        (0, 28),
-        (0, 7),
-        (2, 99),
-        (0, 168),
-        (1, 79),
+        (0, 43),
         (0, 0),
-        (0, 3),
-        (0, 21),
-        (1, 9),
+        (0, 77),
+        (0, 141),
         (0, 0),
-        (5, 171),
-        (0, 45),
-        (0, 18),
-        (99, 110),
-        (31, 206),
-        (5, 125),
         (0, 14),
-        (0, 4),
-        (0, 83),
+        (0, 0),
+        (1, 223),
+        (0, 0),
+        (0, 28),
+        (1, 202),
+        (0, 219),
+        (0, 133),
+        (0, 48),
+        (1, 46),
+        (1, 1),
+        (0, 10),
+        (0, 206),
+        (5, 46),
+        (6, 47),
+        (0, 1),
+        (9, 27),
+        (1, 133),
+        (2, 83),
+        (4, 217),
+        (2, 86),
+        (38, 107),
+        (0, 20),
+        (0, 1),
+        (0, 0),
+        (0, 9),
+        (7, 34),
+        (0, 6),
+        (1, 98),
+        (1, 13),
+        (7, 25),
+        (19, 11),
+        (0, 63),
+        (0, 139),
+        (1, 16),
+        (27, 139),
+        (1, 1),
     ],
     entries: &[
-        ("mask-type", AId::MaskType),
-        ("markerWidth", AId::MarkerWidth),
-        ("textLength", AId::TextLength),
-        ("alignment-baseline", AId::AlignmentBaseline),
-        ("unicode-bidi", AId::UnicodeBidi),
-        ("dominant-baseline", AId::DominantBaseline),
-        ("orient", AId::Orient),
-        ("x", AId::X),
-        ("font-style", AId::FontStyle),
-        ("color-interpolation", AId::ColorInterpolation),
-        ("style", AId::Style),
-        ("clip-rule", AId::ClipRule),
-        ("bias", AId::Bias),
-        ("font-weight", AId::FontWeight),
-        ("fill", AId::Fill),
-        ("mask-border-outset", AId::MaskBorderOutset),
-        ("x1", AId::X1),
-        ("mask", AId::Mask),
-        ("height", AId::Height),
-        ("stroke", AId::Stroke),
-        ("transform", AId::Transform),
-        ("isolation", AId::Isolation),
         ("fy", AId::Fy),
-        ("text-orientation", AId::TextOrientation),
-        ("stop-opacity", AId::StopOpacity),
-        ("fx", AId::Fx),
-        ("font-kerning", AId::FontKerning),
-        ("cx", AId::Cx),
-        ("maskContentUnits", AId::MaskContentUnits),
-        ("opacity", AId::Opacity),
-        ("filterUnits", AId::FilterUnits),
-        ("lengthAdjust", AId::LengthAdjust),
-        ("text-anchor", AId::TextAnchor),
-        ("stroke-miterlimit", AId::StrokeMiterlimit),
-        ("viewBox", AId::ViewBox),
-        ("visibility", AId::Visibility),
-        ("ry", AId::Ry),
-        (
-            "glyph-orientation-horizontal",
-            AId::GlyphOrientationHorizontal,
-        ),
+        ("word-spacing", AId::WordSpacing),
+        ("g2", AId::G2),
+        ("amplitude", AId::Amplitude),
         ("gradientTransform", AId::GradientTransform),
-        ("markerUnits", AId::MarkerUnits),
-        ("shape-inside", AId::ShapeInside),
-        ("font-size", AId::FontSize),
-        ("yChannelSelector", AId::YChannelSelector),
-        ("clip-path", AId::ClipPath),
-        ("flood-color", AId::FloodColor),
-        ("marker-mid", AId::MarkerMid),
-        ("surfaceScale", AId::SurfaceScale),
-        ("color-profile", AId::ColorProfile),
-        ("requiredFeatures", AId::RequiredFeatures),
-        ("class", AId::Class),
-        ("startOffset", AId::StartOffset),
-        ("mask-origin", AId::MaskOrigin),
-        ("stdDeviation", AId::StdDeviation),
-        ("mode", AId::Mode),
-        ("overflow", AId::Overflow),
-        ("text-decoration-fill", AId::TextDecorationFill),
+        ("stroke-dashoffset", AId::StrokeDashoffset),
+        ("k1", AId::K1),
+        ("stroke-linejoin", AId::StrokeLinejoin),
         ("mask-border-mode", AId::MaskBorderMode),
-        ("divisor", AId::Divisor),
-        ("marker-start", AId::MarkerStart),
-        ("text-decoration-line", AId::TextDecorationLine),
-        ("preserveAlpha", AId::PreserveAlpha),
-        ("primitiveUnits", AId::PrimitiveUnits),
-        ("in", AId::In),
-        ("points", AId::Points),
-        ("pointsAtY", AId::PointsAtY),
-        ("specularExponent", AId::SpecularExponent),
-        ("shape-rendering", AId::ShapeRendering),
-        ("refY", AId::RefY),
-        ("clip", AId::Clip),
-        ("white-space", AId::WhiteSpace),
-        ("vector-effect", AId::VectorEffect),
-        ("image-rendering", AId::ImageRendering),
-        ("stitchTiles", AId::StitchTiles),
-        ("stroke-opacity", AId::StrokeOpacity),
-        ("shape-margin", AId::ShapeMargin),
-        ("y2", AId::Y2),
-        ("operator", AId::Operator),
-        ("pathLength", AId::PathLength),
-        ("order", AId::Order),
-        ("text-rendering", AId::TextRendering),
-        ("mask-border", AId::MaskBorder),
-        ("exponent", AId::Exponent),
-        (
-            "color-interpolation-filters",
-            AId::ColorInterpolationFilters,
-        ),
-        ("diffuseConstant", AId::DiffuseConstant),
-        ("space", AId::Space),
-        ("font-synthesis", AId::FontSynthesis),
         ("direction", AId::Direction),
-        ("font-size-adjust", AId::FontSizeAdjust),
+        ("g1", AId::G1),
         ("kerning", AId::Kerning),
-        ("flood-opacity", AId::FloodOpacity),
-        ("mask-border-source", AId::MaskBorderSource),
-        ("line-height", AId::LineHeight),
-        ("mix-blend-mode", AId::MixBlendMode),
-        ("scale", AId::Scale),
-        ("mask-image", AId::MaskImage),
-        ("dy", AId::Dy),
-        ("xChannelSelector", AId::XChannelSelector),
+        ("numOctaves", AId::NumOctaves),
+        ("mask-border-width", AId::MaskBorderWidth),
         ("unicode-range", AId::UnicodeRange),
-        ("y", AId::Y),
-        ("fill-opacity", AId::FillOpacity),
-        ("mask-composite", AId::MaskComposite),
-        ("targetY", AId::TargetY),
-        ("writing-mode", AId::WritingMode),
-        ("transform-origin", AId::TransformOrigin),
-        ("font-variant-caps", AId::FontVariantCaps),
-        ("k2", AId::K2),
-        ("transform-box", AId::TransformBox),
-        ("text-decoration", AId::TextDecoration),
+        ("tableValues", AId::TableValues),
+        ("x2", AId::X2),
+        ("limitingConeAngle", AId::LimitingConeAngle),
+        ("shape-rendering", AId::ShapeRendering),
+        ("transform", AId::Transform),
+        ("pointsAtY", AId::PointsAtY),
+        ("u2", AId::U2),
         ("filter", AId::Filter),
-        ("enable-background", AId::EnableBackground),
-        ("word-spacing", AId::WordSpacing),
+        ("font-variant-east-asian", AId::FontVariantEastAsian),
+        ("text-align-last", AId::TextAlignLast),
+        ("preserveAspectRatio", AId::PreserveAspectRatio),
+        ("white-space", AId::WhiteSpace),
+        ("primitiveUnits", AId::PrimitiveUnits),
+        ("flood-color", AId::FloodColor),
+        ("horiz-adv-x", AId::HorizAdvX),
+        ("text-decoration-style", AId::TextDecorationStyle),
+        ("order", AId::Order),
+        ("refX", AId::RefX),
+        ("dx", AId::Dx),
+        ("font-size-adjust", AId::FontSizeAdjust),
+        ("cx", AId::Cx),
+        ("maskUnits", AId::MaskUnits),
+        ("radius", AId::Radius),
+        ("overflow", AId::Overflow),
+        ("vector-effect", AId::VectorEffect),
         ("gradientUnits", AId::GradientUnits),
-        ("font-feature-settings", AId::FontFeatureSettings),
-        ("stroke-linecap", AId::StrokeLinecap),
+        ("mask-image", AId::MaskImage),
+        ("font-variation-settings", AId::FontVariationSettings),
+        ("clip-path", AId::ClipPath),
+        ("x", AId::X),
+        ("marker-end", AId::MarkerEnd),
+        ("clip", AId::Clip),
+        ("edgeMode", AId::EdgeMode),
+        ("points", AId::Points),
+        ("orient", AId::Orient),
         ("rx", AId::Rx),
+        ("font-kerning", AId::FontKerning),
+        ("requiredFeatures", AId::RequiredFeatures),
+        ("unicode", AId::Unicode),
+        ("letter-spacing", AId::LetterSpacing),
+        ("in", AId::In),
         ("kernelUnitLength", AId::KernelUnitLength),
-        ("shape-image-threshold", AId::ShapeImageThreshold),
-        ("inline-size", AId::InlineSize),
-        ("edgeMode", AId::EdgeMode),
-        ("text-decoration-style", AId::TextDecorationStyle),
-        ("pointsAtZ", AId::PointsAtZ),
-        ("elevation", AId::Elevation),
-        ("tableValues", AId::TableValues),
-        ("kernelMatrix", AId::KernelMatrix),
-        ("patternTransform", AId::PatternTransform),
-        ("fill-rule", AId::FillRule),
-        ("color", AId::Color),
-        ("lighting-color", AId::LightingColor),
-        ("cy", AId::Cy),
-        ("fr", AId::Fr),
+        ("font-synthesis", AId::FontSynthesis),
+        ("color-rendering", AId::ColorRendering),
+        ("x1", AId::X1),
+        ("in2", AId::In2),
+        ("fill-opacity", AId::FillOpacity),
+        ("mask-repeat", AId::MaskRepeat),
+        ("shape-inside", AId::ShapeInside),
+        ("color-interpolation", AId::ColorInterpolation),
+        ("width", AId::Width),
+        ("text-decoration-fill", AId::TextDecorationFill),
+        ("d", AId::D),
+        ("color-profile", AId::ColorProfile),
+        ("yChannelSelector", AId::YChannelSelector),
+        ("k3", AId::K3),
         ("systemLanguage", AId::SystemLanguage),
-        ("text-indent", AId::TextIndent),
-        ("slope", AId::Slope),
-        ("font", AId::Font),
-        ("requiredExtensions", AId::RequiredExtensions),
-        ("href", AId::Href),
-        ("baseFrequency", AId::BaseFrequency),
-        ("stroke-dashoffset", AId::StrokeDashoffset),
-        ("text-decoration-stroke", AId::TextDecorationStroke),
+        ("marker-mid", AId::MarkerMid),
+        ("stroke-width", AId::StrokeWidth),
+        ("units-per-em", AId::UnitsPerEm),
         ("display", AId::Display),
-        ("amplitude", AId::Amplitude),
-        ("mask-size", AId::MaskSize),
-        ("font-variant-ligatures", AId::FontVariantLigatures),
-        ("in2", AId::In2),
-        ("maskUnits", AId::MaskUnits),
-        ("k4", AId::K4),
-        ("shape-padding", AId::ShapePadding),
+        ("transform-origin", AId::TransformOrigin),
+        ("azimuth", AId::Azimuth),
         ("r", AId::R),
+        ("stroke-miterlimit", AId::StrokeMiterlimit),
+        ("glyph-orientation-vertical", AId::GlyphOrientationVertical),
+        ("textLength", AId::TextLength),
+        ("text-overflow", AId::TextOverflow),
+        ("patternContentUnits", AId::PatternContentUnits),
+        ("visibility", AId::Visibility),
+        ("text-anchor", AId::TextAnchor),
         ("text-underline-position", AId::TextUnderlinePosition),
-        ("font-variant-east-asian", AId::FontVariantEastAsian),
-        ("z", AId::Z),
-        ("text-decoration-color", AId::TextDecorationColor),
-        ("intercept", AId::Intercept),
-        ("side", AId::Side),
-        ("x2", AId::X2),
-        ("mask-position", AId::MaskPosition),
-        ("pointsAtX", AId::PointsAtX),
-        ("patternUnits", AId::PatternUnits),
-        ("result", AId::Result),
+        ("ry", AId::Ry),
+        ("text-indent", AId::TextIndent),
+        ("viewBox", AId::ViewBox),
         ("baseline-shift", AId::BaselineShift),
-        ("stop-color", AId::StopColor),
-        ("k3", AId::K3),
-        ("stroke-dasharray", AId::StrokeDasharray),
-        ("y1", AId::Y1),
-        ("targetX", AId::TargetX),
-        ("stroke-width", AId::StrokeWidth),
-        ("limitingConeAngle", AId::LimitingConeAngle),
-        ("id", AId::Id),
-        ("mask-border-width", AId::MaskBorderWidth),
+        ("stdDeviation", AId::StdDeviation),
+        ("seed", AId::Seed),
+        ("glyph-orientation-horizontal", AId::GlyphOrientationHorizontal),
+        ("fr", AId::Fr),
+        ("flood-opacity", AId::FloodOpacity),
+        ("startOffset", AId::StartOffset),
+        ("opacity", AId::Opacity),
+        ("font-style", AId::FontStyle),
+        ("markerWidth", AId::MarkerWidth),
+        ("elevation", AId::Elevation),
+        ("dominant-baseline", AId::DominantBaseline),
+        ("values", AId::Values),
+        ("slope", AId::Slope),
+        ("pointer-events", AId::PointerEvents),
         ("shape-subtract", AId::ShapeSubtract),
-        ("font-variant", AId::FontVariant),
-        ("glyph-orientation-vertical", AId::GlyphOrientationVertical),
+        ("height", AId::Height),
+        ("text-align", AId::TextAlign),
+        ("y1", AId::Y1),
+        ("text-decoration-line", AId::TextDecorationLine),
+        ("font-size", AId::FontSize),
+        ("stroke", AId::Stroke),
+        ("text-orientation", AId::TextOrientation),
         ("offset", AId::Offset),
+        ("pointsAtX", AId::PointsAtX),
+        ("href", AId::Href),
+        ("stop-color", AId::StopColor),
+        ("transform-box", AId::TransformBox),
+        ("font-variant-caps", AId::FontVariantCaps),
+        ("intercept", AId::Intercept),
+        ("patternTransform", AId::PatternTransform),
+        ("cy", AId::Cy),
+        ("alignment-baseline", AId::AlignmentBaseline),
+        ("color-interpolation-filters", AId::ColorInterpolationFilters),
+        ("inline-size", AId::InlineSize),
+        ("divisor", AId::Divisor),
+        ("baseFrequency", AId::BaseFrequency),
+        ("enable-background", AId::EnableBackground),
+        ("markerUnits", AId::MarkerUnits),
+        ("text-decoration-color", AId::TextDecorationColor),
+        ("fill", AId::Fill),
+        ("shape-image-threshold", AId::ShapeImageThreshold),
+        ("k", AId::K),
+        ("stroke-opacity", AId::StrokeOpacity),
+        ("mask-border-source", AId::MaskBorderSource),
+        ("filterUnits", AId::FilterUnits),
+        ("maskContentUnits", AId::MaskContentUnits),
+        ("mask-type", AId::MaskType),
+        ("text-rendering", AId::TextRendering),
+        ("xChannelSelector", AId::XChannelSelector),
+        ("glyph-name", AId::GlyphName),
+        ("font-optical-sizing", AId::FontOpticalSizing),
+        ("font-feature-settings", AId::FontFeatureSettings),
+        ("pointsAtZ", AId::PointsAtZ),
+        ("text-decoration", AId::TextDecoration),
+        ("result", AId::Result),
+        ("isolation", AId::Isolation),
+        ("mask-border-slice", AId::MaskBorderSlice),
+        ("font-variant-ligatures", AId::FontVariantLigatures),
+        ("kernelMatrix", AId::KernelMatrix),
+        ("mask-origin", AId::MaskOrigin),
+        ("k4", AId::K4),
         ("type", AId::Type),
-        ("numOctaves", AId::NumOctaves),
+        ("descent", AId::Descent),
+        ("space", AId::Space),
+        ("mode", AId::Mode),
+        ("u1", AId::U1),
+        ("targetX", AId::TargetX),
+        ("refY", AId::RefY),
         ("font-stretch", AId::FontStretch),
-        ("mask-clip", AId::MaskClip),
-        ("clipPathUnits", AId::ClipPathUnits),
-        ("path", AId::Path),
-        ("seed", AId::Seed),
-        ("font-variant-position", AId::FontVariantPosition),
-        ("rotate", AId::Rotate),
-        ("text-overflow", AId::TextOverflow),
+        ("lighting-color", AId::LightingColor),
         ("mask-border-repeat", AId::MaskBorderRepeat),
-        ("stroke-linejoin", AId::StrokeLinejoin),
+        ("stop-opacity", AId::StopOpacity),
+        ("y2", AId::Y2),
+        ("mask-border", AId::MaskBorder),
+        ("diffuseConstant", AId::DiffuseConstant),
+        ("path", AId::Path),
+        ("id", AId::Id),
+        ("targetY", AId::TargetY),
         ("mask-mode", AId::MaskMode),
-        ("specularConstant", AId::SpecularConstant),
-        ("spreadMethod", AId::SpreadMethod),
-        ("letter-spacing", AId::LetterSpacing),
+        ("color", AId::Color),
+        ("font-variant", AId::FontVariant),
+        ("stroke-dasharray", AId::StrokeDasharray),
+        ("clip-rule", AId::ClipRule),
+        ("font-weight", AId::FontWeight),
+        ("text-decoration-stroke", AId::TextDecorationStroke),
+        ("mask-border-outset", AId::MaskBorderOutset),
+        ("font-variant-position", AId::FontVariantPosition),
+        ("image-rendering", AId::ImageRendering),
+        ("mix-blend-mode", AId::MixBlendMode),
         ("font-family", AId::FontFamily),
-        ("azimuth", AId::Azimuth),
-        ("refX", AId::RefX),
-        ("color-rendering", AId::ColorRendering),
-        ("values", AId::Values),
-        ("marker-end", AId::MarkerEnd),
-        ("font-variant-numeric", AId::FontVariantNumeric),
-        ("markerHeight", AId::MarkerHeight),
-        ("radius", AId::Radius),
-        ("width", AId::Width),
-        ("text-align-last", AId::TextAlignLast),
-        ("preserveAspectRatio", AId::PreserveAspectRatio),
-        ("patternContentUnits", AId::PatternContentUnits),
-        ("k1", AId::K1),
+        ("x-height", AId::XHeight),
+        ("marker-start", AId::MarkerStart),
+        ("y", AId::Y),
+        ("dy", AId::Dy),
+        ("shape-padding", AId::ShapePadding),
+        ("fill-rule", AId::FillRule),
+        ("writing-mode", AId::WritingMode),
+        ("ascent", AId::Ascent),
+        ("exponent", AId::Exponent),
+        ("spreadMethod", AId::SpreadMethod),
+        ("specularExponent", AId::SpecularExponent),
+        ("requiredExtensions", AId::RequiredExtensions),
+        ("unicode-bidi", AId::UnicodeBidi),
+        ("bias", AId::Bias),
+        ("stroke-linecap", AId::StrokeLinecap),
+        ("specularConstant", AId::SpecularConstant),
+        ("side", AId::Side),
+        ("class", AId::Class),
+        ("operator", AId::Operator),
+        ("mask-composite", AId::MaskComposite),
+        ("surfaceScale", AId::SurfaceScale),
+        ("style", AId::Style),
+        ("shape-margin", AId::ShapeMargin),
+        ("font", AId::Font),
+        ("pathLength", AId::PathLength),
+        ("k2", AId::K2),
+        ("mask-size", AId::MaskSize),
+        ("mask", AId::Mask),
+        ("fx", AId::Fx),
+        ("preserveAlpha", AId::PreserveAlpha),
+        ("stitchTiles", AId::StitchTiles),
+        ("clipPathUnits", AId::ClipPathUnits),
+        ("patternUnits", AId::PatternUnits),
+        ("line-height", AId::LineHeight),
+        ("scale", AId::Scale),
+        ("mask-position", AId::MaskPosition),
+        ("rotate", AId::Rotate),
+        ("z", AId::Z),
         ("paint-order", AId::PaintOrder),
-        ("d", AId::D),
-        ("mask-border-slice", AId::MaskBorderSlice),
-        ("dx", AId::Dx),
-        ("text-align", AId::TextAlign),
+        ("lengthAdjust", AId::LengthAdjust),
+        ("mask-clip", AId::MaskClip),
+        ("markerHeight", AId::MarkerHeight),
+        ("font-variant-numeric", AId::FontVariantNumeric),
     ],
 };
 
@@ -665,7 +712,7 @@ impl std::fmt::Display for AId {
 struct Map<V: 'static> {
     pub key: u64,
     pub disps: &'static [(u32, u32)],
-    pub entries: &'static [(&'static str, V)],
+    pub entries: &'static[(&'static str, V)],
 }
 
 impl<V: PartialEq> Map<V> {
@@ -706,11 +753,9 @@ fn split(hash: u64) -> (u32, u32, u32) {
     const BITS: u32 = 21;
     const MASK: u64 = (1 << BITS) - 1;
 
-    (
-        (hash & MASK) as u32,
-        ((hash >> BITS) & MASK) as u32,
-        ((hash >> (2 * BITS)) & MASK) as u32,
-    )
+    ((hash & MASK) as u32,
+     ((hash >> BITS) & MASK) as u32,
+     ((hash >> (2 * BITS)) & MASK) as u32)
 }
 
 #[inline]
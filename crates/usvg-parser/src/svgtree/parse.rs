@@ -3,10 +3,14 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
 
 use roxmltree::Error;
 
-use super::{AId, Attribute, Document, EId, NodeData, NodeId, NodeKind, ShortRange};
+use super::css::{self, Media, StyleSheets};
+use super::{AId, Attribute, Document, EId, NodeData, NodeId, NodeKind, ShortRange, SvgNode};
+use crate::{OptionLog, Options};
 
 const SVG_NS: &str = "http://www.w3.org/2000/svg";
 const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
@@ -14,8 +18,15 @@ const XML_NAMESPACE_NS: &str = "http://www.w3.org/XML/1998/namespace";
 
 impl<'input> Document<'input> {
     /// Parses a [`Document`] from a [`roxmltree::Document`].
-    pub fn parse_tree(xml: &roxmltree::Document<'input>) -> Result<Document<'input>, Error> {
-        parse(xml)
+    ///
+    /// `externals` is a list of URLs and SVG strings referenced by `use` elements.
+    /// `opt` is used to evaluate CSS `@media` rules.
+    pub fn parse_tree(
+        xml: &roxmltree::Document<'input>,
+        externals: &'input [(String, Arc<str>)],
+        opt: &Options,
+    ) -> Result<Document<'input>, Error> {
+        parse(xml, externals, opt)
     }
 
     pub(crate) fn append(&mut self, parent_id: NodeId, kind: NodeKind) -> NodeId {
@@ -49,23 +60,126 @@ impl<'input> Document<'input> {
     }
 }
 
-fn parse<'input>(xml: &roxmltree::Document<'input>) -> Result<Document<'input>, Error> {
+/// An XML document with resolved CSS and IDs.
+struct XmlDocument<'a, 'input: 'a> {
+    /// Document's URL. Empty for the main document.
+    url: &'a str,
+    /// A prefix for IDs and links of an external document.
+    ///
+    /// External documents have their own IDs scope,
+    /// therefore their IDs should not clash with the main document ones.
+    id_prefix: Option<String>,
+    style_sheet: css::StyleSheet<'a>,
+    id_map: HashMap<&'a str, roxmltree::Node<'a, 'input>>,
+    root: roxmltree::Node<'a, 'input>,
+}
+
+impl<'a, 'input: 'a> XmlDocument<'a, 'input> {
+    fn new(xml: &'a roxmltree::Document<'input>, url: &'a str, media: &Media) -> Self {
+        // build a map of id -> node for resolve_href
+        let mut id_map = HashMap::new();
+        for node in xml.descendants() {
+            if let Some(id) = node.attribute("id") {
+                if !id_map.contains_key(id) {
+                    id_map.insert(id, node);
+                }
+            }
+        }
+
+        // Links are parsed up to a space, a quote or a closing parenthesis.
+        let id_prefix = if url.is_empty() {
+            None
+        } else {
+            let url = url.replace([' ', '(', ')', '\'', '"', '#'], "_");
+            Some(format!("{}:", url))
+        };
+
+        XmlDocument {
+            url,
+            id_prefix,
+            style_sheet: resolve_css(xml, media),
+            id_map,
+            root: xml.root(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Context<'a, 'input: 'a> {
+    /// The XML document we're currently parsing.
+    xml: &'a XmlDocument<'a, 'input>,
+    /// External documents by URL.
+    externals: &'a HashMap<&'a str, XmlDocument<'a, 'input>>,
+    /// A style sheet from `Options`. Shared by all documents.
+    user_style_sheet: &'a css::StyleSheet<'a>,
+    /// External links we are currently inside of, as resolved URLs and fragments.
+    /// Used to detect recursive references between documents.
+    external_links: Vec<(&'a str, &'a str)>,
+}
+
+fn parse<'input>(
+    xml: &roxmltree::Document<'input>,
+    externals: &'input [(String, Arc<str>)],
+    opt: &Options,
+) -> Result<Document<'input>, Error> {
     let mut doc = Document {
         nodes: Vec::new(),
         attrs: Vec::new(),
         links: HashMap::new(),
+        animations: Vec::new(),
+        font_faces: Vec::new(),
+        custom_properties: HashMap::new(),
     };
 
-    // build a map of id -> node for resolve_href
-    let mut id_map = HashMap::new();
-    for node in xml.descendants() {
-        if let Some(id) = node.attribute("id") {
-            if !id_map.contains_key(id) {
-                id_map.insert(id, node);
-            }
+    let mut external_xmls = Vec::new();
+    for (url, text) in externals {
+        let xml_opt = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+
+        match roxmltree::Document::parse_with_options(text, xml_opt) {
+            Ok(xml) => external_xmls.push((url.as_str(), xml)),
+            Err(e) => log::warn!("Failed to parse '{}' cause {}.", url, e),
         }
     }
 
+    // External documents are rendered inside the main one,
+    // therefore use the same media environment.
+    let media = Media::new(xml, opt);
+    let external_docs: HashMap<&str, XmlDocument> = external_xmls
+        .iter()
+        .map(|(url, xml)| (*url, XmlDocument::new(xml, url, &media)))
+        .collect();
+
+    // `currentColor` override acts like an inherited `color`,
+    // therefore it has the lowest priority.
+    let current_color_rule = opt.current_color.map(|c| {
+        format!(
+            ":where(:root) {{ color: #{:02x}{:02x}{:02x} }}",
+            c.red, c.green, c.blue
+        )
+    });
+
+    let mut user_style_sheet = css::StyleSheet::new();
+    if let Some(ref text) = current_color_rule {
+        user_style_sheet.parse_more(text, &media);
+    }
+    if let Some(ref text) = opt.style_sheet {
+        user_style_sheet.parse_more(text, &media);
+    }
+
+    let main_doc = XmlDocument::new(xml, "", &media);
+    doc.font_faces = user_style_sheet.font_faces.clone();
+    doc.font_faces
+        .extend_from_slice(&main_doc.style_sheet.font_faces);
+    let ctx = Context {
+        xml: &main_doc,
+        externals: &external_docs,
+        user_style_sheet: &user_style_sheet,
+        external_links: Vec::new(),
+    };
+
     // Add a root node.
     doc.nodes.push(NodeData {
         parent: None,
@@ -74,17 +188,14 @@ fn parse<'input>(xml: &roxmltree::Document<'input>) -> Result<Document<'input>,
         kind: NodeKind::Root,
     });
 
-    let style_sheet = resolve_css(xml);
-
     parse_xml_node_children(
         xml.root(),
         xml.root(),
         doc.root().id,
-        &style_sheet,
         false,
         0,
         &mut doc,
-        &id_map,
+        &ctx,
     )?;
 
     // Check that the root element is `svg`.
@@ -97,6 +208,8 @@ fn parse<'input>(xml: &roxmltree::Document<'input>) -> Result<Document<'input>,
         None => return Err(roxmltree::Error::NoRootNode),
     }
 
+    import_external_resources(&ctx, &mut doc)?;
+
     // Collect all elements with `id` attribute.
     let mut links = HashMap::new();
     for node in doc.descendants() {
@@ -109,6 +222,7 @@ fn parse<'input>(xml: &roxmltree::Document<'input>) -> Result<Document<'input>,
     fix_recursive_patterns(&mut doc);
     fix_recursive_links(EId::ClipPath, AId::ClipPath, &mut doc);
     fix_recursive_links(EId::Mask, AId::Mask, &mut doc);
+    fix_recursive_links(EId::Mask, AId::MaskImage, &mut doc);
     fix_recursive_links(EId::Filter, AId::Filter, &mut doc);
     fix_recursive_fe_image(&mut doc);
 
@@ -127,41 +241,30 @@ pub(crate) fn parse_tag_name(node: roxmltree::Node) -> Option<EId> {
     EId::from_str(node.tag_name().name())
 }
 
-fn parse_xml_node_children<'input>(
-    parent: roxmltree::Node<'_, 'input>,
+fn parse_xml_node_children<'a, 'input: 'a>(
+    parent: roxmltree::Node<'a, 'input>,
     origin: roxmltree::Node,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
     ignore_ids: bool,
     depth: u32,
     doc: &mut Document<'input>,
-    id_map: &HashMap<&str, roxmltree::Node<'_, 'input>>,
+    ctx: &Context<'a, 'input>,
 ) -> Result<(), Error> {
     for node in parent.children() {
-        parse_xml_node(
-            node,
-            origin,
-            parent_id,
-            style_sheet,
-            ignore_ids,
-            depth,
-            doc,
-            id_map,
-        )?;
+        parse_xml_node(node, origin, parent_id, ignore_ids, depth, doc, ctx)?;
     }
 
     Ok(())
 }
 
-fn parse_xml_node<'input>(
-    node: roxmltree::Node<'_, 'input>,
+fn parse_xml_node<'a, 'input: 'a>(
+    node: roxmltree::Node<'a, 'input>,
     origin: roxmltree::Node,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
     ignore_ids: bool,
     depth: u32,
     doc: &mut Document<'input>,
-    id_map: &HashMap<&str, roxmltree::Node<'_, 'input>>,
+    ctx: &Context<'a, 'input>,
 ) -> Result<(), Error> {
     if depth > 1024 {
         return Err(Error::NodesLimitReached);
@@ -176,28 +279,37 @@ fn parse_xml_node<'input>(
         return Ok(());
     }
 
+    if tag_name.is_animation() {
+        super::animation::parse_animation(node, parent_id, ignore_ids, doc);
+        return Ok(());
+    }
+
     // TODO: remove?
     // Treat links as groups.
     if tag_name == EId::A {
         tag_name = EId::G;
     }
 
-    let node_id = parse_svg_element(node, parent_id, tag_name, style_sheet, ignore_ids, doc)?;
+    let style_sheets = StyleSheets {
+        user: ctx.user_style_sheet,
+        author: &ctx.xml.style_sheet,
+    };
+    let id_prefix = ctx.xml.id_prefix.as_deref();
+    let node_id = parse_svg_element(
+        node,
+        parent_id,
+        tag_name,
+        style_sheets,
+        ignore_ids,
+        id_prefix,
+        doc,
+    )?;
     if tag_name == EId::Text {
-        super::text::parse_svg_text_element(node, node_id, style_sheet, doc)?;
+        super::text::parse_svg_text_element(node, node_id, style_sheets, id_prefix, doc)?;
     } else if tag_name == EId::Use {
-        parse_svg_use_element(node, origin, node_id, style_sheet, depth + 1, doc, id_map)?;
+        parse_svg_use_element(node, origin, node_id, depth + 1, doc, ctx)?;
     } else {
-        parse_xml_node_children(
-            node,
-            origin,
-            node_id,
-            style_sheet,
-            ignore_ids,
-            depth + 1,
-            doc,
-            id_map,
-        )?;
+        parse_xml_node_children(node, origin, node_id, ignore_ids, depth + 1, doc, ctx)?;
     }
 
     Ok(())
@@ -207,12 +319,17 @@ pub(crate) fn parse_svg_element<'input>(
     xml_node: roxmltree::Node<'_, 'input>,
     parent_id: NodeId,
     tag_name: EId,
-    style_sheet: &simplecss::StyleSheet,
+    style_sheets: StyleSheets,
     ignore_ids: bool,
+    id_prefix: Option<&str>,
     doc: &mut Document<'input>,
 ) -> Result<NodeId, Error> {
     let attrs_start_idx = doc.attrs.len();
 
+    let (declarations, user_declarations) = css::cascade(style_sheets, xml_node);
+    let custom_properties = resolve_custom_properties(parent_id, &declarations, doc);
+    let own_custom_properties = declarations.iter().any(|d| d.name.starts_with("--"));
+
     // Copy presentational attributes first.
     for attr in xml_node.attributes() {
         match attr.namespace() {
@@ -232,19 +349,73 @@ pub(crate) fn parse_svg_element<'input>(
         }
 
         // For some reason those properties are allowed only inside a `style` attribute and CSS.
-        if matches!(aid, AId::MixBlendMode | AId::Isolation | AId::FontKerning) {
+        if matches!(
+            aid,
+            AId::MixBlendMode
+                | AId::Isolation
+                | AId::FontKerning
+                | AId::MaskComposite
+                | AId::MaskImage
+                | AId::MaskMode
+                | AId::MaskPosition
+                | AId::MaskRepeat
+                | AId::MaskSize
+        ) {
             continue;
         }
 
-        append_attribute(parent_id, tag_name, aid, attr.value_storage().clone(), doc);
+        let mut value = attr.value_storage().clone();
+        if value.contains("var(") {
+            match resolve_vars(&value, custom_properties.as_deref()) {
+                Some(v) => value = roxmltree::StringStorage::new_owned(v),
+                None => continue,
+            }
+        }
+
+        if let Some(v) = id_prefix.and_then(|prefix| scope_links(aid, tag_name, &value, prefix)) {
+            value = roxmltree::StringStorage::new_owned(v);
+        }
+
+        append_attribute(parent_id, tag_name, aid, value, doc);
     }
 
-    let mut insert_attribute = |aid, value: &str| {
+    // Normal user declarations have a lower priority than presentation attributes.
+    let presentation_attrs: Vec<AId> = doc.attrs[attrs_start_idx..]
+        .iter()
+        .map(|a| a.name)
+        .collect();
+
+    // `None` unsets the attribute.
+    let mut insert_attribute = |aid, value: Option<&str>, is_user: bool| {
+        // The `transform` property is mapped onto paint servers' transform attributes.
+        let aid = match (aid, tag_name) {
+            (AId::Transform, EId::LinearGradient | EId::RadialGradient) => AId::GradientTransform,
+            (AId::Transform, EId::Pattern) => AId::PatternTransform,
+            _ => aid,
+        };
+
+        if is_user && presentation_attrs.contains(&aid) {
+            return;
+        }
+
         // Check that attribute already exists.
         let idx = doc.attrs[attrs_start_idx..]
             .iter_mut()
             .position(|a| a.name == aid);
 
+        let value = match value {
+            Some(v) => v,
+            None => {
+                if let Some(idx) = idx {
+                    doc.attrs.remove(attrs_start_idx + idx);
+                }
+                return;
+            }
+        };
+
+        let scoped = id_prefix.and_then(|prefix| scope_links(aid, tag_name, value, prefix));
+        let value = scoped.as_deref().unwrap_or(value);
+
         // Append an attribute as usual.
         let added = append_attribute(
             parent_id,
@@ -266,35 +437,29 @@ pub(crate) fn parse_svg_element<'input>(
         }
     };
 
-    // Apply CSS.
-    for rule in &style_sheet.rules {
-        if rule.selector.matches(&XmlNode(xml_node)) {
-            for declaration in &rule.declarations {
-                // TODO: perform XML attribute normalization
-                if let Some(aid) = AId::from_str(declaration.name) {
-                    // Parse only the presentation attributes.
-                    if aid.is_presentation() {
-                        insert_attribute(aid, declaration.value);
-                    }
-                } else if declaration.name == "marker" {
-                    insert_attribute(AId::MarkerStart, declaration.value);
-                    insert_attribute(AId::MarkerMid, declaration.value);
-                    insert_attribute(AId::MarkerEnd, declaration.value);
-                }
-            }
+    // Apply CSS and the `style` attribute.
+    for (idx, declaration) in declarations.iter().enumerate() {
+        // Custom properties were already resolved.
+        if declaration.name.starts_with("--") {
+            continue;
         }
-    }
 
-    // Split a `style` attribute.
-    if let Some(value) = xml_node.attribute("style") {
-        for declaration in simplecss::DeclarationTokenizer::from(value) {
-            // TODO: preform XML attribute normalization
-            if let Some(aid) = AId::from_str(declaration.name) {
-                // Parse only the presentation attributes.
-                if aid.is_presentation() {
-                    insert_attribute(aid, declaration.value);
-                }
+        // Values with unresolved custom properties are invalid at computed-value time,
+        // which makes the property unset.
+        let value = resolve_vars(&declaration.value, custom_properties.as_deref());
+        let value = value.as_deref();
+        let is_user = idx < user_declarations;
+
+        // TODO: perform XML attribute normalization
+        if let Some(aid) = AId::from_str(declaration.name) {
+            // Parse only the presentation attributes.
+            if aid.is_presentation() {
+                insert_attribute(aid, value, is_user);
             }
+        } else if declaration.name == "marker" {
+            insert_attribute(AId::MarkerStart, value, is_user);
+            insert_attribute(AId::MarkerMid, value, is_user);
+            insert_attribute(AId::MarkerEnd, value, is_user);
         }
     }
 
@@ -310,9 +475,122 @@ pub(crate) fn parse_svg_element<'input>(
         },
     );
 
+    // Store only the properties declared by this element,
+    // since inherited ones can be found via ancestors.
+    if own_custom_properties {
+        if let Some(properties) = custom_properties {
+            doc.custom_properties.insert(node_id, properties);
+        }
+    }
+
     Ok(node_id)
 }
 
+/// Collects element's custom properties, including inherited ones.
+fn resolve_custom_properties(
+    parent_id: NodeId,
+    declarations: &[css::Declaration],
+    doc: &Document,
+) -> Option<Rc<css::CustomProperties>> {
+    let inherited = doc
+        .get(parent_id)
+        .ancestors()
+        .find_map(|n| doc.custom_properties.get(&n.id))
+        .cloned();
+
+    let mut names = Vec::new();
+    for declaration in declarations {
+        // A property can be declared multiple times, but must be resolved only once.
+        if declaration.name.starts_with("--") && !names.contains(&declaration.name) {
+            names.push(declaration.name);
+        }
+    }
+
+    if names.is_empty() {
+        return inherited;
+    }
+
+    let mut properties = inherited.map(|p| (*p).clone()).unwrap_or_default();
+    for declaration in declarations {
+        if declaration.name.starts_with("--") {
+            properties.insert(declaration.name.to_string(), declaration.value.to_string());
+        }
+    }
+
+    // Custom properties can reference each other.
+    // Cyclic and invalid references make a property undefined.
+    for name in names {
+        match css::substitute_vars(&properties[name], &properties) {
+            Some(value) => properties.insert(name.to_string(), value),
+            None => properties.remove(name),
+        };
+    }
+
+    Some(Rc::new(properties))
+}
+
+/// Substitutes `var()` functions in a property value.
+fn resolve_vars<'a>(
+    value: &'a str,
+    properties: Option<&css::CustomProperties>,
+) -> Option<std::borrow::Cow<'a, str>> {
+    if !value.contains("var(") {
+        return Some(std::borrow::Cow::Borrowed(value));
+    }
+
+    let empty = css::CustomProperties::new();
+    match css::substitute_vars(value, properties.unwrap_or(&empty)) {
+        Some(v) => Some(std::borrow::Cow::Owned(v)),
+        None => {
+            log::warn!("Failed to resolve custom properties in '{}'.", value);
+            None
+        }
+    }
+}
+
+/// Moves IDs and local links of an external document into its own IDs scope.
+///
+/// Returns `None` when a value doesn't have to be changed.
+fn scope_links(aid: AId, tag_name: EId, value: &str, prefix: &str) -> Option<String> {
+    match aid {
+        AId::Id => return Some(format!("{}{}", prefix, value)),
+        // `use` links are resolved during parsing.
+        AId::Href if tag_name == EId::Use => return None,
+        AId::Href => {
+            let link = value.trim_start().strip_prefix('#')?;
+            return Some(format!("#{}{}", prefix, link));
+        }
+        _ => {}
+    }
+
+    if !value.contains("url(") {
+        return None;
+    }
+
+    // Paints, filters, clip paths, masks and markers, like `url(#id)` or `url('#id')`.
+    let mut scoped = String::with_capacity(value.len() + prefix.len());
+    let mut rest = value;
+    while let Some(idx) = rest.find("url(") {
+        let (head, tail) = rest.split_at(idx + 4);
+        scoped.push_str(head);
+
+        let link_start = tail
+            .find(|c: char| !c.is_ascii_whitespace() && c != '\'')
+            .unwrap_or(tail.len());
+        scoped.push_str(&tail[..link_start]);
+        rest = &tail[link_start..];
+
+        if let Some(link) = rest.strip_prefix('#') {
+            scoped.push('#');
+            scoped.push_str(prefix);
+            rest = link;
+        }
+    }
+    scoped.push_str(rest);
+
+    Some(scoped)
+}
+
 fn append_attribute<'input>(
     parent_id: NodeId,
     tag_name: EId,
@@ -386,7 +664,9 @@ fn resolve_inherit(parent_id: NodeId, aid: AId, doc: &mut Document) -> bool {
 
     // Fallback to a default value if possible.
     let value = match aid {
-        AId::ImageRendering | AId::ShapeRendering | AId::TextRendering => "auto",
+        AId::FontOpticalSizing | AId::ImageRendering | AId::ShapeRendering | AId::TextRendering => {
+            "auto"
+        }
 
         AId::ClipPath
         | AId::Filter
@@ -398,9 +678,16 @@ fn resolve_inherit(parent_id: NodeId, aid: AId, doc: &mut Document) -> bool {
         | AId::StrokeDasharray
         | AId::TextDecoration => "none",
 
-        AId::FontStretch
+        AId::FontFeatureSettings
+        | AId::FontStretch
         | AId::FontStyle
         | AId::FontVariant
+        | AId::FontVariantCaps
+        | AId::FontVariantEastAsian
+        | AId::FontVariantLigatures
+        | AId::FontVariantNumeric
+        | AId::FontVariantPosition
+        | AId::FontVariationSettings
         | AId::FontWeight
         | AId::LetterSpacing
         | AId::WordSpacing => "normal",
@@ -416,11 +703,13 @@ fn resolve_inherit(parent_id: NodeId, aid: AId, doc: &mut Document) -> bool {
         AId::ClipRule | AId::FillRule => "nonzero",
 
         AId::BaselineShift => "baseline",
+        AId::ColorInterpolation => "sRGB",
         AId::ColorInterpolationFilters => "linearRGB",
         AId::Direction => "ltr",
         AId::Display => "inline",
         AId::FontSize => "medium",
         AId::Overflow => "visible",
+        AId::PointerEvents => "visiblePainted",
         AId::StrokeDashoffset => "0",
         AId::StrokeLinecap => "butt",
         AId::StrokeLinejoin => "miter",
@@ -449,16 +738,107 @@ fn resolve_href<'a, 'input: 'a>(
     id_map.get(link_id).copied()
 }
 
-fn parse_svg_use_element<'input>(
-    node: roxmltree::Node<'_, 'input>,
+/// Resolves a `use` element link, which can point to an external document.
+///
+/// Returns a linked node and a context of a document it belongs to.
+fn resolve_use_href<'a, 'input: 'a>(
+    node: roxmltree::Node<'a, 'input>,
+    ctx: &Context<'a, 'input>,
+) -> Option<(roxmltree::Node<'a, 'input>, Context<'a, 'input>)> {
+    let link_value = node
+        .attribute((XLINK_NS, "href"))
+        .or_else(|| node.attribute("href"))?;
+
+    let (url, fragment) = split_external_href(link_value)?;
+    if url.is_empty() {
+        let link = resolve_href(node, &ctx.xml.id_map)?;
+        return Some((link, ctx.clone()));
+    }
+
+    // Links inside external documents are relative to them.
+    let url = resolve_external_url(ctx.xml.url, url);
+    let (url, xml) = ctx.externals.get_key_value(url.as_str())?;
+
+    if ctx.external_links.contains(&(*url, fragment)) {
+        log::warn!(
+            "Recursive external 'use' detected. '{}' will be skipped.",
+            link_value
+        );
+        return None;
+    }
+
+    let link = if fragment.is_empty() {
+        xml.root.first_element_child()?
+    } else {
+        *xml.id_map.get(fragment).log_none(|| {
+            log::warn!("'{}' doesn't have a '{}' element.", url, fragment);
+        })?
+    };
+
+    let mut external_links = ctx.external_links.clone();
+    external_links.push((url, fragment));
+
+    let ctx = Context {
+        xml,
+        externals: ctx.externals,
+        user_style_sheet: ctx.user_style_sheet,
+        external_links,
+    };
+
+    Some((link, ctx))
+}
+
+/// Splits a link into a URL and a fragment.
+///
+/// The URL is empty for local links.
+pub(crate) fn split_external_href(link: &str) -> Option<(&str, &str)> {
+    let link = link.trim();
+    match link.find('#') {
+        Some(idx) => Some((&link[..idx], &link[idx + 1..])),
+        None if !link.is_empty() => Some((link, "")),
+        None => None,
+    }
+}
+
+/// Resolves an external document URL relative to the `base` document URL.
+///
+/// Absolute paths and URLs with a scheme are returned as is.
+/// The main document has an empty URL, therefore its links are unchanged.
+pub(crate) fn resolve_external_url(base: &str, url: &str) -> String {
+    let is_absolute = url.starts_with('/') || url.contains(':');
+    let dir = match base.rfind('/') {
+        Some(idx) if !is_absolute => &base[..idx + 1],
+        _ => return url.to_string(),
+    };
+
+    // Remove `.` and `..` segments, so the same document would have the same URL.
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in dir.split('/').chain(url.split('/')) {
+        match segment {
+            "" | "." => {}
+            ".." if matches!(segments.last(), Some(s) if *s != "..") => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    let mut resolved = segments.join("/");
+    if base.starts_with('/') {
+        resolved.insert(0, '/');
+    }
+    resolved
+}
+
+fn parse_svg_use_element<'a, 'input: 'a>(
+    node: roxmltree::Node<'a, 'input>,
     origin: roxmltree::Node,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
     depth: u32,
     doc: &mut Document<'input>,
-    id_map: &HashMap<&str, roxmltree::Node<'_, 'input>>,
+    ctx: &Context<'a, 'input>,
 ) -> Result<(), Error> {
-    let link = match resolve_href(node, id_map) {
+    let (link, ctx) = match resolve_use_href(node, ctx) {
         Some(v) => v,
         None => return Ok(()),
     };
@@ -499,7 +879,7 @@ fn parse_svg_use_element<'input>(
         .skip(1)
         .filter(|n| n.has_tag_name((SVG_NS, "use")))
     {
-        if let Some(link2) = resolve_href(link_child, id_map) {
+        if let Some(link2) = resolve_href(link_child, &ctx.xml.id_map) {
             if link2 == node || link2 == link {
                 is_recursive = true;
                 break;
@@ -515,20 +895,66 @@ fn parse_svg_use_element<'input>(
         return Ok(());
     }
 
-    parse_xml_node(
-        link,
-        node,
-        parent_id,
-        style_sheet,
-        true,
-        depth + 1,
-        doc,
-        id_map,
-    )
+    parse_xml_node(link, node, parent_id, true, depth + 1, doc, &ctx)
 }
 
-fn resolve_css<'a>(xml: &'a roxmltree::Document<'a>) -> simplecss::StyleSheet<'a> {
-    let mut sheet = simplecss::StyleSheet::new();
+/// Imports paint servers, clip paths, masks, filters and markers from external documents.
+///
+/// They can be referenced by the elements that were copied by an external `use`.
+/// Their IDs are prefixed with the document URL, therefore they never clash
+/// with the main document or other external documents.
+fn import_external_resources<'a, 'input: 'a>(
+    ctx: &Context<'a, 'input>,
+    doc: &mut Document<'input>,
+) -> Result<(), Error> {
+    fn is_resource(node: roxmltree::Node) -> bool {
+        matches!(
+            parse_tag_name(node),
+            Some(
+                EId::ClipPath
+                    | EId::Filter
+                    | EId::LinearGradient
+                    | EId::Marker
+                    | EId::Mask
+                    | EId::Pattern
+                    | EId::RadialGradient
+            )
+        )
+    }
+
+    let root_id = doc.root_element().id;
+
+    let mut urls: Vec<_> = ctx.externals.keys().copied().collect();
+    urls.sort_unstable();
+
+    for url in urls {
+        let xml = &ctx.externals[url];
+        let external_ctx = Context {
+            xml,
+            externals: ctx.externals,
+            user_style_sheet: ctx.user_style_sheet,
+            external_links: Vec::new(),
+        };
+
+        for node in xml.root.descendants() {
+            if !node.has_attribute("id") || !is_resource(node) {
+                continue;
+            }
+
+            // Nested resources will be imported together with their parent.
+            if node.ancestors().skip(1).any(is_resource) {
+                continue;
+            }
+
+            parse_xml_node(node, node, root_id, false, 1, doc, &external_ctx)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_css<'a>(xml: &'a roxmltree::Document<'a>, media: &Media) -> css::StyleSheet<'a> {
+    let mut sheet = css::StyleSheet::new();
 
     for node in xml.descendants().filter(|n| n.has_tag_name("style")) {
         match node.attribute("type") {
@@ -542,43 +968,12 @@ fn resolve_css<'a>(xml: &'a roxmltree::Document<'a>) -> simplecss::StyleSheet<'a
             None => continue,
         };
 
-        sheet.parse_more(text);
+        sheet.parse_more(text, media);
     }
 
     sheet
 }
 
-struct XmlNode<'a, 'input: 'a>(roxmltree::Node<'a, 'input>);
-
-impl simplecss::Element for XmlNode<'_, '_> {
-    fn parent_element(&self) -> Option<Self> {
-        self.0.parent_element().map(XmlNode)
-    }
-
-    fn prev_sibling_element(&self) -> Option<Self> {
-        self.0.prev_sibling_element().map(XmlNode)
-    }
-
-    fn has_local_name(&self, local_name: &str) -> bool {
-        self.0.tag_name().name() == local_name
-    }
-
-    fn attribute_matches(&self, local_name: &str, operator: simplecss::AttributeOperator) -> bool {
-        match self.0.attribute(local_name) {
-            Some(value) => operator.matches(value),
-            None => false,
-        }
-    }
-
-    fn pseudo_class_matches(&self, class: simplecss::PseudoClass) -> bool {
-        match class {
-            simplecss::PseudoClass::FirstChild => self.prev_sibling_element().is_none(),
-            // TODO: lang
-            _ => false, // Since we are querying a static SVG we can ignore other pseudo-classes.
-        }
-    }
-}
-
 fn fix_recursive_patterns(doc: &mut Document) {
     while let Some(node_id) = find_recursive_pattern(AId::Fill, doc) {
         let idx = doc.get(node_id).attribute_id(AId::Fill).unwrap();
@@ -649,7 +1044,7 @@ fn find_recursive_link(eid: EId, aid: AId, doc: &Document) -> Option<NodeId> {
         .filter(|n| n.tag_name() == Some(eid))
     {
         for child in node.descendants() {
-            if let Some(link) = child.node_attribute(aid) {
+            for link in node_links(child, aid) {
                 if link == node {
                     // If an element child has a link to the element itself
                     // then we have to replace it with `none`.
@@ -658,10 +1053,8 @@ fn find_recursive_link(eid: EId, aid: AId, doc: &Document) -> Option<NodeId> {
                 } else {
                     // Check that linked node children doesn't link this element.
                     for node2 in link.descendants() {
-                        if let Some(link2) = node2.node_attribute(aid) {
-                            if link2 == node {
-                                return Some(node2.id);
-                            }
+                        if node_links(node2, aid).contains(&node) {
+                            return Some(node2.id);
                         }
                     }
                 }
@@ -672,6 +1065,14 @@ fn find_recursive_link(eid: EId, aid: AId, doc: &Document) -> Option<NodeId> {
     None
 }
 
+fn node_links<'a, 'input: 'a>(node: SvgNode<'a, 'input>, aid: AId) -> Vec<SvgNode<'a, 'input>> {
+    match aid {
+        // Can contain multiple mask layers.
+        AId::Mask | AId::MaskImage => crate::mask::links(node, aid),
+        _ => node.node_attribute(aid).into_iter().collect(),
+    }
+}
+
 /// Detects cases like:
 ///
 /// ```xml
@@ -13,26 +13,42 @@ const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
 pub(crate) fn parse_svg_text_element<'input>(
     parent: roxmltree::Node<'_, 'input>,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
+    style_sheets: super::css::StyleSheets,
+    id_prefix: Option<&str>,
     doc: &mut Document<'input>,
 ) -> Result<(), Error> {
     debug_assert_eq!(parent.tag_name().name(), "text");
 
-    let space = if doc.get(parent_id).has_attribute(AId::Space) {
-        get_xmlspace(doc, parent_id, XmlSpace::Default)
-    } else {
-        if let Some(node) = doc
-            .get(parent_id)
-            .ancestors()
-            .find(|n| n.has_attribute(AId::Space))
-        {
-            get_xmlspace(doc, node.id, XmlSpace::Default)
-        } else {
-            XmlSpace::Default
-        }
+    let space = match doc
+        .get(parent_id)
+        .ancestors()
+        .find(|n| n.has_attribute(AId::WhiteSpace) || n.has_attribute(AId::Space))
+    {
+        Some(node) => get_xmlspace(doc, node.id, XmlSpace::Default),
+        None => XmlSpace::Default,
     };
 
-    parse_svg_text_element_impl(parent, parent_id, style_sheet, space, doc)?;
+    // Newlines are preserved only by auto-wrapped text.
+    // Otherwise there is no way to render them.
+    let wrapping = is_auto_wrapped(doc.get(parent_id));
+    let newlines = wrapping
+        && doc
+            .get(parent_id)
+            .ancestors()
+            .find(|n| n.has_attribute(AId::WhiteSpace))
+            .map(|node| get_newlines(doc, node.id, false))
+            == Some(true);
+
+    parse_svg_text_element_impl(
+        parent,
+        parent_id,
+        style_sheets,
+        id_prefix,
+        space,
+        newlines,
+        wrapping,
+        doc,
+    )?;
 
     trim_text_nodes(parent_id, space, doc);
     Ok(())
@@ -41,13 +57,16 @@ pub(crate) fn parse_svg_text_element<'input>(
 fn parse_svg_text_element_impl<'input>(
     parent: roxmltree::Node<'_, 'input>,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
+    style_sheets: super::css::StyleSheets,
+    id_prefix: Option<&str>,
     space: XmlSpace,
+    newlines: bool,
+    wrapping: bool,
     doc: &mut Document<'input>,
 ) -> Result<(), Error> {
     for node in parent.children() {
         if node.is_text() {
-            let text = trim_text(node.text().unwrap(), space);
+            let text = trim_text(node.text().unwrap(), space, newlines);
             doc.append(parent_id, NodeKind::Text(text));
             continue;
         }
@@ -57,6 +76,11 @@ fn parse_svg_text_element_impl<'input>(
             None => continue,
         };
 
+        if tag_name.is_animation() {
+            super::animation::parse_animation(node, parent_id, false, doc);
+            continue;
+        }
+
         if tag_name == EId::A {
             // Treat links as simple text.
             tag_name = EId::Tspan;
@@ -78,9 +102,17 @@ fn parse_svg_text_element_impl<'input>(
             is_tref = true;
         }
 
-        let node_id =
-            super::parse::parse_svg_element(node, parent_id, tag_name, style_sheet, false, doc)?;
+        let node_id = super::parse::parse_svg_element(
+            node,
+            parent_id,
+            tag_name,
+            style_sheets,
+            false,
+            id_prefix,
+            doc,
+        )?;
         let space = get_xmlspace(doc, node_id, space);
+        let newlines = wrapping && get_newlines(doc, node_id, newlines);
 
         if is_tref {
             let link_value = node
@@ -89,12 +121,21 @@ fn parse_svg_text_element_impl<'input>(
 
             if let Some(href) = link_value {
                 if let Some(text) = resolve_tref_text(node.document(), href) {
-                    let text = trim_text(&text, space);
+                    let text = trim_text(&text, space, newlines);
                     doc.append(node_id, NodeKind::Text(text));
                 }
             }
         } else {
-            parse_svg_text_element_impl(node, node_id, style_sheet, space, doc)?;
+            parse_svg_text_element_impl(
+                node,
+                node_id,
+                style_sheets,
+                id_prefix,
+                space,
+                newlines,
+                wrapping,
+                doc,
+            )?;
         }
     }
 
@@ -136,6 +177,13 @@ enum XmlSpace {
 }
 
 fn get_xmlspace(doc: &Document, node_id: NodeId, default: XmlSpace) -> XmlSpace {
+    // `white-space` takes precedence over the deprecated `xml:space`.
+    match doc.get(node_id).attribute(AId::WhiteSpace) {
+        Some("pre") | Some("pre-wrap") | Some("break-spaces") => return XmlSpace::Preserve,
+        Some("normal") | Some("nowrap") | Some("pre-line") => return XmlSpace::Default,
+        _ => {}
+    }
+
     match doc.get(node_id).attribute(AId::Space) {
         Some("preserve") => XmlSpace::Preserve,
         Some(_) => XmlSpace::Default,
@@ -143,6 +191,30 @@ fn get_xmlspace(doc: &Document, node_id: NodeId, default: XmlSpace) -> XmlSpace
     }
 }
 
+/// Checks that `white-space` preserves newlines.
+fn get_newlines(doc: &Document, node_id: NodeId, default: bool) -> bool {
+    match doc.get(node_id).attribute(AId::WhiteSpace) {
+        Some("pre") | Some("pre-wrap") | Some("break-spaces") | Some("pre-line") => true,
+        Some(_) => false,
+        None => default,
+    }
+}
+
+/// Checks that the `text` element has `inline-size` or `shape-inside`.
+fn is_auto_wrapped(node: SvgNode) -> bool {
+    let has_inline_size = matches!(
+        node.attribute::<svgtypes::Length>(AId::InlineSize),
+        Some(v) if v.number > 0.0
+    );
+
+    let has_shape = matches!(
+        node.attribute::<&str>(AId::ShapeInside),
+        Some(v) if v.trim() != "none"
+    );
+
+    has_inline_size || has_shape
+}
+
 trait StrTrim {
     fn remove_first_space(&mut self);
     fn remove_last_space(&mut self);
@@ -342,20 +414,29 @@ fn collect_text_nodes(parent: SvgNode, depth: usize, nodes: &mut Vec<(NodeId, us
     }
 }
 
-fn trim_text(text: &str, space: XmlSpace) -> String {
+fn trim_text(text: &str, space: XmlSpace, newlines: bool) -> String {
     let mut s = String::with_capacity(text.len());
 
     let mut prev = '0';
     for c in text.chars() {
         // \r, \n and \t should be converted into spaces.
+        // Unless newlines are preserved.
         let c = match c {
+            '\n' if newlines => '\n',
             '\r' | '\n' | '\t' => ' ',
             _ => c,
         };
 
-        // Skip continuous spaces.
-        if space == XmlSpace::Default && c == ' ' && c == prev {
-            continue;
+        if space == XmlSpace::Default {
+            // Skip continuous spaces and spaces after a newline.
+            if c == ' ' && (prev == ' ' || prev == '\n') {
+                continue;
+            }
+
+            // Remove spaces before a newline.
+            if c == '\n' && prev == ' ' {
+                s.pop();
+            }
         }
 
         prev = c;
@@ -0,0 +1,166 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! SVG and CSS transforms parsing.
+
+use std::str::FromStr;
+
+use svgtypes::Length;
+use usvg_tree::Transform;
+
+/// A parsed `transform` value.
+///
+/// Unlike SVG transforms, CSS ones can have translations with units and percentages,
+/// therefore they cannot be converted into a matrix right away.
+#[derive(Clone, Debug)]
+pub struct TransformList(Vec<TransformFunction>);
+
+#[derive(Clone, Copy, Debug)]
+enum TransformFunction {
+    Matrix(Transform),
+    Translate(Length, Length),
+}
+
+impl TransformList {
+    /// Converts the list into a transform.
+    ///
+    /// `resolve_length` resolves translations. Its second argument is set for horizontal ones.
+    pub fn resolve(&self, resolve_length: impl Fn(Length, bool) -> f32) -> Transform {
+        let mut ts = Transform::default();
+        for function in &self.0 {
+            let function_ts = match *function {
+                TransformFunction::Matrix(ts) => ts,
+                TransformFunction::Translate(x, y) => {
+                    Transform::from_translate(resolve_length(x, true), resolve_length(y, false))
+                }
+            };
+
+            ts = ts.pre_concat(function_ts);
+        }
+
+        ts
+    }
+
+    /// Checks that the transform is invertible.
+    ///
+    /// Translations cannot affect this, so they are ignored.
+    pub fn is_valid(&self) -> bool {
+        self.resolve(|_, _| 0.0).is_valid()
+    }
+}
+
+/// Parses an SVG transform list, falling back to the CSS syntax.
+pub(crate) fn parse_transform(text: &str) -> Option<TransformList> {
+    if let Ok(ts) = svgtypes::Transform::from_str(text) {
+        let ts = Transform::from_row(
+            ts.a as f32,
+            ts.b as f32,
+            ts.c as f32,
+            ts.d as f32,
+            ts.e as f32,
+            ts.f as f32,
+        );
+        return Some(TransformList(vec![TransformFunction::Matrix(ts)]));
+    }
+
+    parse_css_transform(text)
+}
+
+fn parse_css_transform(text: &str) -> Option<TransformList> {
+    let text = text.trim();
+    if text == "none" {
+        return Some(TransformList(Vec::new()));
+    }
+
+    let mut list = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (name, tail) = rest.split_once('(')?;
+        let (args, tail) = tail.split_once(')')?;
+        rest = tail.trim_start();
+
+        let args: Vec<_> = args
+            .split(|c: char| c == ',' || c.is_ascii_whitespace())
+            .filter(|s| !s.is_empty())
+            .collect();
+        list.push(parse_function(name.trim(), &args)?);
+    }
+
+    if list.is_empty() {
+        return None;
+    }
+
+    Some(TransformList(list))
+}
+
+fn parse_function(name: &str, args: &[&str]) -> Option<TransformFunction> {
+    use TransformFunction::{Matrix, Translate};
+
+    let zero = Length::zero();
+    let function = match (name, args) {
+        ("matrix", [a, b, c, d, e, f]) => Matrix(Transform::from_row(
+            parse_number(a)?,
+            parse_number(b)?,
+            parse_number(c)?,
+            parse_number(d)?,
+            parse_number(e)?,
+            parse_number(f)?,
+        )),
+        ("translate", [x]) => Translate(parse_length(x)?, zero),
+        ("translate", [x, y]) => Translate(parse_length(x)?, parse_length(y)?),
+        ("translateX", [x]) => Translate(parse_length(x)?, zero),
+        ("translateY", [y]) => Translate(zero, parse_length(y)?),
+        ("scale", [s]) => {
+            let s = parse_scale(s)?;
+            Matrix(Transform::from_scale(s, s))
+        }
+        ("scale", [sx, sy]) => Matrix(Transform::from_scale(parse_scale(sx)?, parse_scale(sy)?)),
+        ("scaleX", [sx]) => Matrix(Transform::from_scale(parse_scale(sx)?, 1.0)),
+        ("scaleY", [sy]) => Matrix(Transform::from_scale(1.0, parse_scale(sy)?)),
+        ("rotate" | "rotateZ", [a]) => Matrix(Transform::from_rotate(parse_angle(a)?)),
+        ("skew", [ax]) => Matrix(skew(parse_angle(ax)?, 0.0)),
+        ("skew", [ax, ay]) => Matrix(skew(parse_angle(ax)?, parse_angle(ay)?)),
+        ("skewX", [ax]) => Matrix(skew(parse_angle(ax)?, 0.0)),
+        ("skewY", [ay]) => Matrix(skew(0.0, parse_angle(ay)?)),
+        _ => return None,
+    };
+
+    Some(function)
+}
+
+fn skew(ax: f32, ay: f32) -> Transform {
+    Transform::from_skew(ax.to_radians().tan(), ay.to_radians().tan())
+}
+
+fn parse_number(text: &str) -> Option<f32> {
+    svgtypes::Number::from_str(text).ok().map(|n| n.0 as f32)
+}
+
+fn parse_length(text: &str) -> Option<Length> {
+    Length::from_str(text).ok()
+}
+
+fn parse_scale(text: &str) -> Option<f32> {
+    match text.strip_suffix('%') {
+        Some(n) => parse_number(n).map(|n| n / 100.0),
+        None => parse_number(text),
+    }
+}
+
+/// Parses an angle in degrees.
+///
+/// Unlike CSS, we allow angles without units, like SVG does.
+fn parse_angle(text: &str) -> Option<f32> {
+    if let Some(n) = text.strip_suffix("deg") {
+        parse_number(n)
+    } else if let Some(n) = text.strip_suffix("grad") {
+        parse_number(n).map(|n| n * 0.9)
+    } else if let Some(n) = text.strip_suffix("rad") {
+        parse_number(n).map(f32::to_degrees)
+    } else if let Some(n) = text.strip_suffix("turn") {
+        parse_number(n).map(|n| n * 360.0)
+    } else {
+        parse_number(text)
+    }
+}
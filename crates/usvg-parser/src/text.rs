@@ -3,13 +3,14 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::rc::Rc;
+use std::sync::Arc;
 
 use kurbo::{ParamCurve, ParamCurveArclen};
 use svgtypes::{Length, LengthUnit};
 use usvg_tree::*;
 
 use crate::svgtree::{AId, EId, FromValue, SvgNode};
-use crate::{converter, style};
+use crate::{converter, style, units};
 
 impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::TextAnchor {
     fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
@@ -22,6 +23,32 @@ impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::TextAnchor {
     }
 }
 
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::TextAlign {
+    fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
+        match value {
+            "start" => Some(usvg_tree::TextAlign::Start),
+            "end" => Some(usvg_tree::TextAlign::End),
+            "left" => Some(usvg_tree::TextAlign::Left),
+            "right" => Some(usvg_tree::TextAlign::Right),
+            "center" => Some(usvg_tree::TextAlign::Center),
+            "justify" => Some(usvg_tree::TextAlign::Justify),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::TextOrientation {
+    fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
+        match value {
+            "mixed" => Some(usvg_tree::TextOrientation::Mixed),
+            "upright" => Some(usvg_tree::TextOrientation::Upright),
+            // `sideways-right` is from an older spec draft.
+            "sideways" | "sideways-right" => Some(usvg_tree::TextOrientation::Sideways),
+            _ => None,
+        }
+    }
+}
+
 impl<'a, 'input: 'a> FromValue<'a, 'input> for usvg_tree::AlignmentBaseline {
     fn parse(_: SvgNode, _: AId, value: &str) -> Option<Self> {
         match value {
@@ -110,6 +137,20 @@ pub(crate) fn convert(
 
     let chunks = collect_text_chunks(text_node, &pos_list, state, cache);
 
+    let svg_fonts = cache
+        .svg_fonts
+        .iter()
+        .filter(|font| {
+            chunks.iter().any(|chunk| {
+                chunk
+                    .spans
+                    .iter()
+                    .any(|span| crate::font::matches_families(font, &span.font.families))
+            })
+        })
+        .cloned()
+        .collect();
+
     let rendering_mode: TextRendering = text_node
         .find_attribute(AId::TextRendering)
         .unwrap_or(state.opt.text_rendering);
@@ -129,6 +170,8 @@ pub(crate) fn convert(
         rotate: rotate_list,
         writing_mode,
         chunks,
+        wrap: convert_wrap(text_node, state),
+        svg_fonts,
         bounding_box: None,
         flattened: None,
     };
@@ -268,10 +311,19 @@ fn collect_text_chunks_impl(
             paint_order,
             font,
             font_size,
-            small_caps: parent.find_attribute::<&str>(AId::FontVariant) == Some("small-caps"),
+            small_caps: find_font_variant(parent, AId::FontVariantCaps)
+                .split_whitespace()
+                .any(|name| name == "small-caps"),
             apply_kerning,
+            font_features: convert_font_features(parent),
+            font_variations: convert_font_variations(parent),
+            apply_optical_sizing: parent.find_attribute::<&str>(AId::FontOpticalSizing)
+                != Some("none"),
             decoration: resolve_decoration(parent, state, cache),
             visibility: parent.find_attribute(AId::Visibility).unwrap_or_default(),
+            pointer_events: parent
+                .find_attribute(AId::PointerEvents)
+                .unwrap_or_default(),
             dominant_baseline,
             alignment_baseline: parent
                 .find_attribute(AId::AlignmentBaseline)
@@ -281,6 +333,7 @@ fn collect_text_chunks_impl(
             word_spacing: parent.resolve_length(AId::WordSpacing, state, 0.0),
             text_length,
             length_adjust: parent.find_attribute(AId::LengthAdjust).unwrap_or_default(),
+            text_orientation: convert_text_orientation(parent),
         };
 
         let mut is_new_span = true;
@@ -344,17 +397,7 @@ fn collect_text_chunks_impl(
 
 fn resolve_text_flow(node: SvgNode, state: &converter::State) -> Option<TextFlow> {
     let linked_node = node.attribute::<SvgNode>(AId::Href)?;
-    let path = crate::shapes::convert(linked_node, state)?;
-
-    // The reference path's transform needs to be applied
-    let transform = linked_node.resolve_transform(AId::Transform, state);
-    let path = if !transform.is_identity() {
-        let mut path_copy = path.as_ref().clone();
-        path_copy = path_copy.transform(transform)?;
-        Rc::new(path_copy)
-    } else {
-        path
-    };
+    let path = convert_linked_shape(linked_node, state)?;
 
     let start_offset: Length = node.attribute(AId::StartOffset).unwrap_or_default();
     let start_offset = if start_offset.unit == LengthUnit::Percent {
@@ -374,6 +417,88 @@ fn resolve_text_flow(node: SvgNode, state: &converter::State) -> Option<TextFlow
     })))
 }
 
+fn convert_linked_shape(
+    linked_node: SvgNode,
+    state: &converter::State,
+) -> Option<Arc<tiny_skia_path::Path>> {
+    let path = crate::shapes::convert(linked_node, state)?;
+
+    // The reference path's transform needs to be applied
+    let transform = linked_node.resolve_transform(AId::Transform, state);
+    if !transform.is_identity() {
+        let mut path_copy = path.as_ref().clone();
+        path_copy = path_copy.transform(transform)?;
+        Some(Arc::new(path_copy))
+    } else {
+        Some(path)
+    }
+}
+
+fn convert_wrap(text_node: SvgNode, state: &converter::State) -> Option<TextWrap> {
+    // `shape-inside` has a higher priority than `inline-size`.
+    let area = if let Some(shape) = convert_wrap_shape(text_node, state) {
+        TextWrapArea::Shape(shape)
+    } else {
+        let length: Length = text_node.attribute(AId::InlineSize)?;
+        let inline_size = units::convert_user_length(length, text_node, AId::InlineSize, state);
+        TextWrapArea::InlineSize(NonZeroPositiveF32::new(inline_size)?)
+    };
+
+    let allow_wrap = !matches!(
+        text_node.find_attribute(AId::WhiteSpace),
+        Some("pre") | Some("nowrap")
+    );
+
+    Some(TextWrap {
+        area,
+        line_height: convert_line_height(text_node, state),
+        align: text_node.find_attribute(AId::TextAlign).unwrap_or_default(),
+        allow_wrap,
+    })
+}
+
+fn convert_wrap_shape(text_node: SvgNode, state: &converter::State) -> Option<TextWrapShape> {
+    let value: &str = text_node.attribute(AId::ShapeInside)?;
+    if value == "none" {
+        return None;
+    }
+
+    // Only references to shapes are supported. CSS basic shapes are not.
+    let linked_node = text_node.attribute::<SvgNode>(AId::ShapeInside)?;
+
+    let path = convert_linked_shape(linked_node, state)?;
+    Some(TextWrapShape {
+        id: linked_node.element_id().to_string(),
+        path,
+    })
+}
+
+fn convert_line_height(text_node: SvgNode, state: &converter::State) -> LineHeight {
+    let node = match text_node
+        .ancestors()
+        .find(|n| n.has_attribute(AId::LineHeight))
+    {
+        Some(v) => v,
+        None => return LineHeight::Normal,
+    };
+
+    match node.attribute::<Length>(AId::LineHeight) {
+        Some(length) if length.number < 0.0 => LineHeight::Normal,
+        Some(length) if length.unit == LengthUnit::None => LineHeight::Number(length.number as f32),
+        Some(length) if length.unit == LengthUnit::Percent => {
+            LineHeight::Number(length.number as f32 / 100.0)
+        }
+        Some(length) => LineHeight::Length(units::convert_user_length(
+            length,
+            node,
+            AId::LineHeight,
+            state,
+        )),
+        // `normal` and invalid values.
+        None => LineHeight::Normal,
+    }
+}
+
 fn convert_font(node: SvgNode, state: &converter::State) -> Font {
     let style: FontStyle = node.find_attribute(AId::FontStyle).unwrap_or_default();
     let stretch = conv_font_stretch(node);
@@ -428,13 +553,49 @@ fn conv_font_stretch(node: SvgNode) -> FontStretch {
             "wider" | "expanded" => FontStretch::Expanded,
             "extra-expanded" => FontStretch::ExtraExpanded,
             "ultra-expanded" => FontStretch::UltraExpanded,
-            _ => FontStretch::Normal,
+            value => parse_font_stretch_percentage(value)
+                .map(font_stretch_from_percentage)
+                .unwrap_or_default(),
         }
     } else {
         FontStretch::Normal
     }
 }
 
+fn parse_font_stretch_percentage(value: &str) -> Option<f32> {
+    let n: f32 = value.trim().strip_suffix('%')?.trim().parse().ok()?;
+    if n.is_finite() && n >= 0.0 {
+        Some(n)
+    } else {
+        None
+    }
+}
+
+/// Returns the closest keyword for a `font-stretch` percentage.
+///
+/// Used only for font matching.
+fn font_stretch_from_percentage(n: f32) -> FontStretch {
+    if n < 56.25 {
+        FontStretch::UltraCondensed
+    } else if n < 68.75 {
+        FontStretch::ExtraCondensed
+    } else if n < 81.25 {
+        FontStretch::Condensed
+    } else if n < 93.75 {
+        FontStretch::SemiCondensed
+    } else if n < 106.25 {
+        FontStretch::Normal
+    } else if n < 118.75 {
+        FontStretch::SemiExpanded
+    } else if n < 137.5 {
+        FontStretch::Expanded
+    } else if n < 175.0 {
+        FontStretch::ExtraExpanded
+    } else {
+        FontStretch::UltraExpanded
+    }
+}
+
 fn resolve_font_weight(node: SvgNode) -> u16 {
     fn bound(min: usize, val: usize, max: usize) -> usize {
         std::cmp::max(min, std::cmp::min(max, val))
@@ -447,15 +608,6 @@ fn resolve_font_weight(node: SvgNode) -> u16 {
         weight = match n.attribute(AId::FontWeight).unwrap_or("") {
             "normal" => 400,
             "bold" => 700,
-            "100" => 100,
-            "200" => 200,
-            "300" => 300,
-            "400" => 400,
-            "500" => 500,
-            "600" => 600,
-            "700" => 700,
-            "800" => 800,
-            "900" => 900,
             "bolder" => {
                 // By the CSS2 spec the default value should be 400
                 // so `bolder` will result in 500.
@@ -464,7 +616,12 @@ fn resolve_font_weight(node: SvgNode) -> u16 {
                 // we will follow such behavior for now.
                 let step = if weight == 400 { 300 } else { 100 };
 
-                bound(100, weight + step, 900)
+                // Weights outside of the 100..=900 range should not be affected.
+                if weight >= 900 {
+                    weight
+                } else {
+                    bound(100, weight + step, 900)
+                }
             }
             "lighter" => {
                 // By the CSS2 spec the default value should be 400
@@ -474,9 +631,17 @@ fn resolve_font_weight(node: SvgNode) -> u16 {
                 // we will follow such behavior for now.
                 let step = if weight == 400 { 200 } else { 100 };
 
-                bound(100, weight - step, 900)
+                if weight <= 100 {
+                    weight
+                } else {
+                    bound(100, weight - step, 900)
+                }
             }
-            _ => weight,
+            value => match value.parse::<f32>() {
+                // CSS Fonts 4 allows any number in a 1..=1000 range.
+                Ok(n) if (1.0..=1000.0).contains(&n) => n.round() as usize,
+                _ => weight,
+            },
         };
     }
 
@@ -762,6 +927,224 @@ fn convert_writing_mode(text_node: SvgNode) -> WritingMode {
     }
 }
 
+fn convert_text_orientation(node: SvgNode) -> TextOrientation {
+    let n = match node.ancestors().find(|n| {
+        n.has_attribute(AId::TextOrientation) || n.has_attribute(AId::GlyphOrientationVertical)
+    }) {
+        Some(v) => v,
+        None => return TextOrientation::default(),
+    };
+
+    if let Some(orientation) = n.attribute(AId::TextOrientation) {
+        return orientation;
+    }
+
+    // The deprecated SVG 1.1 property is mapped according to
+    // https://www.w3.org/TR/css-writing-modes-3/#glyph-orientation
+    match n.attribute(AId::GlyphOrientationVertical) {
+        Some("0") | Some("0deg") => TextOrientation::Upright,
+        Some("90") | Some("90deg") => TextOrientation::Sideways,
+        _ => TextOrientation::Mixed,
+    }
+}
+
+fn convert_font_features(node: SvgNode) -> Vec<FontFeature> {
+    let mut features = Vec::new();
+
+    for aid in [
+        AId::FontVariantLigatures,
+        AId::FontVariantCaps,
+        AId::FontVariantNumeric,
+        AId::FontVariantEastAsian,
+        AId::FontVariantPosition,
+    ] {
+        for name in find_font_variant(node, aid).split_whitespace() {
+            for (tag, value) in font_variant_features(aid, name) {
+                features.push(FontFeature {
+                    tag: **tag,
+                    value: *value,
+                });
+            }
+        }
+    }
+
+    // `font-feature-settings` are applied last and can override `font-variant-*`.
+    if let Some(value) = node.find_attribute::<&str>(AId::FontFeatureSettings) {
+        match parse_font_feature_settings(value) {
+            Some(list) => features.extend(list),
+            None => log::warn!("Failed to parse font-feature-settings value: '{}'.", value),
+        }
+    }
+
+    features
+}
+
+/// Returns the nearest `font-variant-*` value.
+///
+/// Since the `font-variant` shorthand resets all longhands,
+/// it's returned as is and the caller should ignore keywords it doesn't know.
+fn find_font_variant<'a>(node: SvgNode<'a, '_>, aid: AId) -> &'a str {
+    for n in node.ancestors() {
+        if let Some(value) = n.attribute(aid) {
+            return value;
+        }
+
+        if let Some(value) = n.attribute(AId::FontVariant) {
+            return value;
+        }
+    }
+
+    "normal"
+}
+
+/// Maps a `font-variant-*` keyword to OpenType features.
+///
+/// `small-caps` is handled separately via `TextSpan::small_caps`.
+fn font_variant_features(aid: AId, name: &str) -> &'static [(&'static [u8; 4], u32)] {
+    match (aid, name) {
+        (AId::FontVariantLigatures, "none") => &[
+            (b"liga", 0),
+            (b"clig", 0),
+            (b"dlig", 0),
+            (b"hlig", 0),
+            (b"calt", 0),
+        ],
+        (AId::FontVariantLigatures, "common-ligatures") => &[(b"liga", 1), (b"clig", 1)],
+        (AId::FontVariantLigatures, "no-common-ligatures") => &[(b"liga", 0), (b"clig", 0)],
+        (AId::FontVariantLigatures, "discretionary-ligatures") => &[(b"dlig", 1)],
+        (AId::FontVariantLigatures, "no-discretionary-ligatures") => &[(b"dlig", 0)],
+        (AId::FontVariantLigatures, "historical-ligatures") => &[(b"hlig", 1)],
+        (AId::FontVariantLigatures, "no-historical-ligatures") => &[(b"hlig", 0)],
+        (AId::FontVariantLigatures, "contextual") => &[(b"calt", 1)],
+        (AId::FontVariantLigatures, "no-contextual") => &[(b"calt", 0)],
+
+        (AId::FontVariantCaps, "all-small-caps") => &[(b"smcp", 1), (b"c2sc", 1)],
+        (AId::FontVariantCaps, "petite-caps") => &[(b"pcap", 1)],
+        (AId::FontVariantCaps, "all-petite-caps") => &[(b"pcap", 1), (b"c2pc", 1)],
+        (AId::FontVariantCaps, "unicase") => &[(b"unic", 1)],
+        (AId::FontVariantCaps, "titling-caps") => &[(b"titl", 1)],
+
+        (AId::FontVariantNumeric, "lining-nums") => &[(b"lnum", 1)],
+        (AId::FontVariantNumeric, "oldstyle-nums") => &[(b"onum", 1)],
+        (AId::FontVariantNumeric, "proportional-nums") => &[(b"pnum", 1)],
+        (AId::FontVariantNumeric, "tabular-nums") => &[(b"tnum", 1)],
+        (AId::FontVariantNumeric, "diagonal-fractions") => &[(b"frac", 1)],
+        (AId::FontVariantNumeric, "stacked-fractions") => &[(b"afrc", 1)],
+        (AId::FontVariantNumeric, "ordinal") => &[(b"ordn", 1)],
+        (AId::FontVariantNumeric, "slashed-zero") => &[(b"zero", 1)],
+
+        (AId::FontVariantEastAsian, "jis78") => &[(b"jp78", 1)],
+        (AId::FontVariantEastAsian, "jis83") => &[(b"jp83", 1)],
+        (AId::FontVariantEastAsian, "jis90") => &[(b"jp90", 1)],
+        (AId::FontVariantEastAsian, "jis04") => &[(b"jp04", 1)],
+        (AId::FontVariantEastAsian, "simplified") => &[(b"smpl", 1)],
+        (AId::FontVariantEastAsian, "traditional") => &[(b"trad", 1)],
+        (AId::FontVariantEastAsian, "full-width") => &[(b"fwid", 1)],
+        (AId::FontVariantEastAsian, "proportional-width") => &[(b"pwid", 1)],
+        (AId::FontVariantEastAsian, "ruby") => &[(b"ruby", 1)],
+
+        (AId::FontVariantPosition, "sub") => &[(b"subs", 1)],
+        (AId::FontVariantPosition, "super") => &[(b"sups", 1)],
+
+        _ => &[],
+    }
+}
+
+/// Parses a `font-feature-settings` value, like `"liga" 0, "tnum"`.
+///
+/// Returns `None` when any of the features is invalid,
+/// since in this case the whole property should be ignored.
+fn parse_font_feature_settings(value: &str) -> Option<Vec<FontFeature>> {
+    parse_font_tag_list(value, |value| match value {
+        "" | "on" => Some(1),
+        "off" => Some(0),
+        n => n.parse().ok(),
+    })
+    .map(|list| {
+        list.into_iter()
+            .map(|(tag, value)| FontFeature { tag, value })
+            .collect()
+    })
+}
+
+/// Parses a `font-variation-settings` value, like `"wght" 650, "wdth" 80.5`.
+///
+/// Returns `None` when any of the axes is invalid,
+/// since in this case the whole property should be ignored.
+fn parse_font_variation_settings(value: &str) -> Option<Vec<FontVariation>> {
+    parse_font_tag_list(value, |value| {
+        value.parse::<f32>().ok().filter(|n| n.is_finite())
+    })
+    .map(|list| {
+        list.into_iter()
+            .map(|(tag, value)| FontVariation { tag, value })
+            .collect()
+    })
+}
+
+/// Parses a comma-separated list of quoted OpenType tags followed by optional values.
+///
+/// `normal` produces an empty list.
+fn parse_font_tag_list<T>(
+    value: &str,
+    parse_value: impl Fn(&str) -> Option<T>,
+) -> Option<Vec<([u8; 4], T)>> {
+    let mut list = Vec::new();
+    if value.trim() == "normal" {
+        return Some(list);
+    }
+
+    for item in value.split(',') {
+        let item = item.trim();
+
+        let quote = item.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+
+        let end = item[1..].find(quote)? + 1;
+        let tag = &item.as_bytes()[1..end];
+        if tag.len() != 4 || !tag.iter().all(|c| (0x20..=0x7E).contains(c)) {
+            return None;
+        }
+
+        let value = parse_value(item[end + 1..].trim())?;
+        list.push(([tag[0], tag[1], tag[2], tag[3]], value));
+    }
+
+    Some(list)
+}
+
+fn convert_font_variations(node: SvgNode) -> Vec<FontVariation> {
+    let mut variations = Vec::new();
+
+    // Keywords are mapped onto the `wdth` axis by the text layout,
+    // but percentages must be preserved as is.
+    if let Some(n) = node.ancestors().find(|n| n.has_attribute(AId::FontStretch)) {
+        if let Some(value) = n
+            .attribute(AId::FontStretch)
+            .and_then(parse_font_stretch_percentage)
+        {
+            variations.push(FontVariation {
+                tag: *b"wdth",
+                value,
+            });
+        }
+    }
+
+    if let Some(value) = node.find_attribute::<&str>(AId::FontVariationSettings) {
+        match parse_font_variation_settings(value) {
+            Some(list) => variations.extend(list),
+            None => log::warn!(
+                "Failed to parse font-variation-settings value: '{}'.",
+                value
+            ),
+        }
+    }
+
+    variations
+}
+
 fn path_length(path: &tiny_skia_path::Path) -> f64 {
     let mut prev_mx = path.points()[0].x;
     let mut prev_my = path.points()[0].y;
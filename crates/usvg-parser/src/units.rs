@@ -37,6 +37,7 @@ pub(crate) fn convert_length(
                     AId::Cx
                     | AId::Dx
                     | AId::Fx
+                    | AId::InlineSize
                     | AId::MarkerWidth
                     | AId::RefX
                     | AId::Rx
@@ -2,7 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 
 use svgtypes::{Length, LengthUnit};
 use usvg_tree::{
@@ -12,6 +15,240 @@ use usvg_tree::{
 
 use crate::converter;
 use crate::svgtree::{AId, EId, SvgNode};
+use crate::Options;
+
+/// A shorthand for [UseHrefResolver]'s string function.
+pub type UseHrefStringResolverFn = Arc<dyn Fn(&str, &Options) -> Option<Vec<u8>> + Send + Sync>;
+
+/// An `xlink:href` resolver for `use` elements referencing external SVG files.
+///
+/// For example, `<use xlink:href="icons.svg#star"/>`.
+///
+/// This type can be useful if you want to have an alternative `xlink:href` handling
+/// to the default one. For example, you can forbid access to local files (which is allowed by default)
+/// or load sprite sheets from memory.
+///
+/// Resolvers are reference-counted, therefore cloning is cheap.
+#[derive(Clone)]
+pub struct UseHrefResolver {
+    /// Resolver function that will be used to load an external SVG document.
+    ///
+    /// A function would be called with a link without a fragment identifier
+    /// (`icons.svg` for `icons.svg#star`) and parsing options.
+    /// Links inside external documents are relative to the document itself,
+    /// therefore `sprite.svg` referenced by `icons/main.svg` is passed as `icons/sprite.svg`.
+    ///
+    /// It should return an SVG or SVGZ data.
+    pub resolve_string: UseHrefStringResolverFn,
+
+    /// Loaded documents.
+    ///
+    /// Each document is requested only once, even across multiple parses.
+    /// Shared by all clones of the resolver.
+    pub cache: UseHrefCache,
+}
+
+impl Default for UseHrefResolver {
+    fn default() -> Self {
+        UseHrefResolver {
+            resolve_string: UseHrefResolver::default_string_resolver(),
+            cache: UseHrefCache::default(),
+        }
+    }
+}
+
+impl UseHrefResolver {
+    /// Creates a default string resolver.
+    ///
+    /// The default implementation treats an input string as a file path and tries to open.
+    /// If a string is an URL or something else it would be ignored.
+    ///
+    /// Paths have to be absolute or relative to the input SVG file or relative to
+    /// [Options::resources_dir](crate::Options::resources_dir).
+    pub fn default_string_resolver() -> UseHrefStringResolverFn {
+        Arc::new(move |href: &str, opts: &Options| {
+            let path = opts.get_abs_path(std::path::Path::new(href));
+
+            if path.exists() {
+                match std::fs::read(&path) {
+                    Ok(data) => Some(data),
+                    Err(_) => {
+                        log::warn!("Failed to load '{}'. Skipped.", href);
+                        None
+                    }
+                }
+            } else {
+                log::warn!("'{}' is not a path to an SVG file.", href);
+                None
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for UseHrefResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("UseHrefResolver { .. }")
+    }
+}
+
+/// A cache of external SVG documents loaded by [UseHrefResolver].
+///
+/// Documents are stored per URL and [Options::resources_dir](crate::Options::resources_dir).
+/// Failed loads are not cached.
+///
+/// The cache is reference-counted, therefore clones share the same storage.
+#[derive(Clone, Default)]
+pub struct UseHrefCache {
+    documents: Arc<Mutex<HashMap<CacheKey, ExternalDocument>>>,
+}
+
+impl UseHrefCache {
+    /// Removes all loaded documents.
+    ///
+    /// Should be called when external files were changed.
+    pub fn clear(&self) {
+        if let Ok(mut documents) = self.documents.lock() {
+            documents.clear();
+        }
+    }
+
+    fn get(&self, url: &str, opt: &Options) -> Option<ExternalDocument> {
+        let documents = self.documents.lock().ok()?;
+        documents
+            .get(&(opt.resources_dir.clone(), url.to_string()))
+            .cloned()
+    }
+
+    fn insert(&self, url: &str, opt: &Options, document: ExternalDocument) {
+        if let Ok(mut documents) = self.documents.lock() {
+            documents.insert((opt.resources_dir.clone(), url.to_string()), document);
+        }
+    }
+}
+
+impl std::fmt::Debug for UseHrefCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("UseHrefCache { .. }")
+    }
+}
+
+/// A document URL and the resources directory it was resolved with.
+type CacheKey = (Option<PathBuf>, String);
+
+#[derive(Clone)]
+struct ExternalDocument {
+    text: Arc<str>,
+    /// External documents referenced by this one.
+    links: Arc<[String]>,
+}
+
+/// Loads all external SVG documents referenced by `use` elements, recursively.
+///
+/// Returns a list of URLs and SVG strings.
+pub(crate) fn load_external_documents(
+    xml: &roxmltree::Document,
+    opt: &Options,
+) -> Vec<(String, Arc<str>)> {
+    let mut documents = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue = collect_external_links(xml, "");
+
+    while let Some(url) = queue.pop() {
+        if !visited.insert(url.clone()) {
+            continue;
+        }
+
+        let cache = &opt.use_href_resolver.cache;
+        let document = match cache.get(&url, opt) {
+            Some(document) => document,
+            None => match load_external_document(&url, opt) {
+                Some(document) => {
+                    cache.insert(&url, opt, document.clone());
+                    document
+                }
+                None => continue,
+            },
+        };
+
+        queue.extend(document.links.iter().cloned());
+        documents.push((url, document.text));
+    }
+
+    documents
+}
+
+fn load_external_document(url: &str, opt: &Options) -> Option<ExternalDocument> {
+    let data = (opt.use_href_resolver.resolve_string)(url, opt)?;
+
+    let data = if data.starts_with(&[0x1f, 0x8b]) {
+        match crate::decompress_svgz(&data) {
+            Ok(data) => data,
+            Err(_) => {
+                log::warn!("Failed to decompress '{}'. Skipped.", url);
+                return None;
+            }
+        }
+    } else {
+        data
+    };
+
+    let text = match String::from_utf8(data) {
+        Ok(text) => text,
+        Err(_) => {
+            log::warn!("'{}' is not an UTF-8 string. Skipped.", url);
+            return None;
+        }
+    };
+
+    let xml_opt = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+
+    // Only nested links are needed here. They are cached together with the text,
+    // therefore cached documents are parsed only by `svgtree`.
+    let links = match roxmltree::Document::parse_with_options(&text, xml_opt) {
+        Ok(xml) => collect_external_links(&xml, url),
+        Err(e) => {
+            log::warn!("Failed to parse '{}' cause {}.", url, e);
+            return None;
+        }
+    };
+
+    Some(ExternalDocument {
+        text: Arc::from(text),
+        links: Arc::from(links),
+    })
+}
+
+/// Collects links to external documents, resolved relative to the `base` document URL.
+fn collect_external_links(xml: &roxmltree::Document, base: &str) -> Vec<String> {
+    const SVG_NS: &str = "http://www.w3.org/2000/svg";
+    const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+
+    let mut links = Vec::new();
+    for node in xml
+        .descendants()
+        .filter(|n| n.has_tag_name((SVG_NS, "use")))
+    {
+        let link = node
+            .attribute((XLINK_NS, "href"))
+            .or_else(|| node.attribute("href"));
+
+        if let Some((url, _)) = link.and_then(crate::svgtree::split_external_href) {
+            if url.is_empty() {
+                continue;
+            }
+
+            let url = crate::svgtree::resolve_external_url(base, url);
+            if !links.contains(&url) {
+                links.push(url);
+            }
+        }
+    }
+
+    links
+}
 
 pub(crate) fn convert(
     node: SvgNode,
@@ -28,6 +265,14 @@ pub(crate) fn convert(
         return None;
     }
 
+    // The referenced content can use the `use` element paint
+    // via `context-fill` and `context-stroke`.
+    let mut state = state.clone();
+    state.context_element = Some(crate::style::resolve_context_element(
+        node, true, &state, cache,
+    ));
+    let state = &state;
+
     // We require an original transformation to setup 'clipPath'.
     let mut orig_ts = node.resolve_transform(AId::Transform, state);
     let mut new_ts = Transform::default();
@@ -206,7 +451,7 @@ fn clip_element(
 
     let clip_path = usvg_tree::ClipPath::default();
 
-    let mut path = Path::new(Rc::new(tiny_skia_path::PathBuilder::from_rect(
+    let mut path = Path::new(Arc::new(tiny_skia_path::PathBuilder::from_rect(
         clip_rect.to_rect(),
     )));
     path.fill = Some(usvg_tree::Fill::default());
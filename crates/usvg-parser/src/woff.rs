@@ -0,0 +1,681 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! WOFF and WOFF2 fonts decoding.
+//!
+//! Both formats are simply converted back into TrueType/OpenType fonts,
+//! which can be then loaded via `fontdb`.
+
+use std::convert::TryFrom;
+use std::io::Read;
+
+type Tag = [u8; 4];
+
+/// The maximum size of a decoded font.
+///
+/// Table lengths are stored in the font itself and cannot be trusted,
+/// therefore we have to limit the amount of memory we would allocate.
+const MAX_FONT_SIZE: usize = 256 * 1024 * 1024;
+
+/// The maximum number of bytes preallocated for a decompressed table.
+const MAX_PREALLOCATION: usize = 1024 * 1024;
+
+/// Converts a WOFF or WOFF2 font into a TrueType/OpenType one.
+///
+/// TrueType/OpenType fonts will be returned as is.
+pub(crate) fn decode(data: &[u8]) -> Option<Vec<u8>> {
+    match data.get(0..4)? {
+        b"wOFF" => decode_woff(data),
+        b"wOF2" => decode_woff2(data),
+        [0x00, 0x01, 0x00, 0x00] | b"OTTO" | b"true" | b"ttcf" => Some(data.to_vec()),
+        _ => None,
+    }
+}
+
+fn decode_woff(data: &[u8]) -> Option<Vec<u8>> {
+    let mut s = Stream::new(data);
+    s.skip(4); // signature
+    let flavor = s.read_u32()?;
+    s.skip(4); // length
+    let num_tables = s.read_u16()?;
+    s.skip(2 + 4 + 2 + 2 + 4 * 5); // reserved, totalSfntSize, version, metadata and private data
+
+    let mut tables = Vec::with_capacity(usize::from(num_tables));
+    let mut total_length = 0usize;
+    for _ in 0..num_tables {
+        let tag = s.read_tag()?;
+        let offset = s.read_u32()? as usize;
+        let comp_length = s.read_u32()? as usize;
+        let orig_length = s.read_u32()? as usize;
+        s.skip(4); // origChecksum
+
+        total_length = total_length.checked_add(orig_length)?;
+        if total_length > MAX_FONT_SIZE {
+            return None;
+        }
+
+        let table_data = data.get(offset..offset.checked_add(comp_length)?)?;
+        let table_data = if comp_length < orig_length {
+            // Do not decompress more than declared.
+            let mut decoder = flate2::read::ZlibDecoder::new(table_data).take(orig_length as u64);
+            let mut decoded = Vec::with_capacity(orig_length.min(MAX_PREALLOCATION));
+            decoder.read_to_end(&mut decoded).ok()?;
+            decoded
+        } else {
+            table_data.to_vec()
+        };
+
+        if table_data.len() != orig_length {
+            return None;
+        }
+
+        tables.push((tag, table_data));
+    }
+
+    build_sfnt(flavor, tables)
+}
+
+/// Tags of tables with a known index in the WOFF2 table directory.
+const KNOWN_TAGS: &[&Tag; 63] = &[
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+struct Woff2Table {
+    tag: Tag,
+    transform_version: u8,
+    orig_length: usize,
+    transform_length: usize,
+}
+
+impl Woff2Table {
+    fn is_transformed(&self) -> bool {
+        if &self.tag == b"glyf" || &self.tag == b"loca" {
+            // The null transform is 3 for `glyf` and `loca`.
+            self.transform_version != 3
+        } else {
+            self.transform_version != 0
+        }
+    }
+}
+
+fn decode_woff2(data: &[u8]) -> Option<Vec<u8>> {
+    let mut s = Stream::new(data);
+    s.skip(4); // signature
+    let flavor = s.read_u32()?;
+    s.skip(4); // length
+    let num_tables = s.read_u16()?;
+    s.skip(2 + 4); // reserved, totalSfntSize
+    let total_compressed_size = s.read_u32()? as usize;
+    s.skip(2 + 2 + 4 * 5); // version, metadata and private data
+
+    if flavor == u32::from_be_bytes(*b"ttcf") {
+        log::warn!("WOFF2 font collections are not supported.");
+        return None;
+    }
+
+    let mut tables = Vec::with_capacity(usize::from(num_tables));
+    for _ in 0..num_tables {
+        let flags = s.read_u8()?;
+        let tag = match flags & 0x3F {
+            63 => s.read_tag()?,
+            idx => *KNOWN_TAGS[usize::from(idx)],
+        };
+
+        let mut table = Woff2Table {
+            tag,
+            transform_version: flags >> 6,
+            orig_length: s.read_uint_base128()? as usize,
+            transform_length: 0,
+        };
+
+        table.transform_length = if table.is_transformed() {
+            s.read_uint_base128()? as usize
+        } else {
+            table.orig_length
+        };
+
+        tables.push(table);
+    }
+
+    let decompressed_size = tables
+        .iter()
+        .try_fold(0usize, |sum, table| sum.checked_add(table.transform_length))?;
+    if decompressed_size > MAX_FONT_SIZE {
+        return None;
+    }
+
+    let compressed = data.get(s.offset..s.offset.checked_add(total_compressed_size)?)?;
+    let mut decompressed = Vec::with_capacity(decompressed_size.min(MAX_PREALLOCATION));
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        .take(decompressed_size as u64)
+        .read_to_end(&mut decompressed)
+        .ok()?;
+
+    let mut tables_data = Vec::with_capacity(tables.len());
+    let mut offset = 0usize;
+    for table in &tables {
+        let end = offset.checked_add(table.transform_length)?;
+        tables_data.push(decompressed.get(offset..end)?);
+        offset = end;
+    }
+
+    let find_table = |tag: &Tag| tables.iter().position(|t| &t.tag == tag);
+
+    let mut glyf_loca = None;
+    if let Some(idx) = find_table(b"glyf") {
+        if tables[idx].is_transformed() {
+            glyf_loca = Some(reconstruct_glyf(tables_data[idx])?);
+        }
+    }
+
+    let mut hmtx = None;
+    if let Some(idx) = find_table(b"hmtx") {
+        if tables[idx].is_transformed() {
+            let (_, _, x_mins) = glyf_loca.as_ref()?;
+            let hhea = tables_data[find_table(b"hhea")?];
+            let maxp = tables_data[find_table(b"maxp")?];
+            let num_h_metrics = Stream::new(hhea.get(34..)?).read_u16()?;
+            let num_glyphs = Stream::new(maxp.get(4..)?).read_u16()?;
+            hmtx = Some(reconstruct_hmtx(
+                tables_data[idx],
+                num_h_metrics,
+                num_glyphs,
+                x_mins,
+            )?);
+        }
+    }
+
+    let mut sfnt_tables = Vec::with_capacity(tables.len());
+    for (table, table_data) in tables.iter().zip(tables_data) {
+        let table_data = match &table.tag {
+            b"glyf" if glyf_loca.is_some() => glyf_loca.as_ref()?.0.clone(),
+            b"loca" if glyf_loca.is_some() => glyf_loca.as_ref()?.1.clone(),
+            b"hmtx" if hmtx.is_some() => hmtx.take()?,
+            _ => table_data.to_vec(),
+        };
+
+        sfnt_tables.push((table.tag, table_data));
+    }
+
+    build_sfnt(flavor, sfnt_tables)
+}
+
+/// Reconstructs `glyf` and `loca` tables from a transformed `glyf` table.
+///
+/// Also returns glyphs `xMin`, which are needed by the `hmtx` reconstruction.
+fn reconstruct_glyf(data: &[u8]) -> Option<(Vec<u8>, Vec<u8>, Vec<i16>)> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+    const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+    let mut s = Stream::new(data);
+    // Overlap bitmap, enabled by `optionFlags`, is not needed for rendering.
+    s.skip(2 + 2); // reserved, optionFlags
+    let num_glyphs = s.read_u16()?;
+    let index_format = s.read_u16()?;
+
+    let mut streams = Vec::with_capacity(7);
+    let mut offset = 36usize;
+    for _ in 0..7 {
+        let size = s.read_u32()? as usize;
+        let end = offset.checked_add(size)?;
+        streams.push(Stream::new(data.get(offset..end)?));
+        offset = end;
+    }
+
+    let mut instructions = streams.pop()?;
+    let mut bbox_stream = streams.pop()?;
+    let mut composites = streams.pop()?;
+    let mut glyphs = streams.pop()?;
+    let mut flags = streams.pop()?;
+    let mut n_points = streams.pop()?;
+    let mut n_contours = streams.pop()?;
+
+    let bitmap_len = ((usize::from(num_glyphs) + 31) >> 5) << 2;
+    let bbox_bitmap = bbox_stream.read_bytes(bitmap_len)?;
+    let has_bbox = |idx: usize| bbox_bitmap[idx >> 3] & (0x80 >> (idx & 7)) != 0;
+
+    let mut glyf = Vec::new();
+    let mut loca = Vec::with_capacity(usize::from(num_glyphs) + 1);
+    let mut x_mins = Vec::with_capacity(usize::from(num_glyphs));
+    for idx in 0..usize::from(num_glyphs) {
+        loca.push(glyf.len());
+
+        let contours_count = n_contours.read_i16()?;
+        if contours_count == 0 {
+            x_mins.push(0);
+            continue;
+        }
+
+        let mut glyph = Vec::new();
+        if contours_count > 0 {
+            let mut end_points = Vec::with_capacity(contours_count as usize);
+            let mut points_count = 0usize;
+            for _ in 0..contours_count {
+                points_count += usize::from(n_points.read_255_u16()?);
+                end_points.push(u16::try_from(points_count.checked_sub(1)?).ok()?);
+            }
+
+            // Each point has a flag.
+            if points_count > flags.remaining() {
+                return None;
+            }
+
+            let mut points = Vec::with_capacity(points_count);
+            let (mut x, mut y) = (0i32, 0i32);
+            for _ in 0..points_count {
+                let flag = flags.read_u8()?;
+                let (dx, dy) = decode_triplet(flag & 0x7F, &mut glyphs)?;
+                x = x.checked_add(dx)?;
+                y = y.checked_add(dy)?;
+                points.push((flag & 0x80 == 0, x, y));
+            }
+
+            let instructions_len = glyphs.read_255_u16()?;
+            let instructions_data = instructions.read_bytes(usize::from(instructions_len))?;
+
+            let bbox = if has_bbox(idx) {
+                bbox_stream.read_bytes(8)?.to_vec()
+            } else {
+                let x_min = points.iter().map(|p| p.1).min()?;
+                let y_min = points.iter().map(|p| p.2).min()?;
+                let x_max = points.iter().map(|p| p.1).max()?;
+                let y_max = points.iter().map(|p| p.2).max()?;
+                let mut bbox = Vec::with_capacity(8);
+                for v in [x_min, y_min, x_max, y_max] {
+                    bbox.extend_from_slice(&i16::try_from(v).ok()?.to_be_bytes());
+                }
+                bbox
+            };
+
+            glyph.extend_from_slice(&contours_count.to_be_bytes());
+            glyph.extend_from_slice(&bbox);
+            for end_point in end_points {
+                glyph.extend_from_slice(&end_point.to_be_bytes());
+            }
+            glyph.extend_from_slice(&instructions_len.to_be_bytes());
+            glyph.extend_from_slice(instructions_data);
+
+            write_glyph_points(&points, &mut glyph)?;
+        } else {
+            // Composite glyphs must have an explicit bbox.
+            if !has_bbox(idx) {
+                return None;
+            }
+
+            glyph.extend_from_slice(&contours_count.to_be_bytes());
+            glyph.extend_from_slice(bbox_stream.read_bytes(8)?);
+
+            let mut have_instructions = false;
+            loop {
+                let component_flags = composites.read_u16()?;
+                let mut len = 2; // glyphIndex
+                len += if component_flags & ARG_1_AND_2_ARE_WORDS != 0 {
+                    4
+                } else {
+                    2
+                };
+
+                if component_flags & WE_HAVE_A_SCALE != 0 {
+                    len += 2;
+                } else if component_flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                    len += 4;
+                } else if component_flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                    len += 8;
+                }
+
+                have_instructions |= component_flags & WE_HAVE_INSTRUCTIONS != 0;
+
+                glyph.extend_from_slice(&component_flags.to_be_bytes());
+                glyph.extend_from_slice(composites.read_bytes(len)?);
+
+                if component_flags & MORE_COMPONENTS == 0 {
+                    break;
+                }
+            }
+
+            if have_instructions {
+                let instructions_len = glyphs.read_255_u16()?;
+                glyph.extend_from_slice(&instructions_len.to_be_bytes());
+                glyph.extend_from_slice(instructions.read_bytes(usize::from(instructions_len))?);
+            }
+        }
+
+        x_mins.push(i16::from_be_bytes([glyph[2], glyph[3]]));
+
+        glyf.extend_from_slice(&glyph);
+        // Keep glyphs 4-byte aligned, which is also suitable for the short `loca` format.
+        while glyf.len() % 4 != 0 {
+            glyf.push(0);
+        }
+    }
+    loca.push(glyf.len());
+
+    let mut loca_data = Vec::new();
+    for offset in loca {
+        if index_format == 0 {
+            loca_data.extend_from_slice(&u16::try_from(offset / 2).ok()?.to_be_bytes());
+        } else {
+            loca_data.extend_from_slice(&u32::try_from(offset).ok()?.to_be_bytes());
+        }
+    }
+
+    Some((glyf, loca_data, x_mins))
+}
+
+/// Writes simple glyph flags and coordinates using the most compact encoding.
+fn write_glyph_points(points: &[(bool, i32, i32)], glyph: &mut Vec<u8>) -> Option<()> {
+    const ON_CURVE_POINT: u8 = 0x01;
+    const X_SHORT_VECTOR: u8 = 0x02;
+    const Y_SHORT_VECTOR: u8 = 0x04;
+    const REPEAT_FLAG: u8 = 0x08;
+    const X_IS_SAME_OR_POSITIVE: u8 = 0x10;
+    const Y_IS_SAME_OR_POSITIVE: u8 = 0x20;
+
+    fn encode_delta(delta: i32, short_flag: u8, same_flag: u8, coords: &mut Vec<u8>) -> Option<u8> {
+        if delta == 0 {
+            Some(same_flag)
+        } else if delta.unsigned_abs() < 256 {
+            coords.push(delta.unsigned_abs() as u8);
+            Some(if delta > 0 {
+                short_flag | same_flag
+            } else {
+                short_flag
+            })
+        } else {
+            coords.extend_from_slice(&i16::try_from(delta).ok()?.to_be_bytes());
+            Some(0)
+        }
+    }
+
+    let mut flags = Vec::with_capacity(points.len());
+    let mut x_coords = Vec::new();
+    let mut y_coords = Vec::new();
+    let (mut prev_x, mut prev_y) = (0, 0);
+    for &(on_curve, x, y) in points {
+        let mut flag = if on_curve { ON_CURVE_POINT } else { 0 };
+        flag |= encode_delta(
+            x.checked_sub(prev_x)?,
+            X_SHORT_VECTOR,
+            X_IS_SAME_OR_POSITIVE,
+            &mut x_coords,
+        )?;
+        flag |= encode_delta(
+            y.checked_sub(prev_y)?,
+            Y_SHORT_VECTOR,
+            Y_IS_SAME_OR_POSITIVE,
+            &mut y_coords,
+        )?;
+        flags.push(flag);
+        prev_x = x;
+        prev_y = y;
+    }
+
+    let mut i = 0;
+    while i < flags.len() {
+        let flag = flags[i];
+        let repeats = flags[i + 1..]
+            .iter()
+            .take(255)
+            .take_while(|f| **f == flag)
+            .count();
+        if repeats > 0 {
+            glyph.push(flag | REPEAT_FLAG);
+            glyph.push(repeats as u8);
+        } else {
+            glyph.push(flag);
+        }
+
+        i += repeats + 1;
+    }
+
+    glyph.extend_from_slice(&x_coords);
+    glyph.extend_from_slice(&y_coords);
+    Some(())
+}
+
+/// Decodes a point coordinates delta using the WOFF2 triplet encoding.
+fn decode_triplet(flag: u8, s: &mut Stream) -> Option<(i32, i32)> {
+    fn with_sign(flag: u8, value: i32) -> i32 {
+        if flag & 1 != 0 {
+            value
+        } else {
+            -value
+        }
+    }
+
+    let flag_i = i32::from(flag);
+    let delta = if flag < 10 {
+        let b0 = i32::from(s.read_u8()?);
+        (0, with_sign(flag, ((flag_i & 14) << 7) + b0))
+    } else if flag < 20 {
+        let b0 = i32::from(s.read_u8()?);
+        (with_sign(flag, (((flag_i - 10) & 14) << 7) + b0), 0)
+    } else if flag < 84 {
+        let b0 = flag_i - 20;
+        let b1 = i32::from(s.read_u8()?);
+        (
+            with_sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+            with_sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (b1 & 0x0F)),
+        )
+    } else if flag < 120 {
+        let b0 = flag_i - 84;
+        let b1 = i32::from(s.read_u8()?);
+        let b2 = i32::from(s.read_u8()?);
+        (
+            with_sign(flag, 1 + ((b0 / 12) << 8) + b1),
+            with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + b2),
+        )
+    } else if flag < 124 {
+        let b1 = i32::from(s.read_u8()?);
+        let b2 = i32::from(s.read_u8()?);
+        let b3 = i32::from(s.read_u8()?);
+        (
+            with_sign(flag, (b1 << 4) + (b2 >> 4)),
+            with_sign(flag >> 1, ((b2 & 0x0F) << 8) + b3),
+        )
+    } else {
+        let b1 = i32::from(s.read_u8()?);
+        let b2 = i32::from(s.read_u8()?);
+        let b3 = i32::from(s.read_u8()?);
+        let b4 = i32::from(s.read_u8()?);
+        (
+            with_sign(flag, (b1 << 8) + b2),
+            with_sign(flag >> 1, (b3 << 8) + b4),
+        )
+    };
+
+    Some(delta)
+}
+
+/// Reconstructs a transformed `hmtx` table.
+///
+/// Missing left side bearings are equal to glyphs `xMin`.
+fn reconstruct_hmtx(
+    data: &[u8],
+    num_h_metrics: u16,
+    num_glyphs: u16,
+    x_mins: &[i16],
+) -> Option<Vec<u8>> {
+    let mut s = Stream::new(data);
+    let flags = s.read_u8()?;
+    let num_h_metrics = usize::from(num_h_metrics);
+    let num_glyphs = usize::from(num_glyphs);
+    // Each glyph must have a left side bearing.
+    let num_lsbs = num_glyphs.checked_sub(num_h_metrics)?;
+
+    let mut advances = Vec::with_capacity(num_h_metrics);
+    for _ in 0..num_h_metrics {
+        advances.push(s.read_u16()?);
+    }
+
+    let mut lsbs = Vec::with_capacity(num_glyphs);
+    for idx in 0..num_h_metrics {
+        if flags & 0x01 == 0 {
+            lsbs.push(s.read_i16()?);
+        } else {
+            lsbs.push(*x_mins.get(idx)?);
+        }
+    }
+
+    for idx in num_h_metrics..num_glyphs {
+        if flags & 0x02 == 0 {
+            lsbs.push(s.read_i16()?);
+        } else {
+            lsbs.push(*x_mins.get(idx)?);
+        }
+    }
+
+    let mut hmtx = Vec::with_capacity(num_h_metrics * 4 + num_lsbs * 2);
+    for (idx, lsb) in lsbs.iter().enumerate() {
+        if let Some(advance) = advances.get(idx) {
+            hmtx.extend_from_slice(&advance.to_be_bytes());
+        }
+        hmtx.extend_from_slice(&lsb.to_be_bytes());
+    }
+
+    Some(hmtx)
+}
+
+/// Builds a TrueType/OpenType font from a list of tables.
+///
+/// Returns `None` when there are too many tables or the font is too big.
+fn build_sfnt(flavor: u32, mut tables: Vec<(Tag, Vec<u8>)>) -> Option<Vec<u8>> {
+    tables.sort_by_key(|table| table.0);
+
+    let num_tables = u16::try_from(tables.len()).ok()?;
+    let entry_selector = (15 - num_tables.max(1).leading_zeros()) as u16;
+    let search_range = (1u16 << entry_selector).checked_mul(16)?;
+    let range_shift = num_tables.checked_mul(16)?.checked_sub(search_range)?;
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&flavor.to_be_bytes());
+    data.extend_from_slice(&num_tables.to_be_bytes());
+    data.extend_from_slice(&search_range.to_be_bytes());
+    data.extend_from_slice(&entry_selector.to_be_bytes());
+    data.extend_from_slice(&range_shift.to_be_bytes());
+
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, table_data) in &tables {
+        data.extend_from_slice(tag);
+        data.extend_from_slice(&checksum(table_data).to_be_bytes());
+        data.extend_from_slice(&u32::try_from(offset).ok()?.to_be_bytes());
+        data.extend_from_slice(&u32::try_from(table_data.len()).ok()?.to_be_bytes());
+        offset = offset.checked_add((table_data.len() + 3) & !3)?;
+    }
+
+    for (_, table_data) in &tables {
+        data.extend_from_slice(table_data);
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+    }
+
+    Some(data)
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+struct Stream<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Stream<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Stream { data, offset: 0 }
+    }
+
+    fn skip(&mut self, len: usize) {
+        self.offset += len;
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.offset)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.offset.checked_add(len)?;
+        let bytes = self.data.get(self.offset..end)?;
+        self.offset = end;
+        Some(bytes)
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        Some(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        let bytes = self.read_bytes(2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_i16(&mut self) -> Option<i16> {
+        self.read_u16().map(|v| v as i16)
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let bytes = self.read_bytes(4)?;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_tag(&mut self) -> Option<Tag> {
+        let bytes = self.read_bytes(4)?;
+        Some([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    /// Reads a `UIntBase128` value.
+    fn read_uint_base128(&mut self) -> Option<u32> {
+        let mut value: u32 = 0;
+        for i in 0..5 {
+            let byte = self.read_u8()?;
+            // Leading zeros are not allowed.
+            if i == 0 && byte == 0x80 {
+                return None;
+            }
+
+            // Overflow check.
+            if value & 0xFE00_0000 != 0 {
+                return None;
+            }
+
+            value = (value << 7) | u32::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+
+    /// Reads a `255UInt16` value.
+    fn read_255_u16(&mut self) -> Option<u16> {
+        const WORD_CODE: u8 = 253;
+        const ONE_MORE_BYTE_CODE_2: u8 = 254;
+        const ONE_MORE_BYTE_CODE_1: u8 = 255;
+        const LOWEST_U_CODE: u16 = 253;
+
+        match self.read_u8()? {
+            WORD_CODE => self.read_u16(),
+            ONE_MORE_BYTE_CODE_1 => Some(u16::from(self.read_u8()?) + LOWEST_U_CODE),
+            ONE_MORE_BYTE_CODE_2 => Some(u16::from(self.read_u8()?) + LOWEST_U_CODE * 2),
+            code => Some(u16::from(code)),
+        }
+    }
+}
@@ -11,9 +11,9 @@ fn clippath_with_invalid_child() {
     </svg>
     ";
 
-    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
     // clipPath is invalid and should be removed together with rect.
-    assert_eq!(tree.root.has_children(), false);
+    assert!(!tree.root.has_children());
 }
 
 #[test]
@@ -24,7 +24,7 @@ fn simplify_paths() {
     </svg>
     ";
 
-    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
     let path = tree.root.first_child().unwrap();
     match *path.borrow() {
         usvg_tree::NodeKind::Path(ref path) => {
@@ -38,7 +38,7 @@ fn simplify_paths() {
 #[test]
 fn size_detection_1() {
     let svg = "<svg viewBox='0 0 10 20' xmlns='http://www.w3.org/2000/svg'/>";
-    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
     assert_eq!(tree.size, usvg_tree::Size::from_wh(10.0, 20.0).unwrap());
 }
 
@@ -46,7 +46,7 @@ fn size_detection_1() {
 fn size_detection_2() {
     let svg =
         "<svg width='30' height='40' viewBox='0 0 10 20' xmlns='http://www.w3.org/2000/svg'/>";
-    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
     assert_eq!(tree.size, usvg_tree::Size::from_wh(30.0, 40.0).unwrap());
 }
 
@@ -54,7 +54,7 @@ fn size_detection_2() {
 fn size_detection_3() {
     let svg =
         "<svg width='50%' height='100%' viewBox='0 0 10 20' xmlns='http://www.w3.org/2000/svg'/>";
-    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
     assert_eq!(tree.size, usvg_tree::Size::from_wh(5.0, 20.0).unwrap());
 }
 
@@ -65,7 +65,7 @@ fn size_detection_4() {
         <circle cx='18' cy='18' r='18'/>
     </svg>
     ";
-    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
     assert_eq!(tree.size, usvg_tree::Size::from_wh(36.0, 36.0).unwrap());
     assert_eq!(
         tree.view_box.rect,
@@ -76,13 +76,1142 @@ fn size_detection_4() {
 #[test]
 fn size_detection_5() {
     let svg = "<svg xmlns='http://www.w3.org/2000/svg'/>";
-    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
     assert_eq!(tree.size, usvg_tree::Size::from_wh(100.0, 100.0).unwrap());
 }
 
 #[test]
 fn invalid_size_1() {
     let svg = "<svg width='0' height='0' viewBox='0 0 10 20' xmlns='http://www.w3.org/2000/svg'/>";
-    let result = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default());
+    let result = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default());
     assert!(result.is_err());
 }
+
+fn hit_test_ids(svg: &str, x: f32, y: f32) -> Vec<String> {
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    tree.hit_test(usvg_tree::Point::from_xy(x, y))
+        .iter()
+        .map(|node| node.borrow().id().to_string())
+        .collect()
+}
+
+#[test]
+fn hit_test_order() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' x='10' y='10' width='50' height='50'/>
+        <g transform='translate(20 20)'>
+            <rect id='rect2' width='50' height='50'/>
+        </g>
+    </svg>
+    ";
+
+    assert_eq!(hit_test_ids(svg, 30.0, 30.0), vec!["rect2", "rect1"]);
+    assert_eq!(hit_test_ids(svg, 15.0, 15.0), vec!["rect1"]);
+    assert_eq!(hit_test_ids(svg, 65.0, 65.0), vec!["rect2"]);
+    assert!(hit_test_ids(svg, 90.0, 90.0).is_empty());
+}
+
+#[test]
+fn hit_test_fill_rule_and_stroke() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <path id='path1' fill-rule='evenodd' d='M 10 10 H 90 V 90 H 10 Z M 30 30 H 70 V 70 H 30 Z'/>
+        <path id='path2' fill='none' stroke='black' d='M 0 95 H 100'/>
+    </svg>
+    ";
+
+    assert_eq!(hit_test_ids(svg, 20.0, 20.0), vec!["path1"]);
+    assert!(hit_test_ids(svg, 50.0, 50.0).is_empty());
+    assert_eq!(hit_test_ids(svg, 50.0, 95.4), vec!["path2"]);
+    assert!(hit_test_ids(svg, 50.0, 96.0).is_empty());
+}
+
+#[test]
+fn hit_test_clip_path() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <clipPath id='clip1'>
+            <circle cx='50' cy='50' r='20'/>
+        </clipPath>
+        <rect id='rect1' width='100' height='100' clip-path='url(#clip1)'/>
+    </svg>
+    ";
+
+    assert_eq!(hit_test_ids(svg, 50.0, 50.0), vec!["rect1"]);
+    assert!(hit_test_ids(svg, 10.0, 10.0).is_empty());
+}
+
+#[test]
+fn hit_test_pointer_events() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' width='100' height='100' fill='none' pointer-events='fill'/>
+        <rect id='rect2' width='100' height='100' visibility='hidden'/>
+        <rect id='rect3' width='100' height='100' pointer-events='none'/>
+    </svg>
+    ";
+
+    assert_eq!(hit_test_ids(svg, 50.0, 50.0), vec!["rect1"]);
+}
+
+fn animated_ids_at(svg: &str, time: f32, x: f32, y: f32) -> Vec<String> {
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    tree.at_time(time)
+        .hit_test(usvg_tree::Point::from_xy(x, y))
+        .iter()
+        .map(|node| node.borrow().id().to_string())
+        .collect()
+}
+
+fn animated_fill_at(svg: &str, time: f32) -> (usvg_tree::Color, f32) {
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let tree = tree.at_time(time);
+    let node = tree.root.first_child().unwrap();
+    let path = match *node.borrow() {
+        usvg_tree::NodeKind::Path(ref path) => path.clone(),
+        _ => unreachable!(),
+    };
+
+    let fill = path.fill.unwrap();
+    match fill.paint {
+        usvg_tree::Paint::Color(c) => (c, fill.opacity.get()),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn static_document_has_no_animation() {
+    let svg = "<svg viewBox='0 0 10 20' xmlns='http://www.w3.org/2000/svg'/>";
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    assert!(tree.animation.is_none());
+}
+
+#[test]
+fn animate_linear() {
+    let svg = "
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' x='0' width='20' height='20'>
+            <animate attributeName='x' from='0' to='100' dur='2s'/>
+        </rect>
+    </svg>
+    ";
+
+    assert_eq!(animated_ids_at(svg, 0.0, 10.0, 10.0), vec!["rect1"]);
+    assert_eq!(animated_ids_at(svg, 1.0, 60.0, 10.0), vec!["rect1"]);
+    assert!(animated_ids_at(svg, 1.0, 10.0, 10.0).is_empty());
+    // Without `fill=freeze` the animation has no effect after it ends.
+    assert_eq!(animated_ids_at(svg, 2.5, 10.0, 10.0), vec!["rect1"]);
+}
+
+#[test]
+fn animate_freeze_and_repeat() {
+    let svg = "
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' x='0' width='20' height='20'>
+            <animate attributeName='x' values='0;100' dur='1s' repeatCount='2' fill='freeze'/>
+        </rect>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    assert_eq!(tree.animation.as_ref().unwrap().duration(), Some(2.0));
+
+    assert_eq!(animated_ids_at(svg, 1.5, 60.0, 10.0), vec!["rect1"]);
+    assert_eq!(animated_ids_at(svg, 5.0, 110.0, 10.0), vec!["rect1"]);
+}
+
+#[test]
+fn animate_key_times_and_discrete() {
+    let svg = "
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' x='0' width='20' height='20'>
+            <animate attributeName='x' values='0;50;100' keyTimes='0;0.8;1'
+                     calcMode='discrete' dur='1s'/>
+        </rect>
+    </svg>
+    ";
+
+    assert_eq!(animated_ids_at(svg, 0.7, 10.0, 10.0), vec!["rect1"]);
+    assert_eq!(animated_ids_at(svg, 0.9, 60.0, 10.0), vec!["rect1"]);
+}
+
+#[test]
+fn animate_color() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='20' height='20' fill='red'>
+            <animate attributeName='fill' to='blue' dur='2s'/>
+            <animate attributeName='fill-opacity' from='1' to='0' begin='1s' dur='2s'/>
+        </rect>
+    </svg>
+    ";
+
+    let (color, opacity) = animated_fill_at(svg, 1.0);
+    assert_eq!(color, usvg_tree::Color::new_rgb(128, 0, 128));
+    assert_eq!(opacity, 1.0);
+
+    let (color, opacity) = animated_fill_at(svg, 2.0);
+    assert_eq!(color, usvg_tree::Color::new_rgb(255, 0, 0));
+    assert_eq!(opacity, 0.5);
+}
+
+#[test]
+fn set_with_begin() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' width='20' height='20' visibility='hidden'>
+            <set attributeName='visibility' to='visible' begin='1s' end='2s'/>
+        </rect>
+    </svg>
+    ";
+
+    assert!(animated_ids_at(svg, 0.5, 10.0, 10.0).is_empty());
+    assert_eq!(animated_ids_at(svg, 1.5, 10.0, 10.0), vec!["rect1"]);
+    assert!(animated_ids_at(svg, 2.5, 10.0, 10.0).is_empty());
+}
+
+#[test]
+fn animate_transform() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'
+         viewBox='0 0 200 200'>
+        <rect id='rect1' width='20' height='20' transform='translate(100 0)'/>
+        <animateTransform xlink:href='#rect1' attributeName='transform' type='translate'
+                          from='0' to='0 100' dur='1s' additive='sum' fill='freeze'/>
+    </svg>
+    ";
+
+    assert_eq!(animated_ids_at(svg, 0.5, 110.0, 60.0), vec!["rect1"]);
+    assert_eq!(animated_ids_at(svg, 2.0, 110.0, 110.0), vec!["rect1"]);
+}
+
+#[test]
+fn animate_with_custom_image_resolver() {
+    use usvg_parser::TreeParsing;
+
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <image id='image1' width='100' height='100' href='first'>
+            <set attributeName='href' to='second' begin='1s'/>
+        </image>
+    </svg>
+    ";
+
+    let opt = usvg_parser::Options {
+        image_href_resolver: usvg_parser::ImageHrefResolver {
+            resolve_string: std::sync::Arc::new(|href, opt| {
+                let width = match href {
+                    "first" => 10,
+                    "second" => 20,
+                    _ => return None,
+                };
+
+                let svg = format!(
+                    "<svg width='{}' height='10' xmlns='http://www.w3.org/2000/svg'/>",
+                    width
+                );
+                let tree = usvg_tree::Tree::from_str(&svg, opt).ok()?;
+                Some(usvg_tree::ImageKind::SVG(tree))
+            }),
+            ..usvg_parser::ImageHrefResolver::default()
+        },
+        ..usvg_parser::Options::default()
+    };
+
+    let image_width = |time: f32| {
+        let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+        let tree = tree.at_time(time);
+        let node = tree.node_by_id("image1").unwrap();
+        let kind = match *node.borrow() {
+            usvg_tree::NodeKind::Image(ref image) => image.kind.clone(),
+            _ => unreachable!(),
+        };
+        match kind {
+            usvg_tree::ImageKind::SVG(ref tree) => tree.size.width(),
+            _ => unreachable!(),
+        }
+    };
+
+    assert_eq!(image_width(0.5), 10.0);
+    // The animated `href` is resolved during the frame conversion.
+    assert_eq!(image_width(1.5), 20.0);
+}
+
+fn external_files_options(
+    files: Vec<(&'static str, &'static str)>,
+    calls: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+) -> usvg_parser::Options {
+    usvg_parser::Options {
+        use_href_resolver: usvg_parser::UseHrefResolver {
+            resolve_string: std::sync::Arc::new(move |href, _| {
+                calls.lock().unwrap().push(href.to_string());
+                files
+                    .iter()
+                    .find(|(name, _)| *name == href)
+                    .map(|(_, text)| text.as_bytes().to_vec())
+            }),
+            ..usvg_parser::UseHrefResolver::default()
+        },
+        ..usvg_parser::Options::default()
+    }
+}
+
+fn sprite_options(calls: std::sync::Arc<std::sync::Mutex<Vec<String>>>) -> usvg_parser::Options {
+    let sprite = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <rect id='rect1' width='20' height='20'/>
+        <g id='g1'>
+            <use xlink:href='icons.svg#g2'/>
+        </g>
+        <g id='g2'>
+            <use xlink:href='icons.svg#g1'/>
+        </g>
+    </svg>
+    ";
+
+    external_files_options(vec![("icons.svg", sprite)], calls)
+}
+
+#[test]
+fn use_external_file() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <use xlink:href='icons.svg#rect1'/>
+        <use xlink:href='icons.svg#rect1' x='50'/>
+        <use xlink:href='missing.svg#rect1' y='50'/>
+    </svg>
+    ";
+
+    let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let opt = sprite_options(calls.clone());
+    let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+    // Each document must be loaded only once.
+    assert_eq!(*calls.lock().unwrap(), ["missing.svg", "icons.svg"]);
+
+    // Loaded documents are cached by the resolver, unlike the missing ones.
+    usvg_tree::Tree::from_str(svg, &opt.clone()).unwrap();
+    assert_eq!(
+        *calls.lock().unwrap(),
+        ["missing.svg", "icons.svg", "missing.svg"]
+    );
+
+    let hit_test = |x, y| tree.hit_test(usvg_tree::Point::from_xy(x, y)).len();
+    assert_eq!(hit_test(10.0, 10.0), 1);
+    assert_eq!(hit_test(60.0, 10.0), 1);
+    assert_eq!(hit_test(10.0, 60.0), 0);
+}
+
+#[test]
+fn use_external_file_recursive() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <use xlink:href='icons.svg#g1'/>
+    </svg>
+    ";
+
+    let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let tree = usvg_tree::Tree::from_str(svg, &sprite_options(calls)).unwrap();
+    assert!(!tree.root.has_children());
+}
+
+#[test]
+fn use_external_file_ids_scope() {
+    let sprite = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <linearGradient id='lg1'>
+            <stop stop-color='red'/>
+        </linearGradient>
+        <rect id='rect1' width='20' height='20' fill='url(#lg1)'/>
+    </svg>
+    ";
+
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <linearGradient id='lg1'>
+            <stop stop-color='green'/>
+        </linearGradient>
+        <rect width='20' height='20' y='50' fill='url(#lg1)'/>
+        <use xlink:href='icons.svg#rect1'/>
+    </svg>
+    ";
+
+    let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let opt = external_files_options(vec![("icons.svg", sprite)], calls);
+    let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(
+        fill_colors(&tree),
+        [
+            usvg_tree::Color::new_rgb(0, 128, 0),
+            usvg_tree::Color::new_rgb(255, 0, 0)
+        ]
+    );
+}
+
+#[test]
+fn use_external_file_relative_links() {
+    let main = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <use id='use1' xlink:href='sprite.svg#rect1'/>
+        <use id='use2' xlink:href='../shared/sprite.svg#rect1' x='50'/>
+    </svg>
+    ";
+
+    let sprite = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' width='20' height='20'/>
+    </svg>
+    ";
+
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <use xlink:href='icons/main.svg#use1'/>
+        <use xlink:href='icons/main.svg#use2'/>
+    </svg>
+    ";
+
+    let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let files = vec![
+        ("icons/main.svg", main),
+        ("icons/sprite.svg", sprite),
+        ("shared/sprite.svg", sprite),
+    ];
+    let tree =
+        usvg_tree::Tree::from_str(svg, &external_files_options(files, calls.clone())).unwrap();
+
+    let mut calls = calls.lock().unwrap().clone();
+    calls.sort();
+    assert_eq!(
+        calls,
+        ["icons/main.svg", "icons/sprite.svg", "shared/sprite.svg"]
+    );
+
+    let hit_test = |x, y| tree.hit_test(usvg_tree::Point::from_xy(x, y)).len();
+    assert_eq!(hit_test(10.0, 10.0), 1);
+    assert_eq!(hit_test(60.0, 10.0), 1);
+}
+
+#[test]
+fn svg_fonts() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <font id='font1' horiz-adv-x='600'>
+            <font-face font-family='Blocks' units-per-em='2048' descent='200'/>
+            <glyph unicode='A' glyph-name='a' d='M 0 0 L 100 100 L 200 0 Z'/>
+            <glyph unicode='B' horiz-adv-x='500'/>
+            <glyph unicode='C'/>
+            <hkern u1='A' u2='U+0042-0043' k='50'/>
+            <hkern g1='a' u2='A' k='20'/>
+            <hkern g1='b' u2='A' k='10'/>
+        </font>
+        <font id='font2'>
+            <font-face font-family='Unused'/>
+        </font>
+        <text font-family='Unknown, blocks'>Text</text>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let node = tree.root.first_child().unwrap();
+    let text = match *node.borrow() {
+        usvg_tree::NodeKind::Text(ref text) => text.clone(),
+        _ => unreachable!(),
+    };
+
+    assert_eq!(text.svg_fonts.len(), 1);
+    let font = &text.svg_fonts[0];
+    assert_eq!(font.id, "font1");
+    assert_eq!(font.units_per_em.get(), 2048.0);
+    assert_eq!(font.ascent, 2048.0);
+    assert_eq!(font.descent, -200.0);
+
+    assert_eq!(font.glyphs.len(), 3);
+    assert!(font.glyphs[0].path.is_some());
+    assert_eq!(font.glyphs[0].horiz_adv_x, 600.0);
+    assert_eq!(font.glyphs[1].horiz_adv_x, 500.0);
+    assert!(font.missing_glyph.path.is_none());
+    assert_eq!(font.missing_glyph.horiz_adv_x, 600.0);
+
+    // The last pair references an unknown glyph.
+    assert_eq!(font.kerning_pairs.len(), 2);
+    assert_eq!(font.kerning_pairs[0].first, vec![0]);
+    assert_eq!(font.kerning_pairs[0].second, vec![1, 2]);
+    assert_eq!(font.kerning_pairs[1].first, vec![0]);
+    assert_eq!(font.kerning_pairs[1].second, vec![0]);
+}
+
+#[test]
+fn font_face_rules() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <style>
+            @font-face {
+                font-family: 'Web Font';
+                src: url(missing.woff2) format('woff2'), url(woff-test.woff);
+                font-weight: bold;
+                font-style: italic;
+            }
+            @font-face {
+                font-family: Unused;
+                src: url(missing.woff2);
+            }
+            rect { fill: none }
+        </style>
+        <rect width='10' height='10'/>
+    </svg>
+    ";
+
+    let opt = usvg_parser::Options {
+        resources_dir: Some(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../resvg/tests/resources"),
+        ),
+        ..usvg_parser::Options::default()
+    };
+
+    let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(tree.font_faces.len(), 1);
+    let face = &tree.font_faces[0];
+    assert_eq!(face.family, "Web Font");
+    assert_eq!(face.weight, 700);
+    assert_eq!(face.style, usvg_tree::FontStyle::Italic);
+    assert_eq!(face.data[0..4], [0x00, 0x01, 0x00, 0x00]);
+
+    // Other rules must still be applied.
+    let node = tree.root.first_child().unwrap();
+    match *node.borrow() {
+        usvg_tree::NodeKind::Path(ref path) => assert!(path.fill.is_none()),
+        _ => unreachable!(),
+    };
+}
+
+/// Loads a font via a data URL and returns the number of loaded font faces.
+fn load_font_face(data: &[u8]) -> usize {
+    let url: String = data.iter().map(|b| format!("%{:02X}", b)).collect();
+    let svg = format!(
+        "
+        <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+            <style>
+                @font-face {{
+                    font-family: Test;
+                    src: url('data:application/octet-stream,{}');
+                }}
+            </style>
+        </svg>
+        ",
+        url
+    );
+
+    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    tree.font_faces.len()
+}
+
+/// Wraps data into an uncompressed Brotli stream.
+fn brotli_stored(data: &[u8]) -> Vec<u8> {
+    // A 16 bits window, a non-last uncompressed meta-block and 4 nibbles length.
+    let header = ((data.len() as u32 - 1) << 4) | (1 << 20);
+    let mut stream = header.to_le_bytes()[0..3].to_vec();
+    stream.extend_from_slice(data);
+    // The last empty meta-block.
+    stream.push(0x03);
+    stream
+}
+
+#[test]
+fn font_face_truncated() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../resvg/tests/resources");
+    for name in ["woff-test.woff", "woff-test.woff2"] {
+        let data = std::fs::read(dir.join(name)).unwrap();
+        assert_eq!(load_font_face(&data), 1);
+
+        for len in (0..data.len()).step_by(13) {
+            // Must not panic.
+            load_font_face(&data[..len]);
+        }
+    }
+}
+
+#[test]
+fn woff_hostile_headers() {
+    let woff_header = |num_tables: u16| {
+        let mut data = b"wOFF".to_vec();
+        data.extend_from_slice(&0x00010000u32.to_be_bytes()); // flavor
+        data.extend_from_slice(&[0; 4]); // length
+        data.extend_from_slice(&num_tables.to_be_bytes());
+        data.extend_from_slice(&[0; 30]);
+        data
+    };
+
+    // Too many tables for an sfnt header.
+    let mut data = woff_header(5000);
+    for i in 0..5000u32 {
+        data.extend_from_slice(&i.to_be_bytes()); // tag
+        data.extend_from_slice(&[0; 16]); // empty, uncompressed table
+    }
+    assert_eq!(load_font_face(&data), 0);
+
+    // A huge declared table length.
+    let mut data = woff_header(1);
+    data.extend_from_slice(b"head");
+    data.extend_from_slice(&0u32.to_be_bytes()); // offset
+    data.extend_from_slice(&16u32.to_be_bytes()); // compLength
+    data.extend_from_slice(&u32::MAX.to_be_bytes()); // origLength
+    data.extend_from_slice(&[0; 4]);
+    assert_eq!(load_font_face(&data), 0);
+}
+
+#[test]
+fn woff2_hostile_headers() {
+    let woff2 = |directory: &[u8], tables: &[u8]| {
+        let compressed = brotli_stored(tables);
+        let mut data = b"wOF2".to_vec();
+        data.extend_from_slice(&0x00010000u32.to_be_bytes()); // flavor
+        data.extend_from_slice(&[0; 4]); // length
+        data.extend_from_slice(&5u16.to_be_bytes()); // numTables
+        data.extend_from_slice(&[0; 6]);
+        data.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        data.extend_from_slice(&[0; 24]);
+        data.extend_from_slice(directory);
+        data.extend_from_slice(&compressed);
+        data
+    };
+
+    // `glyf`, `loca` and `hmtx` are transformed.
+    let directory = [10, 0, 36, 11, 0, 0, 2, 36, 4, 6, 0x43, 8, 9];
+
+    // A transformed `glyf` table without glyphs.
+    let mut tables = vec![0; 36];
+    // `hhea` with numberOfHMetrics set to 2.
+    tables.extend_from_slice(&[0; 34]);
+    tables.extend_from_slice(&2u16.to_be_bytes());
+    // `maxp` with numGlyphs set to 1.
+    tables.extend_from_slice(&[0; 4]);
+    tables.extend_from_slice(&1u16.to_be_bytes());
+    // `hmtx` with explicit advances and left side bearings.
+    tables.extend_from_slice(&[0, 0, 1, 0, 1, 0, 0, 0, 0]);
+
+    // More metrics than glyphs.
+    assert_eq!(load_font_face(&woff2(&directory, &tables)), 0);
+
+    // A huge number of points in a single contour.
+    let mut glyf = vec![0; 36];
+    glyf[5] = 1; // numGlyphs
+    glyf[11] = 2; // nContour stream size
+    glyf[15] = 3; // nPoints stream size
+    glyf.extend_from_slice(&1i16.to_be_bytes());
+    glyf.extend_from_slice(&[253, 0xFF, 0xFF]);
+    tables.splice(0..36, glyf);
+    let mut directory = directory;
+    directory[2] = 41;
+    assert_eq!(load_font_face(&woff2(&directory, &tables)), 0);
+}
+
+#[test]
+fn text_wrap() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' x='20' y='20' width='160' height='160'/>
+        <g style='line-height:150%;white-space:pre-line'>
+            <text inline-size='50%'>First
+            Second</text>
+            <text style='shape-inside:url(#rect1);text-align:justify;white-space:nowrap'>Text</text>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let mut texts = tree
+        .root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Text(ref text) => Some(text.clone()),
+            _ => None,
+        });
+
+    let text = texts.next().unwrap();
+    assert_eq!(text.chunks[0].text, "First\nSecond");
+    let wrap = text.wrap.unwrap();
+    assert_eq!(wrap.line_height, usvg_tree::LineHeight::Number(1.5));
+    assert_eq!(wrap.align, usvg_tree::TextAlign::Start);
+    assert!(wrap.allow_wrap);
+    match wrap.area {
+        usvg_tree::TextWrapArea::InlineSize(size) => assert_eq!(size.get(), 100.0),
+        _ => unreachable!(),
+    }
+
+    let text = texts.next().unwrap();
+    let wrap = text.wrap.unwrap();
+    assert_eq!(wrap.align, usvg_tree::TextAlign::Justify);
+    assert!(!wrap.allow_wrap);
+    match wrap.area {
+        usvg_tree::TextWrapArea::Shape(shape) => {
+            assert_eq!(shape.id, "rect1");
+            assert_eq!(shape.path.bounds().width(), 160.0);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn text_orientation() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <text writing-mode='tb' glyph-orientation-vertical='0'>
+            A<tspan text-orientation='sideways'>B</tspan><tspan glyph-orientation-vertical='90'
+            >C</tspan><tspan text-orientation='invalid'>D</tspan>
+        </text>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let node = tree.root.first_child().unwrap();
+    let text = match *node.borrow() {
+        usvg_tree::NodeKind::Text(ref text) => text.clone(),
+        _ => unreachable!(),
+    };
+
+    let orientations: Vec<_> = text.chunks[0]
+        .spans
+        .iter()
+        .map(|span| span.text_orientation)
+        .collect();
+    assert_eq!(
+        orientations,
+        vec![
+            usvg_tree::TextOrientation::Upright,
+            usvg_tree::TextOrientation::Sideways,
+            usvg_tree::TextOrientation::Sideways,
+            usvg_tree::TextOrientation::Mixed,
+        ]
+    );
+}
+
+#[test]
+fn font_features() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <text font-variant='small-caps none' font-variant-numeric='tabular-nums'
+              style='font-feature-settings:\"liga\" on, \"salt\" 2'>Text</text>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let node = tree.root.first_child().unwrap();
+    let span = match *node.borrow() {
+        usvg_tree::NodeKind::Text(ref text) => text.chunks[0].spans[0].clone(),
+        _ => unreachable!(),
+    };
+
+    assert!(span.small_caps);
+
+    let features: Vec<_> = span
+        .font_features
+        .iter()
+        .map(|f| (std::str::from_utf8(&f.tag).unwrap().to_string(), f.value))
+        .collect();
+    let expected: Vec<_> = [
+        ("liga", 0),
+        ("clig", 0),
+        ("dlig", 0),
+        ("hlig", 0),
+        ("calt", 0),
+        ("tnum", 1),
+        ("liga", 1),
+        ("salt", 2),
+    ]
+    .iter()
+    .map(|(tag, value)| (tag.to_string(), *value))
+    .collect();
+    assert_eq!(features, expected);
+}
+
+#[test]
+fn font_variations() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <text font-weight='650' font-stretch='80%' font-optical-sizing='none'
+              style='font-variation-settings:\"wght\" 350.5, \"TEST\" 10'>Text</text>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let node = tree.root.first_child().unwrap();
+    let span = match *node.borrow() {
+        usvg_tree::NodeKind::Text(ref text) => text.chunks[0].spans[0].clone(),
+        _ => unreachable!(),
+    };
+
+    assert_eq!(span.font.weight, 650);
+    assert_eq!(span.font.stretch, usvg_tree::FontStretch::Condensed);
+    assert!(!span.apply_optical_sizing);
+
+    let variations: Vec<_> = span
+        .font_variations
+        .iter()
+        .map(|v| (std::str::from_utf8(&v.tag).unwrap().to_string(), v.value))
+        .collect();
+    let expected: Vec<_> = [("wdth", 80.0), ("wght", 350.5), ("TEST", 10.0)]
+        .iter()
+        .map(|(tag, value)| (tag.to_string(), *value))
+        .collect();
+    assert_eq!(variations, expected);
+}
+
+#[test]
+fn mask_layers() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <mask id='mask1'>
+            <rect width='10' height='10' fill='white'/>
+        </mask>
+        <rect width='10' height='10'
+              style='mask: url(#mask1) alpha subtract, url(\"data:image/svg+xml;base64,PHN2ZyB4bWxucz0naHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmcnIHdpZHRoPScxMCcgaGVpZ2h0PScxMCc+PHJlY3Qgd2lkdGg9JzUnIGhlaWdodD0nNScvPjwvc3ZnPg==\") right 10px / 50% auto repeat-x;
+                     mask-mode: luminance'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let node = tree.root.first_child().unwrap();
+    let layers = match *node.borrow() {
+        usvg_tree::NodeKind::Group(ref g) => g.masks.clone(),
+        _ => unreachable!(),
+    };
+
+    assert_eq!(layers.len(), 2);
+
+    assert!(matches!(layers[0].source, usvg_tree::MaskSource::Mask(_)));
+    assert_eq!(layers[0].composite, usvg_tree::MaskComposite::Subtract);
+    // A longhand overrides the shorthand.
+    assert_eq!(layers[0].mode, usvg_tree::MaskMode::Luminance);
+
+    assert!(matches!(
+        layers[1].source,
+        usvg_tree::MaskSource::Image(usvg_tree::ImageKind::SVG(_))
+    ));
+    // A longhand value is repeated for all layers.
+    assert_eq!(layers[1].mode, usvg_tree::MaskMode::Luminance);
+    assert_eq!(layers[1].composite, usvg_tree::MaskComposite::Add);
+    assert_eq!(
+        layers[1].position,
+        usvg_tree::MaskPosition {
+            x: usvg_tree::MaskLength::Relative(1.0),
+            y: usvg_tree::MaskLength::Absolute(10.0),
+        }
+    );
+    assert_eq!(
+        layers[1].size,
+        usvg_tree::MaskSize::Explicit {
+            width: Some(usvg_tree::MaskLength::Relative(0.5)),
+            height: None,
+        }
+    );
+    assert_eq!(
+        layers[1].repeat,
+        usvg_tree::MaskRepeat { x: true, y: false }
+    );
+}
+
+#[test]
+fn clip_path_basic_shape() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <rect x='20' y='20' width='160' height='160' stroke='black' stroke-width='20'
+              clip-path='polygon(evenodd, 0 0, 50% 0, 100% 100%) fill-box'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let node = tree.root.first_child().unwrap();
+    let clip_path = match *node.borrow() {
+        usvg_tree::NodeKind::Group(ref g) => g.clip_path.clone().unwrap(),
+        _ => unreachable!(),
+    };
+
+    assert_eq!(clip_path.units, usvg_tree::Units::UserSpaceOnUse);
+
+    let path_node = clip_path.root.first_child().unwrap();
+    let path = match *path_node.borrow() {
+        usvg_tree::NodeKind::Path(ref path) => path.clone(),
+        _ => unreachable!(),
+    };
+
+    assert_eq!(
+        path.fill.as_ref().unwrap().rule,
+        usvg_tree::FillRule::EvenOdd
+    );
+    // Coordinates are relative to the `fill-box`.
+    assert_eq!(
+        path.data.bounds(),
+        usvg_tree::Rect::from_ltrb(20.0, 20.0, 180.0, 180.0).unwrap()
+    );
+}
+
+fn fill_colors(tree: &usvg_tree::Tree) -> Vec<usvg_tree::Color> {
+    tree.root
+        .descendants()
+        .filter_map(|node| match *node.borrow() {
+            usvg_tree::NodeKind::Path(ref path) => match path.fill.as_ref()?.paint {
+                usvg_tree::Paint::Color(c) => Some(c),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn user_style_sheet() {
+    let svg = "
+    <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <style>#rect3 { fill: blue }</style>
+        <rect id='rect1' width='1' height='1'/>
+        <rect id='rect2' width='1' height='1' fill='red'/>
+        <rect id='rect3' width='1' height='1'/>
+        <rect id='rect4' class='forced' width='1' height='1' style='fill: red !important'/>
+    </svg>
+    ";
+
+    let opt = usvg_parser::Options {
+        style_sheet: Some("rect { fill: green } .forced { fill: lime !important }".to_string()),
+        ..usvg_parser::Options::default()
+    };
+
+    let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(
+        fill_colors(&tree),
+        vec![
+            usvg_tree::Color::new_rgb(0, 128, 0),
+            usvg_tree::Color::new_rgb(255, 0, 0),
+            usvg_tree::Color::new_rgb(0, 0, 255),
+            usvg_tree::Color::new_rgb(0, 255, 0),
+        ]
+    );
+}
+
+#[test]
+fn color_scheme() {
+    let svg = "
+    <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <style>@media (prefers-color-scheme: dark) { rect { fill: white } }</style>
+        <rect width='1' height='1' fill='black'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    assert_eq!(fill_colors(&tree), vec![usvg_tree::Color::black()]);
+
+    let opt = usvg_parser::Options {
+        color_scheme: usvg_parser::ColorScheme::Dark,
+        ..usvg_parser::Options::default()
+    };
+    let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(fill_colors(&tree), vec![usvg_tree::Color::white()]);
+}
+
+#[test]
+fn current_color_override() {
+    let opt = usvg_parser::Options {
+        current_color: Some(usvg_tree::Color::new_rgb(0, 128, 0)),
+        ..usvg_parser::Options::default()
+    };
+
+    let svg = "
+    <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='1' height='1' fill='currentColor'/>
+        <g color='red'>
+            <rect width='1' height='1' fill='currentColor'/>
+        </g>
+    </svg>
+    ";
+    let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(
+        fill_colors(&tree),
+        vec![
+            usvg_tree::Color::new_rgb(0, 128, 0),
+            usvg_tree::Color::new_rgb(255, 0, 0),
+        ]
+    );
+
+    // The document's own `color` takes precedence.
+    let svg = "
+    <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg' color='blue'>
+        <rect width='1' height='1' fill='currentColor'/>
+    </svg>
+    ";
+    let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(
+        fill_colors(&tree),
+        vec![usvg_tree::Color::new_rgb(0, 0, 255)]
+    );
+}
+
+#[test]
+fn css_specificity() {
+    let svg = "
+    <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <style>
+            #rect1 { fill: blue }
+            rect.a { fill: green }
+            .a { fill: red }
+            rect { fill: red }
+            :is(.c, rect) { fill: green }
+            rect:where(#rect3) { fill: red }
+            svg > rect.b { fill: blue }
+            * { fill: red }
+        </style>
+        <rect id='rect1' class='a' width='1' height='1'/>
+        <rect id='rect2' class='a' width='1' height='1'/>
+        <rect id='rect3' width='1' height='1'/>
+        <rect id='rect4' class='b' width='1' height='1'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    assert_eq!(
+        fill_colors(&tree),
+        vec![
+            usvg_tree::Color::new_rgb(0, 0, 255),
+            usvg_tree::Color::new_rgb(0, 128, 0),
+            usvg_tree::Color::new_rgb(0, 128, 0),
+            usvg_tree::Color::new_rgb(0, 0, 255),
+        ]
+    );
+}
+
+#[test]
+fn css_important_order() {
+    let svg = "
+    <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <style>
+            rect { fill: green !important }
+            rect { fill: blue !important }
+            #rect1 { fill: red }
+            #rect2 { fill: red }
+            .a { fill: lime !important }
+            rect { stroke: red !important }
+        </style>
+        <rect id='rect1' width='1' height='1' style='fill: red'/>
+        <rect id='rect2' class='a' width='1' height='1'/>
+        <rect id='rect3' width='1' height='1' style='fill: green !important'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    assert_eq!(
+        fill_colors(&tree),
+        vec![
+            // The last important declaration wins.
+            usvg_tree::Color::new_rgb(0, 0, 255),
+            // Important declarations with a higher specificity win.
+            usvg_tree::Color::new_rgb(0, 255, 0),
+            // Important `style` attribute declarations win.
+            usvg_tree::Color::new_rgb(0, 128, 0),
+        ]
+    );
+}
+
+#[test]
+fn css_nth_child() {
+    let matched = |selector: &str| -> Vec<usize> {
+        let svg = format!(
+            "
+            <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+                <style>rect {{ fill: black }} {} {{ fill: white }}</style>
+                <rect width='1' height='1'/>
+                <rect width='1' height='1'/>
+                <circle r='1'/>
+                <rect width='1' height='1'/>
+                <rect width='1' height='1'/>
+                <rect width='1' height='1'/>
+            </svg>
+            ",
+            selector
+        );
+
+        let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+        fill_colors(&tree)
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == usvg_tree::Color::white())
+            .map(|(i, _)| i + 1)
+            .collect()
+    };
+
+    // Indices are among all the paths, including the circle.
+    // The `style` element is the first child, therefore the first path is the second child.
+    assert_eq!(matched("rect:nth-child(odd)"), vec![2, 4, 6]);
+    assert_eq!(matched("rect:nth-child(even)"), vec![1, 5]);
+    assert_eq!(matched("rect:nth-child(3n+2)"), vec![1, 4]);
+    assert_eq!(matched("rect:nth-child(-n+3)"), vec![1, 2]);
+    assert_eq!(matched("rect:nth-child(n + 6)"), vec![5, 6]);
+    assert_eq!(matched("rect:nth-child(3)"), vec![2]);
+    assert_eq!(matched("rect:nth-last-child(1)"), vec![6]);
+    assert_eq!(matched("rect:nth-of-type(2n)"), vec![2, 5]);
+    assert_eq!(matched("rect:nth-last-of-type(-2n+3)"), vec![4, 6]);
+
+    // Must not overflow.
+    assert_eq!(matched("rect:nth-child(-n-2147483648)"), vec![]);
+    assert_eq!(
+        matched("rect:nth-child(-1n+2147483647)"),
+        vec![1, 2, 4, 5, 6]
+    );
+    assert_eq!(matched("rect:nth-child(-2147483648n+2)"), vec![1]);
+    assert_eq!(matched("rect:nth-child(2147483647n-2147483648)"), vec![]);
+}
+
+#[test]
+fn css_media_ranges() {
+    let matched = |query: &str| -> bool {
+        let svg = format!(
+            "
+            <svg width='200' height='100' xmlns='http://www.w3.org/2000/svg'>
+                <style><![CDATA[@media {} {{ rect {{ fill: white }} }}]]></style>
+                <rect width='1' height='1'/>
+            </svg>
+            ",
+            query
+        );
+
+        let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+        fill_colors(&tree) == vec![usvg_tree::Color::white()]
+    };
+
+    assert!(matched("(width >= 200px)"));
+    assert!(!matched("(width > 200px)"));
+    assert!(matched("(100px <= width <= 300px)"));
+    assert!(!matched("(100px < height < 300px)"));
+    assert!(matched("(300px > width)"));
+    assert!(matched("(min-width: 2in)"));
+    assert!(!matched("(max-height: 50px)"));
+    assert!(matched("(width: 200px) and (orientation: landscape)"));
+    assert!(matched("print, (height = 100px)"));
+    assert!(!matched("not all and (width >= 100px)"));
+    assert!(!matched("(unknown >= 10px)"));
+}
+
+#[test]
+fn css_duplicated_invalid_custom_property() {
+    let fill = |svg: &str| {
+        let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+        fill_colors(&tree)
+    };
+
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10'
+              style='--a: var(--missing); --a: var(--missing); fill: var(--a, red)'/>
+    </svg>
+    ";
+    assert_eq!(fill(svg), vec![usvg_tree::Color::new_rgb(255, 0, 0)]);
+
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <style>rect { --a: green }</style>
+        <rect width='10' height='10' style='--a: var(--missing); fill: var(--a, red)'/>
+    </svg>
+    ";
+    assert_eq!(fill(svg), vec![usvg_tree::Color::new_rgb(255, 0, 0)]);
+}
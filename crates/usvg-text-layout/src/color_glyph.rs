@@ -0,0 +1,442 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use fontdb::ID;
+use rustybuzz::ttf_parser;
+use ttf_parser::colr::{
+    ClipBox, CompositeMode, GradientExtend, GradientStopsIter, Paint as ColrPaint,
+};
+use ttf_parser::{GlyphId, RgbaColor};
+use usvg_tree::*;
+
+use crate::{PathBuilder, SvgGlyphParser};
+
+/// A glyph that has its own colors.
+#[derive(Clone)]
+pub enum ColorGlyph {
+    /// Layers from the `COLR` table.
+    ///
+    /// A `Group` in font units, with the Y axis pointing up.
+    Layers(Node),
+    /// An image from the `sbix`, `CBDT` or `SVG ` table.
+    ///
+    /// `rect` is in font units, with the Y axis pointing down.
+    Image { kind: ImageKind, rect: NonZeroRect },
+}
+
+/// Parsed `SVG ` table documents.
+///
+/// A single document can contain multiple glyphs, so each one is parsed only once
+/// per text conversion.
+pub struct SvgGlyphs<'a> {
+    parser: Option<&'a SvgGlyphParser<'a>>,
+    documents: RefCell<HashMap<(ID, u16), Option<Tree>>>,
+}
+
+impl<'a> SvgGlyphs<'a> {
+    pub fn new(parser: Option<&'a SvgGlyphParser<'a>>) -> Self {
+        SvgGlyphs {
+            parser,
+            documents: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn parser(&self) -> Option<&'a SvgGlyphParser<'a>> {
+        self.parser
+    }
+
+    /// Returns a copy of a document that contains the glyph.
+    fn document(&self, id: ID, face: &ttf_parser::Face, glyph_id: GlyphId) -> Option<Tree> {
+        let parser = self.parser?;
+        let document = face.glyph_svg_image(glyph_id)?;
+
+        let mut documents = self.documents.borrow_mut();
+        let tree = documents
+            .entry((id, document.start_glyph_id.0))
+            .or_insert_with(|| parser(document.data))
+            .as_ref()?;
+
+        Some(Tree {
+            root: tree.root.make_deep_copy(),
+            animation: None,
+            font_faces: Vec::new(),
+            ..*tree
+        })
+    }
+}
+
+/// Returns a color representation of a glyph, if any.
+///
+/// `COLR` layers take priority over `SVG ` documents, which take priority over bitmaps.
+pub fn color_glyph(
+    id: ID,
+    face: &ttf_parser::Face,
+    glyph_id: GlyphId,
+    foreground: RgbaColor,
+    svg_glyphs: &SvgGlyphs,
+) -> Option<ColorGlyph> {
+    if face.is_color_glyph(glyph_id) {
+        if let Some(node) = colr_glyph(face, glyph_id, foreground) {
+            return Some(ColorGlyph::Layers(node));
+        }
+    }
+
+    if let Some(tree) = svg_glyphs.document(id, face, glyph_id) {
+        if let Some(glyph) = svg_glyph(face, glyph_id, tree) {
+            return Some(glyph);
+        }
+    }
+
+    raster_glyph(face, glyph_id)
+}
+
+/// Checks that a face has any tables with color glyphs.
+pub fn has_color_glyphs(face: &ttf_parser::Face) -> bool {
+    let tables = face.tables();
+    tables.colr.is_some() || tables.svg.is_some() || tables.sbix.is_some() || tables.cbdt.is_some()
+}
+
+fn colr_glyph(face: &ttf_parser::Face, glyph_id: GlyphId, foreground: RgbaColor) -> Option<Node> {
+    let root = Node::new(NodeKind::Group(Group::default()));
+
+    let mut painter = GlyphPainter {
+        face,
+        outline: None,
+        transform: Transform::default(),
+        transforms_stack: Vec::new(),
+        parents: vec![root.clone()],
+    };
+    face.paint_color_glyph(glyph_id, 0, foreground, &mut painter)?;
+
+    if root.has_children() {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+/// Loads a glyph from the `SVG ` table.
+///
+/// SVG glyphs are defined in font units, with the baseline at zero.
+/// Since a single document can contain multiple glyphs, all other glyphs will be removed.
+fn svg_glyph(face: &ttf_parser::Face, glyph_id: GlyphId, mut tree: Tree) -> Option<ColorGlyph> {
+    let glyph_name = format!("glyph{}", glyph_id.0);
+    let other_glyphs: Vec<Node> = tree
+        .root
+        .descendants()
+        .filter(|n| {
+            let id = n.id();
+            id.as_ref() != glyph_name && is_glyph_id(&id)
+        })
+        .collect();
+    for node in other_glyphs {
+        node.detach();
+    }
+
+    // Map the document 1:1 into font units and let the content overflow.
+    let units_per_em = face.units_per_em() as f32;
+    let rect = NonZeroRect::from_xywh(0.0, -units_per_em, units_per_em, units_per_em)?;
+    tree.size = rect.size();
+    tree.view_box = ViewBox {
+        rect,
+        aspect: AspectRatio::default(),
+    };
+
+    Some(ColorGlyph::Image {
+        kind: ImageKind::SVG(tree),
+        rect,
+    })
+}
+
+fn is_glyph_id(id: &str) -> bool {
+    match id.strip_prefix("glyph") {
+        Some(n) => !n.is_empty() && n.bytes().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+/// Loads a glyph from the `sbix` or `CBDT` table.
+///
+/// Only PNG images are supported.
+fn raster_glyph(face: &ttf_parser::Face, glyph_id: GlyphId) -> Option<ColorGlyph> {
+    // Always use the largest strike, since we do not know the final scale.
+    let image = face.glyph_raster_image(glyph_id, u16::MAX)?;
+    if image.format != ttf_parser::RasterImageFormat::PNG {
+        log::warn!("Only PNG bitmap glyphs are supported.");
+        return None;
+    }
+
+    // `y` is the bottom edge, with the Y axis pointing up.
+    let scale = face.units_per_em() as f32 / image.pixels_per_em as f32;
+    let rect = NonZeroRect::from_xywh(
+        image.x as f32 * scale,
+        -(image.y as f32 + image.height as f32) * scale,
+        image.width as f32 * scale,
+        image.height as f32 * scale,
+    )?;
+
+    Some(ColorGlyph::Image {
+        kind: ImageKind::PNG(Arc::new(image.data.to_vec())),
+        rect,
+    })
+}
+
+/// Converts `COLR` paint commands into a usvg tree.
+///
+/// All outlines are stored in the glyph coordinate system with transforms already applied,
+/// so groups are used only for clipping and compositing.
+struct GlyphPainter<'a> {
+    face: &'a ttf_parser::Face<'a>,
+    outline: Option<tiny_skia_path::Path>,
+    transform: Transform,
+    transforms_stack: Vec<Transform>,
+    parents: Vec<Node>,
+}
+
+impl GlyphPainter<'_> {
+    fn parent(&self) -> &Node {
+        // The root group is never popped.
+        self.parents.last().unwrap()
+    }
+
+    fn push_group(&mut self, group: Group) {
+        let node = Node::new(NodeKind::Group(group));
+        self.parent().append(node.clone());
+        self.parents.push(node);
+    }
+
+    fn pop_group(&mut self) {
+        if self.parents.len() > 1 {
+            self.parents.pop();
+        }
+    }
+
+    fn push_clip_path(&mut self, path: tiny_skia_path::Path) {
+        let mut path = Path::new(Arc::new(path));
+        path.fill = Some(Fill::default());
+
+        let clip_path = ClipPath::default();
+        clip_path.root.append_kind(NodeKind::Path(path));
+
+        self.push_group(Group {
+            clip_path: Some(Rc::new(clip_path)),
+            ..Group::default()
+        });
+    }
+
+    fn convert_stops(&self, stops: GradientStopsIter) -> Vec<Stop> {
+        // TODO: stops outside the 0..1 range are clamped, which is not the same
+        //       as extending the color line.
+        stops
+            .map(|stop| Stop {
+                offset: StopOffset::new_clamped(stop.stop_offset),
+                color: Color::new_rgb(stop.color.red, stop.color.green, stop.color.blue),
+                opacity: Opacity::new_clamped(stop.color.alpha as f32 / 255.0),
+            })
+            .collect()
+    }
+
+    fn convert_paint(&self, paint: ColrPaint) -> Option<Fill> {
+        let coords = self.face.variation_coordinates();
+        let paint = match paint {
+            ColrPaint::Solid(color) => {
+                return Some(Fill {
+                    paint: Paint::Color(Color::new_rgb(color.red, color.green, color.blue)),
+                    opacity: Opacity::new_clamped(color.alpha as f32 / 255.0),
+                    rule: FillRule::NonZero,
+                });
+            }
+            ColrPaint::LinearGradient(gradient) => {
+                // COLR uses a third point to rotate the gradient line,
+                // which has to be projected onto a regular SVG gradient vector.
+                let (x2, y2) = {
+                    let (dx, dy) = (gradient.x2 - gradient.x0, gradient.y2 - gradient.y0);
+                    let (nx, ny) = (dy, -dx);
+                    let len = nx * nx + ny * ny;
+                    if len.approx_zero_ulps(4) {
+                        (gradient.x1, gradient.y1)
+                    } else {
+                        let (vx, vy) = (gradient.x1 - gradient.x0, gradient.y1 - gradient.y0);
+                        let k = (vx * nx + vy * ny) / len;
+                        (gradient.x0 + nx * k, gradient.y0 + ny * k)
+                    }
+                };
+
+                Paint::LinearGradient(Rc::new(LinearGradient {
+                    x1: gradient.x0,
+                    y1: gradient.y0,
+                    x2,
+                    y2,
+                    base: self.base_gradient(
+                        gradient.extend,
+                        self.convert_stops(gradient.stops(0, coords)),
+                    ),
+                }))
+            }
+            ColrPaint::RadialGradient(gradient) => Paint::RadialGradient(Rc::new(RadialGradient {
+                cx: gradient.x1,
+                cy: gradient.y1,
+                r: PositiveF32::new(gradient.r1)?,
+                fx: gradient.x0,
+                fy: gradient.y0,
+                fr: PositiveF32::new(gradient.r0).unwrap_or(PositiveF32::ZERO),
+                base: self.base_gradient(
+                    gradient.extend,
+                    self.convert_stops(gradient.stops(0, coords)),
+                ),
+            })),
+            ColrPaint::SweepGradient(gradient) => {
+                log::warn!("Sweep gradients are not supported. The first stop will be used.");
+                let stop = gradient.stops(0, coords).next()?;
+                return self.convert_paint(ColrPaint::Solid(stop.color));
+            }
+        };
+
+        Some(Fill {
+            paint,
+            opacity: Opacity::ONE,
+            rule: FillRule::NonZero,
+        })
+    }
+
+    fn base_gradient(&self, extend: GradientExtend, stops: Vec<Stop>) -> BaseGradient {
+        BaseGradient {
+            id: String::new(),
+            units: Units::UserSpaceOnUse,
+            transform: self.transform,
+            spread_method: match extend {
+                GradientExtend::Pad => SpreadMethod::Pad,
+                GradientExtend::Repeat => SpreadMethod::Repeat,
+                GradientExtend::Reflect => SpreadMethod::Reflect,
+            },
+            color_interpolation: filter::ColorInterpolation::SRGB,
+            stops,
+        }
+    }
+}
+
+impl<'a> ttf_parser::colr::Painter<'a> for GlyphPainter<'a> {
+    fn outline_glyph(&mut self, glyph_id: GlyphId) {
+        let mut builder = PathBuilder {
+            builder: tiny_skia_path::PathBuilder::new(),
+        };
+
+        self.outline = self
+            .face
+            .outline_glyph(glyph_id, &mut builder)
+            .and_then(|_| builder.builder.finish())
+            .and_then(|p| p.transform(self.transform));
+    }
+
+    fn paint(&mut self, paint: ColrPaint<'a>) {
+        let data = match self.outline {
+            Some(ref v) => v.clone(),
+            None => return,
+        };
+
+        let mut path = Path::new(Arc::new(data));
+        path.fill = self.convert_paint(paint);
+        if path.fill.is_some() {
+            self.parent().append_kind(NodeKind::Path(path));
+        }
+    }
+
+    fn push_clip(&mut self) {
+        match self.outline.clone() {
+            Some(path) => self.push_clip_path(path),
+            // An empty clip path.
+            None => self.push_clip_path(tiny_skia_path::PathBuilder::from_rect(
+                Rect::from_xywh(0.0, 0.0, 0.0, 0.0).unwrap(),
+            )),
+        }
+    }
+
+    fn push_clip_box(&mut self, clipbox: ClipBox) {
+        let path = Rect::from_ltrb(clipbox.x_min, clipbox.y_min, clipbox.x_max, clipbox.y_max)
+            .map(tiny_skia_path::PathBuilder::from_rect)
+            .and_then(|p| p.transform(self.transform));
+
+        match path {
+            Some(path) => self.push_clip_path(path),
+            None => self.push_group(Group::default()),
+        }
+    }
+
+    fn pop_clip(&mut self) {
+        self.pop_group();
+    }
+
+    fn push_layer(&mut self, mode: CompositeMode) {
+        let blend_mode = match mode {
+            CompositeMode::SourceOver => BlendMode::Normal,
+            CompositeMode::Screen => BlendMode::Screen,
+            CompositeMode::Overlay => BlendMode::Overlay,
+            CompositeMode::Darken => BlendMode::Darken,
+            CompositeMode::Lighten => BlendMode::Lighten,
+            CompositeMode::ColorDodge => BlendMode::ColorDodge,
+            CompositeMode::ColorBurn => BlendMode::ColorBurn,
+            CompositeMode::HardLight => BlendMode::HardLight,
+            CompositeMode::SoftLight => BlendMode::SoftLight,
+            CompositeMode::Difference => BlendMode::Difference,
+            CompositeMode::Exclusion => BlendMode::Exclusion,
+            CompositeMode::Multiply => BlendMode::Multiply,
+            CompositeMode::Hue => BlendMode::Hue,
+            CompositeMode::Saturation => BlendMode::Saturation,
+            CompositeMode::Color => BlendMode::Color,
+            CompositeMode::Luminosity => BlendMode::Luminosity,
+            _ => {
+                log::warn!("{:?} composite mode is not supported.", mode);
+                BlendMode::Normal
+            }
+        };
+
+        self.push_group(Group {
+            blend_mode,
+            isolate: true,
+            ..Group::default()
+        });
+    }
+
+    fn pop_layer(&mut self) {
+        self.pop_group();
+    }
+
+    fn push_translate(&mut self, tx: f32, ty: f32) {
+        self.push_transform(ttf_parser::Transform::new_translate(tx, ty));
+    }
+
+    fn push_scale(&mut self, sx: f32, sy: f32) {
+        self.push_transform(ttf_parser::Transform::new(sx, 0.0, 0.0, sy, 0.0, 0.0));
+    }
+
+    fn push_rotate(&mut self, angle: f32) {
+        // The angle is in half-turns.
+        let (sin, cos) = (angle * std::f32::consts::PI).sin_cos();
+        self.push_transform(ttf_parser::Transform::new(cos, sin, -sin, cos, 0.0, 0.0));
+    }
+
+    fn push_skew(&mut self, skew_x: f32, skew_y: f32) {
+        let x = (-skew_x * std::f32::consts::PI).tan();
+        let y = (skew_y * std::f32::consts::PI).tan();
+        self.push_transform(ttf_parser::Transform::new(1.0, y, x, 1.0, 0.0, 0.0));
+    }
+
+    fn push_transform(&mut self, ts: ttf_parser::Transform) {
+        self.transforms_stack.push(self.transform);
+        self.transform = self
+            .transform
+            .pre_concat(Transform::from_row(ts.a, ts.b, ts.c, ts.d, ts.e, ts.f));
+    }
+
+    fn pop_transform(&mut self) {
+        if let Some(ts) = self.transforms_stack.pop() {
+            self.transform = ts;
+        }
+    }
+}
@@ -0,0 +1,204 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::ops::Range;
+
+use usvg_tree::{FontFace, Text, Transform};
+
+use crate::{chunk_span_at, layout_chunks, FontSource, LaidOutChunk};
+
+/// A text layout.
+///
+/// Unlike [`TreeTextToPath`](crate::TreeTextToPath), it doesn't produce any outlines,
+/// only positions of the shaped glyphs.
+#[derive(Clone, Debug)]
+pub struct TextLayout {
+    /// A list of laid out text chunks.
+    ///
+    /// Chunks without any glyphs are skipped.
+    pub chunks: Vec<ChunkLayout>,
+}
+
+/// A laid out text chunk.
+///
+/// Usually, matches [`TextChunk`](usvg_tree::TextChunk),
+/// but an auto-wrapped text will be split into multiple lines.
+#[derive(Clone, Debug)]
+pub struct ChunkLayout {
+    /// A chunk text.
+    ///
+    /// Clusters byte ranges are relative to it.
+    pub text: String,
+
+    /// A baseline start X position.
+    ///
+    /// Always zero for a text on path.
+    pub x: f32,
+
+    /// A baseline start Y position.
+    ///
+    /// Always zero for a text on path.
+    pub y: f32,
+
+    /// The largest ascent of the chunk's fonts.
+    pub ascent: f32,
+
+    /// The smallest descent of the chunk's fonts.
+    ///
+    /// Usually negative.
+    pub descent: f32,
+
+    /// A list of clusters in a visual order.
+    pub clusters: Vec<ClusterLayout>,
+}
+
+/// A laid out glyph cluster.
+///
+/// A cluster is a group of glyphs that represent one or more characters
+/// and cannot be split, like a ligature.
+#[derive(Clone, Debug)]
+pub struct ClusterLayout {
+    /// A byte range in [`ChunkLayout::text`].
+    pub byte_range: Range<usize>,
+
+    /// An advance along the baseline.
+    ///
+    /// Includes `letter-spacing` and `word-spacing`.
+    pub advance: f32,
+
+    /// Maps cluster coordinates into the text coordinates.
+    ///
+    /// Contains cluster's position, rotation and baseline shift.
+    pub transform: Transform,
+
+    /// Not all clusters should be rendered.
+    ///
+    /// For example, clusters outside the text path are hidden.
+    pub visible: bool,
+
+    /// Cluster's glyphs.
+    pub glyphs: Vec<GlyphLayout>,
+}
+
+/// A laid out glyph.
+#[derive(Clone, Copy, Debug)]
+pub struct GlyphLayout {
+    /// A glyph ID in the font.
+    pub id: u16,
+
+    /// A font ID in the fonts database.
+    ///
+    /// `None` for SVG fonts.
+    /// `@font-face` fonts are not present in the database passed to [`layout`].
+    pub font: Option<fontdb::ID>,
+
+    /// A glyph advance in the text coordinates.
+    pub advance: f32,
+
+    /// Maps font units into the text coordinates.
+    ///
+    /// The Y axis is flipped, just like during rendering.
+    pub transform: Transform,
+}
+
+/// Lays out a text node.
+///
+/// `font_faces` are fonts from the `@font-face` rules,
+/// usually [`Tree::font_faces`](usvg_tree::Tree::font_faces).
+/// Just like during the text to path conversion, they take precedence over `fontdb` fonts.
+///
+/// Since the absolute transform is unknown, `textPath` is laid out in the user space.
+pub fn layout(text: &Text, fontdb: &fontdb::Database, font_faces: &[FontFace]) -> TextLayout {
+    crate::with_font_faces(fontdb, font_faces, |fontdb| {
+        let (fonts_cache, chunks) = layout_chunks(text, fontdb, Transform::default());
+        convert_chunks(chunks, &fonts_cache, text)
+    })
+}
+
+fn convert_chunks(
+    chunks: Vec<LaidOutChunk>,
+    fonts_cache: &crate::FontsCache,
+    text: &Text,
+) -> TextLayout {
+    let chunks = chunks
+        .into_iter()
+        .map(|laid_out| {
+            let mut ascent = 0.0;
+            let mut descent = 0.0;
+            for span in &laid_out.chunk.spans {
+                if let Some(font) = fonts_cache.get(&span.font) {
+                    ascent = f32::max(ascent, font.ascent(span.font_size.get()));
+                    descent = f32::min(descent, font.descent(span.font_size.get()));
+                }
+            }
+
+            let clusters = convert_clusters(&laid_out, fonts_cache, text);
+
+            ChunkLayout {
+                text: laid_out.chunk.text,
+                x: laid_out.x,
+                y: laid_out.y,
+                ascent,
+                descent,
+                clusters,
+            }
+        })
+        .collect();
+
+    TextLayout { chunks }
+}
+
+fn convert_clusters(
+    laid_out: &LaidOutChunk,
+    fonts_cache: &crate::FontsCache,
+    text: &Text,
+) -> Vec<ClusterLayout> {
+    // Clusters are in a visual order, so we have to find
+    // the next cluster in a logical order to get the byte range end.
+    let mut starts: Vec<usize> = laid_out.clusters.iter().map(|c| c.byte_idx.0).collect();
+    starts.sort_unstable();
+    starts.dedup();
+
+    let mut clusters = Vec::with_capacity(laid_out.clusters.len());
+    for cluster in &laid_out.clusters {
+        let start = cluster.byte_idx.0;
+        let end = match starts.binary_search(&start) {
+            Ok(idx) => starts
+                .get(idx + 1)
+                .cloned()
+                .unwrap_or(laid_out.chunk.text.len()),
+            Err(_) => laid_out.chunk.text.len(),
+        };
+
+        let span_ts = chunk_span_at(&laid_out.chunk, cluster.byte_idx)
+            .and_then(|span| {
+                let font = fonts_cache.get(&span.font)?;
+                Some(laid_out.span_transform(span, font, text.writing_mode))
+            })
+            .unwrap_or(laid_out.transform.pre_translate(laid_out.x, laid_out.y));
+        let transform = span_ts.pre_concat(cluster.transform);
+
+        clusters.push(ClusterLayout {
+            byte_range: start..end,
+            advance: cluster.advance,
+            transform,
+            visible: cluster.visible,
+            glyphs: cluster
+                .glyphs
+                .iter()
+                .map(|glyph| GlyphLayout {
+                    id: glyph.id.0,
+                    font: match glyph.font.source {
+                        FontSource::Database(id) => Some(id),
+                        FontSource::Svg(_) => None,
+                    },
+                    advance: glyph.advance,
+                    transform: transform.pre_concat(glyph.transform),
+                })
+                .collect(),
+        });
+    }
+
+    clusters
+}
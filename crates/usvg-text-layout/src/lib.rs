@@ -22,11 +22,19 @@ An [SVG] text layout implementation on top of [usvg] crate.
 #![allow(clippy::upper_case_acronyms)]
 
 pub use fontdb;
+pub use layout::{layout, ChunkLayout, ClusterLayout, GlyphLayout, TextLayout};
+pub use report::{FaceInfo, FontReport, SpanFontReport};
+
+mod color_glyph;
+mod layout;
+mod report;
+mod wrap;
 
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::num::NonZeroU16;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use fontdb::{Database, ID};
 use kurbo::{ParamCurve, ParamCurveArclen, ParamCurveDeriv};
@@ -35,20 +43,154 @@ use ttf_parser::GlyphId;
 use unicode_script::UnicodeScript;
 use usvg_tree::*;
 
+use color_glyph::{ColorGlyph, SvgGlyphs};
+
+/// Parses an SVG document from the `SVG ` font table.
+///
+/// This crate doesn't depend on an SVG parser,
+/// therefore `SVG ` glyphs are ignored unless a parser is provided.
+pub type SvgGlyphParser<'a> = dyn Fn(&[u8]) -> Option<Tree> + 'a;
+
 /// A `usvg::Tree` extension trait.
 pub trait TreeTextToPath {
     /// Converts text nodes into paths.
+    ///
+    /// `SVG ` table glyphs are ignored.
     fn convert_text(&mut self, fontdb: &fontdb::Database);
+
+    /// Converts text nodes into paths and reports which fonts were used.
+    ///
+    /// Useful for detecting missing glyphs and unexpected font fallbacks.
+    fn convert_text_with_report(&mut self, fontdb: &fontdb::Database) -> FontReport;
+
+    /// Converts text nodes into paths, using `parser` for `SVG ` table glyphs.
+    ///
+    /// Fonts usage is reported only when `report` is set.
+    fn convert_text_with_svg_glyphs(
+        &mut self,
+        fontdb: &fontdb::Database,
+        parser: &SvgGlyphParser,
+        report: Option<&mut FontReport>,
+    );
 }
 
 impl TreeTextToPath for usvg_tree::Tree {
     fn convert_text(&mut self, fontdb: &fontdb::Database) {
-        convert_text(self.root.clone(), fontdb);
-        self.calculate_abs_transforms();
+        convert_tree(self, fontdb, None, None);
+    }
+
+    fn convert_text_with_report(&mut self, fontdb: &fontdb::Database) -> FontReport {
+        let mut report = FontReport::default();
+        convert_tree(self, fontdb, None, Some(&mut report));
+        report
+    }
+
+    fn convert_text_with_svg_glyphs(
+        &mut self,
+        fontdb: &fontdb::Database,
+        parser: &SvgGlyphParser,
+        report: Option<&mut FontReport>,
+    ) {
+        convert_tree(self, fontdb, Some(parser), report);
+    }
+}
+
+/// Converts text nodes into paths.
+///
+/// Fonts usage is collected only when `report` is set.
+fn convert_tree(
+    tree: &mut Tree,
+    fontdb: &fontdb::Database,
+    svg_glyph_parser: Option<&SvgGlyphParser>,
+    report: Option<&mut FontReport>,
+) {
+    // Face IDs are database specific, therefore the cache is per tree.
+    let svg_glyphs = SvgGlyphs::new(svg_glyph_parser);
+    with_font_faces(fontdb, &tree.font_faces, |fontdb| {
+        convert_text(tree.root.clone(), fontdb, &svg_glyphs, report)
+    });
+
+    tree.calculate_abs_transforms();
+}
+
+/// Calls `f` with a fonts database that contains `@font-face` fonts.
+///
+/// The database is copied only when there are any `@font-face` fonts.
+fn with_font_faces<T>(
+    fontdb: &Database,
+    font_faces: &[FontFace],
+    f: impl FnOnce(&Database) -> T,
+) -> T {
+    if font_faces.is_empty() {
+        f(fontdb)
+    } else {
+        f(&load_font_faces(fontdb, font_faces))
+    }
+}
+
+/// Returns a copy of the fonts database with `@font-face` fonts added.
+///
+/// Web fonts are registered under the family name from the CSS rule
+/// and replace local fonts with the same family name.
+fn load_font_faces(fontdb: &Database, font_faces: &[FontFace]) -> Database {
+    let mut db = fontdb.clone();
+
+    let shadowed: Vec<ID> = db
+        .faces()
+        .filter(|info| {
+            info.families.iter().any(|(name, _)| {
+                font_faces
+                    .iter()
+                    .any(|face| face.family.eq_ignore_ascii_case(name))
+            })
+        })
+        .map(|info| info.id)
+        .collect();
+    for id in shadowed {
+        db.remove_face(id);
+    }
+
+    for face in font_faces {
+        let mut tmp_db = Database::new();
+        let ids = tmp_db.load_font_source(fontdb::Source::Binary(face.data.clone()));
+        if ids.is_empty() {
+            log::warn!(
+                "Failed to load a font for the '{}' font family.",
+                face.family
+            );
+            continue;
+        }
+
+        for id in ids {
+            if let Some(info) = tmp_db.face(id) {
+                // CSS descriptors take precedence over the font's own properties.
+                db.push_face_info(fontdb::FaceInfo {
+                    families: vec![(face.family.clone(), fontdb::Language::English_UnitedStates)],
+                    style: convert_font_style(face.style),
+                    weight: fontdb::Weight(face.weight),
+                    ..info.clone()
+                });
+            }
+        }
+    }
+
+    db
+}
+
+fn convert_font_style(style: FontStyle) -> fontdb::Style {
+    match style {
+        FontStyle::Normal => fontdb::Style::Normal,
+        FontStyle::Italic => fontdb::Style::Italic,
+        FontStyle::Oblique => fontdb::Style::Oblique,
     }
 }
 
-fn convert_text(root: Node, fontdb: &fontdb::Database) {
+fn convert_text(
+    root: Node,
+    fontdb: &fontdb::Database,
+    svg_glyphs: &SvgGlyphs,
+    mut report: Option<&mut FontReport>,
+) {
     let mut text_nodes = Vec::new();
     // We have to update text nodes in clipPaths, masks and patterns as well.
     for node in root.descendants() {
@@ -58,11 +200,11 @@ fn convert_text(root: Node, fontdb: &fontdb::Database) {
 
         if let NodeKind::Image(ref mut image) = *node.borrow_mut() {
             if let ImageKind::SVG(ref mut tree) = image.kind {
-                tree.convert_text(fontdb);
+                convert_tree(tree, fontdb, svg_glyphs.parser(), report.as_deref_mut());
             }
         }
 
-        node.subroots(|subroot| convert_text(subroot, fontdb))
+        node.subroots(|subroot| convert_text(subroot, fontdb, svg_glyphs, report.as_deref_mut()))
     }
 
     if text_nodes.is_empty() {
@@ -72,7 +214,9 @@ fn convert_text(root: Node, fontdb: &fontdb::Database) {
     for node in &text_nodes {
         let absolute_ts = node.parent().unwrap().abs_transform();
         if let NodeKind::Text(ref mut text) = *node.borrow_mut() {
-            if let Some((node, bbox)) = convert_node(text, fontdb, absolute_ts) {
+            if let Some((node, bbox)) =
+                convert_node(text, fontdb, svg_glyphs, absolute_ts, report.as_deref_mut())
+            {
                 text.bounding_box = Some(bbox);
                 text.flattened = Some(node);
             }
@@ -83,9 +227,11 @@ fn convert_text(root: Node, fontdb: &fontdb::Database) {
 fn convert_node(
     text: &Text,
     fontdb: &fontdb::Database,
+    svg_glyphs: &SvgGlyphs,
     absolute_ts: Transform,
+    report: Option<&mut FontReport>,
 ) -> Option<(Node, NonZeroRect)> {
-    let (new_paths, bbox) = text_to_paths(text, fontdb, absolute_ts)?;
+    let (new_nodes, bbox) = text_to_paths(text, fontdb, svg_glyphs, absolute_ts, report)?;
 
     let group = Node::new(NodeKind::Group(Group {
         id: text.id.clone(),
@@ -93,10 +239,19 @@ fn convert_node(
     }));
 
     let rendering_mode = resolve_rendering_mode(text);
-    for mut path in new_paths {
-        fix_obj_bounding_box(&mut path, bbox);
-        path.rendering_mode = rendering_mode;
-        group.append_kind(NodeKind::Path(path));
+    for node in new_nodes {
+        if let NodeKind::Path(ref mut path) = *node.borrow_mut() {
+            fix_obj_bounding_box(path, bbox);
+        }
+
+        // Color glyphs are stored in groups.
+        for child in node.descendants() {
+            if let NodeKind::Path(ref mut path) = *child.borrow_mut() {
+                path.rendering_mode = rendering_mode;
+            }
+        }
+
+        group.append(node);
     }
 
     Some((group, bbox))
@@ -104,7 +259,14 @@ fn convert_node(
 
 trait DatabaseExt {
     fn load_font(&self, id: ID) -> Option<ResolvedFont>;
-    fn outline(&self, id: ID, glyph_id: GlyphId) -> Option<tiny_skia_path::Path>;
+    fn glyph(
+        &self,
+        id: ID,
+        glyph_id: GlyphId,
+        foreground: Option<ttf_parser::RgbaColor>,
+        variations: &[rustybuzz::Variation],
+        svg_glyphs: &SvgGlyphs,
+    ) -> (Option<ColorGlyph>, Option<tiny_skia_path::Path>);
     fn has_char(&self, id: ID, c: char) -> bool;
 }
 
@@ -168,7 +330,8 @@ impl DatabaseExt for Database {
             }
 
             Some(ResolvedFont {
-                id,
+                source: FontSource::Database(id),
+                has_color_glyphs: color_glyph::has_color_glyphs(&font),
                 units_per_em,
                 ascent,
                 descent,
@@ -182,17 +345,38 @@ impl DatabaseExt for Database {
         })?
     }
 
+    /// Returns either a color glyph or a glyph outline.
+    ///
+    /// Color glyphs are looked up only when `foreground` is set.
     #[inline(never)]
-    fn outline(&self, id: ID, glyph_id: GlyphId) -> Option<tiny_skia_path::Path> {
-        self.with_face_data(id, |data, face_index| -> Option<tiny_skia_path::Path> {
-            let font = ttf_parser::Face::parse(data, face_index).ok()?;
+    fn glyph(
+        &self,
+        id: ID,
+        glyph_id: GlyphId,
+        foreground: Option<ttf_parser::RgbaColor>,
+        variations: &[rustybuzz::Variation],
+        svg_glyphs: &SvgGlyphs,
+    ) -> (Option<ColorGlyph>, Option<tiny_skia_path::Path>) {
+        let res = self.with_face_data(id, |data, face_index| {
+            let mut font = ttf_parser::Face::parse(data, face_index).ok()?;
+            set_variations(&mut font, variations);
+
+            if let Some(foreground) = foreground {
+                let color_glyph =
+                    color_glyph::color_glyph(id, &font, glyph_id, foreground, svg_glyphs);
+                if color_glyph.is_some() {
+                    return Some((color_glyph, None));
+                }
+            }
 
             let mut builder = PathBuilder {
                 builder: tiny_skia_path::PathBuilder::new(),
             };
             font.outline_glyph(glyph_id, &mut builder)?;
-            builder.builder.finish()
-        })?
+            Some((None, builder.builder.finish()))
+        });
+
+        res.flatten().unwrap_or((None, None))
     }
 
     #[inline(never)]
@@ -207,9 +391,75 @@ impl DatabaseExt for Database {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+fn set_variations(font: &mut ttf_parser::Face, variations: &[rustybuzz::Variation]) {
+    for variation in variations {
+        // Unknown axes are ignored.
+        let _ = font.set_variation(variation.tag, variation.value);
+    }
+}
+
+/// Resolves font variation axes for a span.
+///
+/// `wght`, `wdth` and `opsz` axes are set according to the font weight, stretch and size
+/// and can be overridden by `font-variation-settings`.
+/// Fonts without those axes are not affected.
+fn resolve_font_variations(span: &TextSpan) -> Vec<rustybuzz::Variation> {
+    let width = match span.font.stretch {
+        FontStretch::UltraCondensed => 50.0,
+        FontStretch::ExtraCondensed => 62.5,
+        FontStretch::Condensed => 75.0,
+        FontStretch::SemiCondensed => 87.5,
+        FontStretch::Normal => 100.0,
+        FontStretch::SemiExpanded => 112.5,
+        FontStretch::Expanded => 125.0,
+        FontStretch::ExtraExpanded => 150.0,
+        FontStretch::UltraExpanded => 200.0,
+    };
+
+    let mut variations = vec![
+        rustybuzz::Variation {
+            tag: ttf_parser::Tag::from_bytes(b"wght"),
+            value: span.font.weight as f32,
+        },
+        rustybuzz::Variation {
+            tag: ttf_parser::Tag::from_bytes(b"wdth"),
+            value: width,
+        },
+    ];
+
+    if span.apply_optical_sizing {
+        variations.push(rustybuzz::Variation {
+            tag: ttf_parser::Tag::from_bytes(b"opsz"),
+            value: span.font_size.get(),
+        });
+    }
+
+    // Later values override earlier ones.
+    for variation in &span.font_variations {
+        variations.push(rustybuzz::Variation {
+            tag: ttf_parser::Tag::from_bytes(&variation.tag),
+            value: variation.value,
+        });
+    }
+
+    variations
+}
+
+/// A font data source.
+#[derive(Clone, Debug)]
+enum FontSource {
+    /// A font from the fonts database.
+    Database(ID),
+    /// An SVG font defined in the document itself.
+    Svg(Rc<SvgFont>),
+}
+
+#[derive(Clone, Debug)]
 struct ResolvedFont {
-    id: ID,
+    source: FontSource,
+
+    /// Whether the face has any color glyph tables.
+    has_color_glyphs: bool,
 
     units_per_em: NonZeroU16,
 
@@ -231,6 +481,36 @@ struct ResolvedFont {
 }
 
 impl ResolvedFont {
+    fn from_svg_font(font: Rc<SvgFont>) -> Option<Self> {
+        let units_per_em = font.units_per_em.get().round().min(u16::MAX as f32) as u16;
+        let units_per_em = NonZeroU16::new(units_per_em)?;
+
+        let ascent = font.ascent.round() as i16;
+        let descent = font.descent.round() as i16;
+
+        let x_height = match font.x_height {
+            Some(height) => height.round() as i32,
+            // Same fallback as for TrueType fonts.
+            None => (f32::from(ascent - descent) * 0.45) as i32,
+        };
+        let x_height = u16::try_from(x_height).ok().and_then(NonZeroU16::new)?;
+
+        Some(ResolvedFont {
+            source: FontSource::Svg(font),
+            has_color_glyphs: false,
+            units_per_em,
+            ascent,
+            descent,
+            x_height,
+            underline_position: -(units_per_em.get() as i16) / 9,
+            underline_thickness: NonZeroU16::new(units_per_em.get() / 12)
+                .unwrap_or(NonZeroU16::new(1).unwrap()),
+            line_through_position: x_height.get() as i16 / 2,
+            subscript_offset: (units_per_em.get() as f32 * 0.2).round() as i16,
+            superscript_offset: (units_per_em.get() as f32 * 0.4).round() as i16,
+        })
+    }
+
     #[inline]
     fn scale(&self, font_size: f32) -> f32 {
         font_size / self.units_per_em.get() as f32
@@ -281,25 +561,6 @@ impl ResolvedFont {
         self.superscript_offset as f32 * self.scale(font_size)
     }
 
-    fn dominant_baseline_shift(&self, baseline: DominantBaseline, font_size: f32) -> f32 {
-        let alignment = match baseline {
-            DominantBaseline::Auto => AlignmentBaseline::Auto,
-            DominantBaseline::UseScript => AlignmentBaseline::Auto, // unsupported
-            DominantBaseline::NoChange => AlignmentBaseline::Auto,  // already resolved
-            DominantBaseline::ResetSize => AlignmentBaseline::Auto, // unsupported
-            DominantBaseline::Ideographic => AlignmentBaseline::Ideographic,
-            DominantBaseline::Alphabetic => AlignmentBaseline::Alphabetic,
-            DominantBaseline::Hanging => AlignmentBaseline::Hanging,
-            DominantBaseline::Mathematical => AlignmentBaseline::Mathematical,
-            DominantBaseline::Central => AlignmentBaseline::Central,
-            DominantBaseline::Middle => AlignmentBaseline::Middle,
-            DominantBaseline::TextAfterEdge => AlignmentBaseline::TextAfterEdge,
-            DominantBaseline::TextBeforeEdge => AlignmentBaseline::TextBeforeEdge,
-        };
-
-        self.alignment_baseline_shift(alignment, font_size)
-    }
-
     // The `alignment-baseline` property is a mess.
     //
     // The SVG 1.1 spec (https://www.w3.org/TR/SVG11/text.html#BaselineAlignmentProperties)
@@ -431,58 +692,130 @@ fn span_contains(span: &TextSpan, byte_offset: ByteIndex) -> bool {
 //
 // See `alignment_baseline_shift` method comment for more details.
 fn resolve_baseline(span: &TextSpan, font: &ResolvedFont, writing_mode: WritingMode) -> f32 {
-    let mut shift = -resolve_baseline_shift(&span.baseline_shift, font, span.font_size.get());
+    let font_size = span.font_size.get();
+    let mut shift = -resolve_baseline_shift(&span.baseline_shift, font, font_size);
 
-    // TODO: support vertical layout as well
-    if writing_mode == WritingMode::LeftToRight {
-        if span.alignment_baseline == AlignmentBaseline::Auto
-            || span.alignment_baseline == AlignmentBaseline::Baseline
-        {
-            shift += font.dominant_baseline_shift(span.dominant_baseline, span.font_size.get());
-        } else {
-            shift += font.alignment_baseline_shift(span.alignment_baseline, span.font_size.get());
+    let alignment = if span.alignment_baseline == AlignmentBaseline::Auto
+        || span.alignment_baseline == AlignmentBaseline::Baseline
+    {
+        dominant_to_alignment_baseline(span.dominant_baseline)
+    } else {
+        span.alignment_baseline
+    };
+
+    match writing_mode {
+        WritingMode::LeftToRight => shift += font.alignment_baseline_shift(alignment, font_size),
+        WritingMode::TopToBottom => {
+            // The central baseline is used by default in the vertical writing mode
+            // and clusters are already aligned by it.
+            // So other baselines must be relative to the central one.
+            if alignment != AlignmentBaseline::Auto {
+                shift += font.alignment_baseline_shift(alignment, font_size)
+                    - font.alignment_baseline_shift(AlignmentBaseline::Central, font_size);
+            }
         }
     }
 
     shift
 }
 
+fn dominant_to_alignment_baseline(baseline: DominantBaseline) -> AlignmentBaseline {
+    match baseline {
+        DominantBaseline::Auto => AlignmentBaseline::Auto,
+        DominantBaseline::UseScript => AlignmentBaseline::Auto, // unsupported
+        DominantBaseline::NoChange => AlignmentBaseline::Auto,  // already resolved
+        DominantBaseline::ResetSize => AlignmentBaseline::Auto, // unsupported
+        DominantBaseline::Ideographic => AlignmentBaseline::Ideographic,
+        DominantBaseline::Alphabetic => AlignmentBaseline::Alphabetic,
+        DominantBaseline::Hanging => AlignmentBaseline::Hanging,
+        DominantBaseline::Mathematical => AlignmentBaseline::Mathematical,
+        DominantBaseline::Central => AlignmentBaseline::Central,
+        DominantBaseline::Middle => AlignmentBaseline::Middle,
+        DominantBaseline::TextAfterEdge => AlignmentBaseline::TextAfterEdge,
+        DominantBaseline::TextBeforeEdge => AlignmentBaseline::TextBeforeEdge,
+    }
+}
+
 type FontsCache = HashMap<Font, Rc<ResolvedFont>>;
 
-fn text_to_paths(
+/// A text chunk with positioned clusters.
+struct LaidOutChunk {
+    chunk: TextChunk,
+    clusters: Vec<GlyphCluster>,
+    /// Chunk's start position.
+    x: f32,
+    y: f32,
+    /// A chunk transform. Rotates a vertical text.
+    transform: Transform,
+}
+
+impl LaidOutChunk {
+    /// Returns a transform that maps span's clusters into the text coordinates.
+    fn span_transform(
+        &self,
+        span: &TextSpan,
+        font: &ResolvedFont,
+        writing_mode: WritingMode,
+    ) -> Transform {
+        let mut span_ts = self.transform.pre_translate(self.x, self.y);
+        if let TextFlow::Linear = self.chunk.text_flow {
+            let shift = resolve_baseline(span, font, writing_mode);
+
+            // In case of a horizontal flow, shift transform and not clusters,
+            // because clusters can be rotated and an additional shift will lead
+            // to invalid results.
+            span_ts = span_ts.pre_translate(0.0, shift);
+        }
+
+        span_ts
+    }
+}
+
+/// Lays out a text node.
+///
+/// `abs_ts` is used only to measure text paths more precisely.
+fn layout_chunks(
     text_node: &Text,
     fontdb: &fontdb::Database,
     abs_ts: Transform,
-) -> Option<(Vec<Path>, NonZeroRect)> {
+) -> (FontsCache, Vec<LaidOutChunk>) {
     let mut fonts_cache: FontsCache = HashMap::new();
     for chunk in &text_node.chunks {
         for span in &chunk.spans {
             if !fonts_cache.contains_key(&span.font) {
-                if let Some(font) = resolve_font(&span.font, fontdb) {
+                if let Some(font) = resolve_font(&span.font, &text_node.svg_fonts, fontdb) {
                     fonts_cache.insert(span.font.clone(), Rc::new(font));
                 }
             }
         }
     }
 
-    let mut bbox = BBox::default();
+    let wrapped_text;
+    let text_node = match wrap::wrap_text(text_node, &fonts_cache, fontdb) {
+        Some(v) => {
+            wrapped_text = v;
+            &wrapped_text
+        }
+        None => text_node,
+    };
+
+    let mut chunks = Vec::new();
     let mut char_offset = 0;
     let mut last_x = 0.0;
     let mut last_y = 0.0;
-    let mut new_paths = Vec::new();
     for chunk in &text_node.chunks {
         let (x, y) = match chunk.text_flow {
             TextFlow::Linear => (chunk.x.unwrap_or(last_x), chunk.y.unwrap_or(last_y)),
             TextFlow::Path(_) => (0.0, 0.0),
         };
 
-        let mut clusters = outline_chunk(chunk, &fonts_cache, fontdb);
+        let mut clusters = form_clusters(chunk, text_node.writing_mode, &fonts_cache, fontdb);
         if clusters.is_empty() {
             char_offset += chunk.text.chars().count();
             continue;
         }
 
-        apply_writing_mode(text_node.writing_mode, &mut clusters);
+        apply_writing_mode(text_node.writing_mode, chunk, &mut clusters);
         apply_letter_spacing(chunk, &mut clusters);
         apply_word_spacing(chunk, &mut clusters);
         apply_length_adjust(chunk, &mut clusters);
@@ -496,31 +829,60 @@ fn text_to_paths(
             &mut clusters,
         );
 
-        let mut text_ts = Transform::default();
+        let mut transform = Transform::default();
         if text_node.writing_mode == WritingMode::TopToBottom {
             if let TextFlow::Linear = chunk.text_flow {
-                text_ts = text_ts.pre_rotate_at(90.0, x, y);
+                transform = transform.pre_rotate_at(90.0, x, y);
             }
         }
 
-        for span in &chunk.spans {
+        char_offset += chunk.text.chars().count();
+
+        if text_node.writing_mode == WritingMode::TopToBottom {
+            if let TextFlow::Linear = chunk.text_flow {
+                std::mem::swap(&mut curr_pos.0, &mut curr_pos.1);
+            }
+        }
+
+        last_x = x + curr_pos.0;
+        last_y = y + curr_pos.1;
+
+        chunks.push(LaidOutChunk {
+            chunk: chunk.clone(),
+            clusters,
+            x,
+            y,
+            transform,
+        });
+    }
+
+    (fonts_cache, chunks)
+}
+
+fn text_to_paths(
+    text_node: &Text,
+    fontdb: &fontdb::Database,
+    svg_glyphs: &SvgGlyphs,
+    abs_ts: Transform,
+    report: Option<&mut FontReport>,
+) -> Option<(Vec<Node>, NonZeroRect)> {
+    let (fonts_cache, chunks) = layout_chunks(text_node, fontdb, abs_ts);
+    if let Some(report) = report {
+        report::collect(text_node, &fonts_cache, &chunks, fontdb, report);
+    }
+
+    let mut bbox = BBox::default();
+    let mut new_nodes = Vec::new();
+    for laid_out in chunks {
+        for span in &laid_out.chunk.spans {
             let font = match fonts_cache.get(&span.font) {
                 Some(v) => v,
                 None => continue,
             };
 
-            let decoration_spans = collect_decoration_spans(span, &clusters);
+            let decoration_spans = collect_decoration_spans(span, &laid_out.clusters);
 
-            let mut span_ts = text_ts;
-            span_ts = span_ts.pre_translate(x, y);
-            if let TextFlow::Linear = chunk.text_flow {
-                let shift = resolve_baseline(span, font, text_node.writing_mode);
-
-                // In case of a horizontal flow, shift transform and not clusters,
-                // because clusters can be rotated and an additional shift will lead
-                // to invalid results.
-                span_ts = span_ts.pre_translate(0.0, shift);
-            }
+            let span_ts = laid_out.span_transform(span, font, text_node.writing_mode);
 
             if let Some(decoration) = span.decoration.underline.clone() {
                 // TODO: No idea what offset should be used for top-to-bottom layout.
@@ -536,7 +898,7 @@ fn text_to_paths(
                     convert_decoration(offset, span, font, decoration, &decoration_spans, span_ts)
                 {
                     bbox = bbox.expand(path.data.bounds());
-                    new_paths.push(path);
+                    new_nodes.push(Node::new(NodeKind::Path(path)));
                 }
             }
 
@@ -550,13 +912,15 @@ fn text_to_paths(
                     convert_decoration(offset, span, font, decoration, &decoration_spans, span_ts)
                 {
                     bbox = bbox.expand(path.data.bounds());
-                    new_paths.push(path);
+                    new_nodes.push(Node::new(NodeKind::Path(path)));
                 }
             }
 
-            if let Some((path, span_bbox)) = convert_span(span, &mut clusters, span_ts) {
+            if let Some((nodes, span_bbox)) =
+                convert_span(span, &laid_out.clusters, span_ts, fontdb, svg_glyphs)
+            {
                 bbox = bbox.expand(span_bbox);
-                new_paths.push(path);
+                new_nodes.extend(nodes);
             }
 
             if let Some(decoration) = span.decoration.line_through.clone() {
@@ -569,28 +933,21 @@ fn text_to_paths(
                     convert_decoration(offset, span, font, decoration, &decoration_spans, span_ts)
                 {
                     bbox = bbox.expand(path.data.bounds());
-                    new_paths.push(path);
+                    new_nodes.push(Node::new(NodeKind::Path(path)));
                 }
             }
         }
-
-        char_offset += chunk.text.chars().count();
-
-        if text_node.writing_mode == WritingMode::TopToBottom {
-            if let TextFlow::Linear = chunk.text_flow {
-                std::mem::swap(&mut curr_pos.0, &mut curr_pos.1);
-            }
-        }
-
-        last_x = x + curr_pos.0;
-        last_y = y + curr_pos.1;
     }
 
     let bbox = bbox.to_non_zero_rect()?;
-    Some((new_paths, bbox))
+    Some((new_nodes, bbox))
 }
 
-fn resolve_font(font: &Font, fontdb: &fontdb::Database) -> Option<ResolvedFont> {
+fn resolve_font(
+    font: &Font,
+    svg_fonts: &[Rc<SvgFont>],
+    fontdb: &fontdb::Database,
+) -> Option<ResolvedFont> {
     let mut name_list = Vec::new();
     for family in &font.families {
         name_list.push(match family.as_str() {
@@ -603,9 +960,6 @@ fn resolve_font(font: &Font, fontdb: &fontdb::Database) -> Option<ResolvedFont>
         });
     }
 
-    // Use the default font as fallback.
-    name_list.push(fontdb::Family::Serif);
-
     let stretch = match font.stretch {
         FontStretch::UltraCondensed => fontdb::Stretch::UltraCondensed,
         FontStretch::ExtraCondensed => fontdb::Stretch::ExtraCondensed,
@@ -618,11 +972,31 @@ fn resolve_font(font: &Font, fontdb: &fontdb::Database) -> Option<ResolvedFont>
         FontStretch::UltraExpanded => fontdb::Stretch::UltraExpanded,
     };
 
-    let style = match font.style {
-        FontStyle::Normal => fontdb::Style::Normal,
-        FontStyle::Italic => fontdb::Style::Italic,
-        FontStyle::Oblique => fontdb::Style::Oblique,
-    };
+    let style = convert_font_style(font.style);
+
+    // SVG fonts take precedence over the fonts database,
+    // but only until we reach a family that is available in the database.
+    if !svg_fonts.is_empty() {
+        for (family, name) in font.families.iter().zip(&name_list) {
+            if let Some(svg_font) = find_svg_font(family, font, svg_fonts) {
+                return ResolvedFont::from_svg_font(svg_font);
+            }
+
+            let query = fontdb::Query {
+                families: std::slice::from_ref(name),
+                weight: fontdb::Weight(font.weight),
+                stretch,
+                style,
+            };
+
+            if fontdb.query(&query).is_some() {
+                break;
+            }
+        }
+    }
+
+    // Use the default font as fallback.
+    name_list.push(fontdb::Family::Serif);
 
     let query = fontdb::Query {
         families: &name_list,
@@ -639,13 +1013,33 @@ fn resolve_font(font: &Font, fontdb: &fontdb::Database) -> Option<ResolvedFont>
     fontdb.load_font(id?)
 }
 
+/// Finds the closest SVG font with the specified family.
+fn find_svg_font(family: &str, font: &Font, svg_fonts: &[Rc<SvgFont>]) -> Option<Rc<SvgFont>> {
+    svg_fonts
+        .iter()
+        .filter(|f| f.family.eq_ignore_ascii_case(family))
+        .min_by_key(|f| {
+            (
+                f.style != font.style,
+                (f.weight as i32 - font.weight as i32).abs(),
+            )
+        })
+        .cloned()
+}
+
 fn convert_span(
     span: &TextSpan,
-    clusters: &mut [OutlinedCluster],
+    clusters: &[GlyphCluster],
     text_ts: Transform,
-) -> Option<(Path, NonZeroRect)> {
+    fontdb: &fontdb::Database,
+    svg_glyphs: &SvgGlyphs,
+) -> Option<(Vec<Node>, NonZeroRect)> {
     let mut path_builder = tiny_skia_path::PathBuilder::new();
     let mut bboxes_builder = tiny_skia_path::PathBuilder::new();
+    let mut color_glyphs = Vec::new();
+
+    let foreground = resolve_foreground_color(span);
+    let variations = resolve_font_variations(span);
 
     for cluster in clusters {
         if !cluster.visible {
@@ -653,15 +1047,18 @@ fn convert_span(
         }
 
         if span_contains(span, cluster.byte_idx) {
-            let path = cluster
-                .path
-                .take()
-                .and_then(|p| p.transform(cluster.transform));
+            let (path, cluster_color_glyphs) =
+                outline_cluster(cluster, foreground, &variations, fontdb, svg_glyphs);
 
-            if let Some(path) = path {
+            if let Some(path) = path.and_then(|p| p.transform(cluster.transform)) {
                 path_builder.push_path(&path);
             }
 
+            for (glyph, ts) in cluster_color_glyphs {
+                let ts = text_ts.pre_concat(cluster.transform).pre_concat(ts);
+                color_glyphs.push(convert_color_glyph(span, glyph, ts));
+            }
+
             // TODO: make sure `advance` is never negative beforehand.
             let mut advance = cluster.advance;
             if advance <= 0.0 {
@@ -678,38 +1075,81 @@ fn convert_span(
         }
     }
 
-    let mut path = path_builder.finish()?;
-    path = path.transform(text_ts)?;
-
     let mut bboxes = bboxes_builder.finish()?;
     bboxes = bboxes.transform(text_ts)?;
+    let bbox = bboxes.compute_tight_bounds()?.to_non_zero_rect()?;
+
+    let mut nodes = Vec::new();
+    if let Some(path) = path_builder.finish().and_then(|p| p.transform(text_ts)) {
+        let mut fill = span.fill.clone();
+        if let Some(ref mut fill) = fill {
+            // The `fill-rule` should be ignored.
+            // https://www.w3.org/TR/SVG2/text.html#TextRenderingOrder
+            //
+            // 'Since the fill-rule property does not apply to SVG text elements,
+            // the specific order of the subpaths within the equivalent path does not matter.'
+            fill.rule = FillRule::NonZero;
+        }
 
-    let mut fill = span.fill.clone();
-    if let Some(ref mut fill) = fill {
-        // The `fill-rule` should be ignored.
-        // https://www.w3.org/TR/SVG2/text.html#TextRenderingOrder
-        //
-        // 'Since the fill-rule property does not apply to SVG text elements,
-        // the specific order of the subpaths within the equivalent path does not matter.'
-        fill.rule = FillRule::NonZero;
+        nodes.push(Node::new(NodeKind::Path(Path {
+            id: String::new(),
+            visibility: span.visibility,
+            pointer_events: span.pointer_events,
+            fill,
+            stroke: span.stroke.clone(),
+            paint_order: span.paint_order,
+            rendering_mode: ShapeRendering::default(),
+            data: Arc::new(path),
+        })));
     }
 
-    let bbox = bboxes.compute_tight_bounds()?.to_non_zero_rect()?;
+    // Color glyphs are rendered after outlines and are not affected by fill and stroke.
+    nodes.extend(color_glyphs);
 
-    let path = Path {
-        id: String::new(),
-        visibility: span.visibility,
-        fill,
-        stroke: span.stroke.clone(),
-        paint_order: span.paint_order,
-        rendering_mode: ShapeRendering::default(),
-        data: Rc::new(path),
-    };
+    if nodes.is_empty() {
+        return None;
+    }
 
-    Some((path, bbox))
+    Some((nodes, bbox))
 }
 
-fn collect_decoration_spans(span: &TextSpan, clusters: &[OutlinedCluster]) -> Vec<DecorationSpan> {
+/// Converts a color glyph into a group with the specified transform.
+fn convert_color_glyph(span: &TextSpan, glyph: ColorGlyph, transform: Transform) -> Node {
+    let group = Node::new(NodeKind::Group(Group {
+        transform,
+        ..Group::default()
+    }));
+
+    match glyph {
+        ColorGlyph::Layers(node) => {
+            for child in node.descendants() {
+                if let NodeKind::Path(ref mut path) = *child.borrow_mut() {
+                    path.visibility = span.visibility;
+                    path.pointer_events = span.pointer_events;
+                }
+            }
+
+            group.append(node);
+        }
+        ColorGlyph::Image { kind, rect } => {
+            group.append_kind(NodeKind::Image(Image {
+                id: String::new(),
+                visibility: span.visibility,
+                pointer_events: span.pointer_events,
+                view_box: ViewBox {
+                    rect,
+                    aspect: AspectRatio::default(),
+                },
+                rendering_mode: ImageRendering::default(),
+                kind,
+            }));
+        }
+    }
+
+    group
+}
+
+fn collect_decoration_spans(span: &TextSpan, clusters: &[GlyphCluster]) -> Vec<DecorationSpan> {
     let mut spans = Vec::new();
 
     let mut started = false;
@@ -778,8 +1218,9 @@ fn convert_decoration(
     let mut path_data = builder.finish()?;
     path_data = path_data.transform(transform)?;
 
-    let mut path = Path::new(Rc::new(path_data));
+    let mut path = Path::new(Arc::new(path_data));
     path.visibility = span.visibility;
+    path.pointer_events = span.pointer_events;
     path.fill = decoration.fill.take();
     path.stroke = decoration.stroke.take();
     Some(path)
@@ -831,6 +1272,7 @@ fn paint_server_to_user_space_on_use(paint: Paint, bbox: NonZeroRect) -> Option<
                     units: Units::UserSpaceOnUse,
                     transform,
                     spread_method: lg.spread_method,
+                    color_interpolation: lg.color_interpolation,
                     stops: lg.stops.clone(),
                 },
             }))
@@ -843,11 +1285,13 @@ fn paint_server_to_user_space_on_use(paint: Paint, bbox: NonZeroRect) -> Option<
                 r: rg.r,
                 fx: rg.fx,
                 fy: rg.fy,
+                fr: rg.fr,
                 base: BaseGradient {
                     id: String::new(),
                     units: Units::UserSpaceOnUse,
                     transform,
                     spread_method: rg.spread_method,
+                    color_interpolation: rg.color_interpolation,
                     stops: rg.stops.clone(),
                 },
             }))
@@ -899,8 +1343,16 @@ struct Glyph {
     dy: i32,
 
     /// The glyph width / X-advance in font units.
+    ///
+    /// A Y-advance for upright glyphs.
     width: i32,
 
+    /// Indicates that the glyph was shaped vertically.
+    ///
+    /// Such glyphs are positioned using vertical metrics
+    /// and their offsets are relative to the vertical origin.
+    upright: bool,
+
     /// Reference to the source font.
     ///
     /// Each glyph can have it's own source font.
@@ -913,16 +1365,16 @@ impl Glyph {
     }
 }
 
-/// An outlined cluster.
+/// A glyph cluster.
 ///
 /// Cluster/grapheme is a single, unbroken, renderable character.
 /// It can be positioned, rotated, spaced, etc.
 ///
 /// Let's say we have `й` which is *CYRILLIC SMALL LETTER I* and *COMBINING BREVE*.
 /// It consists of two code points, will be shaped (via harfbuzz) as two glyphs into one cluster,
-/// and then will be combined into the one `OutlinedCluster`.
+/// and then will be combined into the one `GlyphCluster`.
 #[derive(Clone)]
-struct OutlinedCluster {
+struct GlyphCluster {
     /// Position in bytes in the original string.
     ///
     /// We use it to match a cluster with a character in the text chunk and therefore with the style.
@@ -950,17 +1402,16 @@ struct OutlinedCluster {
     /// A descent in SVG coordinates.
     descent: f32,
 
-    /// A x-height in SVG coordinates.
-    x_height: f32,
-
     /// Indicates that this cluster was affected by the relative shift (via dx/dy attributes)
     /// during the text layouting. Which breaks the `text-decoration` line.
     ///
     /// Used during the `text-decoration` processing.
     has_relative_shift: bool,
 
-    /// An actual outline.
-    path: Option<tiny_skia_path::Path>,
+    /// Cluster's glyphs.
+    ///
+    /// Glyph transforms are relative to the cluster.
+    glyphs: Vec<PositionedGlyph>,
 
     /// A cluster's transform that contains it's position, rotation, etc.
     transform: Transform,
@@ -969,14 +1420,33 @@ struct OutlinedCluster {
     ///
     /// For example, if a cluster is outside the text path than it should not be rendered.
     visible: bool,
+
+    /// Indicates that the cluster was shaped vertically and is already upright.
+    upright: bool,
 }
 
-impl OutlinedCluster {
+impl GlyphCluster {
     fn height(&self) -> f32 {
         self.ascent - self.descent
     }
 }
 
+/// A glyph positioned inside a cluster.
+#[derive(Clone)]
+struct PositionedGlyph {
+    /// The glyph ID in the font.
+    id: GlyphId,
+
+    /// Reference to the source font.
+    font: Rc<ResolvedFont>,
+
+    /// The glyph advance in the cluster coordinates.
+    advance: f32,
+
+    /// Maps font units into the cluster coordinates.
+    transform: Transform,
+}
+
 /// An iterator over glyph clusters.
 ///
 /// Input:  0 2 2 2 3 4 4 5 5
@@ -1014,15 +1484,16 @@ impl<'a> Iterator for GlyphClusters<'a> {
     }
 }
 
-/// Converts a text chunk into a list of outlined clusters.
+/// Converts a text chunk into a list of glyph clusters.
 ///
-/// This function will do the BIDI reordering, text shaping and glyphs outlining,
+/// This function will do the BIDI reordering and text shaping,
 /// but not the text layouting. So all clusters are in the 0x0 position.
-fn outline_chunk(
+fn form_clusters(
     chunk: &TextChunk,
+    writing_mode: WritingMode,
     fonts_cache: &FontsCache,
     fontdb: &fontdb::Database,
-) -> Vec<OutlinedCluster> {
+) -> Vec<GlyphCluster> {
     let mut glyphs = Vec::new();
     for span in &chunk.spans {
         let font = match fonts_cache.get(&span.font) {
@@ -1030,13 +1501,12 @@ fn outline_chunk(
             None => continue,
         };
 
-        let tmp_glyphs = shape_text(
-            &chunk.text,
-            font,
-            span.small_caps,
-            span.apply_kerning,
-            fontdb,
-        );
+        let orientation = match writing_mode {
+            WritingMode::LeftToRight => None,
+            WritingMode::TopToBottom => Some(span.text_orientation),
+        };
+
+        let tmp_glyphs = shape_text(&chunk.text, font, span, orientation, fontdb);
 
         // Do nothing with the first run.
         if glyphs.is_empty() {
@@ -1063,11 +1533,10 @@ fn outline_chunk(
     let mut clusters = Vec::new();
     for (range, byte_idx) in GlyphClusters::new(&glyphs) {
         if let Some(span) = chunk_span_at(chunk, byte_idx) {
-            clusters.push(outline_cluster(
+            clusters.push(form_cluster(
                 &glyphs[range],
                 &chunk.text,
                 span.font_size.get(),
-                fontdb,
             ));
         }
     }
@@ -1076,18 +1545,29 @@ fn outline_chunk(
 }
 
 /// Text shaping with font fallback.
+///
+/// `orientation` must be set only in the vertical writing mode.
 fn shape_text(
     text: &str,
     font: Rc<ResolvedFont>,
-    small_caps: bool,
-    apply_kerning: bool,
+    span: &TextSpan,
+    orientation: Option<TextOrientation>,
     fontdb: &fontdb::Database,
 ) -> Vec<Glyph> {
-    let mut glyphs = shape_text_with_font(text, font.clone(), small_caps, apply_kerning, fontdb)
-        .unwrap_or_default();
+    let base_font_id = match font.source {
+        FontSource::Database(id) => id,
+        FontSource::Svg(ref svg_font) => {
+            // SVG fonts always have a missing glyph, so there is no fallback.
+            let svg_font = svg_font.clone();
+            return shape_text_with_svg_font(text, font, &svg_font, span.apply_kerning);
+        }
+    };
+
+    let mut glyphs =
+        shape_text_with_font(text, font.clone(), span, orientation, fontdb).unwrap_or_default();
 
     // Remember all fonts used for shaping.
-    let mut used_fonts = vec![font.id];
+    let mut used_fonts = vec![base_font_id];
 
     // Loop until all glyphs become resolved or until no more fonts are left.
     'outer: loop {
@@ -1100,20 +1580,17 @@ fn shape_text(
         }
 
         if let Some(c) = missing {
-            let fallback_font = match find_font_for_char(c, &used_fonts, fontdb) {
-                Some(v) => Rc::new(v),
+            let (fallback_font_id, fallback_font) = match find_font_for_char(c, &used_fonts, fontdb)
+            {
+                Some(v) => v,
                 None => break 'outer,
             };
+            let fallback_font = Rc::new(fallback_font);
 
             // Shape again, using a new font.
-            let fallback_glyphs = shape_text_with_font(
-                text,
-                fallback_font.clone(),
-                small_caps,
-                apply_kerning,
-                fontdb,
-            )
-            .unwrap_or_default();
+            let fallback_glyphs =
+                shape_text_with_font(text, fallback_font.clone(), span, orientation, fontdb)
+                    .unwrap_or_default();
 
             let all_matched = fallback_glyphs.iter().all(|g| !g.is_missing());
             if all_matched {
@@ -1138,7 +1615,7 @@ fn shape_text(
             }
 
             // Remember this font.
-            used_fonts.push(fallback_font.id);
+            used_fonts.push(fallback_font_id);
         } else {
             break 'outer;
         }
@@ -1163,15 +1640,24 @@ fn shape_text(
 /// Converts a text into a list of glyph IDs.
 ///
 /// This function will do the BIDI reordering and text shaping.
+///
+/// In the vertical writing mode, upright characters are shaped vertically,
+/// which enables the `vert` feature and vertical metrics.
 fn shape_text_with_font(
     text: &str,
     font: Rc<ResolvedFont>,
-    small_caps: bool,
-    apply_kerning: bool,
+    span: &TextSpan,
+    orientation: Option<TextOrientation>,
     fontdb: &fontdb::Database,
 ) -> Option<Vec<Glyph>> {
-    fontdb.with_face_data(font.id, |font_data, face_index| -> Option<Vec<Glyph>> {
-        let rb_font = rustybuzz::Face::from_slice(font_data, face_index)?;
+    let id = match font.source {
+        FontSource::Database(id) => id,
+        FontSource::Svg(_) => return None,
+    };
+
+    fontdb.with_face_data(id, |font_data, face_index| -> Option<Vec<Glyph>> {
+        let mut rb_font = rustybuzz::Face::from_slice(font_data, face_index)?;
+        rb_font.set_variations(&resolve_font_variations(span));
 
         let bidi_info = unicode_bidi::BidiInfo::new(text, Some(unicode_bidi::Level::ltr()));
         let paragraph = &bidi_info.paragraphs[0];
@@ -1181,103 +1667,274 @@ fn shape_text_with_font(
 
         let (levels, runs) = bidi_info.visual_runs(paragraph, line);
         for run in runs.iter() {
-            let sub_text = &text[run.clone()];
-            if sub_text.is_empty() {
-                continue;
+            let is_rtl = levels[run.start].is_rtl();
+            let mut sub_runs = split_by_orientation(text, run.clone(), orientation);
+            if is_rtl {
+                sub_runs.reverse();
             }
 
-            let hb_direction = if levels[run.start].is_rtl() {
-                rustybuzz::Direction::RightToLeft
-            } else {
-                rustybuzz::Direction::LeftToRight
-            };
+            for (sub_run, upright) in sub_runs {
+                let sub_text = &text[sub_run.clone()];
+                if sub_text.is_empty() {
+                    continue;
+                }
+
+                let hb_direction = if upright {
+                    rustybuzz::Direction::TopToBottom
+                } else if is_rtl {
+                    rustybuzz::Direction::RightToLeft
+                } else {
+                    rustybuzz::Direction::LeftToRight
+                };
+
+                let mut buffer = rustybuzz::UnicodeBuffer::new();
+                buffer.push_str(sub_text);
+                buffer.set_direction(hb_direction);
+
+                let mut features = Vec::new();
+                if span.small_caps {
+                    features.push(rustybuzz::Feature::new(
+                        ttf_parser::Tag::from_bytes(b"smcp"),
+                        1,
+                        ..,
+                    ));
+                }
 
-            let mut buffer = rustybuzz::UnicodeBuffer::new();
-            buffer.push_str(sub_text);
-            buffer.set_direction(hb_direction);
-
-            let mut features = Vec::new();
-            if small_caps {
-                features.push(rustybuzz::Feature::new(
-                    rustybuzz::Tag::from_bytes(b"smcp"),
-                    1,
-                    ..,
-                ));
+                if !span.apply_kerning {
+                    features.push(rustybuzz::Feature::new(
+                        ttf_parser::Tag::from_bytes(b"kern"),
+                        0,
+                        ..,
+                    ));
+                }
+
+                for feature in &span.font_features {
+                    features.push(rustybuzz::Feature::new(
+                        ttf_parser::Tag::from_bytes(&feature.tag),
+                        feature.value,
+                        ..,
+                    ));
+                }
+
+                let output = rustybuzz::shape(&rb_font, &features, buffer);
+
+                let positions = output.glyph_positions();
+                let infos = output.glyph_infos();
+
+                for (pos, info) in positions.iter().zip(infos) {
+                    let idx = sub_run.start + info.cluster as usize;
+                    debug_assert!(text.get(idx..).is_some());
+
+                    glyphs.push(Glyph {
+                        byte_idx: ByteIndex::new(idx),
+                        id: GlyphId(info.glyph_id as u16),
+                        dx: pos.x_offset,
+                        dy: pos.y_offset,
+                        // Vertical advance goes down, while the Y axis goes up.
+                        width: if upright {
+                            -pos.y_advance
+                        } else {
+                            pos.x_advance
+                        },
+                        upright,
+                        font: font.clone(),
+                    });
+                }
             }
+        }
+
+        Some(glyphs)
+    })?
+}
 
-            if !apply_kerning {
-                features.push(rustybuzz::Feature::new(
-                    rustybuzz::Tag::from_bytes(b"kern"),
-                    0,
-                    ..,
-                ));
+/// Splits a text run into sub-runs with the same vertical orientation.
+///
+/// Returns a single sideways sub-run in the horizontal writing mode.
+fn split_by_orientation(
+    text: &str,
+    run: std::ops::Range<usize>,
+    orientation: Option<TextOrientation>,
+) -> Vec<(std::ops::Range<usize>, bool)> {
+    let orientation = match orientation {
+        Some(v) => v,
+        None => return vec![(run, false)],
+    };
+
+    let mut sub_runs: Vec<(std::ops::Range<usize>, bool)> = Vec::new();
+    for (i, c) in text[run.clone()].char_indices() {
+        let idx = run.start + i;
+        let upright = is_upright(c, orientation);
+        match sub_runs.last_mut() {
+            Some((range, prev_upright)) if *prev_upright == upright => {
+                range.end = idx + c.len_utf8();
             }
+            _ => sub_runs.push((idx..idx + c.len_utf8(), upright)),
+        }
+    }
 
-            let output = rustybuzz::shape(&rb_font, &features, buffer);
+    sub_runs
+}
+
+/// Checks that a character should be upright in the vertical writing mode.
+///
+/// Uses [Unicode Vertical_Orientation Property](https://www.unicode.org/reports/tr50/tr50-19.html)
+/// for the mixed orientation.
+fn is_upright(c: char, orientation: TextOrientation) -> bool {
+    match orientation {
+        TextOrientation::Mixed => {
+            // Transformed characters are upright as well,
+            // since their vertical alternates are provided by the `vert` feature.
+            unicode_vo::char_orientation(c) != unicode_vo::Orientation::Rotated
+        }
+        TextOrientation::Upright => true,
+        TextOrientation::Sideways => false,
+    }
+}
 
-            let positions = output.glyph_positions();
-            let infos = output.glyph_infos();
+/// Converts a text into a list of SVG font glyphs.
+///
+/// SVG fonts do not support shaping. Glyphs are simply matched in the document order,
+/// which is how ligatures are supported, and the missing glyph is used for everything else.
+///
+/// The glyph ID is an index in `SvgFont::glyphs` plus one. Zero is the missing glyph.
+fn shape_text_with_svg_font(
+    text: &str,
+    font: Rc<ResolvedFont>,
+    svg_font: &SvgFont,
+    apply_kerning: bool,
+) -> Vec<Glyph> {
+    let bidi_info = unicode_bidi::BidiInfo::new(text, Some(unicode_bidi::Level::ltr()));
+    let paragraph = &bidi_info.paragraphs[0];
+    let line = paragraph.range.clone();
 
-            for (pos, info) in positions.iter().zip(infos) {
-                let idx = run.start + info.cluster as usize;
-                debug_assert!(text.get(idx..).is_some());
+    let mut glyphs = Vec::new();
 
-                glyphs.push(Glyph {
-                    byte_idx: ByteIndex::new(idx),
-                    id: GlyphId(info.glyph_id as u16),
-                    dx: pos.x_offset,
-                    dy: pos.y_offset,
-                    width: pos.x_advance,
-                    font: font.clone(),
+    let (levels, runs) = bidi_info.visual_runs(paragraph, line);
+    for run in runs.iter() {
+        let mut run_glyphs: Vec<Glyph> = Vec::new();
+        let mut prev_glyph_idx = None;
+        let mut idx = run.start;
+        while idx < run.end {
+            let sub_text = &text[idx..run.end];
+            let glyph =
+                svg_font.glyphs.iter().enumerate().find(|(_, g)| {
+                    !g.unicode.is_empty() && sub_text.starts_with(g.unicode.as_str())
                 });
+
+            let (glyph_idx, id, horiz_adv_x, len) = match glyph {
+                Some((i, g)) => (
+                    Some(i),
+                    GlyphId(u16::try_from(i + 1).unwrap_or(0)),
+                    g.horiz_adv_x,
+                    g.unicode.len(),
+                ),
+                None => {
+                    let c = ByteIndex::new(idx).char_from(text);
+                    log::warn!(
+                        "'{}' font doesn't have a {}/U+{:X} character. Using the missing glyph.",
+                        svg_font.family,
+                        c,
+                        c as u32
+                    );
+
+                    (
+                        None,
+                        GlyphId(0),
+                        svg_font.missing_glyph.horiz_adv_x,
+                        c.len_utf8(),
+                    )
+                }
+            };
+
+            if apply_kerning {
+                if let (Some(prev), Some(curr)) = (prev_glyph_idx, glyph_idx) {
+                    let kerning = svg_font
+                        .kerning_pairs
+                        .iter()
+                        .find(|p| p.first.contains(&prev) && p.second.contains(&curr))
+                        .map(|p| p.kerning);
+
+                    if let (Some(kerning), Some(prev)) = (kerning, run_glyphs.last_mut()) {
+                        prev.width -= kerning.round() as i32;
+                    }
+                }
             }
+
+            run_glyphs.push(Glyph {
+                byte_idx: ByteIndex::new(idx),
+                id,
+                dx: 0,
+                dy: 0,
+                width: horiz_adv_x.round() as i32,
+                upright: false,
+                font: font.clone(),
+            });
+
+            prev_glyph_idx = glyph_idx;
+            idx += len;
         }
 
-        Some(glyphs)
-    })?
+        if levels[run.start].is_rtl() {
+            run_glyphs.reverse();
+        }
+
+        glyphs.extend(run_glyphs);
+    }
+
+    glyphs
+}
+
+/// Returns an SVG font glyph outline.
+fn svg_glyph_outline(font: &SvgFont, glyph_id: GlyphId) -> Option<tiny_skia_path::Path> {
+    let glyph = match glyph_id.0 {
+        0 => &font.missing_glyph,
+        id => font.glyphs.get(id as usize - 1)?,
+    };
+
+    glyph.path.as_deref().cloned()
 }
 
-/// Outlines a glyph cluster.
+/// Forms a glyph cluster.
 ///
-/// Uses one or more `Glyph`s to construct an `OutlinedCluster`.
-fn outline_cluster(
-    glyphs: &[Glyph],
-    text: &str,
-    font_size: f32,
-    db: &fontdb::Database,
-) -> OutlinedCluster {
+/// Uses one or more `Glyph`s to construct a `GlyphCluster`.
+fn form_cluster(glyphs: &[Glyph], text: &str, font_size: f32) -> GlyphCluster {
     debug_assert!(!glyphs.is_empty());
 
-    let mut builder = tiny_skia_path::PathBuilder::new();
+    let mut positioned_glyphs = Vec::with_capacity(glyphs.len());
     let mut width = 0.0;
     let mut x: f32 = 0.0;
 
     for glyph in glyphs {
         let sx = glyph.font.scale(font_size);
 
-        if let Some(outline) = db.outline(glyph.font.id, glyph.id) {
+        // Maps font units into the cluster coordinates.
+        let ts = if glyph.upright {
+            // Upright glyphs are rotated 90deg counter clockwise,
+            // since the whole text chunk will be rotated clockwise later.
+            // The offset is relative to the vertical origin,
+            // which is at the top center of the glyph.
+            Transform::from_row(0.0, -sx, -sx, 0.0, 0.0, 0.0)
+                .pre_translate(glyph.dx as f32, glyph.dy as f32 - x)
+        } else {
             // By default, glyphs are upside-down, so we have to mirror them.
-            let mut ts = Transform::from_scale(1.0, -1.0);
-
-            // Scale to font-size.
-            ts = ts.pre_scale(sx, sx);
-
-            // Apply offset.
             //
             // The first glyph in the cluster will have an offset from 0x0,
             // but the later one will have an offset from the "current position".
             // So we have to keep an advance.
             // TODO: should be done only inside a single text span
-            ts = ts.pre_translate(x + glyph.dx as f32, glyph.dy as f32);
-
-            if let Some(outline) = outline.transform(ts) {
-                builder.push_path(&outline);
-            }
-        }
+            Transform::from_scale(sx, -sx).pre_translate(x + glyph.dx as f32, glyph.dy as f32)
+        };
 
         x += glyph.width as f32;
 
         let glyph_width = glyph.width as f32 * sx;
+        positioned_glyphs.push(PositionedGlyph {
+            id: glyph.id,
+            font: glyph.font.clone(),
+            advance: glyph_width,
+            transform: ts,
+        });
+
         if glyph_width > width {
             width = glyph_width;
         }
@@ -1285,18 +1942,93 @@ fn outline_cluster(
 
     let byte_idx = glyphs[0].byte_idx;
     let font = glyphs[0].font.clone();
-    OutlinedCluster {
+    let upright = glyphs[0].upright;
+
+    // Upright glyphs are centered inside the em box.
+    let (ascent, descent) = if upright {
+        (font_size / 2.0, -font_size / 2.0)
+    } else {
+        (font.ascent(font_size), font.descent(font_size))
+    };
+
+    GlyphCluster {
         byte_idx,
         codepoint: byte_idx.char_from(text),
         width,
         advance: width,
-        ascent: font.ascent(font_size),
-        descent: font.descent(font_size),
-        x_height: font.x_height(font_size),
+        ascent,
+        descent,
         has_relative_shift: false,
-        path: builder.finish(),
+        glyphs: positioned_glyphs,
         transform: Transform::default(),
         visible: true,
+        upright,
+    }
+}
+
+/// Outlines cluster's glyphs.
+///
+/// Returns an outline in the cluster coordinates and glyphs with their own colors,
+/// like emojis, with their transforms.
+fn outline_cluster(
+    cluster: &GlyphCluster,
+    foreground: ttf_parser::RgbaColor,
+    variations: &[rustybuzz::Variation],
+    db: &fontdb::Database,
+    svg_glyphs: &SvgGlyphs,
+) -> (Option<tiny_skia_path::Path>, Vec<(ColorGlyph, Transform)>) {
+    let mut builder = tiny_skia_path::PathBuilder::new();
+    let mut color_glyphs = Vec::new();
+
+    for glyph in &cluster.glyphs {
+        let (color_glyph, outline) = match glyph.font.source {
+            FontSource::Database(id) => {
+                let foreground = if glyph.font.has_color_glyphs {
+                    Some(foreground)
+                } else {
+                    None
+                };
+
+                db.glyph(id, glyph.id, foreground, variations, svg_glyphs)
+            }
+            FontSource::Svg(ref svg_font) => (None, svg_glyph_outline(svg_font, glyph.id)),
+        };
+
+        if let Some(color_glyph) = color_glyph {
+            let ts = match color_glyph {
+                // Layers are in font units, just like outlines.
+                ColorGlyph::Layers(_) => glyph.transform,
+                // Images already have the Y axis pointing down.
+                ColorGlyph::Image { .. } => glyph.transform.pre_scale(1.0, -1.0),
+            };
+            color_glyphs.push((color_glyph, ts));
+        } else if let Some(outline) = outline {
+            if let Some(outline) = outline.transform(glyph.transform) {
+                builder.push_path(&outline);
+            }
+        }
+    }
+
+    (builder.finish(), color_glyphs)
+}
+
+/// Returns a color that should be used by color glyphs layers that reference
+/// the foreground color.
+///
+/// Paint servers are not supported, so black is used instead.
+fn resolve_foreground_color(span: &TextSpan) -> ttf_parser::RgbaColor {
+    match span.fill {
+        Some(Fill {
+            paint: Paint::Color(c),
+            opacity,
+            ..
+        }) => ttf_parser::RgbaColor::new(
+            c.red,
+            c.green,
+            c.blue,
+            (opacity.get() * 255.0).round() as u8,
+        ),
+        _ => ttf_parser::RgbaColor::new(0, 0, 0, 255),
     }
 }
 
@@ -1307,7 +2039,7 @@ fn find_font_for_char(
     c: char,
     exclude_fonts: &[fontdb::ID],
     fontdb: &fontdb::Database,
-) -> Option<ResolvedFont> {
+) -> Option<(ID, ResolvedFont)> {
     let base_font_id = exclude_fonts[0];
 
     // Iterate over fonts and check if any of them support the specified char.
@@ -1343,7 +2075,7 @@ fn find_font_for_char(
             .unwrap_or(&base_face.families[0]);
 
         log::warn!("Fallback from {} to {}.", base_family.0, new_family.0);
-        return fontdb.load_font(face.id);
+        return fontdb.load_font(face.id).map(|font| (face.id, font));
     }
 
     None
@@ -1361,7 +2093,7 @@ fn resolve_clusters_positions(
     writing_mode: WritingMode,
     ts: Transform,
     fonts_cache: &FontsCache,
-    clusters: &mut [OutlinedCluster],
+    clusters: &mut [GlyphCluster],
 ) -> (f32, f32) {
     match chunk.text_flow {
         TextFlow::Linear => {
@@ -1385,7 +2117,7 @@ fn resolve_clusters_positions_horizontal(
     chunk: &TextChunk,
     offset: usize,
     writing_mode: WritingMode,
-    clusters: &mut [OutlinedCluster],
+    clusters: &mut [GlyphCluster],
 ) -> (f32, f32) {
     let mut x = process_anchor(chunk.anchor, clusters_length(clusters));
     let mut y = 0.0;
@@ -1426,7 +2158,7 @@ fn resolve_clusters_positions_path(
     writing_mode: WritingMode,
     ts: Transform,
     fonts_cache: &FontsCache,
-    clusters: &mut [OutlinedCluster],
+    clusters: &mut [GlyphCluster],
 ) -> (f32, f32) {
     let mut last_x = 0.0;
     let mut last_y = 0.0;
@@ -1511,7 +2243,7 @@ fn resolve_clusters_positions_path(
     (last_x, last_y)
 }
 
-fn clusters_length(clusters: &[OutlinedCluster]) -> f32 {
+fn clusters_length(clusters: &[GlyphCluster]) -> f32 {
     clusters.iter().fold(0.0, |w, cluster| w + cluster.advance)
 }
 
@@ -1532,7 +2264,7 @@ struct PathNormal {
 fn collect_normals(
     text: &Text,
     chunk: &TextChunk,
-    clusters: &[OutlinedCluster],
+    clusters: &[GlyphCluster],
     path: &tiny_skia_path::Path,
     char_offset: usize,
     offset: f32,
@@ -1629,7 +2361,7 @@ fn collect_normals(
                 let mut offset = curve.inv_arclen(offset - length, arclen_accuracy as f64);
                 // some rounding error may occur, so we give offset a little tolerance
                 debug_assert!((-1.0e-3..=1.0 + 1.0e-3).contains(&offset));
-                offset = offset.min(1.0).max(0.0);
+                offset = offset.clamp(0.0, 1.0);
 
                 let pos = curve.eval(offset);
                 let d = curve.deriv().eval(offset);
@@ -1664,7 +2396,7 @@ fn collect_normals(
 /// Applies the `letter-spacing` property to a text chunk clusters.
 ///
 /// [In the CSS spec](https://www.w3.org/TR/css-text-3/#letter-spacing-property).
-fn apply_letter_spacing(chunk: &TextChunk, clusters: &mut [OutlinedCluster]) {
+fn apply_letter_spacing(chunk: &TextChunk, clusters: &mut [GlyphCluster]) {
     // At least one span should have a non-zero spacing.
     if !chunk
         .spans
@@ -1694,7 +2426,7 @@ fn apply_letter_spacing(chunk: &TextChunk, clusters: &mut [OutlinedCluster]) {
                 if !cluster.advance.is_valid_length() {
                     cluster.width = 0.0;
                     cluster.advance = 0.0;
-                    cluster.path = None;
+                    cluster.glyphs.clear();
                 }
             }
         }
@@ -1733,7 +2465,7 @@ fn script_supports_letter_spacing(script: unicode_script::Script) -> bool {
 /// Applies the `word-spacing` property to a text chunk clusters.
 ///
 /// [In the CSS spec](https://www.w3.org/TR/css-text-3/#propdef-word-spacing).
-fn apply_word_spacing(chunk: &TextChunk, clusters: &mut [OutlinedCluster]) {
+fn apply_word_spacing(chunk: &TextChunk, clusters: &mut [GlyphCluster]) {
     // At least one span should have a non-zero spacing.
     if !chunk
         .spans
@@ -1767,7 +2499,7 @@ fn is_word_separator_characters(c: char) -> bool {
     )
 }
 
-fn apply_length_adjust(chunk: &TextChunk, clusters: &mut [OutlinedCluster]) {
+fn apply_length_adjust(chunk: &TextChunk, clusters: &mut [GlyphCluster]) {
     let is_horizontal = matches!(chunk.text_flow, TextFlow::Linear);
 
     for span in &chunk.spans {
@@ -1827,16 +2559,26 @@ fn apply_length_adjust(chunk: &TextChunk, clusters: &mut [OutlinedCluster]) {
     }
 }
 
-/// Rotates clusters according to
-/// [Unicode Vertical_Orientation Property](https://www.unicode.org/reports/tr50/tr50-19.html).
-fn apply_writing_mode(writing_mode: WritingMode, clusters: &mut [OutlinedCluster]) {
+/// Aligns clusters in the vertical writing mode.
+///
+/// Sideways clusters are aligned by the central baseline.
+/// Upright clusters are already positioned during shaping, unless the font doesn't support
+/// vertical shaping, like SVG fonts. In which case they are simply rotated.
+fn apply_writing_mode(writing_mode: WritingMode, chunk: &TextChunk, clusters: &mut [GlyphCluster]) {
     if writing_mode != WritingMode::TopToBottom {
         return;
     }
 
     for cluster in clusters {
-        let orientation = unicode_vo::char_orientation(cluster.codepoint);
-        if orientation == unicode_vo::Orientation::Upright {
+        if cluster.upright {
+            continue;
+        }
+
+        let orientation = chunk_span_at(chunk, cluster.byte_idx)
+            .map(|span| span.text_orientation)
+            .unwrap_or_default();
+
+        if is_upright(cluster.codepoint, orientation) {
             // Additional offset. Not sure why.
             let dy = cluster.width - cluster.height();
 
@@ -1846,18 +2588,18 @@ fn apply_writing_mode(writing_mode: WritingMode, clusters: &mut [OutlinedCluster
             ts = ts.pre_rotate(-90.0);
             ts = ts.pre_translate(-cluster.width / 2.0, -dy);
 
-            if let Some(path) = cluster.path.take() {
-                cluster.path = path.transform(ts);
+            for glyph in &mut cluster.glyphs {
+                glyph.transform = ts.pre_concat(glyph.transform);
             }
 
             // Move "baseline" to the middle and make height equal to width.
             cluster.ascent = cluster.width / 2.0;
             cluster.descent = -cluster.width / 2.0;
         } else {
-            // Could not find a spec that explains this,
-            // but this is how other applications are shifting the "rotated" characters
-            // in the top-to-bottom mode.
-            cluster.transform = cluster.transform.pre_translate(0.0, cluster.x_height / 2.0);
+            // Move the central baseline, which is halfway between ascent and descent,
+            // to the text position.
+            let shift = (cluster.ascent + cluster.descent) / 2.0;
+            cluster.transform = cluster.transform.pre_translate(0.0, shift);
         }
     }
 }
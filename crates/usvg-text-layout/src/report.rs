@@ -0,0 +1,185 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use fontdb::{Database, ID};
+use usvg_tree::{Text, TextChunk, TextSpan};
+
+use crate::{span_contains, FontSource, FontsCache, LaidOutChunk};
+
+/// A report on how fonts were resolved during text conversion.
+///
+/// Returned by [`TreeTextToPath::convert_text_with_report`](crate::TreeTextToPath).
+#[derive(Clone, Default, Debug)]
+pub struct FontReport {
+    /// A list of text spans in the document order.
+    ///
+    /// An auto-wrapped text span can be reported multiple times, once per line.
+    pub spans: Vec<SpanFontReport>,
+}
+
+impl FontReport {
+    /// Checks that some characters were rendered without a glyph.
+    pub fn has_missing_glyphs(&self) -> bool {
+        self.spans.iter().any(|span| !span.missing_chars.is_empty())
+    }
+
+    /// Checks that some characters were rendered using a fallback font.
+    pub fn has_fallbacks(&self) -> bool {
+        self.spans
+            .iter()
+            .any(|span| !span.fallback_faces.is_empty())
+    }
+}
+
+/// How fonts were resolved for a single text span.
+#[derive(Clone, Debug)]
+pub struct SpanFontReport {
+    /// An ID of the parent `text` element.
+    ///
+    /// Can be empty.
+    pub text_id: String,
+
+    /// A span text.
+    pub text: String,
+
+    /// A list of requested font families.
+    pub families: Vec<String>,
+
+    /// A face that was matched by the requested families.
+    ///
+    /// `None` when no faces were matched or when an SVG font was used.
+    /// In the first case, the span will not be rendered at all.
+    pub face: Option<FaceInfo>,
+
+    /// Faces that were used for characters missing in the matched face.
+    pub fallback_faces: Vec<FaceInfo>,
+
+    /// Characters that were not found in any face.
+    ///
+    /// Each character is listed once.
+    pub missing_chars: Vec<char>,
+}
+
+/// A font face info.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FaceInfo {
+    /// A face ID in the fonts database.
+    ///
+    /// `@font-face` fonts are loaded into a temporary database,
+    /// so their IDs are not present in the original one.
+    pub id: ID,
+
+    /// A font family name.
+    pub family: String,
+
+    /// A PostScript name.
+    pub post_script_name: String,
+}
+
+impl FaceInfo {
+    fn new(id: ID, fontdb: &Database) -> Option<Self> {
+        let face = fontdb.face(id)?;
+        let family = face
+            .families
+            .iter()
+            .find(|f| f.1 == fontdb::Language::English_UnitedStates)
+            .or_else(|| face.families.first())
+            .map(|f| f.0.clone())
+            .unwrap_or_default();
+
+        Some(FaceInfo {
+            id,
+            family,
+            post_script_name: face.post_script_name.clone(),
+        })
+    }
+}
+
+/// Collects fonts used by each span of a laid out text.
+pub(crate) fn collect(
+    text: &Text,
+    fonts_cache: &FontsCache,
+    chunks: &[LaidOutChunk],
+    fontdb: &Database,
+    report: &mut FontReport,
+) {
+    let mut face_infos = HashMap::new();
+    let mut face_info = |id: ID| -> Option<FaceInfo> {
+        face_infos
+            .entry(id)
+            .or_insert_with(|| FaceInfo::new(id, fontdb))
+            .clone()
+    };
+
+    for laid_out in chunks {
+        for span in &laid_out.chunk.spans {
+            let face_id = match fonts_cache.get(&span.font).map(|font| &font.source) {
+                Some(FontSource::Database(id)) => Some(*id),
+                Some(FontSource::Svg(_)) => None,
+                None => {
+                    // Nothing will be rendered.
+                    report.spans.push(SpanFontReport {
+                        text_id: text.id.clone(),
+                        text: span_text(&laid_out.chunk, span).to_string(),
+                        families: span.font.families.clone(),
+                        face: None,
+                        fallback_faces: Vec::new(),
+                        missing_chars: unique_chars(
+                            span_text(&laid_out.chunk, span)
+                                .chars()
+                                .filter(|c| !c.is_whitespace()),
+                        ),
+                    });
+                    continue;
+                }
+            };
+
+            let mut fallback_faces: Vec<FaceInfo> = Vec::new();
+            let mut missing = Vec::new();
+            for cluster in &laid_out.clusters {
+                if !span_contains(span, cluster.byte_idx) {
+                    continue;
+                }
+
+                for glyph in &cluster.glyphs {
+                    if glyph.id.0 == 0 {
+                        missing.push(cluster.codepoint);
+                    } else if let FontSource::Database(id) = glyph.font.source {
+                        if Some(id) != face_id && !fallback_faces.iter().any(|f| f.id == id) {
+                            if let Some(info) = face_info(id) {
+                                fallback_faces.push(info);
+                            }
+                        }
+                    }
+                }
+            }
+
+            report.spans.push(SpanFontReport {
+                text_id: text.id.clone(),
+                text: span_text(&laid_out.chunk, span).to_string(),
+                families: span.font.families.clone(),
+                face: face_id.and_then(&mut face_info),
+                fallback_faces,
+                missing_chars: unique_chars(missing.into_iter()),
+            });
+        }
+    }
+}
+
+fn span_text<'a>(chunk: &'a TextChunk, span: &TextSpan) -> &'a str {
+    chunk.text.get(span.start..span.end).unwrap_or_default()
+}
+
+fn unique_chars(iter: impl Iterator<Item = char>) -> Vec<char> {
+    let mut chars = Vec::new();
+    for c in iter {
+        if !chars.contains(&c) {
+            chars.push(c);
+        }
+    }
+
+    chars
+}
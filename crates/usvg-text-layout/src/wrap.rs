@@ -0,0 +1,513 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! SVG 2 auto-wrapped text.
+//!
+//! Wrapping is done by splitting a text into a list of lines,
+//! where each line becomes a separate text chunk with an absolute position.
+//! After that, lines are processed like any other text chunk.
+
+use tiny_skia_path::{PathSegment, Point};
+use unicode_linebreak::BreakOpportunity;
+use usvg_tree::*;
+
+use crate::{FontsCache, ResolvedFont};
+
+/// Splits an auto-wrapped text into lines.
+///
+/// Returns `None` when a text is not auto-wrapped or when wrapping is not supported.
+pub(crate) fn wrap_text(
+    text: &Text,
+    fonts_cache: &FontsCache,
+    fontdb: &fontdb::Database,
+) -> Option<Text> {
+    let wrap = text.wrap.as_ref()?;
+
+    if text.writing_mode == WritingMode::TopToBottom {
+        log::warn!("Auto-wrapped vertical text is not supported.");
+        return None;
+    }
+
+    if text
+        .chunks
+        .iter()
+        .any(|chunk| matches!(chunk.text_flow, TextFlow::Path(_)))
+    {
+        log::warn!("Auto-wrapped text cannot contain a 'textPath'.");
+        return None;
+    }
+
+    let paragraph = merge_chunks(&text.chunks)?;
+    let metrics = ParagraphMetrics::new(&paragraph, wrap.line_height, fonts_cache);
+    let breaker = LineBreaker::new(&paragraph, wrap.allow_wrap, fonts_cache, fontdb);
+
+    let chunks = match wrap.area {
+        TextWrapArea::InlineSize(inline_size) => {
+            layout_inline_size(&paragraph, &breaker, &metrics, inline_size.get())
+        }
+        TextWrapArea::Shape(ref shape) => {
+            layout_shape(&paragraph, &breaker, &metrics, shape, wrap.align)
+        }
+    };
+
+    // Lines are positioned absolutely, therefore `dx`, `dy` and `rotate` must be ignored.
+    // Otherwise they would be applied to a wrong characters,
+    // since whitespaces at the line end are removed.
+    Some(Text {
+        dx: Vec::new(),
+        dy: Vec::new(),
+        rotate: Vec::new(),
+        chunks,
+        wrap: None,
+        ..text.clone()
+    })
+}
+
+/// Merges all text chunks into one.
+///
+/// Auto-wrapped text ignores absolute positioning, except the first one.
+fn merge_chunks(chunks: &[TextChunk]) -> Option<TextChunk> {
+    let first = chunks.first()?;
+    let mut paragraph = TextChunk {
+        x: first.x,
+        y: first.y,
+        anchor: first.anchor,
+        spans: Vec::new(),
+        text_flow: TextFlow::Linear,
+        text: String::new(),
+    };
+
+    for chunk in chunks {
+        let offset = paragraph.text.len();
+        paragraph.text.push_str(&chunk.text);
+        for span in &chunk.spans {
+            paragraph.spans.push(TextSpan {
+                start: span.start + offset,
+                end: span.end + offset,
+                ..span.clone()
+            });
+        }
+    }
+
+    Some(paragraph)
+}
+
+/// Line metrics shared by all lines.
+struct ParagraphMetrics {
+    line_height: f32,
+    ascent: f32,
+    height: f32,
+}
+
+impl ParagraphMetrics {
+    fn new(paragraph: &TextChunk, line_height: LineHeight, fonts_cache: &FontsCache) -> Self {
+        let mut metrics = ParagraphMetrics {
+            line_height: 0.0,
+            ascent: 0.0,
+            height: 0.0,
+        };
+
+        for span in &paragraph.spans {
+            let font_size = span.font_size.get();
+            let font: Option<&ResolvedFont> = fonts_cache.get(&span.font).map(|v| v.as_ref());
+            let (ascent, height) = match font {
+                Some(font) => (font.ascent(font_size), font.height(font_size)),
+                None => (font_size, font_size),
+            };
+
+            let span_line_height = match line_height {
+                LineHeight::Normal => height,
+                LineHeight::Number(n) => n * font_size,
+                LineHeight::Length(n) => n,
+            };
+
+            metrics.line_height = metrics.line_height.max(span_line_height);
+            metrics.ascent = metrics.ascent.max(ascent);
+            metrics.height = metrics.height.max(height);
+        }
+
+        metrics
+    }
+}
+
+/// A single line of text.
+struct Line {
+    /// Line's content start in bytes.
+    start: usize,
+    /// Line's content end in bytes.
+    ///
+    /// Excludes trailing whitespaces and newlines.
+    end: usize,
+    /// Next line start in bytes.
+    next: usize,
+    /// Line's content width.
+    width: f32,
+    /// Indicates that a line ends with a forced line break or with the text end.
+    is_last_in_paragraph: bool,
+}
+
+/// A greedy line breaker, based on UAX #14 break opportunities.
+struct LineBreaker<'a> {
+    text: &'a str,
+    breaks: Vec<(usize, BreakOpportunity)>,
+    /// A sum of cluster advances before each byte.
+    offsets: Vec<f32>,
+}
+
+impl<'a> LineBreaker<'a> {
+    fn new(
+        paragraph: &'a TextChunk,
+        allow_wrap: bool,
+        fonts_cache: &FontsCache,
+        fontdb: &fontdb::Database,
+    ) -> Self {
+        // Measure the whole paragraph at once.
+        // This is not exactly correct, since shaping across a line break
+        // can be different, but good enough in practice.
+        //
+        // Newlines are replaced with spaces, since fonts usually do not have a glyph for them
+        // and their width is ignored anyway.
+        let measured = TextChunk {
+            text: paragraph.text.replace('\n', " "),
+            ..paragraph.clone()
+        };
+        let mut clusters =
+            crate::form_clusters(&measured, WritingMode::LeftToRight, fonts_cache, fontdb);
+        crate::apply_letter_spacing(&measured, &mut clusters);
+        crate::apply_word_spacing(&measured, &mut clusters);
+
+        let mut advances = vec![0.0; paragraph.text.len() + 1];
+        for cluster in &clusters {
+            advances[cluster.byte_idx.value()] += cluster.advance;
+        }
+
+        let mut offsets = Vec::with_capacity(advances.len());
+        let mut sum = 0.0;
+        for advance in advances {
+            offsets.push(sum);
+            sum += advance;
+        }
+
+        let breaks = unicode_linebreak::linebreaks(&paragraph.text)
+            .filter(|(_, op)| allow_wrap || *op == BreakOpportunity::Mandatory)
+            .collect();
+
+        LineBreaker {
+            text: &paragraph.text,
+            breaks,
+            offsets,
+        }
+    }
+
+    fn width(&self, start: usize, end: usize) -> f32 {
+        self.offsets[end] - self.offsets[start]
+    }
+
+    /// Returns the next line that starts at `start` and is not longer than `max_width`.
+    ///
+    /// When even a single word doesn't fit, it will be returned as is.
+    /// The caller can check it using the `width` field.
+    fn next_line(&self, start: usize, max_width: f32) -> Option<Line> {
+        if start >= self.text.len() {
+            return None;
+        }
+
+        let mut last_fit: Option<Line> = None;
+        for &(idx, op) in &self.breaks {
+            if idx <= start {
+                continue;
+            }
+
+            let end = start + self.text[start..idx].trim_end().len();
+            let line = Line {
+                start,
+                end,
+                next: idx,
+                width: self.width(start, end),
+                is_last_in_paragraph: op == BreakOpportunity::Mandatory,
+            };
+
+            if line.width > max_width {
+                return Some(last_fit.unwrap_or(line));
+            }
+
+            if line.is_last_in_paragraph {
+                return Some(line);
+            }
+
+            last_fit = Some(line);
+        }
+
+        last_fit
+    }
+}
+
+fn layout_inline_size(
+    paragraph: &TextChunk,
+    breaker: &LineBreaker,
+    metrics: &ParagraphMetrics,
+    inline_size: f32,
+) -> Vec<TextChunk> {
+    let x = paragraph.x.unwrap_or(0.0);
+    let mut y = paragraph.y.unwrap_or(0.0);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while let Some(line) = breaker.next_line(start, inline_size) {
+        if line.end > line.start {
+            chunks.push(line_chunk(paragraph, &line, x, y, paragraph.anchor, 0.0));
+        }
+
+        start = line.next;
+        y += metrics.line_height;
+    }
+
+    chunks
+}
+
+fn layout_shape(
+    paragraph: &TextChunk,
+    breaker: &LineBreaker,
+    metrics: &ParagraphMetrics,
+    shape: &TextWrapShape,
+    align: TextAlign,
+) -> Vec<TextChunk> {
+    let line_height = metrics.line_height;
+    if !(line_height > 0.0) {
+        return Vec::new();
+    }
+
+    let edges = collect_edges(&shape.path);
+    let bounds = shape.path.bounds();
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut top = bounds.top();
+    while top + line_height <= bounds.bottom() {
+        let (left, right) = match find_band_interval(&edges, top, top + line_height) {
+            Some(v) => v,
+            None => {
+                top += line_height;
+                continue;
+            }
+        };
+
+        let max_width = right - left;
+        let line = match breaker.next_line(start, max_width) {
+            Some(v) => v,
+            None => break,
+        };
+
+        // A word doesn't fit into this band. Try the next one.
+        if line.width > max_width {
+            top += line_height;
+            continue;
+        }
+
+        if line.end > line.start {
+            let baseline = top + (line_height - metrics.height) / 2.0 + metrics.ascent;
+
+            let (x, anchor) = match align {
+                TextAlign::Start | TextAlign::Left | TextAlign::Justify => {
+                    (left, TextAnchor::Start)
+                }
+                TextAlign::Center => ((left + right) / 2.0, TextAnchor::Middle),
+                TextAlign::End | TextAlign::Right => (right, TextAnchor::End),
+            };
+
+            let mut extra_word_spacing = 0.0;
+            if align == TextAlign::Justify && !line.is_last_in_paragraph {
+                let separators = paragraph.text[line.start..line.end]
+                    .chars()
+                    .filter(|c| crate::is_word_separator_characters(*c))
+                    .count();
+                if separators != 0 {
+                    extra_word_spacing = (max_width - line.width) / separators as f32;
+                }
+            }
+
+            chunks.push(line_chunk(
+                paragraph,
+                &line,
+                x,
+                baseline,
+                anchor,
+                extra_word_spacing,
+            ));
+        }
+
+        start = line.next;
+        top += line_height;
+    }
+
+    // Text that doesn't fit into the shape is hidden.
+    chunks
+}
+
+/// Creates a text chunk from a line.
+fn line_chunk(
+    paragraph: &TextChunk,
+    line: &Line,
+    x: f32,
+    y: f32,
+    anchor: TextAnchor,
+    extra_word_spacing: f32,
+) -> TextChunk {
+    let spans = paragraph
+        .spans
+        .iter()
+        .filter(|span| span.start < line.end && span.end > line.start)
+        .map(|span| TextSpan {
+            start: span.start.max(line.start) - line.start,
+            end: span.end.min(line.end) - line.start,
+            word_spacing: span.word_spacing + extra_word_spacing,
+            // `textLength` cannot be applied to a part of a span.
+            text_length: None,
+            ..span.clone()
+        })
+        .collect();
+
+    TextChunk {
+        x: Some(x),
+        y: Some(y),
+        anchor,
+        spans,
+        text_flow: TextFlow::Linear,
+        text: paragraph.text[line.start..line.end].to_string(),
+    }
+}
+
+/// Collects shape edges, with curves flattened into lines and all subpaths closed.
+fn collect_edges(path: &tiny_skia_path::Path) -> Vec<(Point, Point)> {
+    const CURVE_STEPS: u16 = 16;
+
+    let mut edges = Vec::new();
+    let mut subpath_start = Point::zero();
+    let mut prev = Point::zero();
+    for seg in path.segments() {
+        match seg {
+            PathSegment::MoveTo(p) => {
+                if prev != subpath_start {
+                    edges.push((prev, subpath_start));
+                }
+
+                subpath_start = p;
+                prev = p;
+            }
+            PathSegment::LineTo(p) => {
+                edges.push((prev, p));
+                prev = p;
+            }
+            PathSegment::QuadTo(p1, p) => {
+                let curve = kurbo::QuadBez::new(to_kurbo(prev), to_kurbo(p1), to_kurbo(p));
+                flatten(&curve, CURVE_STEPS, &mut edges);
+                prev = p;
+            }
+            PathSegment::CubicTo(p1, p2, p) => {
+                let curve =
+                    kurbo::CubicBez::new(to_kurbo(prev), to_kurbo(p1), to_kurbo(p2), to_kurbo(p));
+                flatten(&curve, CURVE_STEPS, &mut edges);
+                prev = p;
+            }
+            PathSegment::Close => {
+                if prev != subpath_start {
+                    edges.push((prev, subpath_start));
+                }
+
+                prev = subpath_start;
+            }
+        }
+    }
+
+    if prev != subpath_start {
+        edges.push((prev, subpath_start));
+    }
+
+    edges
+}
+
+fn to_kurbo(p: Point) -> kurbo::Point {
+    kurbo::Point::new(p.x as f64, p.y as f64)
+}
+
+fn flatten(curve: &impl kurbo::ParamCurve, steps: u16, edges: &mut Vec<(Point, Point)>) {
+    let mut prev = curve.eval(0.0);
+    for i in 1..=steps {
+        let p = curve.eval(i as f64 / steps as f64);
+        edges.push((
+            Point::from_xy(prev.x as f32, prev.y as f32),
+            Point::from_xy(p.x as f32, p.y as f32),
+        ));
+        prev = p;
+    }
+}
+
+/// Finds the widest horizontal interval that is inside the shape for the whole band.
+///
+/// The band is sampled at the top, middle and bottom, which is enough for most shapes.
+fn find_band_interval(edges: &[(Point, Point)], top: f32, bottom: f32) -> Option<(f32, f32)> {
+    // Move the sampling lines a bit inside, so a band that touches a horizontal edge
+    // would not be treated as empty.
+    let eps = (bottom - top) * 0.01;
+    let mut intervals = scanline_intervals(edges, top + eps);
+    for y in [(top + bottom) / 2.0, bottom - eps] {
+        intervals = intersect_intervals(&intervals, &scanline_intervals(edges, y));
+    }
+
+    intervals
+        .into_iter()
+        .filter(|(l, r)| r > l)
+        .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)))
+}
+
+/// Returns a sorted list of intervals that are inside the shape using the nonzero fill rule.
+fn scanline_intervals(edges: &[(Point, Point)], y: f32) -> Vec<(f32, f32)> {
+    let mut crossings = Vec::new();
+    for (p0, p1) in edges {
+        let (winding, top, bottom) = if p0.y < p1.y {
+            (1, p0, p1)
+        } else if p0.y > p1.y {
+            (-1, p1, p0)
+        } else {
+            continue;
+        };
+
+        if y >= top.y && y < bottom.y {
+            let t = (y - top.y) / (bottom.y - top.y);
+            crossings.push((top.x + (bottom.x - top.x) * t, winding));
+        }
+    }
+
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut intervals = Vec::new();
+    let mut winding = 0;
+    let mut start = 0.0;
+    for (x, w) in crossings {
+        let prev_winding = winding;
+        winding += w;
+        if prev_winding == 0 && winding != 0 {
+            start = x;
+        } else if prev_winding != 0 && winding == 0 {
+            intervals.push((start, x));
+        }
+    }
+
+    intervals
+}
+
+fn intersect_intervals(a: &[(f32, f32)], b: &[(f32, f32)]) -> Vec<(f32, f32)> {
+    let mut intervals = Vec::new();
+    for &(l1, r1) in a {
+        for &(l2, r2) in b {
+            let l = l1.max(l2);
+            let r = r1.min(r2);
+            if r > l {
+                intervals.push((l, r));
+            }
+        }
+    }
+
+    intervals
+}
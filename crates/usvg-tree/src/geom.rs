@@ -3,7 +3,7 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use strict_num::ApproxEqUlps;
-pub use tiny_skia_path::{NonZeroRect, Rect, Size, Transform};
+pub use tiny_skia_path::{NonZeroRect, Point, Rect, Size, Transform};
 
 use crate::AspectRatio;
 
@@ -0,0 +1,362 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use tiny_skia_path::{PathSegment, Point};
+
+use crate::{
+    calc_node_bbox, ClipPath, FillRule, LineCap, LineJoin, Node, NodeKind, Path, PointerEvents,
+    Rect, Stroke, Transform, Tree, Units, Visibility,
+};
+
+impl Tree {
+    /// Returns all nodes under the specified point.
+    ///
+    /// Nodes are ordered from the topmost to the bottommost one.
+    ///
+    /// Only paths, images and text nodes are returned.
+    /// Parent groups can be accessed via `Node::ancestors`.
+    ///
+    /// `point` must be in the root coordinate system, which is defined by the `viewBox`.
+    /// Use [`utils::view_box_to_transform`](crate::utils::view_box_to_transform)
+    /// to map canvas coordinates into it.
+    ///
+    /// Takes transforms, fill rules, stroke geometry, clip paths,
+    /// `visibility` and `pointer-events` into account.
+    /// Masks, filters and opacity do not affect hit-testing.
+    /// Text nodes must be converted to paths first, otherwise they will be ignored.
+    pub fn hit_test(&self, point: Point) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        hit_test_children(&self.root, Transform::default(), point, &mut nodes);
+        nodes
+    }
+}
+
+fn hit_test_children(parent: &Node, ts: Transform, point: Point, nodes: &mut Vec<Node>) {
+    // Children are stored in the rendering order, therefore the last one is the topmost.
+    let mut child = parent.last_child();
+    while let Some(node) = child {
+        hit_test_node(&node, ts, point, nodes);
+        child = node.previous_sibling();
+    }
+}
+
+fn hit_test_node(node: &Node, ts: Transform, point: Point, nodes: &mut Vec<Node>) {
+    match *node.borrow() {
+        NodeKind::Group(ref group) => {
+            let ts = ts.pre_concat(group.transform);
+
+            if let Some(ref clip_path) = group.clip_path {
+                if !clip_path_contains(clip_path, node, ts, point) {
+                    return;
+                }
+            }
+
+            hit_test_children(node, ts, point, nodes);
+        }
+        NodeKind::Path(ref path) => {
+            if path_contains(path, ts, point) {
+                nodes.push(node.clone());
+            }
+        }
+        NodeKind::Image(ref image) => {
+            let is_visible = image.visibility == Visibility::Visible;
+            // Images do not have a fill or a stroke, so their whole area is "painted".
+            let is_enabled = match image.pointer_events {
+                PointerEvents::VisiblePainted
+                | PointerEvents::VisibleFill
+                | PointerEvents::VisibleStroke
+                | PointerEvents::Visible => is_visible,
+                PointerEvents::Painted
+                | PointerEvents::Fill
+                | PointerEvents::Stroke
+                | PointerEvents::All
+                | PointerEvents::BoundingBox => true,
+                PointerEvents::None => false,
+            };
+
+            if is_enabled {
+                if let Some(p) = map_point(ts, point) {
+                    if rect_contains(image.view_box.rect.to_rect(), p) {
+                        nodes.push(node.clone());
+                    }
+                }
+            }
+        }
+        NodeKind::Text(ref text) => {
+            if let Some(ref flattened) = text.flattened {
+                let mut text_nodes = Vec::new();
+                hit_test_node(flattened, ts, point, &mut text_nodes);
+                if !text_nodes.is_empty() {
+                    nodes.push(node.clone());
+                }
+            }
+        }
+    }
+}
+
+fn path_contains(path: &Path, ts: Transform, point: Point) -> bool {
+    let is_visible = path.visibility == Visibility::Visible;
+    let (check_fill, check_stroke) = match path.pointer_events {
+        PointerEvents::VisiblePainted => (
+            is_visible && path.fill.is_some(),
+            is_visible && path.stroke.is_some(),
+        ),
+        PointerEvents::VisibleFill => (is_visible, false),
+        PointerEvents::VisibleStroke => (false, is_visible),
+        PointerEvents::Visible => (is_visible, is_visible),
+        PointerEvents::Painted => (path.fill.is_some(), path.stroke.is_some()),
+        PointerEvents::Fill => (true, false),
+        PointerEvents::Stroke => (false, true),
+        PointerEvents::All => (true, true),
+        PointerEvents::BoundingBox => {
+            return match (map_point(ts, point), path.data.compute_tight_bounds()) {
+                (Some(p), Some(bbox)) => rect_contains(bbox, p),
+                _ => false,
+            };
+        }
+        PointerEvents::None => (false, false),
+    };
+
+    let p = match map_point(ts, point) {
+        Some(v) => v,
+        None => return false,
+    };
+
+    if check_fill {
+        let rule = path.fill.as_ref().map(|f| f.rule).unwrap_or_default();
+        if outline_contains(&path.data, rule, p) {
+            return true;
+        }
+    }
+
+    // The stroke geometry cannot be resolved without a stroke,
+    // even when `pointer-events` ignores the paint.
+    if check_stroke {
+        if let Some(ref stroke) = path.stroke {
+            if stroke_contains(&path.data, stroke, ts, p, point) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// `p` is in the path coordinates and `point` is in the canvas coordinates.
+fn stroke_contains(
+    path: &tiny_skia_path::Path,
+    stroke: &Stroke,
+    ts: Transform,
+    mut p: Point,
+    point: Point,
+) -> bool {
+    let mut res_scale = tiny_skia_path::PathStroker::compute_resolution_scale(&ts);
+
+    // A non-scaling stroke is applied in the canvas coordinates.
+    let transformed_path;
+    let mut path = path;
+    if stroke.non_scaling {
+        transformed_path = match path.clone().transform(ts) {
+            Some(v) => v,
+            None => return false,
+        };
+        path = &transformed_path;
+        p = point;
+        res_scale = 1.0;
+    }
+
+    let dashed_path;
+    if let Some(ref list) = stroke.dasharray {
+        let dash = match tiny_skia_path::StrokeDash::new(list.clone(), stroke.dashoffset) {
+            Some(v) => v,
+            None => return false,
+        };
+
+        dashed_path = match path.dash(&dash, res_scale) {
+            Some(v) => v,
+            None => return false,
+        };
+        path = &dashed_path;
+    }
+
+    let ts_stroke = tiny_skia_path::Stroke {
+        width: stroke.width.get(),
+        miter_limit: stroke.miterlimit.get(),
+        line_cap: match stroke.linecap {
+            LineCap::Butt => tiny_skia_path::LineCap::Butt,
+            LineCap::Round => tiny_skia_path::LineCap::Round,
+            LineCap::Square => tiny_skia_path::LineCap::Square,
+        },
+        line_join: match stroke.linejoin {
+            LineJoin::Miter => tiny_skia_path::LineJoin::Miter,
+            // `arcs` is close enough to `miter-clip` for hit testing.
+            LineJoin::MiterClip | LineJoin::Arcs => tiny_skia_path::LineJoin::MiterClip,
+            LineJoin::Round => tiny_skia_path::LineJoin::Round,
+            LineJoin::Bevel => tiny_skia_path::LineJoin::Bevel,
+        },
+        dash: None,
+    };
+
+    match path.stroke(&ts_stroke, res_scale) {
+        Some(outline) => outline_contains(&outline, FillRule::NonZero, p),
+        None => false,
+    }
+}
+
+fn clip_path_contains(clip_path: &ClipPath, node: &Node, ts: Transform, point: Point) -> bool {
+    if let Some(ref sub_clip_path) = clip_path.clip_path {
+        if !clip_path_contains(sub_clip_path, node, ts, point) {
+            return false;
+        }
+    }
+
+    let mut ts = ts.pre_concat(clip_path.transform);
+    if clip_path.units == Units::ObjectBoundingBox {
+        // An element without a bbox cannot be clipped using `objectBoundingBox` units
+        // and should not be rendered at all.
+        let bbox =
+            match calc_node_bbox(node, Transform::default()).and_then(|r| r.to_non_zero_rect()) {
+                Some(v) => v,
+                None => return false,
+            };
+
+        ts = ts.pre_concat(Transform::from_bbox(bbox));
+    }
+
+    clip_path_children_contain(&clip_path.root, ts, point)
+}
+
+fn clip_path_children_contain(parent: &Node, ts: Transform, point: Point) -> bool {
+    for node in parent.children() {
+        match *node.borrow() {
+            NodeKind::Group(ref group) => {
+                let ts = ts.pre_concat(group.transform);
+
+                if let Some(ref clip_path) = group.clip_path {
+                    if !clip_path_contains(clip_path, &node, ts, point) {
+                        continue;
+                    }
+                }
+
+                if clip_path_children_contain(&node, ts, point) {
+                    return true;
+                }
+            }
+            NodeKind::Path(ref path) => {
+                // Only the geometry matters inside a clip path.
+                if path.visibility != Visibility::Visible {
+                    continue;
+                }
+
+                let rule = path.fill.as_ref().map(|f| f.rule).unwrap_or_default();
+                if let Some(p) = map_point(ts, point) {
+                    if outline_contains(&path.data, rule, p) {
+                        return true;
+                    }
+                }
+            }
+            NodeKind::Image(_) => {}
+            NodeKind::Text(ref text) => {
+                if let Some(ref flattened) = text.flattened {
+                    if clip_path_children_contain(flattened, ts, point) {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    false
+}
+
+/// Maps a point from the root coordinate system into the coordinate system
+/// defined by `ts`.
+fn map_point(ts: Transform, point: Point) -> Option<Point> {
+    let mut p = point;
+    ts.invert()?.map_point(&mut p);
+    Some(p)
+}
+
+fn rect_contains(rect: Rect, p: Point) -> bool {
+    p.x >= rect.left() && p.x <= rect.right() && p.y >= rect.top() && p.y <= rect.bottom()
+}
+
+/// Checks that a point is inside a path using the specified fill rule.
+///
+/// Curves are flattened and all subpaths are closed implicitly, just like during filling.
+fn outline_contains(path: &tiny_skia_path::Path, rule: FillRule, p: Point) -> bool {
+    // The number of lines a curve will be flattened into.
+    const CURVE_STEPS: u32 = 16;
+
+    let mut winding = 0;
+    let mut start = Point::zero();
+    let mut prev = Point::zero();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p1) => {
+                winding += line_winding(prev, start, p);
+                start = p1;
+                prev = p1;
+            }
+            PathSegment::LineTo(p1) => {
+                winding += line_winding(prev, p1, p);
+                prev = p1;
+            }
+            PathSegment::QuadTo(p1, p2) => {
+                for i in 1..=CURVE_STEPS {
+                    let t = i as f32 / CURVE_STEPS as f32;
+                    let mt = 1.0 - t;
+                    let next = Point::from_xy(
+                        mt * mt * prev.x + 2.0 * mt * t * p1.x + t * t * p2.x,
+                        mt * mt * prev.y + 2.0 * mt * t * p1.y + t * t * p2.y,
+                    );
+                    winding += line_winding(prev, next, p);
+                    prev = next;
+                }
+            }
+            PathSegment::CubicTo(p1, p2, p3) => {
+                let p0 = prev;
+                for i in 1..=CURVE_STEPS {
+                    let t = i as f32 / CURVE_STEPS as f32;
+                    let mt = 1.0 - t;
+                    let a = mt * mt * mt;
+                    let b = 3.0 * mt * mt * t;
+                    let c = 3.0 * mt * t * t;
+                    let d = t * t * t;
+                    let next = Point::from_xy(
+                        a * p0.x + b * p1.x + c * p2.x + d * p3.x,
+                        a * p0.y + b * p1.y + c * p2.y + d * p3.y,
+                    );
+                    winding += line_winding(prev, next, p);
+                    prev = next;
+                }
+            }
+            PathSegment::Close => {
+                winding += line_winding(prev, start, p);
+                prev = start;
+            }
+        }
+    }
+
+    winding += line_winding(prev, start, p);
+
+    match rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// Returns a winding number contribution of a line for a horizontal ray cast from `p`.
+fn line_winding(p0: Point, p1: Point, p: Point) -> i32 {
+    let is_left = (p1.x - p0.x) * (p.y - p0.y) - (p.x - p0.x) * (p1.y - p0.y);
+    if p0.y <= p.y {
+        if p1.y > p.y && is_left > 0.0 {
+            return 1;
+        }
+    } else if p1.y <= p.y && is_left < 0.0 {
+        return -1;
+    }
+
+    0
+}
@@ -20,6 +20,7 @@
 
 pub mod filter;
 mod geom;
+mod hit_test;
 mod text;
 
 use std::rc::Rc;
@@ -87,6 +88,30 @@ impl Default for Visibility {
     }
 }
 
+/// A pointer events property.
+///
+/// `pointer-events` attribute in the SVG.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PointerEvents {
+    VisiblePainted,
+    VisibleFill,
+    VisibleStroke,
+    Visible,
+    Painted,
+    Fill,
+    Stroke,
+    All,
+    BoundingBox,
+    None,
+}
+
+impl Default for PointerEvents {
+    fn default() -> Self {
+        Self::VisiblePainted
+    }
+}
+
 /// A shape rendering method.
 ///
 /// `shape-rendering` attribute in the SVG.
@@ -258,6 +283,13 @@ pub struct BaseGradient {
     /// `spreadMethod` in SVG.
     pub spread_method: SpreadMethod,
 
+    /// Color interpolation mode.
+    ///
+    /// Unlike filters, gradients are interpolated in sRGB by default.
+    ///
+    /// `color-interpolation` in SVG.
+    pub color_interpolation: filter::ColorInterpolation,
+
     /// A list of `stop` elements.
     pub stops: Vec<Stop>,
 }
@@ -299,6 +331,10 @@ pub struct RadialGradient {
     pub r: PositiveF32,
     pub fx: f32,
     pub fy: f32,
+    /// A focal radius.
+    ///
+    /// `fr` attribute in SVG 2.
+    pub fr: PositiveF32,
 }
 
 impl std::ops::Deref for RadialGradient {
@@ -450,6 +486,7 @@ pub enum LineJoin {
     MiterClip,
     Round,
     Bevel,
+    Arcs,
 }
 
 impl Default for LineJoin {
@@ -470,6 +507,10 @@ pub struct Stroke {
     pub width: StrokeWidth,
     pub linecap: LineCap,
     pub linejoin: LineJoin,
+    /// Indicates that the stroke must be applied after the transform.
+    ///
+    /// `vector-effect="non-scaling-stroke"` in SVG.
+    pub non_scaling: bool,
 }
 
 impl Default for Stroke {
@@ -485,6 +526,7 @@ impl Default for Stroke {
             width: StrokeWidth::new(1.0).unwrap(),
             linecap: LineCap::default(),
             linejoin: LineJoin::default(),
+            non_scaling: false,
         }
     }
 }
@@ -695,10 +737,10 @@ pub struct Mask {
     /// `mask-type` in SVG.
     pub kind: MaskType,
 
-    /// Additional mask.
+    /// Additional mask layers.
     ///
     /// `mask` in SVG.
-    pub mask: Option<Rc<Self>>,
+    pub masks: Vec<MaskLayer>,
 
     /// Clip path children.
     ///
@@ -706,6 +748,196 @@ pub struct Mask {
     pub root: Node,
 }
 
+/// A mask layer source.
+#[derive(Clone, Debug)]
+pub enum MaskSource {
+    /// A `mask` element.
+    Mask(Rc<Mask>),
+    /// An image.
+    ///
+    /// Can be set only via CSS. Like `mask-image: url(image.png)`.
+    Image(ImageKind),
+}
+
+/// A mask layer mode.
+///
+/// `mask-mode` in CSS.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MaskMode {
+    Alpha,
+    Luminance,
+    /// Uses `mask-type` for `mask` elements and alpha for images.
+    MatchSource,
+}
+
+impl Default for MaskMode {
+    fn default() -> Self {
+        Self::MatchSource
+    }
+}
+
+/// A mask layer compositing operator.
+///
+/// `mask-composite` in CSS.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MaskComposite {
+    Add,
+    Subtract,
+    Intersect,
+    Exclude,
+}
+
+impl Default for MaskComposite {
+    fn default() -> Self {
+        Self::Add
+    }
+}
+
+/// A mask image position or size component.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MaskLength {
+    /// A length in the user space.
+    Absolute(f32),
+    /// A percentage, where `50%` is `0.5`.
+    ///
+    /// Resolved relative to the object bounding box, just like in CSS.
+    Relative(f32),
+}
+
+/// A mask image position.
+///
+/// `mask-position` in CSS.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MaskPosition {
+    pub x: MaskLength,
+    pub y: MaskLength,
+}
+
+impl Default for MaskPosition {
+    fn default() -> Self {
+        MaskPosition {
+            x: MaskLength::Relative(0.0),
+            y: MaskLength::Relative(0.0),
+        }
+    }
+}
+
+/// A mask image size.
+///
+/// `mask-size` in CSS.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MaskSize {
+    /// Scales the image to cover the whole object bounding box.
+    Cover,
+    /// Scales the image to fit the object bounding box.
+    Contain,
+    /// An explicit size.
+    ///
+    /// `None` indicates `auto`, which preserves the image aspect ratio
+    /// or uses the image size when both sides are `auto`.
+    Explicit {
+        /// Image width.
+        width: Option<MaskLength>,
+        /// Image height.
+        height: Option<MaskLength>,
+    },
+}
+
+impl Default for MaskSize {
+    fn default() -> Self {
+        Self::Explicit {
+            width: None,
+            height: None,
+        }
+    }
+}
+
+/// A mask image repeat style.
+///
+/// `mask-repeat` in CSS.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MaskRepeat {
+    /// Repeat horizontally.
+    pub x: bool,
+    /// Repeat vertically.
+    pub y: bool,
+}
+
+impl Default for MaskRepeat {
+    fn default() -> Self {
+        MaskRepeat { x: true, y: true }
+    }
+}
+
+/// A mask layer.
+///
+/// One of the comma-separated layers of the `mask` property.
+#[derive(Clone, Debug)]
+pub struct MaskLayer {
+    /// Layer source.
+    ///
+    /// `mask-image` in CSS.
+    pub source: MaskSource,
+
+    /// Layer mode.
+    ///
+    /// `mask-mode` in CSS.
+    pub mode: MaskMode,
+
+    /// Specifies how this layer is combined with the layers below it.
+    ///
+    /// Ignored for the last layer.
+    ///
+    /// `mask-composite` in CSS.
+    pub composite: MaskComposite,
+
+    /// Image position inside the object bounding box.
+    ///
+    /// Not used by `mask` elements.
+    ///
+    /// `mask-position` in CSS.
+    pub position: MaskPosition,
+
+    /// Image size.
+    ///
+    /// Not used by `mask` elements.
+    ///
+    /// `mask-size` in CSS.
+    pub size: MaskSize,
+
+    /// Image repeat style.
+    ///
+    /// Not used by `mask` elements.
+    ///
+    /// `mask-repeat` in CSS.
+    pub repeat: MaskRepeat,
+}
+
+impl MaskLayer {
+    /// Creates a layer with default properties.
+    pub fn new(source: MaskSource) -> Self {
+        MaskLayer {
+            source,
+            mode: MaskMode::default(),
+            composite: MaskComposite::default(),
+            position: MaskPosition::default(),
+            size: MaskSize::default(),
+            repeat: MaskRepeat::default(),
+        }
+    }
+
+    /// Returns the layer's `mask` element, if any.
+    pub fn mask(&self) -> Option<&Rc<Mask>> {
+        match self.source {
+            MaskSource::Mask(ref mask) => Some(mask),
+            MaskSource::Image(_) => None,
+        }
+    }
+}
+
 /// Node's kind.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
@@ -772,11 +1004,20 @@ pub struct Group {
     /// `isolation` in SVG.
     pub isolate: bool,
 
+    /// Color space in which group's children are composited.
+    ///
+    /// `color-interpolation` in SVG.
+    pub color_interpolation: filter::ColorInterpolation,
+
     /// Element's clip path.
     pub clip_path: Option<Rc<ClipPath>>,
 
-    /// Element's mask.
-    pub mask: Option<Rc<Mask>>,
+    /// Element's mask layers.
+    ///
+    /// The first layer is the top one.
+    ///
+    /// `mask` and `mask-*` in SVG and CSS.
+    pub masks: Vec<MaskLayer>,
 
     /// Element's filters.
     pub filters: Vec<Rc<filter::Filter>>,
@@ -791,8 +1032,9 @@ impl Default for Group {
             opacity: Opacity::ONE,
             blend_mode: BlendMode::Normal,
             isolate: false,
+            color_interpolation: filter::ColorInterpolation::SRGB,
             clip_path: None,
-            mask: None,
+            masks: Vec::new(),
             filters: Vec::new(),
         }
     }
@@ -802,9 +1044,10 @@ impl Group {
     /// Checks if this group should be isolated during rendering.
     pub fn should_isolate(&self) -> bool {
         self.isolate
+            || self.color_interpolation == filter::ColorInterpolation::LinearRGB
             || self.opacity != Opacity::ONE
             || self.clip_path.is_some()
-            || self.mask.is_some()
+            || !self.masks.is_empty()
             || !self.filters.is_empty()
             || self.blend_mode != BlendMode::Normal // TODO: probably not needed?
     }
@@ -842,6 +1085,13 @@ pub struct Path {
     /// Element visibility.
     pub visibility: Visibility,
 
+    /// Pointer events.
+    ///
+    /// Used only by [`Tree::hit_test`]. `resvg` doesn't rely on this property.
+    ///
+    /// `pointer-events` in SVG.
+    pub pointer_events: PointerEvents,
+
     /// Fill style.
     pub fill: Option<Fill>,
 
@@ -864,15 +1114,19 @@ pub struct Path {
     /// Segments list.
     ///
     /// All segments are in absolute coordinates.
-    pub data: Rc<tiny_skia_path::Path>,
+    ///
+    /// Reference-counted and thread-safe, therefore the geometry can be shared
+    /// with render trees without copying.
+    pub data: Arc<tiny_skia_path::Path>,
 }
 
 impl Path {
     /// Creates a new `Path` with default values.
-    pub fn new(data: Rc<tiny_skia_path::Path>) -> Self {
+    pub fn new(data: Arc<tiny_skia_path::Path>) -> Self {
         Path {
             id: String::new(),
             visibility: Visibility::Visible,
+            pointer_events: PointerEvents::default(),
             fill: None,
             stroke: None,
             paint_order: PaintOrder::default(),
@@ -921,6 +1175,13 @@ pub struct Image {
     /// Element visibility.
     pub visibility: Visibility,
 
+    /// Pointer events.
+    ///
+    /// Used only by [`Tree::hit_test`]. `resvg` doesn't rely on this property.
+    ///
+    /// `pointer-events` in SVG.
+    pub pointer_events: PointerEvents,
+
     /// An image rectangle in which it should be fit.
     ///
     /// Combination of the `x`, `y`, `width`, `height` and `preserveAspectRatio`
@@ -939,6 +1200,20 @@ pub struct Image {
 /// Alias for `rctree::Node<NodeKind>`.
 pub type Node = rctree::Node<NodeKind>;
 
+/// An animated document.
+///
+/// Produces static trees for a specific point in time.
+/// Implemented by the parser for SVG documents with SMIL animations.
+pub trait Animation {
+    /// Returns the time in seconds when all animations are finished.
+    ///
+    /// Returns `None` when animations never end.
+    fn duration(&self) -> Option<f32>;
+
+    /// Returns a static tree at the specified time in seconds.
+    fn at_time(&self, time: f32) -> Option<Tree>;
+}
+
 // TODO: impl a Debug
 /// A nodes tree container.
 #[allow(missing_debug_implementations)]
@@ -962,6 +1237,16 @@ pub struct Tree {
     ///
     /// The root node is always `Group`.
     pub root: Node,
+
+    /// Document animations.
+    ///
+    /// `None` for static documents.
+    pub animation: Option<Rc<dyn Animation>>,
+
+    /// Fonts loaded from `@font-face` rules.
+    ///
+    /// Used only during text to path conversion.
+    pub font_faces: Vec<FontFace>,
 }
 
 impl Tree {
@@ -978,6 +1263,23 @@ impl Tree {
         self.root.descendants().find(|node| &*node.id() == id)
     }
 
+    /// Returns a static tree at the specified time in seconds.
+    ///
+    /// Static documents and documents that failed to be animated return a deep copy.
+    ///
+    /// Text nodes of the returned tree have to be converted again.
+    pub fn at_time(&self, time: f32) -> Tree {
+        self.animation
+            .as_ref()
+            .and_then(|animation| animation.at_time(time))
+            .unwrap_or_else(|| Tree {
+                root: self.root.make_deep_copy(),
+                animation: None,
+                font_faces: self.font_faces.clone(),
+                ..*self
+            })
+    }
+
     /// Checks if the current tree has any text nodes.
     pub fn has_text_nodes(&self) -> bool {
         has_text_nodes(&self.root)
@@ -1113,10 +1415,10 @@ fn loop_over_clip_paths(root: &Node, f: &mut dyn FnMut(Rc<ClipPath>)) {
 fn loop_over_masks(root: &Node, f: &mut dyn FnMut(Rc<Mask>)) {
     for node in root.descendants() {
         if let NodeKind::Group(ref g) = *node.borrow() {
-            if let Some(ref mask) = g.mask {
+            for mask in g.masks.iter().filter_map(MaskLayer::mask) {
                 f(mask.clone());
 
-                if let Some(ref sub_mask) = mask.mask {
+                for sub_mask in mask.masks.iter().filter_map(MaskLayer::mask) {
                     f(sub_mask.clone());
                 }
             }
@@ -1155,10 +1457,10 @@ fn node_subroots(node: &Node, f: &mut dyn FnMut(Node)) {
                 }
             }
 
-            if let Some(ref mask) = g.mask {
+            for mask in g.masks.iter().filter_map(MaskLayer::mask) {
                 f(mask.root.clone());
 
-                if let Some(ref sub_mask) = mask.mask {
+                for sub_mask in mask.masks.iter().filter_map(MaskLayer::mask) {
                     f(sub_mask.root.clone());
                 }
             }
@@ -1218,7 +1520,7 @@ pub trait NodeExt {
     ///
     /// If a current node doesn't support ID - an empty string
     /// will be returned.
-    fn id(&self) -> std::cell::Ref<str>;
+    fn id(&self) -> std::cell::Ref<'_, str>;
 
     /// Returns node's absolute transform.
     ///
@@ -1264,7 +1566,7 @@ pub trait NodeExt {
 
 impl NodeExt for Node {
     #[inline]
-    fn id(&self) -> std::cell::Ref<str> {
+    fn id(&self) -> std::cell::Ref<'_, str> {
         std::cell::Ref::map(self.borrow(), |v| v.id())
     }
 
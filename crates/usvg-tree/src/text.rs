@@ -3,10 +3,11 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::rc::Rc;
+use std::sync::Arc;
 
 use strict_num::NonZeroPositiveF32;
 
-use crate::{Fill, Node, PaintOrder, Stroke, TextRendering, Visibility};
+use crate::{Fill, Node, PaintOrder, PointerEvents, Stroke, TextRendering, Visibility};
 use tiny_skia_path::NonZeroRect;
 
 /// A font stretch property.
@@ -60,7 +61,9 @@ pub struct Font {
     pub style: FontStyle,
     /// A font stretch.
     pub stretch: FontStretch,
-    /// A font width.
+    /// A font weight.
+    ///
+    /// In a 1..=1000 range.
     pub weight: u16,
 }
 
@@ -143,6 +146,44 @@ impl Default for LengthAdjust {
     }
 }
 
+/// A text orientation property.
+///
+/// Supports both `text-orientation` and `glyph-orientation-vertical` properties.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TextOrientation {
+    Mixed,
+    Upright,
+    Sideways,
+}
+
+impl Default for TextOrientation {
+    fn default() -> Self {
+        Self::Mixed
+    }
+}
+
+/// An OpenType font feature.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FontFeature {
+    /// A feature tag, like `liga` or `tnum`.
+    pub tag: [u8; 4],
+    /// A feature value.
+    ///
+    /// `0` disables a feature and `1` enables it.
+    /// Features like `salt` can also select an alternate via larger values.
+    pub value: u32,
+}
+
+/// A font variation axis value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FontVariation {
+    /// An axis tag, like `wght` or `wdth`.
+    pub tag: [u8; 4],
+    /// An axis value.
+    pub value: f32,
+}
+
 /// A text span decoration style.
 ///
 /// In SVG, text decoration and text it's applied to can have different styles.
@@ -193,12 +234,27 @@ pub struct TextSpan {
     pub font_size: NonZeroPositiveF32,
     /// Indicates that small caps should be used.
     ///
-    /// Set by `font-variant="small-caps"`
+    /// Set by `font-variant="small-caps"` or `font-variant-caps="small-caps"`.
     pub small_caps: bool,
     /// Indicates that a kerning should be applied.
     ///
     /// Supports both `kerning` and `font-kerning` properties.
     pub apply_kerning: bool,
+    /// A list of OpenType features that should be applied during shaping.
+    ///
+    /// Resolved from `font-variant-*` and `font-feature-settings` properties,
+    /// in that order. Doesn't include `small_caps` and `apply_kerning`.
+    pub font_features: Vec<FontFeature>,
+    /// A list of font variation axes values.
+    ///
+    /// Resolved from `font-variation-settings` and `font-stretch` percentages.
+    /// `wght`, `wdth` and `opsz` axes are also set by the text layout according to
+    /// the font weight, stretch and size, unless overridden by this list.
+    pub font_variations: Vec<FontVariation>,
+    /// Indicates that the `opsz` font axis should match the font size.
+    ///
+    /// Set by `font-optical-sizing`.
+    pub apply_optical_sizing: bool,
     /// A span decorations.
     pub decoration: TextDecoration,
     /// A span dominant baseline.
@@ -211,6 +267,8 @@ pub struct TextSpan {
     pub baseline_shift: Vec<BaselineShift>,
     /// A visibility property.
     pub visibility: Visibility,
+    /// A pointer events property.
+    pub pointer_events: PointerEvents,
     /// A letter spacing property.
     pub letter_spacing: f32,
     /// A word spacing property.
@@ -219,6 +277,10 @@ pub struct TextSpan {
     pub text_length: Option<f32>,
     /// A length adjust property.
     pub length_adjust: LengthAdjust,
+    /// A text orientation property.
+    ///
+    /// Used only by the vertical writing mode.
+    pub text_orientation: TextOrientation,
 }
 
 /// A text chunk anchor property.
@@ -250,7 +312,7 @@ pub struct TextPath {
     pub start_offset: f32,
 
     /// A path.
-    pub path: Rc<tiny_skia_path::Path>,
+    pub path: Arc<tiny_skia_path::Path>,
 }
 
 /// A text chunk flow property.
@@ -283,6 +345,136 @@ pub struct TextChunk {
     pub text: String,
 }
 
+/// An SVG font glyph.
+///
+/// `glyph` and `missing-glyph` elements in SVG.
+#[derive(Clone, Debug)]
+pub struct SvgGlyph {
+    /// A glyph name.
+    ///
+    /// `glyph-name` in SVG.
+    pub name: String,
+
+    /// A sequence of characters represented by this glyph.
+    ///
+    /// A glyph with more than one character is a ligature.
+    /// Always empty for a missing glyph.
+    ///
+    /// `unicode` in SVG.
+    pub unicode: String,
+
+    /// A horizontal advance in font units.
+    ///
+    /// Already resolved using the parent font.
+    ///
+    /// `horiz-adv-x` in SVG.
+    pub horiz_adv_x: f32,
+
+    /// A glyph outline in font units.
+    ///
+    /// Just like in TrueType fonts, the Y axis points up.
+    ///
+    /// `d` in SVG. Child elements are not supported.
+    pub path: Option<Arc<tiny_skia_path::Path>>,
+}
+
+/// An SVG font kerning pair.
+///
+/// `hkern` element in SVG.
+#[derive(Clone, Debug)]
+pub struct SvgKerningPair {
+    /// Indices of the first glyphs in the pair.
+    ///
+    /// Resolved from `u1` and `g1`.
+    pub first: Vec<usize>,
+
+    /// Indices of the second glyphs in the pair.
+    ///
+    /// Resolved from `u2` and `g2`.
+    pub second: Vec<usize>,
+
+    /// An amount to decrease the spacing between the glyphs by, in font units.
+    ///
+    /// `k` in SVG.
+    pub kerning: f32,
+}
+
+/// An SVG font.
+///
+/// `font` element in SVG, along with its `font-face`.
+#[derive(Clone, Debug)]
+pub struct SvgFont {
+    /// Element's ID.
+    ///
+    /// Taken from the SVG itself.
+    /// Can be empty.
+    pub id: String,
+
+    /// A family name.
+    ///
+    /// `font-family` of the `font-face` element in SVG.
+    pub family: String,
+
+    /// A font style.
+    pub style: FontStyle,
+
+    /// A font weight.
+    pub weight: u16,
+
+    /// Number of units per em.
+    ///
+    /// `units-per-em` in SVG.
+    pub units_per_em: NonZeroPositiveF32,
+
+    /// An ascent in font units.
+    pub ascent: f32,
+
+    /// A descent in font units.
+    ///
+    /// Unlike in SVG, is usually negative, like in TrueType fonts.
+    pub descent: f32,
+
+    /// A x-height in font units.
+    pub x_height: Option<f32>,
+
+    /// A list of glyphs.
+    pub glyphs: Vec<SvgGlyph>,
+
+    /// A glyph that should be used for characters not present in the font.
+    ///
+    /// A blank glyph when `missing-glyph` is not set.
+    pub missing_glyph: SvgGlyph,
+
+    /// A list of horizontal kerning pairs.
+    pub kerning_pairs: Vec<SvgKerningPair>,
+}
+
+/// A web font.
+///
+/// `@font-face` rule in CSS.
+#[derive(Clone, Debug)]
+pub struct FontFace {
+    /// A family name.
+    ///
+    /// `font-family` descriptor in CSS.
+    pub family: String,
+
+    /// A font style.
+    ///
+    /// `font-style` descriptor in CSS.
+    pub style: FontStyle,
+
+    /// A font weight.
+    ///
+    /// `font-weight` descriptor in CSS.
+    pub weight: u16,
+
+    /// Raw font data.
+    ///
+    /// WOFF and WOFF2 fonts are already decompressed.
+    pub data: Arc<Vec<u8>>,
+}
+
 /// A writing mode.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -291,6 +483,100 @@ pub enum WritingMode {
     TopToBottom,
 }
 
+/// A text alignment inside a `shape-inside` area.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TextAlign {
+    Start,
+    End,
+    Left,
+    Right,
+    Center,
+    Justify,
+}
+
+impl Default for TextAlign {
+    fn default() -> Self {
+        Self::Start
+    }
+}
+
+/// A line height property.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LineHeight {
+    /// Font's ascent plus descent.
+    Normal,
+    /// A font size multiplier.
+    ///
+    /// Percentage values are stored as multipliers as well.
+    Number(f32),
+    /// An absolute line height in user units.
+    Length(f32),
+}
+
+impl Default for LineHeight {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+/// A shape used by `shape-inside`.
+#[derive(Clone, Debug)]
+pub struct TextWrapShape {
+    /// Referenced element's ID.
+    ///
+    /// Taken from the SVG itself.
+    pub id: String,
+
+    /// A shape outline in text coordinates.
+    ///
+    /// Referenced element's transform is already applied.
+    pub path: Arc<tiny_skia_path::Path>,
+}
+
+/// A content area of an auto-wrapped text.
+#[derive(Clone, Debug)]
+pub enum TextWrapArea {
+    /// A maximum line length.
+    ///
+    /// Lines are positioned using the first chunk's position and anchor.
+    ///
+    /// `inline-size` in SVG.
+    InlineSize(NonZeroPositiveF32),
+    /// A shape to fill with text.
+    ///
+    /// `shape-inside` in SVG.
+    Shape(TextWrapShape),
+}
+
+/// Auto-wrapped text properties.
+///
+/// Lines are created during text layout, since they depend on glyph metrics.
+#[derive(Clone, Debug)]
+pub struct TextWrap {
+    /// A content area.
+    pub area: TextWrapArea,
+
+    /// A line height.
+    ///
+    /// `line-height` in CSS.
+    pub line_height: LineHeight,
+
+    /// A text alignment.
+    ///
+    /// Used only by `shape-inside`. `inline-size` uses `text-anchor` instead.
+    ///
+    /// `text-align` in CSS.
+    pub align: TextAlign,
+
+    /// Indicates that lines can be broken at soft wrap opportunities.
+    ///
+    /// Lines are still broken at preserved newlines.
+    ///
+    /// `false` for `white-space: pre` and `white-space: nowrap`.
+    pub allow_wrap: bool,
+}
+
 /// A text element.
 ///
 /// `text` element in SVG.
@@ -327,8 +613,21 @@ pub struct Text {
     pub writing_mode: WritingMode,
 
     /// A list of text chunks.
+    ///
+    /// When `wrap` is set, can contain preserved newlines.
     pub chunks: Vec<TextChunk>,
 
+    /// Auto-wrapping properties.
+    ///
+    /// `None` when neither `inline-size` nor `shape-inside` is set.
+    pub wrap: Option<TextWrap>,
+
+    /// SVG fonts that can be used by this text.
+    ///
+    /// Contains only fonts referenced by `font-family` of the text spans.
+    /// Have a higher priority than system fonts with the same family name.
+    pub svg_fonts: Vec<Rc<SvgFont>>,
+
     /// Contains a text bounding box.
     ///
     /// Text bounding box is special in SVG and doesn't represent
@@ -342,6 +641,8 @@ pub struct Text {
 
     /// Text converted into paths, ready to render.
     ///
+    /// Color glyphs, like emojis, are represented by groups of paths and by images.
+    ///
     /// Will be set by
     /// [`usvg_text_layout::TreeTextToPath::convert_text`](
     /// https://docs.rs/usvg-text-layout/latest/usvg_text_layout/trait.TreeTextToPath.html#tymethod.convert_text)
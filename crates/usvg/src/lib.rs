@@ -59,7 +59,21 @@ pub use usvg_parser::*;
 pub use usvg_text_layout::*;
 pub use usvg_tree::*;
 
-pub use writer::XmlOptions;
+pub use writer::{FlattenedText, XmlOptions};
+
+/// Parses an SVG document from the `SVG ` font table.
+///
+/// Can be passed to [`TreeTextToPath::convert_text_with_svg_glyphs`].
+#[cfg(feature = "text")]
+pub fn parse_svg_glyph(data: &[u8]) -> Option<Tree> {
+    match Tree::from_data(data, &Options::default()) {
+        Ok(tree) => Some(tree),
+        Err(e) => {
+            log::warn!("Failed to parse an SVG glyph cause {}.", e);
+            None
+        }
+    }
+}
 
 /// A trait to write `usvg::Tree` back to SVG.
 pub trait TreeWriting {
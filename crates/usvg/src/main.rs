@@ -92,6 +92,8 @@ OPTIONS:
                                     option. [values: 1..4294967295 (inclusive)] [default: 100]
 
   --preserve-text                   Do not convert text into paths.
+  --flattened-text MODE             Sets how text converted into paths is written
+                                    [values: paths, aria-label, invisible-text] [default: paths]
   --id-prefix                       Adds a prefix to each ID attribute
   --indent INDENT                   Sets the XML nodes indent
                                     [values: none, 0, 1, 2, 3, 4, tabs] [default: 4]
@@ -130,6 +132,7 @@ struct Args {
     font_dirs: Vec<PathBuf>,
     skip_system_fonts: bool,
     preserve_text: bool,
+    flattened_text: usvg::FlattenedText,
     list_fonts: bool,
     default_width: u32,
     default_height: u32,
@@ -190,6 +193,9 @@ fn collect_args() -> Result<Args, pico_args::Error> {
         font_dirs: input.values_from_str("--use-fonts-dir")?,
         skip_system_fonts: input.contains("--skip-system-fonts"),
         preserve_text: input.contains("--preserve-text"),
+        flattened_text: input
+            .opt_value_from_fn("--flattened-text", parse_flattened_text)?
+            .unwrap_or_default(),
         list_fonts: input.contains("--list-fonts"),
         default_width: input
             .opt_value_from_fn("--default-width", parse_length)?
@@ -219,7 +225,7 @@ fn collect_args() -> Result<Args, pico_args::Error> {
 fn parse_dpi(s: &str) -> Result<u32, String> {
     let n: u32 = s.parse().map_err(|_| "invalid number")?;
 
-    if n >= 10 && n <= 4000 {
+    if (10..=4000).contains(&n) {
         Ok(n)
     } else {
         Err("DPI out of bounds".to_string())
@@ -249,6 +255,15 @@ fn parse_languages(s: &str) -> Result<Vec<String>, String> {
     Ok(langs)
 }
 
+fn parse_flattened_text(s: &str) -> Result<usvg::FlattenedText, String> {
+    match s {
+        "paths" => Ok(usvg::FlattenedText::Paths),
+        "aria-label" => Ok(usvg::FlattenedText::AriaLabel),
+        "invisible-text" => Ok(usvg::FlattenedText::InvisibleText),
+        _ => Err("invalid flattened text mode".to_string()),
+    }
+}
+
 fn parse_indent(s: &str) -> Result<xmlwriter::Indent, String> {
     let indent = match s {
         "none" => xmlwriter::Indent::None,
@@ -312,7 +327,7 @@ fn main() {
     }
 
     if let Err(e) = process(args) {
-        eprintln!("Error: {}.", e.to_string());
+        eprintln!("Error: {}.", e);
         process::exit(1);
     }
 }
@@ -325,7 +340,7 @@ fn process(args: Args) -> Result<(), String> {
         let svg_from = if in_svg == "-" {
             InputFrom::Stdin
         } else if in_svg == "-c" {
-            return Err(format!("-c should be set after input"));
+            return Err("-c should be set after input".to_string());
         } else {
             InputFrom::File(in_svg)
         };
@@ -421,6 +436,10 @@ fn process(args: Args) -> Result<(), String> {
         )
         .unwrap(),
         image_href_resolver: usvg_parser::ImageHrefResolver::default(),
+        use_href_resolver: usvg_parser::UseHrefResolver::default(),
+        style_sheet: None,
+        color_scheme: usvg_parser::ColorScheme::default(),
+        current_color: None,
     };
 
     let input_svg = match in_svg {
@@ -430,13 +449,14 @@ fn process(args: Args) -> Result<(), String> {
 
     let mut tree = usvg_tree::Tree::from_data(&input_svg, &re_opt).map_err(|e| format!("{}", e))?;
     if !args.preserve_text {
-        tree.convert_text(&fontdb);
+        tree.convert_text_with_svg_glyphs(&fontdb, &usvg::parse_svg_glyph, None);
     }
 
     let xml_opt = usvg::XmlOptions {
         id_prefix: args.id_prefix,
         coordinates_precision: args.coordinates_precision.unwrap_or(8),
         transforms_precision: args.transforms_precision.unwrap_or(8),
+        flattened_text: args.flattened_text,
         writer_opts: xmlwriter::Options {
             use_single_quote: false,
             indent: args.indent,
@@ -449,13 +469,13 @@ fn process(args: Args) -> Result<(), String> {
         OutputTo::Stdout => {
             io::stdout()
                 .write_all(s.as_bytes())
-                .map_err(|_| format!("failed to write to the stdout"))?;
+                .map_err(|_| "failed to write to the stdout".to_string())?;
         }
         OutputTo::File(path) => {
             let mut f =
-                File::create(path).map_err(|_| format!("failed to create the output file"))?;
+                File::create(path).map_err(|_| "failed to create the output file".to_string())?;
             f.write_all(s.as_bytes())
-                .map_err(|_| format!("failed to write to the output file"))?;
+                .map_err(|_| "failed to write to the output file".to_string())?;
         }
     }
 
@@ -469,7 +489,7 @@ fn load_stdin() -> Result<Vec<u8>, String> {
 
     handle
         .read_to_end(&mut buf)
-        .map_err(|_| format!("failed to read from stdin"))?;
+        .map_err(|_| "failed to read from stdin".to_string())?;
 
     Ok(buf)
 }
@@ -484,7 +504,7 @@ impl log::Log for SimpleLogger {
 
     fn log(&self, record: &log::Record) {
         if self.enabled(record.metadata()) {
-            let target = if record.target().len() > 0 {
+            let target = if !record.target().is_empty() {
                 record.target()
             } else {
                 record.module_path().unwrap_or_default()
@@ -45,6 +45,11 @@ pub struct XmlOptions {
     /// Default: 8
     pub transforms_precision: u8,
 
+    /// Controls how text converted into paths is written.
+    ///
+    /// Default: `FlattenedText::Paths`
+    pub flattened_text: FlattenedText,
+
     /// `xmlwriter` options.
     pub writer_opts: xmlwriter::Options,
 }
@@ -55,11 +60,29 @@ impl Default for XmlOptions {
             id_prefix: Default::default(),
             coordinates_precision: 8,
             transforms_precision: 8,
+            flattened_text: FlattenedText::default(),
             writer_opts: Default::default(),
         }
     }
 }
 
+/// Controls how text converted into paths is written.
+///
+/// Has no effect on text that wasn't converted into paths
+/// and on text inside clip paths.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum FlattenedText {
+    /// Only glyph paths are written. The original text is lost.
+    #[default]
+    Paths,
+    /// Glyph paths are wrapped into a group with an `aria-label` attribute
+    /// containing the original text.
+    AriaLabel,
+    /// Glyph paths are followed by the original `text` element with a transparent fill,
+    /// so the text stays selectable and searchable, while the rendering stays exact.
+    InvisibleText,
+}
+
 struct WriterContext<'a> {
     opt: &'a XmlOptions,
 
@@ -140,9 +163,7 @@ impl WriterContext<'_> {
 
     fn push_defs_id<T>(&mut self, node: &Rc<T>, id: String) {
         let key = Rc::as_ptr(node) as usize;
-        if !self.id_map.contains_key(&key) {
-            self.id_map.insert(key, id);
-        }
+        self.id_map.entry(key).or_insert(id);
     }
 
     fn get_defs_id<T>(&self, node: &Rc<T>) -> Option<&str> {
@@ -638,7 +659,7 @@ fn write_defs(tree: &Tree, ctx: &mut WriterContext, xml: &mut XmlWriter) {
             Paint::Color(_) => {}
             Paint::LinearGradient(lg) => {
                 xml.start_svg_element(EId::LinearGradient);
-                ctx.prepare_defs_id(&lg, &lg.id, xml, |ctx| ctx.gen_linear_gradient_id());
+                ctx.prepare_defs_id(lg, &lg.id, xml, |ctx| ctx.gen_linear_gradient_id());
                 xml.write_svg_attribute(AId::X1, &lg.x1);
                 xml.write_svg_attribute(AId::Y1, &lg.y1);
                 xml.write_svg_attribute(AId::X2, &lg.x2);
@@ -648,12 +669,15 @@ fn write_defs(tree: &Tree, ctx: &mut WriterContext, xml: &mut XmlWriter) {
             }
             Paint::RadialGradient(rg) => {
                 xml.start_svg_element(EId::RadialGradient);
-                ctx.prepare_defs_id(&rg, &rg.id, xml, |ctx| ctx.gen_radial_gradient_id());
+                ctx.prepare_defs_id(rg, &rg.id, xml, |ctx| ctx.gen_radial_gradient_id());
                 xml.write_svg_attribute(AId::Cx, &rg.cx);
                 xml.write_svg_attribute(AId::Cy, &rg.cy);
                 xml.write_svg_attribute(AId::R, &rg.r.get());
                 xml.write_svg_attribute(AId::Fx, &rg.fx);
                 xml.write_svg_attribute(AId::Fy, &rg.fy);
+                if rg.fr.get() != 0.0 {
+                    xml.write_svg_attribute(AId::Fr, &rg.fr.get());
+                }
                 write_base_grad(&rg.base, ctx, xml);
                 xml.end_element();
             }
@@ -735,10 +759,14 @@ fn write_defs(tree: &Tree, ctx: &mut WriterContext, xml: &mut XmlWriter) {
         );
         xml.write_rect_attrs(mask.rect);
 
-        if let Some(ref mask) = mask.mask {
-            // TODO: usvg-parser guarantees that this id is never empty,
-            //       but a manually created `Tree` is not. Figure out what to do.
-            xml.write_func_iri(AId::Mask, &mask.id, ctx);
+        // TODO: usvg-parser guarantees that this id is never empty,
+        //       but a manually created `Tree` is not. Figure out what to do.
+        let sub_mask_id = |mask: &Rc<Mask>| Some(mask.id.clone());
+        if let Some(id) = single_mask_id(&mask.masks, sub_mask_id) {
+            xml.write_func_iri(AId::Mask, &id, ctx);
+        } else if !mask.masks.is_empty() {
+            let value = format_mask_layers(&mask.masks, sub_mask_id, ctx);
+            xml.write_attribute_fmt(AId::Style.to_str(), format_args!("mask:{}", value));
         }
 
         write_elements(&mask.root, false, ctx, xml);
@@ -756,6 +784,7 @@ fn write_text_path_paths(node: &Node, ctx: &mut WriterContext, xml: &mut XmlWrit
                         id: ctx.gen_path_id(),
                         data: text_path.path.clone(),
                         visibility: Visibility::default(),
+                        pointer_events: PointerEvents::default(),
                         fill: None,
                         stroke: None,
                         rendering_mode: ShapeRendering::default(),
@@ -766,6 +795,21 @@ fn write_text_path_paths(node: &Node, ctx: &mut WriterContext, xml: &mut XmlWrit
                         .insert(text_path.id.clone(), path.id.clone());
                 }
             }
+
+            if let Some(TextWrapArea::Shape(ref shape)) = text.wrap.as_ref().map(|w| &w.area) {
+                let path = Path {
+                    id: ctx.gen_path_id(),
+                    data: shape.path.clone(),
+                    visibility: Visibility::default(),
+                    pointer_events: PointerEvents::default(),
+                    fill: None,
+                    stroke: None,
+                    rendering_mode: ShapeRendering::default(),
+                    paint_order: PaintOrder::default(),
+                };
+                write_path(&path, false, Transform::default(), None, ctx, xml);
+                ctx.text_path_map.insert(shape.id.clone(), path.id.clone());
+            }
         }
 
         node.subroots(|subroot| write_text_path_paths(&subroot, ctx, xml));
@@ -795,6 +839,7 @@ fn write_element(node: &Node, is_clip_path: bool, ctx: &mut WriterContext, xml:
             }
 
             xml.write_visibility(img.visibility);
+            xml.write_pointer_events(img.pointer_events);
 
             match img.rendering_mode {
                 ImageRendering::OptimizeQuality => {}
@@ -851,10 +896,15 @@ fn write_element(node: &Node, is_clip_path: bool, ctx: &mut WriterContext, xml:
                 }
             }
 
-            if let Some(ref mask) = g.mask {
-                if let Some(id) = ctx.get_defs_id(mask) {
-                    xml.write_func_iri(AId::Mask, id, ctx);
-                }
+            let mut style = Vec::new();
+            let mask_id = |mask: &Rc<Mask>| ctx.get_defs_id(mask).map(str::to_string);
+            if let Some(id) = single_mask_id(&g.masks, mask_id) {
+                xml.write_func_iri(AId::Mask, &id, ctx);
+            } else if !g.masks.is_empty() {
+                style.push(format!(
+                    "mask:{}",
+                    format_mask_layers(&g.masks, mask_id, ctx)
+                ));
             }
 
             if !g.filters.is_empty() {
@@ -872,6 +922,10 @@ fn write_element(node: &Node, is_clip_path: bool, ctx: &mut WriterContext, xml:
                 xml.write_svg_attribute(AId::Opacity, &g.opacity.get());
             }
 
+            if g.color_interpolation == filter::ColorInterpolation::LinearRGB {
+                xml.write_svg_attribute(AId::ColorInterpolation, "linearRGB");
+            }
+
             xml.write_transform(AId::Transform, g.transform, ctx);
 
             if g.blend_mode != BlendMode::Normal || g.isolate {
@@ -897,10 +951,14 @@ fn write_element(node: &Node, is_clip_path: bool, ctx: &mut WriterContext, xml:
                 // For reasons unknown, `mix-blend-mode` and `isolation` must be written
                 // as `style` attribute.
                 let isolation = if g.isolate { "isolate" } else { "auto" };
-                xml.write_attribute_fmt(
-                    AId::Style.to_str(),
-                    format_args!("mix-blend-mode:{};isolation:{}", blend_mode, isolation),
-                );
+                style.push(format!(
+                    "mix-blend-mode:{};isolation:{}",
+                    blend_mode, isolation
+                ));
+            }
+
+            if !style.is_empty() {
+                xml.write_svg_attribute(AId::Style, &style.join(";"));
             }
 
             write_elements(node, false, ctx, xml);
@@ -909,120 +967,176 @@ fn write_element(node: &Node, is_clip_path: bool, ctx: &mut WriterContext, xml:
         }
         NodeKind::Text(ref text) => {
             if let Some(ref flattened) = text.flattened {
-                write_element(flattened, is_clip_path, ctx, xml);
+                write_flattened_text(text, flattened, is_clip_path, ctx, xml);
             } else {
-                xml.start_svg_element(EId::Text);
+                write_text(text, is_clip_path, ctx, xml);
+            }
+        }
+    }
+}
+
+fn write_flattened_text(
+    text: &Text,
+    flattened: &Node,
+    is_clip_path: bool,
+    ctx: &mut WriterContext,
+    xml: &mut XmlWriter,
+) {
+    // Clip paths cannot contain groups and any additional text would affect the clip region.
+    if is_clip_path {
+        write_element(flattened, is_clip_path, ctx, xml);
+        return;
+    }
 
-                if !text.id.is_empty() {
-                    xml.write_id_attribute(&text.id, ctx);
+    match ctx.opt.flattened_text {
+        FlattenedText::Paths => {
+            write_element(flattened, is_clip_path, ctx, xml);
+        }
+        FlattenedText::AriaLabel => {
+            xml.start_svg_element(EId::G);
+            let label: String = text.chunks.iter().map(|c| c.text.as_str()).collect();
+            xml.write_attribute("aria-label", &escape_attribute(&label));
+            write_element(flattened, is_clip_path, ctx, xml);
+            xml.end_element();
+        }
+        FlattenedText::InvisibleText => {
+            write_element(flattened, is_clip_path, ctx, xml);
+
+            // The ID is already used by the flattened group.
+            let mut text = text.clone();
+            text.id.clear();
+            for chunk in &mut text.chunks {
+                for span in &mut chunk.spans {
+                    span.fill = Some(Fill {
+                        opacity: Opacity::ZERO,
+                        ..Fill::default()
+                    });
+                    span.stroke = None;
+                    span.decoration.underline = None;
+                    span.decoration.overline = None;
+                    span.decoration.line_through = None;
                 }
+            }
 
-                xml.write_attribute("xml:space", "preserve");
+            write_text(&text, is_clip_path, ctx, xml);
+        }
+    }
+}
 
-                match text.writing_mode {
-                    WritingMode::LeftToRight => {}
-                    WritingMode::TopToBottom => xml.write_svg_attribute(AId::WritingMode, "tb"),
-                }
+/// Escapes characters that are not escaped by `xmlwriter` in attribute values.
+fn escape_attribute(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;")
+}
 
-                match text.rendering_mode {
-                    TextRendering::OptimizeSpeed => {
-                        xml.write_svg_attribute(AId::TextRendering, "optimizeSpeed")
-                    }
-                    TextRendering::GeometricPrecision => {
-                        xml.write_svg_attribute(AId::TextRendering, "geometricPrecision")
-                    }
-                    TextRendering::OptimizeLegibility => {}
-                }
+fn write_text(text: &Text, is_clip_path: bool, ctx: &mut WriterContext, xml: &mut XmlWriter) {
+    xml.start_svg_element(EId::Text);
 
-                if text.rotate.iter().any(|r| *r != 0.0) {
-                    xml.write_numbers(AId::Rotate, &text.rotate);
-                }
+    if !text.id.is_empty() {
+        xml.write_id_attribute(&text.id, ctx);
+    }
 
-                if text.dx.iter().any(|dx| *dx != 0.0) {
-                    xml.write_numbers(AId::Dx, &text.dx);
-                }
+    xml.write_attribute("xml:space", "preserve");
 
-                if text.dy.iter().any(|dy| *dy != 0.0) {
-                    xml.write_numbers(AId::Dy, &text.dy);
-                }
+    match text.writing_mode {
+        WritingMode::LeftToRight => {}
+        WritingMode::TopToBottom => xml.write_svg_attribute(AId::WritingMode, "tb"),
+    }
 
-                xml.set_preserve_whitespaces(true);
+    match text.rendering_mode {
+        TextRendering::OptimizeSpeed => {
+            xml.write_svg_attribute(AId::TextRendering, "optimizeSpeed")
+        }
+        TextRendering::GeometricPrecision => {
+            xml.write_svg_attribute(AId::TextRendering, "geometricPrecision")
+        }
+        TextRendering::OptimizeLegibility => {}
+    }
 
-                for chunk in &text.chunks {
-                    if let TextFlow::Path(text_path) = &chunk.text_flow {
-                        xml.start_svg_element(EId::TextPath);
+    if text.rotate.iter().any(|r| *r != 0.0) {
+        xml.write_numbers(AId::Rotate, &text.rotate);
+    }
 
-                        let prefix = ctx.opt.id_prefix.as_deref().unwrap_or_default();
-                        let ref_path = ctx.text_path_map.get(&text_path.id).unwrap();
-                        xml.write_attribute_fmt(
-                            "xlink:href",
-                            format_args!("#{}{}", prefix, ref_path),
-                        );
+    if text.dx.iter().any(|dx| *dx != 0.0) {
+        xml.write_numbers(AId::Dx, &text.dx);
+    }
 
-                        if text_path.start_offset != 0.0 {
-                            xml.write_svg_attribute(AId::StartOffset, &text_path.start_offset);
-                        }
-                    }
+    if text.dy.iter().any(|dy| *dy != 0.0) {
+        xml.write_numbers(AId::Dy, &text.dy);
+    }
 
-                    xml.start_svg_element(EId::Tspan);
+    if let Some(ref wrap) = text.wrap {
+        write_text_wrap(wrap, ctx, xml);
+    }
 
-                    if let Some(x) = chunk.x {
-                        xml.write_svg_attribute(AId::X, &x);
-                    }
+    xml.set_preserve_whitespaces(true);
 
-                    if let Some(y) = chunk.y {
-                        xml.write_svg_attribute(AId::Y, &y);
-                    }
+    for chunk in &text.chunks {
+        if let TextFlow::Path(text_path) = &chunk.text_flow {
+            xml.start_svg_element(EId::TextPath);
 
-                    match chunk.anchor {
-                        TextAnchor::Start => {}
-                        TextAnchor::Middle => xml.write_svg_attribute(AId::TextAnchor, "middle"),
-                        TextAnchor::End => xml.write_svg_attribute(AId::TextAnchor, "end"),
-                    }
+            let prefix = ctx.opt.id_prefix.as_deref().unwrap_or_default();
+            let ref_path = ctx.text_path_map.get(&text_path.id).unwrap();
+            xml.write_attribute_fmt("xlink:href", format_args!("#{}{}", prefix, ref_path));
 
-                    for span in &chunk.spans {
-                        let decorations: Vec<_> = [
-                            ("underline", &span.decoration.underline),
-                            ("line-through", &span.decoration.line_through),
-                            ("overline", &span.decoration.overline),
-                        ]
-                        .iter()
-                        .filter_map(|&(key, option_value)| {
-                            option_value.as_ref().map(|value| (key, value))
-                        })
-                        .collect();
-
-                        // Decorations need to be dumped BEFORE we write the actual span data
-                        // (so that for example stroke color of span doesn't affect the text
-                        // itself while baseline shifts need to be written after (since they are
-                        // affected by the font size)
-                        for (deco_name, deco) in &decorations {
-                            xml.start_svg_element(EId::Tspan);
-                            xml.write_svg_attribute(AId::TextDecoration, deco_name);
-                            write_fill(&deco.fill, false, ctx, xml);
-                            write_stroke(&deco.stroke, ctx, xml);
-                        }
+            if text_path.start_offset != 0.0 {
+                xml.write_svg_attribute(AId::StartOffset, &text_path.start_offset);
+            }
+        }
 
-                        write_span(is_clip_path, ctx, xml, chunk, span);
+        xml.start_svg_element(EId::Tspan);
 
-                        // End for each tspan we needed to create for decorations
-                        for _ in &decorations {
-                            xml.end_element();
-                        }
-                    }
-                    xml.end_element();
+        if let Some(x) = chunk.x {
+            xml.write_svg_attribute(AId::X, &x);
+        }
 
-                    // End textPath element
-                    if matches!(&chunk.text_flow, TextFlow::Path(_)) {
-                        xml.end_element();
-                    }
-                }
+        if let Some(y) = chunk.y {
+            xml.write_svg_attribute(AId::Y, &y);
+        }
+
+        match chunk.anchor {
+            TextAnchor::Start => {}
+            TextAnchor::Middle => xml.write_svg_attribute(AId::TextAnchor, "middle"),
+            TextAnchor::End => xml.write_svg_attribute(AId::TextAnchor, "end"),
+        }
+
+        for span in &chunk.spans {
+            let decorations: Vec<_> = [
+                ("underline", &span.decoration.underline),
+                ("line-through", &span.decoration.line_through),
+                ("overline", &span.decoration.overline),
+            ]
+            .iter()
+            .filter_map(|&(key, option_value)| option_value.as_ref().map(|value| (key, value)))
+            .collect();
+
+            // Decorations need to be dumped BEFORE we write the actual span data
+            // (so that for example stroke color of span doesn't affect the text
+            // itself while baseline shifts need to be written after (since they are
+            // affected by the font size)
+            for (deco_name, deco) in &decorations {
+                xml.start_svg_element(EId::Tspan);
+                xml.write_svg_attribute(AId::TextDecoration, deco_name);
+                write_fill(&deco.fill, false, ctx, xml);
+                write_stroke(&deco.stroke, ctx, xml);
+            }
+
+            write_span(is_clip_path, ctx, xml, chunk, span);
 
+            // End for each tspan we needed to create for decorations
+            for _ in &decorations {
                 xml.end_element();
-                xml.set_preserve_whitespaces(false);
             }
         }
+        xml.end_element();
+
+        // End textPath element
+        if matches!(&chunk.text_flow, TextFlow::Path(_)) {
+            xml.end_element();
+        }
     }
+
+    xml.end_element();
+    xml.set_preserve_whitespaces(false);
 }
 
 trait XmlWriterExt {
@@ -1035,6 +1149,7 @@ trait XmlWriterExt {
     fn write_units(&mut self, id: AId, units: Units, def: Units);
     fn write_transform(&mut self, id: AId, units: Transform, ctx: &WriterContext);
     fn write_visibility(&mut self, value: Visibility);
+    fn write_pointer_events(&mut self, value: PointerEvents);
     fn write_func_iri(&mut self, aid: AId, id: &str, ctx: &WriterContext);
     fn write_rect_attrs(&mut self, r: NonZeroRect);
     fn write_numbers(&mut self, aid: AId, list: &[f32]);
@@ -1170,6 +1285,22 @@ impl XmlWriterExt for XmlWriter {
         }
     }
 
+    fn write_pointer_events(&mut self, value: PointerEvents) {
+        let name = match value {
+            PointerEvents::VisiblePainted => return,
+            PointerEvents::VisibleFill => "visibleFill",
+            PointerEvents::VisibleStroke => "visibleStroke",
+            PointerEvents::Visible => "visible",
+            PointerEvents::Painted => "painted",
+            PointerEvents::Fill => "fill",
+            PointerEvents::Stroke => "stroke",
+            PointerEvents::All => "all",
+            PointerEvents::BoundingBox => "bounding-box",
+            PointerEvents::None => "none",
+        };
+        self.write_attribute(AId::PointerEvents.to_str(), name);
+    }
+
     fn write_func_iri(&mut self, aid: AId, id: &str, ctx: &WriterContext) {
         debug_assert!(!id.is_empty());
         let prefix = ctx.opt.id_prefix.as_deref().unwrap_or_default();
@@ -1289,6 +1420,117 @@ impl XmlWriterExt for XmlWriter {
     }
 }
 
+/// Returns a mask ID when mask layers can be written as a plain `mask` attribute.
+fn single_mask_id(
+    layers: &[MaskLayer],
+    mask_id: impl Fn(&Rc<Mask>) -> Option<String>,
+) -> Option<String> {
+    match layers {
+        [layer] if layer.mode.is_default() && layer.composite.is_default() => {
+            mask_id(layer.mask()?)
+        }
+        _ => None,
+    }
+}
+
+/// Formats mask layers as a `mask` shorthand value.
+fn format_mask_layers(
+    layers: &[MaskLayer],
+    mask_id: impl Fn(&Rc<Mask>) -> Option<String>,
+    ctx: &WriterContext,
+) -> String {
+    fn length(value: MaskLength) -> String {
+        match value {
+            MaskLength::Absolute(n) => format!("{}px", n),
+            MaskLength::Relative(n) => format!("{}%", n * 100.0),
+        }
+    }
+
+    let prefix = ctx.opt.id_prefix.as_deref().unwrap_or_default();
+
+    let mut values = Vec::new();
+    for layer in layers {
+        let mut parts = Vec::new();
+        match layer.source {
+            MaskSource::Mask(ref mask) => match mask_id(mask) {
+                Some(id) => parts.push(format!("url(#{}{})", prefix, id)),
+                None => continue,
+            },
+            MaskSource::Image(ref kind) => {
+                parts.push(format!("url({})", image_data_url(kind)));
+
+                // Position, size and repeat are used only by images.
+                if !layer.size.is_default() || !layer.position.is_default() {
+                    parts.push(format!(
+                        "{} {}",
+                        length(layer.position.x),
+                        length(layer.position.y)
+                    ));
+                }
+
+                if !layer.size.is_default() {
+                    let side = |v: Option<MaskLength>| v.map(length).unwrap_or("auto".into());
+                    parts.push("/".to_string());
+                    parts.push(match layer.size {
+                        MaskSize::Cover => "cover".to_string(),
+                        MaskSize::Contain => "contain".to_string(),
+                        MaskSize::Explicit { width, height } => {
+                            format!("{} {}", side(width), side(height))
+                        }
+                    });
+                }
+
+                if !layer.repeat.is_default() {
+                    let repeat = |v: bool| if v { "repeat" } else { "no-repeat" };
+                    parts.push(format!(
+                        "{} {}",
+                        repeat(layer.repeat.x),
+                        repeat(layer.repeat.y)
+                    ));
+                }
+            }
+        }
+
+        match layer.mode {
+            MaskMode::Alpha => parts.push("alpha".to_string()),
+            MaskMode::Luminance => parts.push("luminance".to_string()),
+            MaskMode::MatchSource => {}
+        }
+
+        match layer.composite {
+            MaskComposite::Add => {}
+            MaskComposite::Subtract => parts.push("subtract".to_string()),
+            MaskComposite::Intersect => parts.push("intersect".to_string()),
+            MaskComposite::Exclude => parts.push("exclude".to_string()),
+        }
+
+        values.push(parts.join(" "));
+    }
+
+    values.join(", ")
+}
+
+fn image_data_url(kind: &ImageKind) -> String {
+    use base64::Engine;
+
+    let svg_string;
+    let (mime, data) = match kind {
+        ImageKind::JPEG(ref data) => ("jpeg", data.as_slice()),
+        ImageKind::PNG(ref data) => ("png", data.as_slice()),
+        ImageKind::GIF(ref data) => ("gif", data.as_slice()),
+        ImageKind::SVG(ref tree) => {
+            svg_string = tree.to_string(&XmlOptions::default());
+            ("svg+xml", svg_string.as_bytes())
+        }
+    };
+
+    format!(
+        "data:image/{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(data)
+    )
+}
+
 fn has_xlink(node: &Node) -> bool {
     for n in node.descendants() {
         match *n.borrow() {
@@ -1303,12 +1545,12 @@ fn has_xlink(node: &Node) -> bool {
                     }
                 }
 
-                if let Some(ref mask) = g.mask {
+                for mask in g.masks.iter().filter_map(MaskLayer::mask) {
                     if has_xlink(&mask.root) {
                         return true;
                     }
 
-                    if let Some(ref sub_mask) = mask.mask {
+                    for sub_mask in mask.masks.iter().filter_map(MaskLayer::mask) {
                         if has_xlink(&sub_mask.root) {
                             return true;
                         }
@@ -1318,15 +1560,14 @@ fn has_xlink(node: &Node) -> bool {
             NodeKind::Image(_) => {
                 return true;
             }
-            NodeKind::Text(ref text) => {
+            NodeKind::Text(ref text)
                 if text
                     .chunks
                     .iter()
                     .find(|t| matches!(t.text_flow, TextFlow::Path(_)))
-                    .is_some()
-                {
-                    return true;
-                }
+                    .is_some() =>
+            {
+                return true;
             }
             _ => {}
         }
@@ -1351,6 +1592,10 @@ fn write_base_grad(g: &BaseGradient, ctx: &WriterContext, xml: &mut XmlWriter) {
         SpreadMethod::Repeat => xml.write_svg_attribute(AId::SpreadMethod, "repeat"),
     }
 
+    if g.color_interpolation == filter::ColorInterpolation::LinearRGB {
+        xml.write_svg_attribute(AId::ColorInterpolation, "linearRGB");
+    }
+
     for s in &g.stops {
         xml.start_svg_element(EId::Stop);
         xml.write_svg_attribute(AId::Offset, &s.offset.get());
@@ -1380,6 +1625,7 @@ fn write_path(
     write_stroke(&path.stroke, ctx, xml);
 
     xml.write_visibility(path.visibility);
+    xml.write_pointer_events(path.pointer_events);
 
     if path.paint_order == PaintOrder::StrokeAndFill {
         xml.write_svg_attribute(AId::PaintOrder, "stroke");
@@ -1511,11 +1757,16 @@ fn write_stroke(stroke: &Option<Stroke>, ctx: &WriterContext, xml: &mut XmlWrite
             LineJoin::MiterClip => xml.write_svg_attribute(AId::StrokeLinejoin, "miter-clip"),
             LineJoin::Round => xml.write_svg_attribute(AId::StrokeLinejoin, "round"),
             LineJoin::Bevel => xml.write_svg_attribute(AId::StrokeLinejoin, "bevel"),
+            LineJoin::Arcs => xml.write_svg_attribute(AId::StrokeLinejoin, "arcs"),
         }
 
         if let Some(ref array) = stroke.dasharray {
             xml.write_numbers(AId::StrokeDasharray, array);
         }
+
+        if stroke.non_scaling {
+            xml.write_svg_attribute(AId::VectorEffect, "non-scaling-stroke");
+        }
     } else {
         // Always set `stroke` to `none` to override the parent value.
         // In 99.9% of the cases it's redundant, but a group with `filter` with `StrokePaint`
@@ -1612,6 +1863,65 @@ fn write_num(num: f32, buf: &mut Vec<u8>, precision: u8) {
 }
 
 /// Write all of the tspan attributes except for decorations.
+fn write_text_wrap(wrap: &TextWrap, ctx: &WriterContext, xml: &mut XmlWriter) {
+    match wrap.area {
+        TextWrapArea::InlineSize(inline_size) => {
+            xml.write_svg_attribute(AId::InlineSize, &inline_size.get());
+        }
+        TextWrapArea::Shape(ref shape) => {
+            let ref_path = ctx.text_path_map.get(&shape.id).unwrap();
+            xml.write_func_iri(AId::ShapeInside, ref_path, ctx);
+        }
+    }
+
+    match wrap.line_height {
+        LineHeight::Normal => {}
+        LineHeight::Number(n) => xml.write_svg_attribute(AId::LineHeight, &n),
+        LineHeight::Length(n) => {
+            xml.write_attribute_fmt(AId::LineHeight.to_str(), format_args!("{}px", n))
+        }
+    }
+
+    match wrap.align {
+        TextAlign::Start => {}
+        TextAlign::End => xml.write_svg_attribute(AId::TextAlign, "end"),
+        TextAlign::Left => xml.write_svg_attribute(AId::TextAlign, "left"),
+        TextAlign::Right => xml.write_svg_attribute(AId::TextAlign, "right"),
+        TextAlign::Center => xml.write_svg_attribute(AId::TextAlign, "center"),
+        TextAlign::Justify => xml.write_svg_attribute(AId::TextAlign, "justify"),
+    }
+
+    // Whitespaces are already processed, so we have to preserve them as is.
+    // Newlines must be preserved as well, since they are forced line breaks.
+    let white_space = if wrap.allow_wrap { "pre-wrap" } else { "pre" };
+    xml.write_svg_attribute(AId::WhiteSpace, white_space);
+}
+
+/// Writes a `font-feature-settings`/`font-variation-settings` value, like `'wght' 650`.
+fn write_font_tag_list(
+    ctx: &WriterContext,
+    list: impl Iterator<Item = ([u8; 4], String)>,
+) -> String {
+    // Use quotes different from the ones used for attributes.
+    let quote = if ctx.opt.writer_opts.use_single_quote {
+        '"'
+    } else {
+        '\''
+    };
+
+    list.map(|(tag, value)| {
+        format!(
+            "{}{}{} {}",
+            quote,
+            String::from_utf8_lossy(&tag),
+            quote,
+            value
+        )
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
 fn write_span(
     is_clip_path: bool,
     ctx: &mut WriterContext,
@@ -1621,7 +1931,7 @@ fn write_span(
 ) {
     xml.start_svg_element(EId::Tspan);
 
-    if span.font.families.len() > 0 {
+    if !span.font.families.is_empty() {
         let families = if span.font.families.len() == 1 {
             span.font.families[0].clone()
         } else {
@@ -1674,6 +1984,8 @@ fn write_span(
         Visibility::Collapse => xml.write_svg_attribute(AId::Visibility, "collapse"),
     }
 
+    xml.write_pointer_events(span.pointer_events);
+
     if span.letter_spacing != 0.0 {
         xml.write_svg_attribute(AId::LetterSpacing, &span.letter_spacing);
     }
@@ -1690,10 +2002,40 @@ fn write_span(
         xml.write_svg_attribute(AId::LengthAdjust, "spacingAndGlyphs");
     }
 
+    match span.text_orientation {
+        TextOrientation::Mixed => {}
+        TextOrientation::Upright => xml.write_svg_attribute(AId::TextOrientation, "upright"),
+        TextOrientation::Sideways => xml.write_svg_attribute(AId::TextOrientation, "sideways"),
+    }
+
     if span.small_caps {
         xml.write_svg_attribute(AId::FontVariant, "small-caps");
     }
 
+    if !span.font_features.is_empty() {
+        let settings = write_font_tag_list(
+            ctx,
+            span.font_features
+                .iter()
+                .map(|f| (f.tag, f.value.to_string())),
+        );
+        xml.write_svg_attribute(AId::FontFeatureSettings, &settings);
+    }
+
+    if !span.font_variations.is_empty() {
+        let settings = write_font_tag_list(
+            ctx,
+            span.font_variations
+                .iter()
+                .map(|v| (v.tag, v.value.to_string())),
+        );
+        xml.write_svg_attribute(AId::FontVariationSettings, &settings);
+    }
+
+    if !span.apply_optical_sizing {
+        xml.write_svg_attribute(AId::FontOpticalSizing, "none");
+    }
+
     if span.paint_order == PaintOrder::StrokeAndFill {
         xml.write_svg_attribute(AId::PaintOrder, "stroke fill");
     }
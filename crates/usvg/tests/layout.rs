@@ -0,0 +1,257 @@
+use usvg_parser::TreeParsing;
+
+fn layout(svg: &str) -> usvg_text_layout::TextLayout {
+    let mut fontdb = usvg_text_layout::fontdb::Database::new();
+    fontdb.load_fonts_dir("../resvg/tests/fonts");
+    fontdb.set_serif_family("Noto Serif");
+
+    layout_with_fonts(svg, &fontdb)
+}
+
+fn layout_with_fonts(
+    svg: &str,
+    fontdb: &usvg_text_layout::fontdb::Database,
+) -> usvg_text_layout::TextLayout {
+    let tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let node = tree
+        .root
+        .descendants()
+        .find(|n| matches!(*n.borrow(), usvg_tree::NodeKind::Text(_)))
+        .unwrap();
+    let text = match *node.borrow() {
+        usvg_tree::NodeKind::Text(ref text) => text.clone(),
+        _ => unreachable!(),
+    };
+    usvg_text_layout::layout(&text, fontdb, &tree.font_faces)
+}
+
+#[test]
+fn simple_case() {
+    let layout = layout(
+        "<svg xmlns='http://www.w3.org/2000/svg'>
+            <text x='10' y='20' font-family='Variable Test' font-size='10'>ABC</text>
+        </svg>",
+    );
+
+    assert_eq!(layout.chunks.len(), 1);
+    let chunk = &layout.chunks[0];
+    assert_eq!(chunk.text, "ABC");
+    assert_eq!((chunk.x, chunk.y), (10.0, 20.0));
+    assert_eq!((chunk.ascent, chunk.descent), (8.0, -2.0));
+
+    assert_eq!(chunk.clusters.len(), 3);
+    for (i, cluster) in chunk.clusters.iter().enumerate() {
+        assert_eq!(cluster.byte_range, i..i + 1);
+        assert_eq!(cluster.advance, 10.0);
+        assert_eq!(
+            cluster.transform,
+            usvg_tree::Transform::from_translate(10.0 + i as f32 * 10.0, 20.0)
+        );
+        assert!(cluster.visible);
+
+        assert_eq!(cluster.glyphs.len(), 1);
+        let glyph = &cluster.glyphs[0];
+        assert_eq!(glyph.id, i as u16 + 1);
+        assert!(glyph.font.is_some());
+        assert_eq!(glyph.advance, 10.0);
+        assert_eq!(
+            glyph.transform,
+            usvg_tree::Transform::from_row(0.01, 0.0, 0.0, -0.01, 10.0 + i as f32 * 10.0, 20.0)
+        );
+    }
+}
+
+#[test]
+fn ligature() {
+    let layout = layout(
+        "<svg xmlns='http://www.w3.org/2000/svg'>
+            <text x='10' y='20' font-family='Noto Serif'>file</text>
+        </svg>",
+    );
+
+    let clusters = &layout.chunks[0].clusters;
+    let ranges: Vec<_> = clusters.iter().map(|c| c.byte_range.clone()).collect();
+    assert_eq!(ranges, vec![0..2, 2..3, 3..4]);
+}
+
+#[test]
+fn right_to_left() {
+    let layout = layout(
+        "<svg xmlns='http://www.w3.org/2000/svg'>
+            <text x='10' y='20' font-family='Amiri'>ابج</text>
+        </svg>",
+    );
+
+    // Clusters are in the visual order.
+    let clusters = &layout.chunks[0].clusters;
+    let ranges: Vec<_> = clusters.iter().map(|c| c.byte_range.clone()).collect();
+    assert_eq!(ranges, vec![4..6, 2..4, 0..2]);
+    assert!(clusters[0].transform.tx < clusters[2].transform.tx);
+}
+
+#[test]
+fn multiple_chunks() {
+    let layout = layout(
+        "<svg xmlns='http://www.w3.org/2000/svg'>
+            <text font-family='Variable Test' font-size='10'>
+                <tspan x='10' y='20'>AB</tspan>
+                <tspan x='10' y='40' baseline-shift='5'>CD</tspan>
+            </text>
+        </svg>",
+    );
+
+    assert_eq!(layout.chunks.len(), 2);
+    assert_eq!(layout.chunks[1].text, "CD");
+    assert_eq!((layout.chunks[1].x, layout.chunks[1].y), (10.0, 40.0));
+    // Baseline shift is applied to clusters and not to the chunk.
+    assert_eq!(layout.chunks[1].clusters[0].transform.ty, 35.0);
+}
+
+#[test]
+fn vertical() {
+    let layout = layout(
+        "<svg xmlns='http://www.w3.org/2000/svg'>
+            <text x='20' y='10' font-family='Variable Test' font-size='10'
+                  writing-mode='tb'>AB</text>
+        </svg>",
+    );
+
+    let clusters = &layout.chunks[0].clusters;
+    assert_eq!(clusters.len(), 2);
+    assert!((clusters[1].transform.ty - clusters[0].transform.ty - 10.0).abs() < 0.001);
+    assert!((clusters[1].transform.tx - clusters[0].transform.tx).abs() < 0.001);
+}
+
+#[test]
+fn font_face() {
+    let mut fontdb = usvg_text_layout::fontdb::Database::new();
+    fontdb.load_fonts_dir("../resvg/tests/fonts");
+
+    let svg = std::fs::read_to_string("../resvg/tests/tests/text/font-face/data-url.svg").unwrap();
+    let layout = layout_with_fonts(&svg, &fontdb);
+
+    // The `@font-face` font is not present in the `fontdb`.
+    let clusters = &layout.chunks[0].clusters;
+    assert_eq!(clusters.len(), 3);
+    for cluster in clusters {
+        let glyph = &cluster.glyphs[0];
+        assert_ne!(glyph.id, 0);
+        assert!(fontdb.face(glyph.font.unwrap()).is_none());
+    }
+}
+
+fn font_report(svg: &str) -> usvg_text_layout::FontReport {
+    use usvg_text_layout::TreeTextToPath;
+
+    let mut fontdb = usvg_text_layout::fontdb::Database::new();
+    fontdb.load_fonts_dir("../resvg/tests/fonts");
+    fontdb.set_serif_family("Noto Serif");
+
+    let mut tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    tree.convert_text_with_report(&fontdb)
+}
+
+#[test]
+fn font_report_simple_case() {
+    let report = font_report(
+        "<svg xmlns='http://www.w3.org/2000/svg'>
+            <text id='text1' x='10' y='20' font-family='Noto Sans'>Text</text>
+        </svg>",
+    );
+
+    assert_eq!(report.spans.len(), 1);
+    let span = &report.spans[0];
+    assert_eq!(span.text_id, "text1");
+    assert_eq!(span.text, "Text");
+    assert_eq!(span.families, vec!["Noto Sans".to_string()]);
+    assert_eq!(span.face.as_ref().unwrap().family, "Noto Sans");
+    assert!(span.fallback_faces.is_empty());
+    assert!(span.missing_chars.is_empty());
+    assert!(!report.has_fallbacks());
+    assert!(!report.has_missing_glyphs());
+}
+
+#[test]
+fn font_report_fallback() {
+    let report = font_report(
+        "<svg xmlns='http://www.w3.org/2000/svg'>
+            <text x='10' y='20' font-family='Noto Sans'>Text</text>
+            <text x='10' y='40' font-family='Unknown'>テキスト</text>
+        </svg>",
+    );
+
+    assert_eq!(report.spans.len(), 2);
+    assert!(report.spans[0].fallback_faces.is_empty());
+
+    let span = &report.spans[1];
+    assert_eq!(span.text, "テキスト");
+    assert_eq!(span.families, vec!["Unknown".to_string()]);
+    // Unknown families fallback to the serif family.
+    assert_eq!(span.face.as_ref().unwrap().family, "Noto Serif");
+    assert_eq!(span.fallback_faces.len(), 1);
+    assert_eq!(span.fallback_faces[0].family, "Mplus 1p");
+    assert!(span.missing_chars.is_empty());
+    assert!(report.has_fallbacks());
+}
+
+#[test]
+fn font_report_missing_glyphs() {
+    let report = font_report(
+        "<svg xmlns='http://www.w3.org/2000/svg'>
+            <text x='10' y='20' font-family='Noto Sans'>a&#xE000;b&#xE001;&#xE000;</text>
+        </svg>",
+    );
+
+    assert_eq!(report.spans[0].missing_chars, vec!['\u{E000}', '\u{E001}']);
+    assert!(report.has_missing_glyphs());
+}
+
+fn svg_glyph_images(tree: &usvg_tree::Tree) -> usize {
+    let mut count = 0;
+    for node in tree.root.descendants() {
+        if let usvg_tree::NodeKind::Text(usvg_tree::Text {
+            flattened: Some(ref flattened),
+            ..
+        }) = *node.borrow()
+        {
+            count += flattened
+                .descendants()
+                .filter(|n| {
+                    matches!(*n.borrow(), usvg_tree::NodeKind::Image(ref image)
+                        if matches!(image.kind, usvg_tree::ImageKind::SVG(_)))
+                })
+                .count();
+        }
+    }
+
+    count
+}
+
+#[test]
+fn svg_glyphs_are_parsed_once() {
+    use usvg_text_layout::TreeTextToPath;
+
+    let mut fontdb = usvg_text_layout::fontdb::Database::new();
+    fontdb.load_fonts_dir("../resvg/tests/fonts");
+
+    let svg = "<svg xmlns='http://www.w3.org/2000/svg'>
+        <text x='10' y='20' font-family='Color Test'>CC</text>
+        <text x='10' y='40' font-family='Color Test'>C</text>
+    </svg>";
+
+    let mut tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    let calls = std::cell::Cell::new(0);
+    let parser = |data: &[u8]| {
+        calls.set(calls.get() + 1);
+        usvg::parse_svg_glyph(data)
+    };
+    tree.convert_text_with_svg_glyphs(&fontdb, &parser, None);
+
+    assert_eq!(calls.get(), 1);
+    assert_eq!(svg_glyph_images(&tree), 3);
+
+    // `SVG ` glyphs are ignored without a parser.
+    let mut tree = usvg_tree::Tree::from_str(svg, &usvg_parser::Options::default()).unwrap();
+    tree.convert_text(&fontdb);
+    assert_eq!(svg_glyph_images(&tree), 0);
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::field_reassign_with_default)]
+
 use once_cell::sync::Lazy;
 
 use usvg::TreeWriting;
@@ -17,18 +19,32 @@ static GLOBAL_FONTDB: Lazy<std::sync::Mutex<usvg_text_layout::fontdb::Database>>
     });
 
 fn resave(name: &str) {
-    resave_impl(name, None, false);
+    resave_impl(name, None, false, usvg::FlattenedText::Paths);
 }
 
 fn resave_with_text(name: &str) {
-    resave_impl(name, None, true);
+    resave_impl(name, None, true, usvg::FlattenedText::Paths);
 }
 
 fn resave_with_prefix(name: &str, id_prefix: &str) {
-    resave_impl(name, Some(id_prefix.to_string()), false);
+    resave_impl(
+        name,
+        Some(id_prefix.to_string()),
+        false,
+        usvg::FlattenedText::Paths,
+    );
+}
+
+fn resave_with_flattened_text(name: &str, flattened_text: usvg::FlattenedText) {
+    resave_impl(name, None, false, flattened_text);
 }
 
-fn resave_impl(name: &str, id_prefix: Option<String>, preserve_text: bool) {
+fn resave_impl(
+    name: &str,
+    id_prefix: Option<String>,
+    preserve_text: bool,
+    flattened_text: usvg::FlattenedText,
+) {
     let input_svg = std::fs::read_to_string(format!("tests/files/{}.svg", name)).unwrap();
 
     let tree = {
@@ -44,6 +60,7 @@ fn resave_impl(name: &str, id_prefix: Option<String>, preserve_text: bool) {
     xml_opt.id_prefix = id_prefix;
     xml_opt.coordinates_precision = 4; // Reduce noise and file size.
     xml_opt.transforms_precision = 4;
+    xml_opt.flattened_text = flattened_text;
     let output_svg = tree.to_string(&xml_opt);
 
     // std::fs::write(
@@ -63,6 +80,26 @@ fn path_simple_case() {
     resave("path-simple-case");
 }
 
+#[test]
+fn path_with_non_scaling_stroke() {
+    resave("path-with-non-scaling-stroke");
+}
+
+#[test]
+fn radial_gradient_with_fr() {
+    resave("radial-gradient-with-fr");
+}
+
+#[test]
+fn mask_layers() {
+    resave("mask-layers");
+}
+
+#[test]
+fn group_color_interpolation() {
+    resave("group-color-interpolation");
+}
+
 #[test]
 fn ellipse_simple_case() {
     resave("ellipse-simple-case");
@@ -182,3 +219,60 @@ fn preserve_text_with_complex_text_decoration() {
 fn preserve_text_with_nested_baseline_shift() {
     resave_with_text("preserve-text-with-nested-baseline-shift");
 }
+
+#[test]
+fn preserve_text_with_inline_size() {
+    resave_with_text("preserve-text-with-inline-size");
+}
+
+#[test]
+fn preserve_text_with_shape_inside() {
+    resave_with_text("preserve-text-with-shape-inside");
+}
+
+#[test]
+fn preserve_text_with_text_orientation() {
+    resave_with_text("preserve-text-with-text-orientation");
+}
+
+#[test]
+fn preserve_text_with_font_features() {
+    resave_with_text("preserve-text-with-font-features");
+}
+
+#[test]
+fn preserve_text_with_font_variations() {
+    resave_with_text("preserve-text-with-font-variations");
+}
+
+#[test]
+fn flattened_text_with_aria_label() {
+    resave_with_flattened_text(
+        "flattened-text-with-aria-label",
+        usvg::FlattenedText::AriaLabel,
+    );
+}
+
+#[test]
+fn flattened_text_with_invisible_text() {
+    resave_with_flattened_text(
+        "flattened-text-with-invisible-text",
+        usvg::FlattenedText::InvisibleText,
+    );
+}
+
+#[test]
+fn flattened_text_on_path_with_invisible_text() {
+    resave_with_flattened_text(
+        "flattened-text-on-path-with-invisible-text",
+        usvg::FlattenedText::InvisibleText,
+    );
+}
+
+#[test]
+fn flattened_text_in_clip_path_with_invisible_text() {
+    resave_with_flattened_text(
+        "flattened-text-in-clip-path-with-invisible-text",
+        usvg::FlattenedText::InvisibleText,
+    );
+}